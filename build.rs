@@ -1,4 +1,12 @@
 fn main() {
+    #[cfg(feature = "blas")]
+    if let Err(cause) = pkg_config::Config::new().probe("blas") {
+        panic!(
+            "the blas feature requires a system BLAS library (e.g. OpenBLAS, or libblas-dev \
+            on Debian and Ubuntu) with a pkg-config file named blas.pc: {cause}"
+        );
+    }
+
     #[cfg(feature = "opencl")]
     pkg_config::Config::new()
         .atleast_version("2.0")
@@ -1,5 +1,7 @@
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+use rayon::prelude::*;
 
 use ha_ndarray::*;
 
@@ -76,6 +78,66 @@ fn matmul(context: &Context) -> Result<(), Error> {
     Ok(())
 }
 
+// the previous CPU matrix multiplication, which computes each output element
+// as the dot product of a row of the left matrix with a row of the transposed right matrix
+fn matmul_dot(left: &[f32], right: &[f32], a: usize, b: usize, c: usize) -> Vec<f32> {
+    let mut right_t = vec![0.; b * c];
+    transpose::transpose(right, &mut right_t[..], c, b);
+
+    let mut output = Vec::with_capacity(a * c);
+
+    let product = left
+        .par_chunks_exact(b)
+        .map(|row| {
+            right_t
+                .par_chunks_exact(b)
+                .map(move |col| f32::dot(row, col))
+        })
+        .flatten();
+
+    output.par_extend(product);
+    output
+}
+
+fn matmul_gflops(context: &Context) -> Result<(), Error> {
+    // compare the host implementations, even if there is an OpenCL device
+    let context = context.clone().with_policy(HostOnly);
+
+    for dim in [64, 128, 256, 512, 1024] {
+        let left = vec![1.0f32; dim * dim];
+        let right = vec![1.0f32; dim * dim];
+        let num_ops = 2 * dim * dim * dim;
+        let gflops = |duration: Duration| num_ops as f64 / duration.as_secs_f64() / 1e9;
+
+        println!("matmul {dim}x{dim} with {dim}x{dim} ({num_ops} FLOPs)");
+
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            let _output = matmul_dot(&left, &right, dim, dim, dim);
+        }
+        let before = start.elapsed() / ITERATIONS as u32;
+
+        let l = ArrayBase::<Vec<_>>::with_context(context.clone(), vec![dim, dim], left)?;
+        let r = ArrayBase::<Vec<_>>::with_context(context.clone(), vec![dim, dim], right)?;
+        let x = l.matmul(r)?;
+        let queue = Queue::new(context.clone(), x.size())?;
+
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            let _output = x.read(&queue)?;
+        }
+        let after = start.elapsed() / ITERATIONS as u32;
+
+        println!(
+            "dot product: {:.2} GFLOP/s, blocked: {:.2} GFLOP/s",
+            gflops(before),
+            gflops(after)
+        );
+    }
+
+    Ok(())
+}
+
 fn reduce_sum_axis(context: &Context) -> Result<(), Error> {
     let shape = vec![10, 20, 30, 40, 50];
     let size = shape.iter().product();
@@ -145,6 +207,7 @@ fn main() -> Result<(), Error> {
 
     broadcast_and_multiply(&context)?;
     matmul(&context)?;
+    matmul_gflops(&context)?;
     reduce_sum_axis(&context)?;
     reduce_sum_all(&context)?;
     transpose(&context)?;
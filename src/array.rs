@@ -1,20 +1,26 @@
 use std::cmp::Ordering;
-use std::ops::{Add, Div, Mul, Neg, Not, Rem, Sub};
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Neg, Not, Rem, Shl, Shr, Sub};
 use std::sync::{Arc, RwLock};
 use std::{fmt, iter};
 
 use rayon::prelude::*;
 
 use super::ops::*;
+#[cfg(feature = "complex")]
+use super::Complex;
+#[cfg(feature = "blas")]
+use super::SliceConverter;
 use super::{
     offset_of, strides_for, AsBuffer, AxisBound, Buffer, BufferConverter, BufferConverterMut,
-    BufferInstance, BufferRead, BufferWrite, CDatatype, Context, Error, NDArray, NDArrayRead,
-    NDArrayTransform, NDArrayWrite, Queue, Shape,
+    BufferInstance, BufferRead, BufferWrite, CDatatype, Context, DType, Error, Integer, NDArray,
+    NDArrayCast, NDArrayCompare, NDArrayMath, NDArrayRead, NDArrayReduce, NDArrayReduceBoolean,
+    NDArrayTransform, NDArrayTrig, NDArrayUnary, NDArrayWrite, Queue, Shape,
 };
 
 /// A generic n-dimensional array
+#[derive(Clone)]
 pub enum Array<T: CDatatype> {
-    Base(ArrayBase<Box<dyn BufferRead<DType = T>>>),
+    Base(ArrayBase<Arc<dyn BufferRead<DType = T>>>),
     Op(ArrayOp<Arc<dyn super::ops::Op<Out = T>>>),
     Slice(Box<ArraySlice<Self>>),
     View(Box<ArrayView<Self>>),
@@ -51,6 +57,14 @@ impl<T: CDatatype> NDArrayRead for Array<T> {
     fn read_value(&self, coord: &[usize]) -> Result<Self::DType, Error> {
         array_dispatch!(self, this, this.read_value(coord))
     }
+
+    #[cfg(feature = "blas")]
+    fn to_host_matrices(
+        &self,
+        queue: &Queue,
+    ) -> Result<(SliceConverter<Self::DType>, bool), Error> {
+        array_dispatch!(self, this, this.to_host_matrices(queue))
+    }
 }
 
 impl<T: CDatatype> NDArrayTransform for Array<T> {
@@ -87,6 +101,494 @@ impl<T: CDatatype> fmt::Debug for Array<T> {
     }
 }
 
+/// An n-dimensional array whose data type is only known at runtime,
+/// the dynamically-typed counterpart of [`Array`]
+#[derive(Clone)]
+pub enum DynArray {
+    U8(Array<u8>),
+    U16(Array<u16>),
+    U32(Array<u32>),
+    U64(Array<u64>),
+    I8(Array<i8>),
+    I16(Array<i16>),
+    I32(Array<i32>),
+    I64(Array<i64>),
+    #[cfg(feature = "half")]
+    F16(Array<crate::F16>),
+    #[cfg(feature = "half")]
+    BF16(Array<crate::BF16>),
+    F32(Array<f32>),
+    F64(Array<f64>),
+    #[cfg(feature = "complex")]
+    C64(Array<Complex<f32>>),
+    #[cfg(feature = "complex")]
+    C128(Array<Complex<f64>>),
+}
+
+// if an expression is given for complex arrays, it's used in place of `$call`,
+// for operations which require an ordered data type
+macro_rules! dyn_dispatch {
+    ($this:expr, $var:ident, $call:expr) => {
+        dyn_dispatch!($this, $var, $call, {
+            let $var = $var;
+            $call
+        })
+    };
+    ($this:expr, $var:ident, $call:expr, $complex:expr) => {
+        match $this {
+            DynArray::U8($var) => $call,
+            DynArray::U16($var) => $call,
+            DynArray::U32($var) => $call,
+            DynArray::U64($var) => $call,
+            DynArray::I8($var) => $call,
+            DynArray::I16($var) => $call,
+            DynArray::I32($var) => $call,
+            DynArray::I64($var) => $call,
+            #[cfg(feature = "half")]
+            DynArray::F16($var) => $call,
+            #[cfg(feature = "half")]
+            DynArray::BF16($var) => $call,
+            DynArray::F32($var) => $call,
+            DynArray::F64($var) => $call,
+            #[cfg(feature = "complex")]
+            #[allow(unused_variables)]
+            DynArray::C64($var) => $complex,
+            #[cfg(feature = "complex")]
+            #[allow(unused_variables)]
+            DynArray::C128($var) => $complex,
+        }
+    };
+}
+
+// the operands must already have been cast to the same data type
+macro_rules! dyn_dual {
+    ($left:expr, $right:expr, $l:ident, $r:ident, $call:expr) => {
+        dyn_dual!($left, $right, $l, $r, $call, {
+            let ($l, $r) = ($l, $r);
+            $call
+        })
+    };
+    ($left:expr, $right:expr, $l:ident, $r:ident, $call:expr, $complex:expr) => {
+        match ($left, $right) {
+            (DynArray::U8($l), DynArray::U8($r)) => $call,
+            (DynArray::U16($l), DynArray::U16($r)) => $call,
+            (DynArray::U32($l), DynArray::U32($r)) => $call,
+            (DynArray::U64($l), DynArray::U64($r)) => $call,
+            (DynArray::I8($l), DynArray::I8($r)) => $call,
+            (DynArray::I16($l), DynArray::I16($r)) => $call,
+            (DynArray::I32($l), DynArray::I32($r)) => $call,
+            (DynArray::I64($l), DynArray::I64($r)) => $call,
+            #[cfg(feature = "half")]
+            (DynArray::F16($l), DynArray::F16($r)) => $call,
+            #[cfg(feature = "half")]
+            (DynArray::BF16($l), DynArray::BF16($r)) => $call,
+            (DynArray::F32($l), DynArray::F32($r)) => $call,
+            (DynArray::F64($l), DynArray::F64($r)) => $call,
+            #[cfg(feature = "complex")]
+            #[allow(unused_variables)]
+            (DynArray::C64($l), DynArray::C64($r)) => $complex,
+            #[cfg(feature = "complex")]
+            #[allow(unused_variables)]
+            (DynArray::C128($l), DynArray::C128($r)) => $complex,
+            (l, r) => unreachable!("{:?} and {:?} have different data types", l, r),
+        }
+    };
+}
+
+// these return a `Result` like their counterparts in [`NDArrayMath`], so they can't be operator impls
+macro_rules! dyn_dual_op {
+    ($name:ident, $doc:expr) => {
+        #[doc = $doc]
+        #[allow(clippy::should_implement_trait)]
+        pub fn $name(self, other: Self) -> Result<Self, Error> {
+            let (left, right) = self.promote(other)?;
+            dyn_dual!(left, right, l, r, l.$name(r).map(Self::from))
+        }
+    };
+}
+
+// the operands are cast to a common float type, since the right-hand side must match `T::Float`
+macro_rules! dyn_float_op {
+    ($name:ident, $doc:expr) => {
+        #[doc = $doc]
+        pub fn $name(self, other: Self) -> Result<Self, Error> {
+            let (left, right) = self.promote_float(other)?;
+            match (left, right) {
+                (Self::F32(l), Self::F32(r)) => l.$name(r).map(Self::from),
+                (Self::F64(l), Self::F64(r)) => l.$name(r).map(Self::from),
+                #[cfg(feature = "complex")]
+                (l, _) if l.dtype().is_complex() => Err(unordered(stringify!($name))),
+                (l, r) => unreachable!("{:?} and {:?} have different data types", l, r),
+            }
+        }
+    };
+}
+
+macro_rules! dyn_unary_op {
+    ($name:ident, $doc:expr) => {
+        #[doc = $doc]
+        #[allow(clippy::should_implement_trait)]
+        pub fn $name(self) -> Result<Self, Error> {
+            dyn_dispatch!(self, this, this.$name().map(Self::from))
+        }
+    };
+    ($name:ident, $doc:expr, ordered) => {
+        #[doc = $doc]
+        pub fn $name(self) -> Result<Self, Error> {
+            dyn_dispatch!(
+                self,
+                this,
+                this.$name().map(Self::from),
+                Err(unordered(stringify!($name)))
+            )
+        }
+    };
+}
+
+macro_rules! dyn_compare_op {
+    ($name:ident, $doc:expr) => {
+        #[doc = $doc]
+        pub fn $name(self, other: Self) -> Result<Self, Error> {
+            let (left, right) = self.promote(other)?;
+            dyn_dual!(left, right, l, r, l.$name(r).map(Self::from))
+        }
+    };
+    ($name:ident, $doc:expr, ordered) => {
+        #[doc = $doc]
+        pub fn $name(self, other: Self) -> Result<Self, Error> {
+            let (left, right) = self.promote(other)?;
+            dyn_dual!(
+                left,
+                right,
+                l,
+                r,
+                l.$name(r).map(Self::from),
+                Err(unordered(stringify!($name)))
+            )
+        }
+    };
+}
+
+macro_rules! dyn_reduce_op {
+    ($name:ident, $doc:expr) => {
+        #[doc = $doc]
+        pub fn $name(self, axes: Vec<usize>, keepdims: bool) -> Result<Self, Error> {
+            dyn_dispatch!(self, this, this.$name(axes, keepdims).map(Self::from))
+        }
+    };
+    ($name:ident, $doc:expr, ordered) => {
+        #[doc = $doc]
+        pub fn $name(self, axes: Vec<usize>, keepdims: bool) -> Result<Self, Error> {
+            dyn_dispatch!(
+                self,
+                this,
+                this.$name(axes, keepdims).map(Self::from),
+                Err(unordered(stringify!($name)))
+            )
+        }
+    };
+}
+
+impl DynArray {
+    /// Return the runtime data type of this array.
+    pub fn dtype(&self) -> DType {
+        match self {
+            Self::U8(_) => DType::U8,
+            Self::U16(_) => DType::U16,
+            Self::U32(_) => DType::U32,
+            Self::U64(_) => DType::U64,
+            Self::I8(_) => DType::I8,
+            Self::I16(_) => DType::I16,
+            Self::I32(_) => DType::I32,
+            Self::I64(_) => DType::I64,
+            #[cfg(feature = "half")]
+            Self::F16(_) => DType::F16,
+            #[cfg(feature = "half")]
+            Self::BF16(_) => DType::BF16,
+            Self::F32(_) => DType::F32,
+            Self::F64(_) => DType::F64,
+            #[cfg(feature = "complex")]
+            Self::C64(_) => DType::C64,
+            #[cfg(feature = "complex")]
+            Self::C128(_) => DType::C128,
+        }
+    }
+
+    /// Borrow the execution [`Context`] of this array.
+    pub fn context(&self) -> &Context {
+        dyn_dispatch!(self, this, this.context())
+    }
+
+    /// Return the number of dimensions of this array.
+    pub fn ndim(&self) -> usize {
+        self.shape().len()
+    }
+
+    /// Return the number of elements in this array.
+    pub fn size(&self) -> usize {
+        self.shape().iter().product()
+    }
+
+    /// Borrow the shape of this array.
+    pub fn shape(&self) -> &[usize] {
+        dyn_dispatch!(self, this, this.shape())
+    }
+
+    /// Construct a cast operation to the given data type.
+    /// This is a no-op if this array already has the given data type.
+    pub fn cast_to(self, dtype: DType) -> Result<Self, Error> {
+        if self.dtype() == dtype {
+            return Ok(self);
+        }
+
+        match dtype {
+            DType::U8 => dyn_dispatch!(self, this, this.cast::<u8>().map(Self::from)),
+            DType::U16 => dyn_dispatch!(self, this, this.cast::<u16>().map(Self::from)),
+            DType::U32 => dyn_dispatch!(self, this, this.cast::<u32>().map(Self::from)),
+            DType::U64 => dyn_dispatch!(self, this, this.cast::<u64>().map(Self::from)),
+            DType::I8 => dyn_dispatch!(self, this, this.cast::<i8>().map(Self::from)),
+            DType::I16 => dyn_dispatch!(self, this, this.cast::<i16>().map(Self::from)),
+            DType::I32 => dyn_dispatch!(self, this, this.cast::<i32>().map(Self::from)),
+            DType::I64 => dyn_dispatch!(self, this, this.cast::<i64>().map(Self::from)),
+            #[cfg(feature = "half")]
+            DType::F16 => dyn_dispatch!(self, this, this.cast::<crate::F16>().map(Self::from)),
+            #[cfg(feature = "half")]
+            DType::BF16 => dyn_dispatch!(self, this, this.cast::<crate::BF16>().map(Self::from)),
+            DType::F32 => dyn_dispatch!(self, this, this.cast::<f32>().map(Self::from)),
+            DType::F64 => dyn_dispatch!(self, this, this.cast::<f64>().map(Self::from)),
+            #[cfg(feature = "complex")]
+            DType::C64 => dyn_dispatch!(self, this, this.cast::<Complex<f32>>().map(Self::from)),
+            #[cfg(feature = "complex")]
+            DType::C128 => {
+                dyn_dispatch!(self, this, this.cast::<Complex<f64>>().map(Self::from))
+            }
+        }
+    }
+
+    /// Cast this array and `other` to their promoted data type (see [`DType::promote`]).
+    pub fn promote(self, other: Self) -> Result<(Self, Self), Error> {
+        let dtype = self.dtype().promote(other.dtype());
+        Ok((self.cast_to(dtype)?, other.cast_to(dtype)?))
+    }
+
+    /// Cast this array and `other` to the floating point type in which to compute an op on both.
+    /// Integer and 16-bit float types are promoted to [`DType::F32`] or [`DType::F64`].
+    pub fn promote_float(self, other: Self) -> Result<(Self, Self), Error> {
+        let dtype = match self.dtype().promote(other.dtype()) {
+            DType::F64 => DType::F64,
+            dtype if dtype.is_complex() => dtype,
+            dtype => DType::F32.promote(dtype),
+        };
+
+        Ok((self.cast_to(dtype)?, other.cast_to(dtype)?))
+    }
+
+    /// Construct an operation to broadcast this array into the given `shape`.
+    pub fn broadcast(self, shape: Shape) -> Result<Self, Error> {
+        dyn_dispatch!(self, this, this.broadcast(shape).map(Self::from))
+    }
+
+    /// Construct an operation to insert new dimensions of size 1 at the given `axes`.
+    pub fn expand_dims(self, axes: Vec<usize>) -> Result<Self, Error> {
+        dyn_dispatch!(self, this, this.expand_dims(axes).map(Self::from))
+    }
+
+    /// Construct an operation to reshape this array into the given `shape`.
+    pub fn reshape(self, shape: Shape) -> Result<Self, Error> {
+        dyn_dispatch!(self, this, this.reshape(shape).map(Self::from))
+    }
+
+    /// Construct a slice of this array.
+    pub fn slice(self, bounds: Vec<AxisBound>) -> Result<Self, Error> {
+        dyn_dispatch!(self, this, this.slice(bounds).map(Self::from))
+    }
+
+    /// Construct an operation to transpose this array according to the given permutation of `axes`.
+    pub fn transpose(self, axes: Option<Vec<usize>>) -> Result<Self, Error> {
+        dyn_dispatch!(self, this, this.transpose(axes).map(Self::from))
+    }
+
+    dyn_dual_op!(
+        add,
+        "Construct an addition operation with `other`, after type promotion."
+    );
+    dyn_dual_op!(
+        div,
+        "Construct a division operation with `other`, after type promotion.
+        The result is undefined where `other` contains zeros."
+    );
+    dyn_dual_op!(
+        mul,
+        "Construct a multiplication operation with `other`, after type promotion."
+    );
+    dyn_dual_op!(
+        rem,
+        "Construct a modulo operation with `other`, after type promotion."
+    );
+    dyn_dual_op!(
+        sub,
+        "Construct a subtraction operation with `other`, after type promotion."
+    );
+
+    dyn_float_op!(
+        atan2,
+        "Construct a four-quadrant arctangent operation of this array (`y`) and `other` (`x`),
+        after promotion to a common floating point type."
+    );
+    dyn_float_op!(
+        hypot,
+        "Construct a hypotenuse operation with `other`,
+        after promotion to a common floating point type."
+    );
+    dyn_float_op!(
+        log,
+        "Construct a logarithm operation with the base `other`,
+        after promotion to a common floating point type."
+    );
+    dyn_float_op!(
+        pow,
+        "Construct an exponentiation operation with the exponent `other`,
+        after promotion to a common floating point type."
+    );
+
+    dyn_unary_op!(abs, "Construct an absolute value operation.");
+    dyn_unary_op!(ceil, "Construct a ceiling operation.", ordered);
+    dyn_unary_op!(erf, "Construct an error function operation.", ordered);
+    dyn_unary_op!(exp, "Construct an exponentiation operation.", ordered);
+    dyn_unary_op!(expm1, "Construct an `e^x - 1` operation.", ordered);
+    dyn_unary_op!(floor, "Construct a floor operation.", ordered);
+    dyn_unary_op!(ln, "Construct a natural logarithm operation.", ordered);
+    dyn_unary_op!(log1p, "Construct an `ln(1 + x)` operation.", ordered);
+    dyn_unary_op!(not, "Construct a boolean not operation.");
+    dyn_unary_op!(round, "Construct an integer rounding operation.");
+    dyn_unary_op!(
+        rsqrt,
+        "Construct a reciprocal square root operation.",
+        ordered
+    );
+    dyn_unary_op!(sign, "Construct a sign operation.", ordered);
+    dyn_unary_op!(sqrt, "Construct a square root operation.", ordered);
+    dyn_unary_op!(trunc, "Construct a truncation operation.", ordered);
+
+    dyn_unary_op!(acos, "Construct an arccosine operation.", ordered);
+    dyn_unary_op!(asin, "Construct an arcsine operation.", ordered);
+    dyn_unary_op!(atan, "Construct an arctangent operation.", ordered);
+    dyn_unary_op!(cos, "Construct a cosine operation.", ordered);
+    dyn_unary_op!(cosh, "Construct a hyperbolic cosine operation.", ordered);
+    dyn_unary_op!(sin, "Construct a sine operation.", ordered);
+    dyn_unary_op!(sinh, "Construct a hyperbolic sine operation.", ordered);
+    dyn_unary_op!(tan, "Construct a tangent operation.", ordered);
+    dyn_unary_op!(tanh, "Construct a hyperbolic tangent operation.", ordered);
+
+    dyn_compare_op!(
+        eq,
+        "Construct an equality comparison with `other`, after type promotion."
+    );
+    dyn_compare_op!(
+        gt,
+        "Construct a greater-than comparison with `other`, after type promotion.",
+        ordered
+    );
+    dyn_compare_op!(
+        ge,
+        "Construct an equal-or-greater-than comparison with `other`, after type promotion.",
+        ordered
+    );
+    dyn_compare_op!(
+        lt,
+        "Construct a less-than comparison with `other`, after type promotion.",
+        ordered
+    );
+    dyn_compare_op!(
+        le,
+        "Construct an equal-or-less-than comparison with `other`, after type promotion.",
+        ordered
+    );
+    dyn_compare_op!(
+        ne,
+        "Construct a not-equal comparison with `other`, after type promotion."
+    );
+
+    dyn_reduce_op!(
+        max,
+        "Construct a max-reduce operation over the given `axes`.",
+        ordered
+    );
+    dyn_reduce_op!(
+        min,
+        "Construct a min-reduce operation over the given `axes`.",
+        ordered
+    );
+    dyn_reduce_op!(
+        product,
+        "Construct a product-reduce operation over the given `axes`."
+    );
+    dyn_reduce_op!(
+        sum,
+        "Construct a sum-reduce operation over the given `axes`."
+    );
+
+    /// Return `true` if this array contains only non-zero elements.
+    pub fn all(&self) -> Result<bool, Error> {
+        dyn_dispatch!(self, this, this.all())
+    }
+
+    /// Return `true` if this array contains any non-zero elements.
+    pub fn any(&self) -> Result<bool, Error> {
+        dyn_dispatch!(self, this, this.any())
+    }
+}
+
+macro_rules! dyn_from {
+    ($t:ty, $var:ident) => {
+        impl From<Array<$t>> for DynArray {
+            fn from(array: Array<$t>) -> Self {
+                Self::$var(array)
+            }
+        }
+    };
+}
+
+dyn_from!(u8, U8);
+dyn_from!(u16, U16);
+dyn_from!(u32, U32);
+dyn_from!(u64, U64);
+dyn_from!(i8, I8);
+dyn_from!(i16, I16);
+dyn_from!(i32, I32);
+dyn_from!(i64, I64);
+#[cfg(feature = "half")]
+dyn_from!(crate::F16, F16);
+#[cfg(feature = "half")]
+dyn_from!(crate::BF16, BF16);
+dyn_from!(f32, F32);
+dyn_from!(f64, F64);
+#[cfg(feature = "complex")]
+dyn_from!(Complex<f32>, C64);
+#[cfg(feature = "complex")]
+dyn_from!(Complex<f64>, C128);
+
+impl<Op> From<ArrayOp<Op>> for DynArray
+where
+    Op: super::ops::Op + 'static,
+    Array<Op::Out>: Into<Self>,
+{
+    fn from(op: ArrayOp<Op>) -> Self {
+        Array::from(op).into()
+    }
+}
+
+impl fmt::Debug for DynArray {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        dyn_dispatch!(self, this, this.fmt(f))
+    }
+}
+
+#[cfg(feature = "complex")]
+fn unordered(op: &str) -> Error {
+    Error::Interface(format!("{op} is not defined for complex numbers"))
+}
+
 #[derive(Clone)]
 /// An n-dimensional array with a specific buffer type
 pub struct ArrayBase<Buf> {
@@ -395,7 +897,7 @@ impl<Buf: BufferInstance> NDArray for ArrayBase<Buf> {
     }
 }
 
-impl<T: CDatatype> NDArrayRead for ArrayBase<Box<dyn BufferRead<DType = T>>> {
+impl<T: CDatatype> NDArrayRead for ArrayBase<Arc<dyn BufferRead<DType = T>>> {
     fn read(&self, _queue: &Queue) -> Result<BufferConverter<Self::DType>, Error> {
         Ok(self.data.read())
     }
@@ -886,9 +1388,12 @@ where
 
 macro_rules! impl_base_op {
     ($op:ident, $name:ident) => {
+        impl_base_op!($op, $name, CDatatype);
+    };
+    ($op:ident, $name:ident, $t:ident) => {
         impl<T, LB, RB> $op<ArrayBase<RB>> for ArrayBase<LB>
         where
-            T: CDatatype,
+            T: $t,
             LB: BufferInstance<DType = T>,
             RB: BufferInstance<DType = T>,
         {
@@ -910,10 +1415,18 @@ impl_base_op!(Div, div);
 impl_base_op!(Mul, mul);
 impl_base_op!(Rem, rem);
 impl_base_op!(Sub, sub);
+impl_base_op!(BitAnd, bitand, Integer);
+impl_base_op!(BitOr, bitor, Integer);
+impl_base_op!(BitXor, bitxor, Integer);
+impl_base_op!(Shl, shl, Integer);
+impl_base_op!(Shr, shr, Integer);
 
 macro_rules! impl_base_dual_op {
     ($op:ident, $name:ident, $o:ty) => {
-        impl<T: CDatatype, Buf: BufferInstance<DType = T>, O> $op<$o> for ArrayBase<Buf>
+        impl_base_dual_op!($op, $name, $o, CDatatype);
+    };
+    ($op:ident, $name:ident, $o:ty, $t:ident) => {
+        impl<T: $t, Buf: BufferInstance<DType = T>, O> $op<$o> for ArrayBase<Buf>
         where
             $o: NDArray<DType = T>,
         {
@@ -935,22 +1448,40 @@ impl_base_dual_op!(Div, div, ArrayOp<O>);
 impl_base_dual_op!(Mul, mul, ArrayOp<O>);
 impl_base_dual_op!(Rem, rem, ArrayOp<O>);
 impl_base_dual_op!(Sub, sub, ArrayOp<O>);
+impl_base_dual_op!(BitAnd, bitand, ArrayOp<O>, Integer);
+impl_base_dual_op!(BitOr, bitor, ArrayOp<O>, Integer);
+impl_base_dual_op!(BitXor, bitxor, ArrayOp<O>, Integer);
+impl_base_dual_op!(Shl, shl, ArrayOp<O>, Integer);
+impl_base_dual_op!(Shr, shr, ArrayOp<O>, Integer);
 
 impl_base_dual_op!(Add, add, ArraySlice<O>);
 impl_base_dual_op!(Div, div, ArraySlice<O>);
 impl_base_dual_op!(Mul, mul, ArraySlice<O>);
 impl_base_dual_op!(Rem, rem, ArraySlice<O>);
 impl_base_dual_op!(Sub, sub, ArraySlice<O>);
+impl_base_dual_op!(BitAnd, bitand, ArraySlice<O>, Integer);
+impl_base_dual_op!(BitOr, bitor, ArraySlice<O>, Integer);
+impl_base_dual_op!(BitXor, bitxor, ArraySlice<O>, Integer);
+impl_base_dual_op!(Shl, shl, ArraySlice<O>, Integer);
+impl_base_dual_op!(Shr, shr, ArraySlice<O>, Integer);
 
 impl_base_dual_op!(Add, add, ArrayView<O>);
 impl_base_dual_op!(Div, div, ArrayView<O>);
 impl_base_dual_op!(Mul, mul, ArrayView<O>);
 impl_base_dual_op!(Rem, rem, ArrayView<O>);
 impl_base_dual_op!(Sub, sub, ArrayView<O>);
+impl_base_dual_op!(BitAnd, bitand, ArrayView<O>, Integer);
+impl_base_dual_op!(BitOr, bitor, ArrayView<O>, Integer);
+impl_base_dual_op!(BitXor, bitxor, ArrayView<O>, Integer);
+impl_base_dual_op!(Shl, shl, ArrayView<O>, Integer);
+impl_base_dual_op!(Shr, shr, ArrayView<O>, Integer);
 
 macro_rules! impl_base_scalar_op {
     ($op:ident, $name:ident) => {
-        impl<T: CDatatype, Buf: BufferInstance<DType = T>> $op<T> for ArrayBase<Buf> {
+        impl_base_scalar_op!($op, $name, CDatatype);
+    };
+    ($op:ident, $name:ident, $t:ident) => {
+        impl<T: $t, Buf: BufferInstance<DType = T>> $op<T> for ArrayBase<Buf> {
             type Output = ArrayOp<ArrayScalar<T, Self>>;
 
             fn $name(self, rhs: T) -> Self::Output {
@@ -967,6 +1498,11 @@ impl_base_scalar_op!(Div, div);
 impl_base_scalar_op!(Mul, mul);
 impl_base_scalar_op!(Rem, rem);
 impl_base_scalar_op!(Sub, sub);
+impl_base_scalar_op!(BitAnd, bitand, Integer);
+impl_base_scalar_op!(BitOr, bitor, Integer);
+impl_base_scalar_op!(BitXor, bitxor, Integer);
+impl_base_scalar_op!(Shl, shl, Integer);
+impl_base_scalar_op!(Shr, shr, Integer);
 
 impl<Buf: BufferInstance> Neg for ArrayBase<Buf> {
     type Output = ArrayOp<ArrayUnary<Buf::DType, <Buf::DType as CDatatype>::Neg, Self>>;
@@ -998,11 +1534,11 @@ impl<T: CDatatype> From<ArrayBase<Vec<T>>> for ArrayBase<Arc<Vec<T>>> {
     }
 }
 
-impl<T: CDatatype> From<ArrayBase<Vec<T>>> for ArrayBase<Box<dyn BufferRead<DType = T>>> {
+impl<T: CDatatype> From<ArrayBase<Vec<T>>> for ArrayBase<Arc<dyn BufferRead<DType = T>>> {
     fn from(base: ArrayBase<Vec<T>>) -> Self {
         ArrayBase {
             context: base.context,
-            data: Box::new(Arc::new(base.data)),
+            data: Arc::new(base.data),
             shape: base.shape,
         }
     }
@@ -1062,11 +1598,11 @@ impl<T: CDatatype> From<ArrayBase<ocl::Buffer<T>>> for ArrayBase<Arc<Buffer<T>>>
 }
 
 #[cfg(feature = "opencl")]
-impl<T: CDatatype> From<ArrayBase<ocl::Buffer<T>>> for ArrayBase<Box<dyn BufferRead<DType = T>>> {
+impl<T: CDatatype> From<ArrayBase<ocl::Buffer<T>>> for ArrayBase<Arc<dyn BufferRead<DType = T>>> {
     fn from(base: ArrayBase<ocl::Buffer<T>>) -> Self {
         ArrayBase {
             context: base.context,
-            data: Box::new(Arc::new(base.data)),
+            data: Arc::new(base.data),
             shape: base.shape,
         }
     }
@@ -1102,7 +1638,7 @@ macro_rules! array_from_base {
                 Self::Base(ArrayBase {
                     context: base.context,
                     shape: base.shape,
-                    data: Box::new(base.data),
+                    data: Arc::new(base.data),
                 })
             }
         }
@@ -1125,7 +1661,7 @@ where
         Self::Base(ArrayBase {
             context: base.context,
             shape: base.shape,
-            data: Box::new(base.data),
+            data: Arc::new(base.data),
         })
     }
 }
@@ -1140,14 +1676,24 @@ where
         Self::Base(ArrayBase {
             context: base.context,
             shape: base.shape,
-            data: Box::new(base.data),
+            data: Arc::new(base.data),
         })
     }
 }
 
+impl<T: CDatatype> From<ArrayBase<Arc<dyn BufferRead<DType = T>>>> for Array<T> {
+    fn from(base: ArrayBase<Arc<dyn BufferRead<DType = T>>>) -> Self {
+        Self::Base(base)
+    }
+}
+
 impl<T: CDatatype> From<ArrayBase<Box<dyn BufferRead<DType = T>>>> for Array<T> {
     fn from(base: ArrayBase<Box<dyn BufferRead<DType = T>>>) -> Self {
-        Self::Base(base)
+        Self::Base(ArrayBase {
+            context: base.context,
+            shape: base.shape,
+            data: base.data.into(),
+        })
     }
 }
 
@@ -1190,7 +1736,7 @@ impl<Op: super::ops::Op> NDArray for ArrayOp<Op> {
 
 impl<Op: super::ops::Op> NDArrayRead for ArrayOp<Op> {
     fn read(&self, queue: &Queue) -> Result<BufferConverter<Op::Out>, Error> {
-        self.op.enqueue(queue).map(BufferConverter::from)
+        self.op.enqueue(queue)
     }
 
     fn read_value(&self, coord: &[usize]) -> Result<Self::DType, Error> {
@@ -1240,7 +1786,10 @@ impl<Op: super::ops::Op> NDArrayTransform for ArrayOp<Op> {
 
 macro_rules! impl_op_dual_op {
     ($op:ident, $name:ident, $o:ty) => {
-        impl<T: CDatatype, Op: super::ops::Op<Out = T>, O> $op<$o> for ArrayOp<Op>
+        impl_op_dual_op!($op, $name, $o, CDatatype);
+    };
+    ($op:ident, $name:ident, $o:ty, $t:ident) => {
+        impl<T: $t, Op: super::ops::Op<Out = T>, O> $op<$o> for ArrayOp<Op>
         where
             $o: NDArray<DType = T>,
         {
@@ -1262,28 +1811,51 @@ impl_op_dual_op!(Div, div, ArrayBase<O>);
 impl_op_dual_op!(Mul, mul, ArrayBase<O>);
 impl_op_dual_op!(Rem, rem, ArrayBase<O>);
 impl_op_dual_op!(Sub, sub, ArrayBase<O>);
+impl_op_dual_op!(BitAnd, bitand, ArrayBase<O>, Integer);
+impl_op_dual_op!(BitOr, bitor, ArrayBase<O>, Integer);
+impl_op_dual_op!(BitXor, bitxor, ArrayBase<O>, Integer);
+impl_op_dual_op!(Shl, shl, ArrayBase<O>, Integer);
+impl_op_dual_op!(Shr, shr, ArrayBase<O>, Integer);
 
 impl_op_dual_op!(Add, add, ArrayOp<O>);
 impl_op_dual_op!(Div, div, ArrayOp<O>);
 impl_op_dual_op!(Mul, mul, ArrayOp<O>);
 impl_op_dual_op!(Rem, rem, ArrayOp<O>);
 impl_op_dual_op!(Sub, sub, ArrayOp<O>);
+impl_op_dual_op!(BitAnd, bitand, ArrayOp<O>, Integer);
+impl_op_dual_op!(BitOr, bitor, ArrayOp<O>, Integer);
+impl_op_dual_op!(BitXor, bitxor, ArrayOp<O>, Integer);
+impl_op_dual_op!(Shl, shl, ArrayOp<O>, Integer);
+impl_op_dual_op!(Shr, shr, ArrayOp<O>, Integer);
 
 impl_op_dual_op!(Add, add, ArraySlice<O>);
 impl_op_dual_op!(Div, div, ArraySlice<O>);
 impl_op_dual_op!(Mul, mul, ArraySlice<O>);
 impl_op_dual_op!(Rem, rem, ArraySlice<O>);
 impl_op_dual_op!(Sub, sub, ArraySlice<O>);
+impl_op_dual_op!(BitAnd, bitand, ArraySlice<O>, Integer);
+impl_op_dual_op!(BitOr, bitor, ArraySlice<O>, Integer);
+impl_op_dual_op!(BitXor, bitxor, ArraySlice<O>, Integer);
+impl_op_dual_op!(Shl, shl, ArraySlice<O>, Integer);
+impl_op_dual_op!(Shr, shr, ArraySlice<O>, Integer);
 
 impl_op_dual_op!(Add, add, ArrayView<O>);
 impl_op_dual_op!(Div, div, ArrayView<O>);
 impl_op_dual_op!(Mul, mul, ArrayView<O>);
 impl_op_dual_op!(Rem, rem, ArrayView<O>);
 impl_op_dual_op!(Sub, sub, ArrayView<O>);
+impl_op_dual_op!(BitAnd, bitand, ArrayView<O>, Integer);
+impl_op_dual_op!(BitOr, bitor, ArrayView<O>, Integer);
+impl_op_dual_op!(BitXor, bitxor, ArrayView<O>, Integer);
+impl_op_dual_op!(Shl, shl, ArrayView<O>, Integer);
+impl_op_dual_op!(Shr, shr, ArrayView<O>, Integer);
 
 macro_rules! impl_op_scalar_op {
     ($op:ident, $name:ident) => {
-        impl<T: CDatatype, Op: super::ops::Op<Out = T>> $op<T> for ArrayOp<Op> {
+        impl_op_scalar_op!($op, $name, CDatatype);
+    };
+    ($op:ident, $name:ident, $t:ident) => {
+        impl<T: $t, Op: super::ops::Op<Out = T>> $op<T> for ArrayOp<Op> {
             type Output = ArrayOp<ArrayScalar<Op::Out, Self>>;
 
             fn $name(self, rhs: Op::Out) -> Self::Output {
@@ -1300,6 +1872,11 @@ impl_op_scalar_op!(Mul, mul);
 impl_op_scalar_op!(Div, div);
 impl_op_scalar_op!(Rem, rem);
 impl_op_scalar_op!(Sub, sub);
+impl_op_scalar_op!(BitAnd, bitand, Integer);
+impl_op_scalar_op!(BitOr, bitor, Integer);
+impl_op_scalar_op!(BitXor, bitxor, Integer);
+impl_op_scalar_op!(Shl, shl, Integer);
+impl_op_scalar_op!(Shr, shr, Integer);
 
 impl<Op: super::ops::Op> Neg for ArrayOp<Op> {
     type Output = ArrayOp<ArrayUnary<Op::Out, <Op::Out as CDatatype>::Neg, Self>>;
@@ -1542,7 +2119,11 @@ impl<A: NDArray + fmt::Debug> ArraySlice<A> {
     }
 
     #[cfg(feature = "opencl")]
-    fn read_cl(&self, source: &ocl::Buffer<A::DType>) -> Result<ocl::Buffer<A::DType>, Error> {
+    fn read_cl(
+        &self,
+        queue: &Queue,
+        source: &ocl::Buffer<A::DType>,
+    ) -> Result<ocl::Buffer<A::DType>, Error> {
         let cl_queue = source.default_queue().expect("queue").clone();
 
         let output = ocl::Buffer::builder()
@@ -1559,7 +2140,7 @@ impl<A: NDArray + fmt::Debug> ArraySlice<A> {
             .arg(&output)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { queue.enqueue_kernel(&kernel)? }
 
         Ok(output)
     }
@@ -1631,7 +2212,11 @@ impl<A: NDArrayRead> NDArrayRead for ArraySlice<A> {
                 self.read_vec(source.as_ref()).map(BufferConverter::from)
             }
             #[cfg(feature = "opencl")]
-            BufferConverter::CL(source) => self.read_cl(source.as_ref()).map(BufferConverter::from),
+            BufferConverter::CL(source) => {
+                queue.wait_for(&source_queue);
+                self.read_cl(queue, source.as_ref())
+                    .map(BufferConverter::from)
+            }
         }
     }
 
@@ -1782,7 +2367,10 @@ impl<A: NDArrayRead + fmt::Debug> NDArrayTransform for ArraySlice<A> {
 
 macro_rules! impl_slice_dual_op {
     ($op:ident, $name:ident, $o:ty) => {
-        impl<T: CDatatype, A: NDArray<DType = T>, O> $op<$o> for ArraySlice<A>
+        impl_slice_dual_op!($op, $name, $o, CDatatype);
+    };
+    ($op:ident, $name:ident, $o:ty, $t:ident) => {
+        impl<T: $t, A: NDArray<DType = T>, O> $op<$o> for ArraySlice<A>
         where
             $o: NDArray<DType = T>,
         {
@@ -1804,28 +2392,51 @@ impl_slice_dual_op!(Div, div, ArrayBase<O>);
 impl_slice_dual_op!(Mul, mul, ArrayBase<O>);
 impl_slice_dual_op!(Rem, rem, ArrayBase<O>);
 impl_slice_dual_op!(Sub, sub, ArrayBase<O>);
+impl_slice_dual_op!(BitAnd, bitand, ArrayBase<O>, Integer);
+impl_slice_dual_op!(BitOr, bitor, ArrayBase<O>, Integer);
+impl_slice_dual_op!(BitXor, bitxor, ArrayBase<O>, Integer);
+impl_slice_dual_op!(Shl, shl, ArrayBase<O>, Integer);
+impl_slice_dual_op!(Shr, shr, ArrayBase<O>, Integer);
 
 impl_slice_dual_op!(Add, add, ArrayOp<O>);
 impl_slice_dual_op!(Div, div, ArrayOp<O>);
 impl_slice_dual_op!(Mul, mul, ArrayOp<O>);
 impl_slice_dual_op!(Rem, rem, ArrayOp<O>);
 impl_slice_dual_op!(Sub, sub, ArrayOp<O>);
+impl_slice_dual_op!(BitAnd, bitand, ArrayOp<O>, Integer);
+impl_slice_dual_op!(BitOr, bitor, ArrayOp<O>, Integer);
+impl_slice_dual_op!(BitXor, bitxor, ArrayOp<O>, Integer);
+impl_slice_dual_op!(Shl, shl, ArrayOp<O>, Integer);
+impl_slice_dual_op!(Shr, shr, ArrayOp<O>, Integer);
 
 impl_slice_dual_op!(Add, add, ArraySlice<O>);
 impl_slice_dual_op!(Div, div, ArraySlice<O>);
 impl_slice_dual_op!(Mul, mul, ArraySlice<O>);
 impl_slice_dual_op!(Rem, rem, ArraySlice<O>);
 impl_slice_dual_op!(Sub, sub, ArraySlice<O>);
+impl_slice_dual_op!(BitAnd, bitand, ArraySlice<O>, Integer);
+impl_slice_dual_op!(BitOr, bitor, ArraySlice<O>, Integer);
+impl_slice_dual_op!(BitXor, bitxor, ArraySlice<O>, Integer);
+impl_slice_dual_op!(Shl, shl, ArraySlice<O>, Integer);
+impl_slice_dual_op!(Shr, shr, ArraySlice<O>, Integer);
 
 impl_slice_dual_op!(Add, add, ArrayView<O>);
 impl_slice_dual_op!(Div, div, ArrayView<O>);
 impl_slice_dual_op!(Mul, mul, ArrayView<O>);
 impl_slice_dual_op!(Rem, rem, ArrayView<O>);
 impl_slice_dual_op!(Sub, sub, ArrayView<O>);
+impl_slice_dual_op!(BitAnd, bitand, ArrayView<O>, Integer);
+impl_slice_dual_op!(BitOr, bitor, ArrayView<O>, Integer);
+impl_slice_dual_op!(BitXor, bitxor, ArrayView<O>, Integer);
+impl_slice_dual_op!(Shl, shl, ArrayView<O>, Integer);
+impl_slice_dual_op!(Shr, shr, ArrayView<O>, Integer);
 
 macro_rules! impl_slice_scalar_op {
     ($op:ident, $name:ident) => {
-        impl<T: CDatatype, A: NDArray<DType = T>> $op<T> for ArraySlice<A> {
+        impl_slice_scalar_op!($op, $name, CDatatype);
+    };
+    ($op:ident, $name:ident, $t:ident) => {
+        impl<T: $t, A: NDArray<DType = T>> $op<T> for ArraySlice<A> {
             type Output = ArrayOp<ArrayScalar<T, Self>>;
 
             fn $name(self, rhs: T) -> Self::Output {
@@ -1842,6 +2453,11 @@ impl_slice_scalar_op!(Div, div);
 impl_slice_scalar_op!(Mul, mul);
 impl_slice_scalar_op!(Rem, rem);
 impl_slice_scalar_op!(Sub, sub);
+impl_slice_scalar_op!(BitAnd, bitand, Integer);
+impl_slice_scalar_op!(BitOr, bitor, Integer);
+impl_slice_scalar_op!(BitXor, bitxor, Integer);
+impl_slice_scalar_op!(Shl, shl, Integer);
+impl_slice_scalar_op!(Shr, shr, Integer);
 
 impl<T: CDatatype, A: NDArrayRead<DType = T>> Neg for ArraySlice<A> {
     type Output = ArrayOp<ArrayUnary<T, T::Neg, Self>>;
@@ -1919,6 +2535,24 @@ impl<A: NDArray> ArrayView<A> {
         })
     }
 
+    #[cfg(feature = "blas")]
+    // return `true` if this view only swaps the last two axes of its (contiguous) source
+    fn is_transposed_matrices(&self) -> bool {
+        let ndim = self.ndim();
+
+        if ndim < 2 || self.source.size() != self.size() {
+            return false;
+        }
+
+        let mut shape = self.shape.to_vec();
+        shape.swap(ndim - 2, ndim - 1);
+
+        let mut strides = strides_for(&shape, ndim);
+        strides.swap(ndim - 2, ndim - 1);
+
+        strides == self.strides
+    }
+
     fn broadcast(source: A, shape: Shape) -> Result<Self, Error> {
         if shape.len() < source.ndim() {
             return Err(Error::Bounds(format!(
@@ -1979,7 +2613,11 @@ impl<A: NDArray> ArrayView<A> {
     }
 
     #[cfg(feature = "opencl")]
-    fn read_cl(&self, source: &ocl::Buffer<A::DType>) -> Result<ocl::Buffer<A::DType>, Error> {
+    fn read_cl(
+        &self,
+        queue: &Queue,
+        source: &ocl::Buffer<A::DType>,
+    ) -> Result<ocl::Buffer<A::DType>, Error> {
         let cl_queue = source.default_queue().expect("queue").clone();
 
         let output = ocl::Buffer::builder()
@@ -1996,7 +2634,7 @@ impl<A: NDArray> ArrayView<A> {
             .arg(&output)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { queue.enqueue_kernel(&kernel)? }
 
         Ok(output)
     }
@@ -2021,7 +2659,9 @@ impl<A: NDArrayRead> NDArrayRead for ArrayView<A> {
                 self.read_vec(source.as_ref()).map(BufferConverter::from)
             }
             #[cfg(feature = "opencl")]
-            BufferConverter::CL(source) => self.read_cl(source.as_ref()).map(BufferConverter::from),
+            BufferConverter::CL(source) => self
+                .read_cl(queue, source.as_ref())
+                .map(BufferConverter::from),
         }
     }
 
@@ -2046,11 +2686,26 @@ impl<A: NDArrayRead> NDArrayRead for ArrayView<A> {
 
         self.source.read_value(&source_coord)
     }
+
+    #[cfg(feature = "blas")]
+    fn to_host_matrices(
+        &self,
+        queue: &Queue,
+    ) -> Result<(SliceConverter<Self::DType>, bool), Error> {
+        if self.is_transposed_matrices() {
+            self.source.to_host(queue).map(|matrices| (matrices, true))
+        } else {
+            self.to_host(queue).map(|matrices| (matrices, false))
+        }
+    }
 }
 
 macro_rules! impl_view_dual_op {
     ($op:ident, $name:ident, $o:ty) => {
-        impl<T: CDatatype, A: NDArray<DType = T>, O> $op<$o> for ArrayView<A>
+        impl_view_dual_op!($op, $name, $o, CDatatype);
+    };
+    ($op:ident, $name:ident, $o:ty, $t:ident) => {
+        impl<T: $t, A: NDArray<DType = T>, O> $op<$o> for ArrayView<A>
         where
             $o: NDArray<DType = T>,
         {
@@ -2072,28 +2727,51 @@ impl_view_dual_op!(Div, div, ArrayBase<O>);
 impl_view_dual_op!(Mul, mul, ArrayBase<O>);
 impl_view_dual_op!(Rem, rem, ArrayBase<O>);
 impl_view_dual_op!(Sub, sub, ArrayBase<O>);
+impl_view_dual_op!(BitAnd, bitand, ArrayBase<O>, Integer);
+impl_view_dual_op!(BitOr, bitor, ArrayBase<O>, Integer);
+impl_view_dual_op!(BitXor, bitxor, ArrayBase<O>, Integer);
+impl_view_dual_op!(Shl, shl, ArrayBase<O>, Integer);
+impl_view_dual_op!(Shr, shr, ArrayBase<O>, Integer);
 
 impl_view_dual_op!(Add, add, ArrayOp<O>);
 impl_view_dual_op!(Div, div, ArrayOp<O>);
 impl_view_dual_op!(Mul, mul, ArrayOp<O>);
 impl_view_dual_op!(Rem, rem, ArrayOp<O>);
 impl_view_dual_op!(Sub, sub, ArrayOp<O>);
+impl_view_dual_op!(BitAnd, bitand, ArrayOp<O>, Integer);
+impl_view_dual_op!(BitOr, bitor, ArrayOp<O>, Integer);
+impl_view_dual_op!(BitXor, bitxor, ArrayOp<O>, Integer);
+impl_view_dual_op!(Shl, shl, ArrayOp<O>, Integer);
+impl_view_dual_op!(Shr, shr, ArrayOp<O>, Integer);
 
 impl_view_dual_op!(Add, add, ArraySlice<O>);
 impl_view_dual_op!(Div, div, ArraySlice<O>);
 impl_view_dual_op!(Mul, mul, ArraySlice<O>);
 impl_view_dual_op!(Rem, rem, ArraySlice<O>);
 impl_view_dual_op!(Sub, sub, ArraySlice<O>);
+impl_view_dual_op!(BitAnd, bitand, ArraySlice<O>, Integer);
+impl_view_dual_op!(BitOr, bitor, ArraySlice<O>, Integer);
+impl_view_dual_op!(BitXor, bitxor, ArraySlice<O>, Integer);
+impl_view_dual_op!(Shl, shl, ArraySlice<O>, Integer);
+impl_view_dual_op!(Shr, shr, ArraySlice<O>, Integer);
 
 impl_view_dual_op!(Add, add, ArrayView<O>);
 impl_view_dual_op!(Div, div, ArrayView<O>);
 impl_view_dual_op!(Mul, mul, ArrayView<O>);
 impl_view_dual_op!(Rem, rem, ArrayView<O>);
 impl_view_dual_op!(Sub, sub, ArrayView<O>);
+impl_view_dual_op!(BitAnd, bitand, ArrayView<O>, Integer);
+impl_view_dual_op!(BitOr, bitor, ArrayView<O>, Integer);
+impl_view_dual_op!(BitXor, bitxor, ArrayView<O>, Integer);
+impl_view_dual_op!(Shl, shl, ArrayView<O>, Integer);
+impl_view_dual_op!(Shr, shr, ArrayView<O>, Integer);
 
 macro_rules! impl_view_scalar_op {
     ($op:ident, $name:ident) => {
-        impl<T: CDatatype, A: NDArray<DType = T>> $op<T> for ArrayView<A> {
+        impl_view_scalar_op!($op, $name, CDatatype);
+    };
+    ($op:ident, $name:ident, $t:ident) => {
+        impl<T: $t, A: NDArray<DType = T>> $op<T> for ArrayView<A> {
             type Output = ArrayOp<ArrayScalar<T, Self>>;
 
             fn $name(self, rhs: T) -> Self::Output {
@@ -2110,6 +2788,11 @@ impl_view_scalar_op!(Div, div);
 impl_view_scalar_op!(Mul, mul);
 impl_view_scalar_op!(Rem, rem);
 impl_view_scalar_op!(Sub, sub);
+impl_view_scalar_op!(BitAnd, bitand, Integer);
+impl_view_scalar_op!(BitOr, bitor, Integer);
+impl_view_scalar_op!(BitXor, bitxor, Integer);
+impl_view_scalar_op!(Shl, shl, Integer);
+impl_view_scalar_op!(Shr, shr, Integer);
 
 impl<A: NDArrayRead> Neg for ArrayView<A> {
     type Output = ArrayOp<ArrayUnary<A::DType, <A::DType as CDatatype>::Neg, Self>>;
@@ -0,0 +1,142 @@
+//! Matrix multiplication using the CBLAS interface of the system BLAS library
+
+use std::any::TypeId;
+use std::os::raw::c_int;
+
+use super::gemm::{cast, cast_mut};
+use super::{CDatatype, Error};
+
+const ROW_MAJOR: c_int = 101;
+const NO_TRANS: c_int = 111;
+const TRANS: c_int = 112;
+
+extern "C" {
+    fn cblas_sgemm(
+        layout: c_int,
+        trans_a: c_int,
+        trans_b: c_int,
+        m: c_int,
+        n: c_int,
+        k: c_int,
+        alpha: f32,
+        a: *const f32,
+        lda: c_int,
+        b: *const f32,
+        ldb: c_int,
+        beta: f32,
+        c: *mut f32,
+        ldc: c_int,
+    );
+
+    fn cblas_dgemm(
+        layout: c_int,
+        trans_a: c_int,
+        trans_b: c_int,
+        m: c_int,
+        n: c_int,
+        k: c_int,
+        alpha: f64,
+        a: *const f64,
+        lda: c_int,
+        b: *const f64,
+        ldb: c_int,
+        beta: f64,
+        c: *mut f64,
+        ldc: c_int,
+    );
+}
+
+/// Return `true` if BLAS supports matrix multiplication of the data type `T`.
+pub(crate) fn supports<T: 'static>() -> bool {
+    let dtype = TypeId::of::<T>();
+    dtype == TypeId::of::<f32>() || dtype == TypeId::of::<f64>()
+}
+
+/// Compute the product of the `a x b` matrix `left` and the `b x c` matrix `right`,
+/// writing the `a x c` result to `output` in row-major order.
+///
+/// If `left_t` or `right_t` is `true`, the corresponding matrix is stored in column-major order.
+pub(crate) fn gemm<T: CDatatype>(
+    left: &[T],
+    left_t: bool,
+    right: &[T],
+    right_t: bool,
+    output: &mut [T],
+    dims: [usize; 3],
+) -> Result<(), Error> {
+    let [a, b, c] = dims;
+    assert_eq!(left.len(), a * b);
+    assert_eq!(right.len(), b * c);
+    assert_eq!(output.len(), a * c);
+
+    if a == 0 || b == 0 || c == 0 {
+        output.iter_mut().for_each(|o| *o = T::zero());
+        return Ok(());
+    }
+
+    let dim = |dim: usize| {
+        c_int::try_from(dim).map_err(|_| {
+            Error::Bounds(format!(
+                "matrix dimension {dim} is too large for BLAS matrix multiplication"
+            ))
+        })
+    };
+
+    let (m, k, n) = (dim(a)?, dim(b)?, dim(c)?);
+    let (trans_a, lda) = if left_t { (TRANS, m) } else { (NO_TRANS, k) };
+    let (trans_b, ldb) = if right_t { (TRANS, k) } else { (NO_TRANS, n) };
+
+    if let (Some(left), Some(right), Some(output)) = (
+        cast::<T, f32>(left),
+        cast::<T, f32>(right),
+        cast_mut(output),
+    ) {
+        // SAFETY: the bounds of each matrix are checked above
+        unsafe {
+            cblas_sgemm(
+                ROW_MAJOR,
+                trans_a,
+                trans_b,
+                m,
+                n,
+                k,
+                1.,
+                left.as_ptr(),
+                lda,
+                right.as_ptr(),
+                ldb,
+                0.,
+                output.as_mut_ptr(),
+                n,
+            )
+        }
+    } else if let (Some(left), Some(right), Some(output)) = (
+        cast::<T, f64>(left),
+        cast::<T, f64>(right),
+        cast_mut(output),
+    ) {
+        // SAFETY: the bounds of each matrix are checked above
+        unsafe {
+            cblas_dgemm(
+                ROW_MAJOR,
+                trans_a,
+                trans_b,
+                m,
+                n,
+                k,
+                1.,
+                left.as_ptr(),
+                lda,
+                right.as_ptr(),
+                ldb,
+                0.,
+                output.as_mut_ptr(),
+                n,
+            )
+        }
+    } else {
+        unreachable!("BLAS matrix multiplication of {}", T::TYPE_STR)
+    }
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 //! The underlying [`Buffer`] types used to store array elements
 
-use std::ops::{Add, Mul};
+use std::ops::Mul;
 use std::sync::{Arc, RwLock};
 use std::{fmt, iter};
 
@@ -13,6 +13,7 @@ use rayon::prelude::*;
 
 #[cfg(feature = "opencl")]
 use super::cl_programs;
+use super::pool::Pooled;
 use super::{CDatatype, Error, Queue};
 
 /// An array buffer
@@ -24,6 +25,10 @@ impl<B: BufferInstance + ?Sized> BufferInstance for Box<B> {
     type DType = B::DType;
 }
 
+impl<T: CDatatype> BufferInstance for Arc<dyn BufferRead<DType = T>> {
+    type DType = T;
+}
+
 /// Buffer read methods
 pub trait BufferRead: BufferInstance {
     /// Access the elements of this buffer as a [`BufferConverter`].
@@ -265,10 +270,14 @@ pub trait BufferReduce {
     fn any(&self, queue: &Queue) -> Result<bool, Error>;
 
     /// Return the maximum element in this buffer.
-    fn max(&self, queue: &Queue) -> Result<Self::DType, Error>;
+    fn max(&self, queue: &Queue) -> Result<Self::DType, Error>
+    where
+        Self::DType: PartialOrd;
 
     /// Return the minimum element in this buffer.
-    fn min(&self, queue: &Queue) -> Result<Self::DType, Error>;
+    fn min(&self, queue: &Queue) -> Result<Self::DType, Error>
+    where
+        Self::DType: PartialOrd;
 
     /// Return the product of all elements in this buffer.
     fn product(&self, queue: &Queue) -> Result<Self::DType, Error>;
@@ -278,49 +287,53 @@ pub trait BufferReduce {
 }
 
 #[derive(Clone)]
-/// A buffer in host memory, either borrowed or owned
-pub enum SliceConverter<'a, T> {
+/// A buffer in host memory, either borrowed, owned, or drawn from a [`BufferPool`]
+///
+/// [`BufferPool`]: crate::BufferPool
+pub enum SliceConverter<'a, T: CDatatype> {
     Vec(Vec<T>),
+    Pooled(Pooled<Vec<T>>),
     Slice(&'a [T]),
 }
 
-impl<'a, T> SliceConverter<'a, T> {
+impl<'a, T: CDatatype> SliceConverter<'a, T> {
     /// Return the number of elements in this buffer.
     pub fn len(&self) -> usize {
         match self {
             Self::Vec(vec) => vec.len(),
+            Self::Pooled(vec) => vec.len(),
             Self::Slice(slice) => slice.len(),
         }
     }
-}
 
-impl<'a, T: Clone> SliceConverter<'a, T> {
     /// Return this buffer as an owned [`Vec`].
     /// This will allocate a new [`Vec`] only if this buffer is a borrowed slice.
     pub fn into_vec(self) -> Vec<T> {
         match self {
             Self::Vec(vec) => vec,
+            Self::Pooled(vec) => vec.into_inner(),
             Self::Slice(slice) => slice.to_vec(),
         }
     }
 }
 
-impl<'a, T> GetSize for SliceConverter<'a, T> {
+impl<'a, T: CDatatype> GetSize for SliceConverter<'a, T> {
     fn get_size(&self) -> usize {
         self.len() * std::mem::size_of::<T>()
     }
 }
 
-impl<'a, T> From<&'a Vec<T>> for SliceConverter<'a, T> {
+impl<'a, T: CDatatype> From<&'a Vec<T>> for SliceConverter<'a, T> {
     fn from(slice: &'a Vec<T>) -> Self {
         Self::Slice(slice)
     }
 }
 
-impl<'a, T> AsRef<[T]> for SliceConverter<'a, T> {
+impl<'a, T: CDatatype> AsRef<[T]> for SliceConverter<'a, T> {
     fn as_ref(&self) -> &[T] {
         match self {
             Self::Vec(data) => data.as_slice(),
+            Self::Pooled(data) => data.as_slice(),
             Self::Slice(slice) => slice,
         }
     }
@@ -328,9 +341,12 @@ impl<'a, T> AsRef<[T]> for SliceConverter<'a, T> {
 
 #[cfg(feature = "opencl")]
 #[derive(Clone)]
-/// A buffer in OpenCL memory
+/// A buffer in OpenCL memory, either borrowed, owned, or drawn from a [`BufferPool`]
+///
+/// [`BufferPool`]: crate::BufferPool
 pub enum CLConverter<'a, T: CDatatype> {
     Owned(ocl::Buffer<T>),
+    Pooled(Pooled<ocl::Buffer<T>>),
     Borrowed(&'a ocl::Buffer<T>),
 }
 
@@ -341,6 +357,7 @@ impl<'a, T: CDatatype> CLConverter<'a, T> {
     pub fn into_buffer(self) -> Result<ocl::Buffer<T>, Error> {
         match self {
             Self::Owned(buffer) => Ok(buffer),
+            Self::Pooled(buffer) => Ok(buffer.into_inner()),
             Self::Borrowed(buffer) => {
                 let cl_queue = buffer.default_queue().expect("OpenCL queue");
                 let mut copy = ocl::Buffer::builder()
@@ -359,6 +376,7 @@ impl<'a, T: CDatatype> CLConverter<'a, T> {
     pub fn len(&self) -> usize {
         match self {
             Self::Owned(buffer) => buffer.len(),
+            Self::Pooled(buffer) => buffer.len(),
             Self::Borrowed(buffer) => buffer.len(),
         }
     }
@@ -369,6 +387,7 @@ impl<'a, T: CDatatype> AsRef<ocl::Buffer<T>> for CLConverter<'a, T> {
     fn as_ref(&self) -> &ocl::Buffer<T> {
         match self {
             Self::Owned(buffer) => &buffer,
+            Self::Pooled(buffer) => buffer,
             Self::Borrowed(buffer) => buffer,
         }
     }
@@ -419,14 +438,15 @@ impl<'a, T: CDatatype> BufferConverter<'a, T> {
                 let cl_queue = if let Some(cl_queue) = &queue.cl_queue {
                     cl_queue.clone()
                 } else {
-                    let cl_context = queue.context().cl_context();
-                    let size_hint = Ord::max(buffer.len(), queue.context.gpu_min);
-                    let device = queue
-                        .context()
-                        .select_device(size_hint)
+                    // the data has to be copied to some device, even if the policy
+                    // of this context would compute an operation this size on the host
+                    let context = queue.context();
+                    let device = context
+                        .select_device(buffer.len())
+                        .or_else(|| context.platform().devices().first())
                         .expect("OpenCL device");
 
-                    ocl::Queue::new(cl_context, device, None)?
+                    ocl::Queue::new(context.cl_context(), device.cl_device(), None)?
                 };
 
                 let buffer = ocl::Buffer::builder()
@@ -476,11 +496,13 @@ macro_rules! buffer_reduce {
         match $this {
             Self::Host(buffer) => match buffer {
                 SliceConverter::Vec($var) => $call,
+                SliceConverter::Pooled($var) => $call,
                 SliceConverter::Slice($var) => $call,
             },
             #[cfg(feature = "opencl")]
             Self::CL(buffer) => match buffer {
                 CLConverter::Owned($var) => $call,
+                CLConverter::Pooled($var) => $call,
                 CLConverter::Borrowed($var) => $call,
             },
         }
@@ -498,11 +520,17 @@ impl<'a, T: CDatatype> BufferReduce for BufferConverter<'a, T> {
         buffer_reduce!(self, this, this.any(queue))
     }
 
-    fn max(&self, queue: &Queue) -> Result<Self::DType, Error> {
+    fn max(&self, queue: &Queue) -> Result<Self::DType, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         buffer_reduce!(self, this, this.max(queue))
     }
 
-    fn min(&self, queue: &Queue) -> Result<Self::DType, Error> {
+    fn min(&self, queue: &Queue) -> Result<Self::DType, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         buffer_reduce!(self, this, this.min(queue))
     }
 
@@ -586,7 +614,7 @@ impl<'a, T> From<&'a mut Vec<T>> for SliceConverterMut<'a, T> {
     }
 }
 
-impl<'a, T> From<SliceConverterMut<'a, T>> for SliceConverter<'a, T> {
+impl<'a, T: CDatatype> From<SliceConverterMut<'a, T>> for SliceConverter<'a, T> {
     fn from(slice: SliceConverterMut<'a, T>) -> Self {
         match slice {
             SliceConverterMut::Vec(slice) => Self::Vec(slice),
@@ -693,11 +721,17 @@ impl<T: CDatatype> BufferReduce for Vec<T> {
         self.as_slice().any(queue)
     }
 
-    fn max(&self, queue: &Queue) -> Result<Self::DType, Error> {
+    fn max(&self, queue: &Queue) -> Result<Self::DType, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         self.as_slice().max(queue)
     }
 
-    fn min(&self, queue: &Queue) -> Result<Self::DType, Error> {
+    fn min(&self, queue: &Queue) -> Result<Self::DType, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         self.as_slice().min(queue)
     }
 
@@ -713,17 +747,28 @@ impl<T: CDatatype> BufferReduce for Vec<T> {
 impl<T: CDatatype> BufferReduce for [T] {
     type DType = T;
 
-    fn all(&self, _queue: &Queue) -> Result<bool, Error> {
+    fn all(&self, queue: &Queue) -> Result<bool, Error> {
         let zero = Self::DType::zero();
-        Ok(self.par_iter().copied().all(|n| n != zero))
+        let min_len = queue.min_len(1);
+
+        Ok(queue
+            .context()
+            .install(|| self.par_iter().with_min_len(min_len).all(|n| *n != zero)))
     }
 
-    fn any(&self, _queue: &Queue) -> Result<bool, Error> {
+    fn any(&self, queue: &Queue) -> Result<bool, Error> {
         let zero = Self::DType::zero();
-        Ok(self.par_iter().copied().any(|n| n != zero))
+        let min_len = queue.min_len(1);
+
+        Ok(queue
+            .context()
+            .install(|| self.par_iter().with_min_len(min_len).any(|n| *n != zero)))
     }
 
-    fn max(&self, _queue: &Queue) -> Result<Self::DType, Error> {
+    fn max(&self, queue: &Queue) -> Result<Self::DType, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         let collector = |l, r| {
             if r > l {
                 r
@@ -732,10 +777,20 @@ impl<T: CDatatype> BufferReduce for [T] {
             }
         };
 
-        Ok(self.par_iter().copied().reduce(T::min, collector))
+        let min_len = queue.min_len(1);
+
+        Ok(queue.context().install(|| {
+            self.par_iter()
+                .copied()
+                .with_min_len(min_len)
+                .reduce(T::min, collector)
+        }))
     }
 
-    fn min(&self, _queue: &Queue) -> Result<Self::DType, Error> {
+    fn min(&self, queue: &Queue) -> Result<Self::DType, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         let collector = |l, r| {
             if r < l {
                 r
@@ -744,21 +799,30 @@ impl<T: CDatatype> BufferReduce for [T] {
             }
         };
 
-        Ok(self.par_iter().copied().reduce(T::max, collector))
+        let min_len = queue.min_len(1);
+
+        Ok(queue.context().install(|| {
+            self.par_iter()
+                .copied()
+                .with_min_len(min_len)
+                .reduce(T::max, collector)
+        }))
     }
 
-    fn product(&self, _queue: &Queue) -> Result<Self::DType, Error> {
-        Ok(self
-            .par_chunks(8)
-            .map(|chunk| chunk.into_iter().copied().fold(T::one(), Mul::mul))
-            .reduce(T::one, Mul::mul))
+    fn product(&self, queue: &Queue) -> Result<Self::DType, Error> {
+        let min_len = queue.min_len(8);
+
+        Ok(queue.context().install(|| {
+            self.par_chunks(8)
+                .with_min_len(min_len)
+                .map(|chunk| chunk.iter().copied().fold(T::one(), Mul::mul))
+                .reduce(T::one, Mul::mul)
+        }))
     }
 
-    fn sum(&self, _queue: &Queue) -> Result<Self::DType, Error> {
-        Ok(self
-            .par_chunks(8)
-            .map(|chunk| chunk.into_iter().copied().fold(T::zero(), Add::add))
-            .reduce(T::zero, Add::add))
+    fn sum(&self, queue: &Queue) -> Result<Self::DType, Error> {
+        let min_len = queue.min_len(8);
+        Ok(queue.context().install(|| T::par_sum(self, min_len)))
     }
 }
 
@@ -775,54 +839,76 @@ impl<T: CDatatype> BufferInstance for ocl::Buffer<T> {
     type DType = T;
 }
 
+// read a device buffer of a type which OpenCL can't compute on, to reduce it on the host
+#[cfg(feature = "opencl")]
+fn read_to_host<T: CDatatype>(buffer: &ocl::Buffer<T>) -> Result<Vec<T>, Error> {
+    let mut data = vec![T::zero(); buffer.len()];
+    buffer.read(&mut data).enq()?;
+    Ok(data)
+}
+
 #[cfg(feature = "opencl")]
 impl<T: CDatatype> BufferReduce for ocl::Buffer<T> {
     type DType = T;
 
     fn all(&self, queue: &Queue) -> Result<bool, Error> {
+        if !queue.context().cl_compute::<T>() {
+            return read_to_host(self)?.all(queue);
+        }
+
         let cl_queue = queue.cl_queue(self.default_queue());
-        cl_programs::reduce_all(cl_queue, self).map_err(Error::from)
+        cl_programs::reduce_all(queue.context(), cl_queue, self).map_err(Error::from)
     }
 
     fn any(&self, queue: &Queue) -> Result<bool, Error> {
+        if !queue.context().cl_compute::<T>() {
+            return read_to_host(self)?.any(queue);
+        }
+
         let cl_queue = queue.cl_queue(self.default_queue());
-        cl_programs::reduce_any(cl_queue, self).map_err(Error::from)
+        cl_programs::reduce_any(queue.context(), cl_queue, self).map_err(Error::from)
     }
 
-    fn max(&self, queue: &Queue) -> Result<Self::DType, Error> {
-        let collector = |l, r| {
-            if r > l {
-                r
-            } else {
-                l
-            }
-        };
+    fn max(&self, queue: &Queue) -> Result<Self::DType, Error>
+    where
+        Self::DType: PartialOrd,
+    {
+        if !queue.context().cl_compute::<T>() {
+            return read_to_host(self)?.max(queue);
+        }
 
         let cl_queue = queue.cl_queue(self.default_queue());
-        cl_programs::reduce(T::min(), "max", cl_queue, self, collector).map_err(Error::from)
+        cl_programs::reduce(T::min(), "max", queue.context(), cl_queue, self)?.max(queue)
     }
 
-    fn min(&self, queue: &Queue) -> Result<Self::DType, Error> {
-        let collector = |l, r| {
-            if r < l {
-                r
-            } else {
-                l
-            }
-        };
+    fn min(&self, queue: &Queue) -> Result<Self::DType, Error>
+    where
+        Self::DType: PartialOrd,
+    {
+        if !queue.context().cl_compute::<T>() {
+            return read_to_host(self)?.min(queue);
+        }
 
         let cl_queue = queue.cl_queue(self.default_queue());
-        cl_programs::reduce(T::max(), "min", cl_queue, self, collector).map_err(Error::from)
+        cl_programs::reduce(T::max(), "min", queue.context(), cl_queue, self)?.min(queue)
     }
 
     fn product(&self, queue: &Queue) -> Result<Self::DType, Error> {
+        if !queue.context().cl_compute::<T>() {
+            return read_to_host(self)?.product(queue);
+        }
+
         let cl_queue = queue.cl_queue(self.default_queue());
-        cl_programs::reduce(T::one(), "mul", cl_queue, self, Mul::mul).map_err(Error::from)
+        cl_programs::reduce(T::one(), "mul", queue.context(), cl_queue, self)?.product(queue)
     }
 
     fn sum(&self, queue: &Queue) -> Result<Self::DType, Error> {
+        if !queue.context().cl_compute::<T>() {
+            return read_to_host(self)?.sum(queue);
+        }
+
         let cl_queue = queue.cl_queue(self.default_queue());
-        cl_programs::reduce(T::zero(), "add", cl_queue, self, Add::add).map_err(Error::from)
+        cl_programs::reduce(T::zero(), "add", queue.context(), cl_queue, self)?.sum(queue)
     }
 }
 
@@ -943,11 +1029,17 @@ impl<T: CDatatype> BufferReduce for Buffer<T> {
         buffer_dispatch!(self, this, BufferReduce::any(this, queue))
     }
 
-    fn max(&self, queue: &Queue) -> Result<Self::DType, Error> {
+    fn max(&self, queue: &Queue) -> Result<Self::DType, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         buffer_dispatch!(self, this, BufferReduce::max(this, queue))
     }
 
-    fn min(&self, queue: &Queue) -> Result<Self::DType, Error> {
+    fn min(&self, queue: &Queue) -> Result<Self::DType, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         buffer_dispatch!(self, this, BufferReduce::min(this, queue))
     }
 
@@ -1165,6 +1257,70 @@ decode_buffer!(
     encode_array_f64
 );
 
+// half-precision values are encoded as their bit patterns, since destream has no native 16-bit float
+#[cfg(all(feature = "stream", feature = "half"))]
+macro_rules! decode_half_buffer {
+    ($t:ty) => {
+        #[async_trait]
+        impl de::FromStream for Buffer<$t> {
+            type Context = ();
+
+            async fn from_stream<D: de::Decoder>(
+                cxt: (),
+                decoder: &mut D,
+            ) -> Result<Self, D::Error> {
+                let bits = match Buffer::<u16>::from_stream(cxt, decoder).await? {
+                    Buffer::Host(bits) => bits,
+                    #[cfg(feature = "opencl")]
+                    Buffer::CL(_) => unreachable!("decoded buffer in device memory"),
+                };
+
+                Ok(Buffer::Host(
+                    bits.into_iter().map(<$t>::from_bits).collect(),
+                ))
+            }
+        }
+
+        impl<'en> en::ToStream<'en> for Buffer<$t> {
+            fn to_stream<E: en::Encoder<'en>>(&'en self, encoder: E) -> Result<E::Ok, E::Error> {
+                let bits: Vec<u16> = match self {
+                    Self::Host(buffer) => buffer.iter().copied().map(<$t>::to_bits).collect(),
+                    #[cfg(feature = "opencl")]
+                    Self::CL(buffer) => read_to_host(buffer)
+                        .map_err(en::Error::custom)?
+                        .into_iter()
+                        .map(<$t>::to_bits)
+                        .collect(),
+                };
+
+                encoder.encode_array_u16(futures::stream::once(futures::future::ready(bits)))
+            }
+        }
+
+        impl<'en> en::IntoStream<'en> for Buffer<$t> {
+            fn into_stream<E: en::Encoder<'en>>(self, encoder: E) -> Result<E::Ok, E::Error> {
+                let bits: Vec<u16> = match self {
+                    Self::Host(buffer) => buffer.into_iter().map(<$t>::to_bits).collect(),
+                    #[cfg(feature = "opencl")]
+                    Self::CL(buffer) => read_to_host(&buffer)
+                        .map_err(en::Error::custom)?
+                        .into_iter()
+                        .map(<$t>::to_bits)
+                        .collect(),
+                };
+
+                encoder.encode_array_u16(futures::stream::once(futures::future::ready(bits)))
+            }
+        }
+    };
+}
+
+#[cfg(all(feature = "stream", feature = "half"))]
+decode_half_buffer!(crate::F16);
+
+#[cfg(all(feature = "stream", feature = "half"))]
+decode_half_buffer!(crate::BF16);
+
 impl<T: CDatatype + fmt::Debug> fmt::Debug for Buffer<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
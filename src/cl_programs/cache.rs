@@ -0,0 +1,235 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use ocl::enums::{DeviceInfo, ProgramInfo, ProgramInfoResult};
+use ocl::{Device, Error, Program};
+
+/// A cache of the programs compiled for an OpenCL context, keyed by their generated source
+///
+/// An OpenCL program is compiled for every device in its context, so the devices of a cached
+/// program are those of the context which owns the cache. The binaries of a compiled program
+/// are specific to each device, so if a directory is configured, the binary for each device
+/// is persisted in a file keyed by both the program source and the device.
+///
+/// Many programs are specialized to the shape of their input, so at most `capacity` programs
+/// are kept in memory and the least recently used program is evicted to make room for a new one.
+/// An evicted program is loaded again from its persisted binary, if any, or else recompiled.
+#[derive(Clone)]
+pub struct ProgramCache {
+    programs: Arc<RwLock<HashMap<String, CachedProgram>>>,
+    clock: Arc<AtomicU64>,
+    capacity: usize,
+    dir: Option<PathBuf>,
+}
+
+struct CachedProgram {
+    program: Program,
+    last_used: AtomicU64,
+}
+
+impl Default for ProgramCache {
+    fn default() -> Self {
+        Self {
+            programs: Arc::new(RwLock::new(HashMap::new())),
+            clock: Arc::new(AtomicU64::new(0)),
+            capacity: Self::DEFAULT_CAPACITY,
+            dir: None,
+        }
+    }
+}
+
+impl ProgramCache {
+    /// The default maximum number of programs to keep in memory
+    pub const DEFAULT_CAPACITY: usize = 256;
+
+    /// Keep at most `capacity` programs in memory.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+    }
+
+    /// Persist compiled program binaries in the given directory,
+    /// and load previously persisted binaries from it.
+    pub fn set_dir(&mut self, dir: PathBuf) {
+        self.dir = Some(dir);
+    }
+
+    /// Return the program with the given source, compiling it if it's not already cached.
+    pub fn get_or_build(&self, context: &ocl::Context, src: String) -> Result<Program, Error> {
+        if let Some(cached) = self.programs.read().expect("program cache").get(&src) {
+            cached.last_used.store(self.tick(), Ordering::Relaxed);
+            return Ok(cached.program.clone());
+        }
+
+        let program = if let Some(program) = self.load(context, &src) {
+            program
+        } else {
+            let program = Program::builder().source(src.clone()).build(context)?;
+            self.store(context, &src, &program);
+            program
+        };
+
+        // if another thread compiled the same program in the meantime, keep its copy
+        let mut programs = self.programs.write().expect("program cache");
+
+        if !programs.contains_key(&src) && programs.len() >= self.capacity {
+            let lru = programs
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used.load(Ordering::Relaxed))
+                .map(|(src, _)| src.clone());
+
+            if let Some(lru) = lru {
+                programs.remove(&lru);
+            }
+        }
+
+        let cached = programs.entry(src).or_insert_with(|| CachedProgram {
+            program,
+            last_used: AtomicU64::new(0),
+        });
+
+        cached.last_used.store(self.tick(), Ordering::Relaxed);
+        Ok(cached.program.clone())
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed)
+    }
+
+    // load the persisted binary of the program with the given source for each device,
+    // or return `None` if any binary is missing or fails to load
+    fn load(&self, context: &ocl::Context, src: &str) -> Option<Program> {
+        let dir = self.dir.as_ref()?;
+        let devices = context.devices();
+
+        let binaries = devices
+            .iter()
+            .map(|device| {
+                let contents = fs::read(binary_path(dir, src, device)?).ok()?;
+                let (source, binary) = decode(&contents)?;
+
+                if source == src.as_bytes() {
+                    Some(binary.to_vec())
+                } else {
+                    None
+                }
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let binaries = binaries.iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+        Program::builder()
+            .devices(&devices[..])
+            .binaries(&binaries)
+            .build(context)
+            .ok()
+    }
+
+    // persist the binary of the given program for each device, ignoring any failure
+    // since a missing binary is just compiled again from source
+    fn store(&self, context: &ocl::Context, src: &str, program: &Program) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+
+        let Ok(ProgramInfoResult::Binaries(binaries)) = program.info(ProgramInfo::Binaries) else {
+            return;
+        };
+
+        if fs::create_dir_all(dir).is_err() {
+            return;
+        }
+
+        for (device, binary) in context.devices().iter().zip(binaries) {
+            if let Some(path) = binary_path(dir, src, device) {
+                // write to a temporary file first so that a concurrent reader never sees
+                // a partially-written binary
+                let tmp = path.with_extension("tmp");
+                if fs::write(&tmp, encode(src, &binary)).is_ok() {
+                    let _ = fs::rename(&tmp, &path);
+                }
+            }
+        }
+    }
+}
+
+// the path of the persisted binary of the program with the given source for the given device
+fn binary_path(dir: &Path, src: &str, device: &Device) -> Option<PathBuf> {
+    let name = device.name().ok()?;
+    let driver = device.info(DeviceInfo::DriverVersion).ok()?.to_string();
+
+    let mut hasher = DefaultHasher::new();
+    (src, name, driver).hash(&mut hasher);
+    Some(dir.join(format!("{:016x}.bin", hasher.finish())))
+}
+
+// the length of the source, the source itself, and then the binary,
+// so that a hash collision is detected when the binary is loaded
+fn encode(src: &str, binary: &[u8]) -> Vec<u8> {
+    let mut contents = Vec::with_capacity(8 + src.len() + binary.len());
+    contents.extend((src.len() as u64).to_le_bytes());
+    contents.extend(src.as_bytes());
+    contents.extend(binary);
+    contents
+}
+
+fn decode(contents: &[u8]) -> Option<(&[u8], &[u8])> {
+    let len = u64::from_le_bytes(contents.get(..8)?.try_into().ok()?);
+    let len = usize::try_from(len).ok()?;
+    let contents = &contents[8..];
+
+    if contents.len() >= len {
+        Some(contents.split_at(len))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        let src = "__kernel void noop() {}";
+        let binary = [0u8, 1, 2, 255];
+
+        let contents = encode(src, &binary);
+        let (source, decoded) = decode(&contents).expect("decode");
+        assert_eq!(source, src.as_bytes());
+        assert_eq!(decoded, &binary[..]);
+
+        assert!(decode(&contents[..4]).is_none());
+        assert!(decode(&contents[..8 + src.len() - 1]).is_none());
+
+        let contents = encode("", &[]);
+        let (source, decoded) = decode(&contents).expect("decode");
+        assert!(source.is_empty());
+        assert!(decoded.is_empty());
+    }
+
+    #[test]
+    fn test_binary_path() -> Result<(), Error> {
+        let Ok(platform) = ocl::Platform::first() else {
+            return Ok(());
+        };
+
+        let devices = Device::list_all(platform)?;
+        let Some(device) = devices.first() else {
+            return Ok(());
+        };
+
+        let dir = Path::new("programs");
+        let path = binary_path(dir, "a", device).expect("path");
+        assert_eq!(path.parent(), Some(dir));
+        assert_eq!(path.extension().and_then(|ext| ext.to_str()), Some("bin"));
+        assert_eq!(binary_path(dir, "a", device), Some(path.clone()));
+        assert_ne!(binary_path(dir, "b", device), Some(path));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,245 @@
+use ocl::{Error, Program};
+
+use crate::{CDatatype, Context};
+
+/// Return `true` if `T` is stored as a two-component OpenCL vector of (real, imaginary) parts.
+pub(super) fn is_complex<T: CDatatype>() -> bool {
+    matches!(T::TYPE_STR, "float2" | "double2")
+}
+
+/// Complex multiplication, for use in programs which would otherwise multiply componentwise.
+pub(super) fn complex_mul(ctype: &str) -> String {
+    format!(
+        r#"
+        inline {ctype} mul(const {ctype} left, const {ctype} right) {{
+            return ({ctype})(
+                left.x * right.x - left.y * right.y,
+                left.x * right.y + left.y * right.x);
+        }}
+        "#
+    )
+}
+
+// the arithmetic ops on complex numbers of type `ctype` whose parts are of type `rtype`
+// (ops only defined for real numbers, like `pow` and `atan2`, are not defined for complex arrays)
+fn arithmetic(ctype: &str, rtype: &str) -> String {
+    format!(
+        r#"
+        inline {ctype} add(const {ctype} left, const {ctype} right) {{
+            return left + right;
+        }}
+
+        inline {ctype} div(const {ctype} left, const {ctype} right) {{
+            const {rtype} denom = right.x * right.x + right.y * right.y;
+
+            return ({ctype})(
+                (left.x * right.x + left.y * right.y) / denom,
+                (left.y * right.x - left.x * right.y) / denom);
+        }}
+
+        {mul}
+
+        // like `num_complex`, the remainder after rounding each part of the quotient toward zero
+        inline {ctype} rem(const {ctype} left, const {ctype} right) {{
+            return left - mul(right, trunc(div(left, right)));
+        }}
+
+        inline {ctype} sub(const {ctype} left, const {ctype} right) {{
+            return left - right;
+        }}
+        "#,
+        mul = complex_mul(ctype),
+    )
+}
+
+/// Compare the complex numbers `left` and `right`, which are equal if all their parts are equal.
+pub(super) fn compare_complex(left: &str, cmp: &str, right: &str) -> String {
+    match cmp {
+        "==" => format!("all({left} == {right})"),
+        "!=" => format!("any({left} != {right})"),
+        other => unreachable!("complex numbers are not ordered ({other})"),
+    }
+}
+
+/// Cast `x` of type `IT` to `OT` where either is complex.
+/// Like a cast on the host, this goes through the real part of `x`.
+pub(super) fn cast_complex<IT: CDatatype, OT: CDatatype>(x: &str) -> String {
+    let otype = OT::TYPE_STR;
+    let real = if is_complex::<IT>() {
+        format!("{x}.x")
+    } else {
+        x.to_string()
+    };
+
+    if is_complex::<OT>() {
+        format!(
+            "({otype})(({rtype}) {real}, 0)",
+            rtype = OT::Float::TYPE_STR
+        )
+    } else {
+        format!("({otype}) {real}")
+    }
+}
+
+/// In-place reduce ops on complex numbers of type `ctype`.
+pub(super) fn complex_reduce(ctype: &str) -> String {
+    format!(
+        r#"
+        inline void add({ctype}* left, const {ctype} right) {{
+            *left += right;
+        }}
+
+        inline void mul({ctype}* left, const {ctype} right) {{
+            const {ctype} l = *left;
+            *left = ({ctype})(l.x * right.x - l.y * right.y, l.x * right.y + l.y * right.x);
+        }}
+        "#
+    )
+}
+
+// the expression to convert an input `x` of type `IT` to the complex type `T`
+fn to_complex<IT: CDatatype, T: CDatatype>() -> String {
+    let ctype = T::TYPE_STR;
+    let rtype = T::Float::TYPE_STR;
+
+    if is_complex::<IT>() {
+        format!("({ctype})(({rtype}) x.x, ({rtype}) x.y)")
+    } else {
+        format!("({ctype})(({rtype}) x, 0)")
+    }
+}
+
+/// A dual op on complex numbers of type `T`, whose inputs may be of any type promotable to `T`.
+pub fn elementwise_dual_complex<LT, RT, T>(
+    op: &'static str,
+    context: &Context,
+) -> Result<Program, Error>
+where
+    LT: CDatatype,
+    RT: CDatatype,
+    T: CDatatype,
+{
+    let src = format!(
+        r#"
+        {arithmetic}
+
+        inline {ctype} promote_left(const {ltype} x) {{
+            return {left};
+        }}
+
+        inline {ctype} promote_right(const {rtype_in} x) {{
+            return {right};
+        }}
+
+        __kernel void elementwise_dual(
+            __global const {ltype}* restrict left,
+            __global const {rtype_in}* restrict right,
+            __global {ctype}* restrict output)
+        {{
+            const ulong offset = get_global_id(0);
+            output[offset] = {op}(promote_left(left[offset]), promote_right(right[offset]));
+        }}
+        "#,
+        arithmetic = arithmetic(T::TYPE_STR, T::Float::TYPE_STR),
+        ctype = T::TYPE_STR,
+        ltype = LT::TYPE_STR,
+        rtype_in = RT::TYPE_STR,
+        left = to_complex::<LT, T>(),
+        right = to_complex::<RT, T>(),
+    );
+
+    context.cl_program(src)
+}
+
+/// An op on an array of complex numbers of type `T` with a scalar argument of type `IT`,
+/// which may be `T` or its real component type.
+pub fn elementwise_scalar_complex<IT, T>(
+    op: &'static str,
+    context: &Context,
+) -> Result<Program, Error>
+where
+    IT: CDatatype,
+    T: CDatatype,
+{
+    let src = format!(
+        r#"
+        {arithmetic}
+
+        inline {ctype} promote_right(const {itype} x) {{
+            return {right};
+        }}
+
+        __kernel void elementwise_scalar(
+            __global const {ctype}* left,
+            const {itype} right,
+            __global {ctype}* output)
+        {{
+            const ulong offset = get_global_id(0);
+            output[offset] = {op}(left[offset], promote_right(right));
+        }}
+        "#,
+        arithmetic = arithmetic(T::TYPE_STR, T::Float::TYPE_STR),
+        ctype = T::TYPE_STR,
+        itype = IT::TYPE_STR,
+        right = to_complex::<IT, T>(),
+    );
+
+    context.cl_program(src)
+}
+
+/// A unary op which is defined for both real and complex numbers (the absolute value,
+/// which is the modulus, rounding, negation, and logical not) on an array of complex numbers.
+pub fn unary_arithmetic<IT, OT>(op: &'static str, context: &Context) -> Result<Program, Error>
+where
+    IT: CDatatype,
+    OT: CDatatype,
+{
+    let otype = OT::TYPE_STR;
+
+    let expr = match op {
+        "fabs" => format!("({otype})(hypot(z.x, z.y), 0)"),
+        "round" => "round(z)".to_string(),
+        "-" => "-z".to_string(),
+        "!" => "all(z == 0)".to_string(),
+        other => unreachable!("{other} is only defined for real numbers"),
+    };
+
+    let src = format!(
+        r#"
+        __kernel void unary(__global const {itype}* input, __global {otype}* output) {{
+            const ulong offset = get_global_id(0);
+            const {itype} z = input[offset];
+            output[offset] = {expr};
+        }}
+        "#,
+        itype = IT::TYPE_STR,
+    );
+
+    context.cl_program(src)
+}
+
+pub fn unary_complex<IT, OT>(op: &'static str, context: &Context) -> Result<Program, Error>
+where
+    IT: CDatatype,
+    OT: CDatatype,
+{
+    // these are macros so that only the selected op needs to type-check
+    let src = format!(
+        r#"
+        #define angle(z) atan2((z).y, (z).x)
+        #define conj(z) ({itype})((z).x, -(z).y)
+        #define imag(z) (z).y
+        #define norm(z) hypot((z).x, (z).y)
+        #define real(z) (z).x
+
+        __kernel void unary(__global const {itype}* input, __global {otype}* output) {{
+            const ulong offset = get_global_id(0);
+            output[offset] = {op}(input[offset]);
+        }}
+        "#,
+        itype = IT::TYPE_STR,
+        otype = OT::TYPE_STR,
+    );
+
+    context.cl_program(src)
+}
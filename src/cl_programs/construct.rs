@@ -2,45 +2,56 @@ use ocl::{Error, Program};
 
 use crate::{CDatatype, Context};
 
-const LIB: &'static str = r#"
-const float pi = 3.14159;
-const float resolution = 1.0 / ((float) UINT_MAX);
-
-// PCG hash by Melissa E. O'Neill: https://www.pcg-random.org/
-uint pcg_hash(uint seed) {
-    uint state = seed * 747796405 + 2891336453;
-    uint word = ((state >> ((state >> 28) + 4)) ^ state) * 277803737;
-    return (word >> 22) ^ word;
-}
+use crate::ops::RANDOM_RESOLUTION;
 
-// Xorshift algorithm by George Marsalia: https://www.jstatsoft.org/article/view/v008i14
-uint xorshift(uint rng_state) {
-    rng_state ^= (rng_state << 13);
-    rng_state ^= (rng_state >> 17);
-    rng_state ^= (rng_state << 5);
-    return rng_state;
-}
+// the random number generator, whose constants and rounding are shared with the host
+// implementation so that a value read from the host matches the value computed here
+fn lib() -> String {
+    format!(
+        r#"
+        const float pi = {pi:e}f;
+        const float resolution = {resolution:e}f;
+
+        // PCG hash by Melissa E. O'Neill: https://www.pcg-random.org/
+        uint pcg_hash(uint seed) {{
+            uint state = seed * 747796405 + 2891336453;
+            uint word = ((state >> ((state >> 28) + 4)) ^ state) * 277803737;
+            return (word >> 22) ^ word;
+        }}
 
-float random(const ulong seed, const ulong offset) {
-    // rotate the offset seed number of places through the range 0..2^32
-    uint rng_state = (offset + seed) & UINT_MAX;
+        // Xorshift algorithm by George Marsalia: https://www.jstatsoft.org/article/view/v008i14
+        uint xorshift(uint rng_state) {{
+            rng_state ^= (rng_state << 13);
+            rng_state ^= (rng_state >> 17);
+            rng_state ^= (rng_state << 5);
+            return rng_state;
+        }}
 
-    rng_state = xorshift(rng_state);
+        float random(const ulong seed, const ulong offset) {{
+            // rotate the offset seed number of places through the range 0..2^32
+            uint rng_state = (offset + seed) & UINT_MAX;
 
-    for (uint i = 32; i < log2((float) offset); ++i) {{
-        rng_state = xorshift(rng_state);
-    }}
+            rng_state = xorshift(rng_state);
 
-    rng_state = pcg_hash(rng_state);
+            // i.e. while i < log2(offset), without rounding
+            for (uint i = 32; i < 64 && (1UL << i) < offset; ++i) {{
+                rng_state = xorshift(rng_state);
+            }}
 
-    return rng_state * resolution;
+            rng_state = pcg_hash(rng_state);
+
+            return rng_state * resolution;
+        }}
+        "#,
+        pi = std::f32::consts::PI,
+        resolution = RANDOM_RESOLUTION,
+    )
 }
-"#;
 
 pub fn random_normal(context: &Context) -> Result<Program, Error> {
     let src = format!(
         r#"
-        {LIB}
+        {lib}
 
         __kernel void random_normal(
                 const ulong seed,
@@ -68,39 +79,42 @@ pub fn random_normal(context: &Context) -> Result<Program, Error> {
                 buffer[global_offset] = r * sin(theta);
             }}
         }}
-        "#
+        "#,
+        lib = lib(),
     );
 
-    Program::builder().source(src).build(context.cl_context())
+    context.cl_program(src)
 }
 
 pub fn random_uniform(context: &Context) -> Result<Program, Error> {
     let src = format!(
         r#"
-        {LIB}
+        {lib}
 
         __kernel void random_uniform(const ulong seed, __global float* output) {{
             const ulong offset = get_global_id(0);
             output[offset] = random(seed, offset);
         }}
-        "#
+        "#,
+        lib = lib(),
     );
 
-    Program::builder().source(src).build(context.cl_context())
+    context.cl_program(src)
 }
 
 pub fn range<T: CDatatype>(context: &Context) -> Result<Program, Error> {
     let src = format!(
         r#"
-        {LIB}
+        {lib}
 
         __kernel void range(const double step, __global {dtype}* output) {{
             const ulong offset = get_global_id(0);
             output[offset] = offset * step;
         }}
         "#,
-        dtype = T::TYPE_STR
+        dtype = T::TYPE_STR,
+        lib = lib(),
     );
 
-    Program::builder().source(src).build(context.cl_context())
+    context.cl_program(src)
 }
@@ -2,11 +2,23 @@ use ocl::{Error, Program};
 
 use crate::{CDatatype, Context};
 
+use super::{compare, real};
+
 pub fn cast<IT, OT>(context: &Context) -> Result<Program, Error>
 where
     IT: CDatatype,
     OT: CDatatype,
 {
+    #[cfg(feature = "complex")]
+    let cast = if super::complex::is_complex::<IT>() || super::complex::is_complex::<OT>() {
+        super::complex::cast_complex::<IT, OT>("input[offset]")
+    } else {
+        format!("({}) input[offset]", OT::TYPE_STR)
+    };
+
+    #[cfg(not(feature = "complex"))]
+    let cast = format!("({}) input[offset]", OT::TYPE_STR);
+
     let src = format!(
         r#"
         __kernel void cast_dtype(
@@ -14,14 +26,49 @@ where
             __global {otype}* restrict output)
         {{
             const ulong offset = get_global_id(0);
-            output[offset] = ({otype}) input[offset];
+            output[offset] = {cast};
         }}
         "#,
         itype = IT::TYPE_STR,
         otype = OT::TYPE_STR
     );
 
-    Program::builder().source(src).build(context.cl_context())
+    context.cl_program(src)
+}
+
+pub fn elementwise_bitwise<T>(op: &'static str, context: &Context) -> Result<Program, Error>
+where
+    T: CDatatype,
+{
+    let src = format!(
+        r#"
+        __kernel void elementwise_dual(
+            __global const {dtype}* restrict left,
+            __global const {dtype}* restrict right,
+            __global {dtype}* restrict output)
+        {{
+            const ulong offset = get_global_id(0);
+            output[offset] = {expr};
+        }}
+        "#,
+        dtype = T::TYPE_STR,
+        expr = bitwise::<T>("left[offset]", op, "right[offset]"),
+    );
+
+    context.cl_program(src)
+}
+
+// the bitwise op `op` on `left` and `right` of type `T`, where a shift is taken modulo
+// the bit width of `T` like `wrapping_shl` and `wrapping_shr` on the host
+// (OpenCL would otherwise promote a char or short to an int and shift it modulo 32)
+fn bitwise<T: CDatatype>(left: &str, op: &str, right: &str) -> String {
+    match op {
+        "<<" | ">>" => format!(
+            "({dtype}) ({left} {op} ({right} & (8 * sizeof({dtype}) - 1)))",
+            dtype = T::TYPE_STR
+        ),
+        op => format!("{left} {op} {right}"),
+    }
 }
 
 pub fn elementwise_boolean<T>(cmp: &'static str, context: &Context) -> Result<Program, Error>
@@ -36,8 +83,8 @@ where
             __global uchar* output)
         {{
             const ulong offset = get_global_id(0);
-            const bool left_bool = left[offset] != 0;
-            const bool right_bool = right[offset] != 0;
+            const bool left_bool = {left_bool};
+            const bool right_bool = {right_bool};
 
             if (left_bool {cmp} right_bool) {{
                 output[offset] = 1;
@@ -47,9 +94,11 @@ where
         }}
         "#,
         dtype = T::TYPE_STR,
+        left_bool = compare::<T>("left[offset]", "!=", "0"),
+        right_bool = compare::<T>("right[offset]", "!=", "0"),
     );
 
-    Program::builder().source(src).build(context.cl_context())
+    context.cl_program(src)
 }
 
 pub fn elementwise_cmp<T>(cmp: &'static str, context: &Context) -> Result<Program, Error>
@@ -65,7 +114,7 @@ where
         {{
             const ulong offset = get_global_id(0);
 
-            if (left[offset] {cmp} right[offset]) {{
+            if ({cmp}) {{
                 output[offset] = 1;
             }} else {{
                 output[offset] = 0;
@@ -73,9 +122,30 @@ where
         }}
         "#,
         dtype = T::TYPE_STR,
+        cmp = compare::<T>("left[offset]", cmp, "right[offset]"),
     );
 
-    Program::builder().source(src).build(context.cl_context())
+    context.cl_program(src)
+}
+
+pub fn elementwise_clamp<T: CDatatype>(context: &Context) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        __kernel void elementwise_clamp(
+            __global const {dtype}* input,
+            const {dtype} min,
+            const {dtype} max,
+            __global {dtype}* output)
+        {{
+            const ulong offset = get_global_id(0);
+            const {dtype} n = min > input[offset] ? min : input[offset];
+            output[offset] = max < n ? max : n;
+        }}
+        "#,
+        dtype = T::TYPE_STR,
+    );
+
+    context.cl_program(src)
 }
 
 pub fn elementwise_dual<LT, RT>(op: &'static str, context: &Context) -> Result<Program, Error>
@@ -83,6 +153,56 @@ where
     LT: CDatatype,
     RT: CDatatype,
 {
+    #[cfg(feature = "complex")]
+    if super::complex::is_complex::<LT>() {
+        return super::complex::elementwise_dual_complex::<LT, RT, LT>(op, context);
+    }
+
+    elementwise_dual_inner(
+        op,
+        LT::TYPE_STR,
+        RT::TYPE_STR,
+        LT::TYPE_STR,
+        RT::TYPE_STR,
+        context,
+    )
+}
+
+/// Like [`elementwise_dual`] but casts both inputs to `OT` before applying the `op`.
+pub fn elementwise_dual_promoted<LT, RT, OT>(
+    op: &'static str,
+    context: &Context,
+) -> Result<Program, Error>
+where
+    LT: CDatatype,
+    RT: CDatatype,
+    OT: CDatatype,
+{
+    #[cfg(feature = "complex")]
+    if super::complex::is_complex::<OT>() {
+        return super::complex::elementwise_dual_complex::<LT, RT, OT>(op, context);
+    }
+
+    elementwise_dual_inner(
+        op,
+        OT::TYPE_STR,
+        OT::TYPE_STR,
+        LT::TYPE_STR,
+        RT::TYPE_STR,
+        context,
+    )
+}
+
+// `ltype` and `rtype` are the operand types of the `op`,
+// which the inputs of type `left_in` and `right_in` are cast to
+fn elementwise_dual_inner(
+    op: &'static str,
+    ltype: &str,
+    rtype: &str,
+    left_in: &str,
+    right_in: &str,
+    context: &Context,
+) -> Result<Program, Error> {
     let src = format!(
         r#"
         inline {ltype} add(const {ltype} left, const {rtype} right) {{
@@ -105,6 +225,22 @@ where
             return left / right;
         }}
 
+        inline double atan2_(const double left, const double right) {{
+            return atan2(left, right);
+        }}
+
+        inline double hypot_(const double left, const double right) {{
+            return hypot(left, right);
+        }}
+
+        inline {ltype} maximum(const {ltype} left, const {rtype} right) {{
+            return right > left ? right : left;
+        }}
+
+        inline {ltype} minimum(const {ltype} left, const {rtype} right) {{
+            return right < left ? right : left;
+        }}
+
         inline {ltype} mul(const {ltype} left, const {rtype} right) {{
             return left * right;
         }}
@@ -122,19 +258,59 @@ where
         }}
 
         __kernel void elementwise_dual(
-            __global const {ltype}* restrict left,
-            __global const {rtype}* restrict right,
+            __global const {left_in}* restrict left,
+            __global const {right_in}* restrict right,
             __global {ltype}* restrict output)
         {{
             const ulong offset = get_global_id(0);
-            output[offset] = {op}(left[offset], right[offset]);
+            output[offset] = {op}(({ltype}) left[offset], ({rtype}) right[offset]);
         }}
         "#,
-        ltype = LT::TYPE_STR,
-        rtype = RT::TYPE_STR,
     );
 
-    Program::builder().source(src).build(context.cl_context())
+    context.cl_program(src)
+}
+
+pub fn elementwise_isclose<T>(
+    rtol: f64,
+    atol: f64,
+    equal_nan: bool,
+    context: &Context,
+) -> Result<Program, Error>
+where
+    T: CDatatype,
+{
+    let src = format!(
+        r#"
+        __kernel void elementwise_cmp(
+            __global const {dtype}* restrict left,
+            __global const {dtype}* restrict right,
+            __global uchar* output)
+        {{
+            const ulong offset = get_global_id(0);
+            const double l = {left};
+            const double r = {right};
+
+            if (l == r) {{
+                output[offset] = 1;
+            }} else if (isnan(l) || isnan(r)) {{
+                output[offset] = {equal_nan} && isnan(l) && isnan(r);
+            }} else if (isinf(l) || isinf(r)) {{
+                output[offset] = 0;
+            }} else if (fabs(l - r) <= {atol:e} + ({rtol:e} * fabs(r))) {{
+                output[offset] = 1;
+            }} else {{
+                output[offset] = 0;
+            }}
+        }}
+        "#,
+        dtype = T::TYPE_STR,
+        left = real::<T>("left[offset]"),
+        right = real::<T>("right[offset]"),
+        equal_nan = if equal_nan { 1 } else { 0 },
+    );
+
+    context.cl_program(src)
 }
 
 pub fn elementwise_scalar<IT, OT>(op: &'static str, context: &Context) -> Result<Program, Error>
@@ -142,6 +318,11 @@ where
     IT: CDatatype,
     OT: CDatatype,
 {
+    #[cfg(feature = "complex")]
+    if super::complex::is_complex::<OT>() {
+        return super::complex::elementwise_scalar_complex::<IT, OT>(op, context);
+    }
+
     let src = format!(
         r#"
         inline {otype} add(const {otype} left, const {itype} right) {{
@@ -152,6 +333,22 @@ where
             return left / right;
         }}
 
+        inline {otype} elu(const double left, const double right) {{
+            return left > 0 ? left : right * (exp(left) - 1.);
+        }}
+
+        inline {otype} leaky_relu(const double left, const double right) {{
+            return left > 0 ? left : left * right;
+        }}
+
+        inline {otype} maximum(const {otype} left, const {itype} right) {{
+            return right > left ? right : left;
+        }}
+
+        inline {otype} minimum(const {otype} left, const {itype} right) {{
+            return right < left ? right : left;
+        }}
+
         inline {otype} mul(const {otype} left, const {itype} right) {{
             return left * right;
         }}
@@ -181,7 +378,29 @@ where
         otype = OT::TYPE_STR,
     );
 
-    Program::builder().source(src).build(context.cl_context())
+    context.cl_program(src)
+}
+
+pub fn scalar_bitwise<T>(op: &'static str, context: &Context) -> Result<Program, Error>
+where
+    T: CDatatype,
+{
+    let src = format!(
+        r#"
+        __kernel void elementwise_scalar(
+            __global const {dtype}* left,
+            const {dtype} right,
+            __global {dtype}* output)
+        {{
+            const ulong offset = get_global_id(0);
+            output[offset] = {expr};
+        }}
+        "#,
+        dtype = T::TYPE_STR,
+        expr = bitwise::<T>("left[offset]", op, "right"),
+    );
+
+    context.cl_program(src)
 }
 
 pub fn scalar_boolean<T: CDatatype>(
@@ -196,17 +415,19 @@ pub fn scalar_boolean<T: CDatatype>(
             __global uchar* output)
         {{
             const ulong offset = get_global_id(0);
-            if (input[offset] != 0 {cmp} right != 0) {{
+            if (({left}) {cmp} ({right})) {{
                 output[offset] = 1;
             }} else {{
                 output[offset] = 0;
             }}
         }}
         "#,
-        dtype = T::TYPE_STR
+        dtype = T::TYPE_STR,
+        left = compare::<T>("input[offset]", "!=", "0"),
+        right = compare::<T>("right", "!=", "0"),
     );
 
-    Program::builder().source(src).build(context.cl_context())
+    context.cl_program(src)
 }
 
 pub fn scalar_cmp<T: CDatatype>(cmp: &'static str, context: &Context) -> Result<Program, Error> {
@@ -218,7 +439,7 @@ pub fn scalar_cmp<T: CDatatype>(cmp: &'static str, context: &Context) -> Result<
             __global uchar* output)
         {{
             const ulong offset = get_global_id(0);
-            if (input[offset] {cmp} right) {{
+            if ({cmp}) {{
                 output[offset] = 1;
             }} else {{
                 output[offset] = 0;
@@ -226,9 +447,10 @@ pub fn scalar_cmp<T: CDatatype>(cmp: &'static str, context: &Context) -> Result<
         }}
         "#,
         dtype = T::TYPE_STR,
+        cmp = compare::<T>("input[offset]", cmp, "right"),
     );
 
-    Program::builder().source(src).build(context.cl_context())
+    context.cl_program(src)
 }
 
 // TODO: use the built-in log function without casting every input to a 64-bit float
@@ -237,20 +459,257 @@ where
     IT: CDatatype,
     OT: CDatatype,
 {
+    #[cfg(feature = "complex")]
+    if super::complex::is_complex::<IT>() {
+        return super::complex::unary_arithmetic::<IT, OT>(op, context);
+    }
+
     let src = format!(
         r#"
-        inline {otype} _log(const double input) {{
-            return log(input);
-        }}
+        {helpers}
 
         __kernel void unary(__global const {itype}* input, __global {otype}* output) {{
             const ulong offset = get_global_id(0);
             output[offset] = {op}(input[offset]);
         }}
         "#,
+        helpers = unary_helpers(IT::TYPE_STR, OT::TYPE_STR),
         itype = IT::TYPE_STR,
         otype = OT::TYPE_STR,
     );
 
-    Program::builder().source(src).build(context.cl_context())
+    context.cl_program(src)
+}
+
+// the helper functions which implement unary ops from an input of type `itype` to `otype`
+fn unary_helpers(itype: &str, otype: &str) -> String {
+    format!(
+        r#"
+        inline {otype} _log(const double input) {{
+            return log(input);
+        }}
+
+        inline {otype} _ceil(const double input) {{
+            return ceil(input);
+        }}
+
+        inline {otype} _erf(const double input) {{
+            return erf(input);
+        }}
+
+        inline {otype} _expm1(const double input) {{
+            return expm1(input);
+        }}
+
+        inline {otype} _floor(const double input) {{
+            return floor(input);
+        }}
+
+        inline {otype} _log1p(const double input) {{
+            return log1p(input);
+        }}
+
+        inline {otype} _rsqrt(const double input) {{
+            return rsqrt(input);
+        }}
+
+        inline {otype} _sqrt(const double input) {{
+            return sqrt(input);
+        }}
+
+        inline {otype} _trunc(const double input) {{
+            return trunc(input);
+        }}
+
+        inline {otype} sign_(const {itype} input) {{
+            return input > 0 ? 1 : (input < 0 ? -1 : input);
+        }}
+
+        inline {otype} gelu(const double x) {{
+            return 0.5 * x * (1. + erf(x * M_SQRT1_2));
+        }}
+
+        inline {otype} gelu_tanh(const double x) {{
+            return 0.5 * x * (1. + tanh(0.7978845608028654 * (x + 0.044715 * x * x * x)));
+        }}
+
+        inline {otype} hardtanh(const double x) {{
+            return fmin(fmax(x, -1.), 1.);
+        }}
+
+        inline {otype} relu(const double x) {{
+            return x > 0 ? x : 0;
+        }}
+
+        inline {otype} sigmoid(const double x) {{
+            return 1. / (1. + exp(-x));
+        }}
+
+        inline {otype} silu(const double x) {{
+            return x / (1. + exp(-x));
+        }}
+
+        inline {otype} softplus(const double x) {{
+            return fmax(x, 0.) + log(1. + exp(-fabs(x)));
+        }}
+        "#
+    )
+}
+
+// helper functions for integer arithmetic with well-defined overflow behavior,
+// where wrapping ops are computed as unsigned since signed overflow is undefined in OpenCL C
+fn integer_overflow<T: CDatatype>() -> String {
+    let dtype = T::TYPE_STR;
+    let signed = !dtype.starts_with('u');
+
+    let (utype, limit) = match dtype.trim_start_matches('u') {
+        "char" => ("uchar", "CHAR"),
+        "short" => ("ushort", "SHRT"),
+        "int" => ("uint", "INT"),
+        "long" => ("ulong", "LONG"),
+        other => unreachable!("integer type {other}"),
+    };
+
+    let (max, min) = if signed {
+        (format!("{limit}_MAX"), format!("{limit}_MIN"))
+    } else {
+        (format!("U{limit}_MAX"), "0".to_string())
+    };
+
+    // a product overflows iff its high half is not the sign extension of its low half
+    let mul_overflow = if signed {
+        format!(
+            "mul_hi(left, right) != (wrapping_mul(left, right) >> {bits})",
+            bits = (std::mem::size_of::<T>() * 8) - 1
+        )
+    } else {
+        "mul_hi(left, right) != 0".to_string()
+    };
+
+    format!(
+        r#"
+        inline {dtype} wrapping_add(const {dtype} left, const {dtype} right) {{
+            return ({dtype}) (({utype}) left + ({utype}) right);
+        }}
+
+        inline {dtype} wrapping_sub(const {dtype} left, const {dtype} right) {{
+            return ({dtype}) (({utype}) left - ({utype}) right);
+        }}
+
+        inline {dtype} wrapping_mul(const {dtype} left, const {dtype} right) {{
+            return ({dtype}) (({utype}) left * ({utype}) right);
+        }}
+
+        inline bool add_overflow(const {dtype} left, const {dtype} right) {{
+            return add_sat(left, right) != wrapping_add(left, right);
+        }}
+
+        inline bool sub_overflow(const {dtype} left, const {dtype} right) {{
+            return sub_sat(left, right) != wrapping_sub(left, right);
+        }}
+
+        inline bool mul_overflow(const {dtype} left, const {dtype} right) {{
+            return {mul_overflow};
+        }}
+
+        inline {dtype} saturating_add(const {dtype} left, const {dtype} right) {{
+            return add_sat(left, right);
+        }}
+
+        inline {dtype} saturating_sub(const {dtype} left, const {dtype} right) {{
+            return sub_sat(left, right);
+        }}
+
+        inline {dtype} saturating_mul(const {dtype} left, const {dtype} right) {{
+            if (mul_overflow(left, right)) {{
+                return ((left < 0) != (right < 0)) ? {min} : {max};
+            }} else {{
+                return left * right;
+            }}
+        }}
+        "#
+    )
+}
+
+/// Wrapping or saturating integer arithmetic, with both an array and a scalar `right` operand.
+pub fn elementwise_integer<T>(op: &'static str, context: &Context) -> Result<Program, Error>
+where
+    T: CDatatype,
+{
+    let src = format!(
+        r#"
+        {helpers}
+
+        __kernel void elementwise_dual(
+            __global const {dtype}* restrict left,
+            __global const {dtype}* restrict right,
+            __global {dtype}* restrict output)
+        {{
+            const ulong offset = get_global_id(0);
+            output[offset] = {op}(left[offset], right[offset]);
+        }}
+
+        __kernel void elementwise_scalar(
+            __global const {dtype}* left,
+            const {dtype} right,
+            __global {dtype}* output)
+        {{
+            const ulong offset = get_global_id(0);
+            output[offset] = {op}(left[offset], right);
+        }}
+        "#,
+        helpers = integer_overflow::<T>(),
+        dtype = T::TYPE_STR,
+    );
+
+    context.cl_program(src)
+}
+
+/// Checked integer arithmetic, which sets `overflow[0]` if any element of the output overflows.
+pub fn elementwise_checked<T>(op: &'static str, context: &Context) -> Result<Program, Error>
+where
+    T: CDatatype,
+{
+    let src = format!(
+        r#"
+        {helpers}
+
+        __kernel void elementwise_dual_checked(
+            __global const {dtype}* restrict left,
+            __global const {dtype}* restrict right,
+            __global {dtype}* restrict output,
+            __global uchar* overflow)
+        {{
+            const ulong offset = get_global_id(0);
+            const {dtype} l = left[offset];
+            const {dtype} r = right[offset];
+
+            if ({op}_overflow(l, r)) {{
+                overflow[0] = 1;
+            }}
+
+            output[offset] = wrapping_{op}(l, r);
+        }}
+
+        __kernel void elementwise_scalar_checked(
+            __global const {dtype}* left,
+            const {dtype} right,
+            __global {dtype}* output,
+            __global uchar* overflow)
+        {{
+            const ulong offset = get_global_id(0);
+            const {dtype} l = left[offset];
+
+            if ({op}_overflow(l, right)) {{
+                overflow[0] = 1;
+            }}
+
+            output[offset] = wrapping_{op}(l, right);
+        }}
+        "#,
+        helpers = integer_overflow::<T>(),
+        dtype = T::TYPE_STR,
+    );
+
+    context.cl_program(src)
 }
@@ -0,0 +1,96 @@
+use ocl::{Error, Program};
+
+use crate::{CDatatype, Context};
+
+// each kernel addresses the elements of a lane along the transform axis as
+// `base + (k * stride)`, where `base` is the offset of the first element of the lane
+pub fn fft<T: CDatatype>(context: &Context) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        inline ulong lane_base(const ulong lane, const ulong dim, const ulong stride) {{
+            return ((lane / stride) * dim * stride) + (lane % stride);
+        }}
+
+        // one radix-2 pass of a Stockham auto-sort FFT, where `ns` is the size of the
+        // sub-transforms already computed, so `log2(n)` passes are needed in total
+        __kernel void fft_pass(
+            const ulong n,
+            const ulong stride,
+            const ulong ns,
+            const {rtype} sign,
+            const {rtype} scale,
+            __global const {ctype}* restrict input,
+            __global {ctype}* restrict output)
+        {{
+            const ulong base = lane_base(get_global_id(0), n, stride);
+            const ulong j = get_global_id(1);
+            const ulong k = j % ns;
+
+            const {rtype} angle = sign * (({rtype}) M_PI) * k / ns;
+            const {rtype} c = cos(angle);
+            const {rtype} s = sin(angle);
+
+            const {ctype} v0 = input[base + (j * stride)];
+            const {ctype} v = input[base + ((j + (n / 2)) * stride)];
+            const {ctype} v1 = ({ctype})((v.x * c) - (v.y * s), (v.x * s) + (v.y * c));
+
+            const ulong d = ((j / ns) * ns * 2) + k;
+            output[base + (d * stride)] = (v0 + v1) * scale;
+            output[base + ((d + ns) * stride)] = (v0 - v1) * scale;
+        }}
+
+        __kernel void rfft_input(
+            const ulong n,
+            const ulong stride,
+            __global const {rtype}* restrict input,
+            __global {ctype}* restrict output)
+        {{
+            const ulong offset = lane_base(get_global_id(0), n, stride) + (get_global_id(1) * stride);
+            output[offset] = ({ctype})(input[offset], 0);
+        }}
+
+        __kernel void rfft_output(
+            const ulong n,
+            const ulong m,
+            const ulong stride,
+            __global const {ctype}* restrict input,
+            __global {ctype}* restrict output)
+        {{
+            const ulong lane = get_global_id(0);
+            const ulong k = get_global_id(1);
+            output[lane_base(lane, m, stride) + (k * stride)] = input[lane_base(lane, n, stride) + (k * stride)];
+        }}
+
+        __kernel void irfft_input(
+            const ulong m,
+            const ulong n,
+            const ulong stride,
+            __global const {ctype}* restrict input,
+            __global {ctype}* restrict output)
+        {{
+            const ulong lane = get_global_id(0);
+            const ulong k = get_global_id(1);
+            const ulong base = lane_base(lane, m, stride);
+
+            {ctype} value;
+            if (k <= n / 2) {{
+                value = input[base + (k * stride)];
+            }} else {{
+                const {ctype} mirror = input[base + ((n - k) * stride)];
+                value = ({ctype})(mirror.x, -mirror.y);
+            }}
+
+            output[lane_base(lane, n, stride) + (k * stride)] = value;
+        }}
+
+        __kernel void irfft_output(__global const {ctype}* restrict input, __global {rtype}* restrict output) {{
+            const ulong offset = get_global_id(0);
+            output[offset] = input[offset].x;
+        }}
+        "#,
+        ctype = T::TYPE_STR,
+        rtype = T::Float::TYPE_STR,
+    );
+
+    context.cl_program(src)
+}
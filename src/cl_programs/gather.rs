@@ -22,5 +22,5 @@ pub fn gather_cond<T: CDatatype>(context: &Context) -> Result<Program, Error> {
         dtype = T::TYPE_STR
     );
 
-    Program::builder().source(src).build(context.cl_context())
+    context.cl_program(src)
 }
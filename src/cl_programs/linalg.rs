@@ -2,39 +2,126 @@ use ocl::{Error, Program};
 
 use crate::{CDatatype, Context};
 
-use super::{TILE_SIZE, WG_SIZE};
+use super::{accumulator, TILE_SIZE, WG_SIZE};
 
 pub fn diagonal<T: CDatatype>(context: &Context) -> Result<Program, Error> {
     let src = format!(
         r#"
-        __kernel void diagonal(const {dtype}* matrices, {dtype}* diagonals) {{
-            const ulong m = get_global_id(0);
+        // read the diagonal of each m x n matrix which starts at (row, col)
+        __kernel void diagonal(
+                const ulong m,
+                const ulong n,
+                const ulong row,
+                const ulong col,
+                __global const {dtype}* restrict matrices,
+                __global {dtype}* restrict diagonals)
+        {{
+            const ulong w = get_global_id(0);
             const ulong i = get_global_id(1);
-            diagonals[m, i] = matrices[m, i, i];
+            const ulong len = get_global_size(1);
+            diagonals[(w * len) + i] = matrices[(w * m * n) + ((row + i) * n) + col + i];
+        }}
+
+        // construct an n x n diagonal matrix from each vector of length n
+        __kernel void diag_embed(
+                const ulong n,
+                __global const {dtype}* restrict vectors,
+                __global {dtype}* restrict matrices)
+        {{
+            const ulong offset = get_global_id(0);
+            const ulong i = (offset / n) % n;
+            const ulong j = offset % n;
+
+            if (i == j) {{
+                matrices[offset] = vectors[offset / n];
+            }} else {{
+                matrices[offset] = 0;
+            }}
+        }}
+
+        // sum the main diagonal of each m x n matrix
+        __kernel void trace(
+                const ulong m,
+                const ulong n,
+                __global const {dtype}* restrict matrices,
+                __global {dtype}* restrict output)
+        {{
+            const ulong w = get_global_id(0);
+            const ulong len = min(m, n);
+
+            {dtype} sum = 0;
+            for (ulong i = 0; i < len; i++) {{
+                sum += matrices[(w * m * n) + (i * n) + i];
+            }}
+
+            output[w] = sum;
         }}
         "#,
         dtype = T::TYPE_STR
     );
 
-    Program::builder().source(src).build(context.cl_context())
+    context.cl_program(src)
+}
+
+pub fn kron<T: CDatatype>(context: &Context) -> Result<Program, Error> {
+    let (preamble, product) = product::<T>(
+        "left[(w * dims.x * dims.y) + ((i / dims.z) * dims.y) + (j / dims.w)]",
+        "right[(w * dims.z * dims.w) + ((i % dims.z) * dims.w) + (j % dims.w)]",
+    );
+
+    let src = format!(
+        r#"
+        {preamble}
+
+        // dims := (m, n, p, q) for an m x n left matrix and a p x q right matrix
+        __kernel void kron(
+                ulong4 const dims,
+                __global const {dtype}* restrict left,
+                __global const {dtype}* restrict right,
+                __global {dtype}* restrict output)
+        {{
+            const ulong offset = get_global_id(0);
+            const ulong rows = dims.x * dims.z;
+            const ulong cols = dims.y * dims.w;
+
+            const ulong w = offset / (rows * cols);
+            const ulong i = (offset / cols) % rows;
+            const ulong j = offset % cols;
+
+            output[offset] = {product};
+        }}
+        "#,
+        dtype = T::TYPE_STR
+    );
+
+    context.cl_program(src)
 }
 
 pub fn matmul<T: CDatatype>(context: &Context) -> Result<Program, Error> {
     debug_assert_eq!(TILE_SIZE * TILE_SIZE, WG_SIZE);
 
+    let (preamble, product) = product::<T>("left_tile[i][j]", "right_tile[j][k]");
+
     let src = format!(
         r#"
+        {preamble}
+
         __kernel void matmul(
                 ulong4 const dims,
                 ulong const reduce_tiles,
                 __global const {dtype}* restrict left,
+                __global const ulong* restrict left_batch,
                 __global const {dtype}* restrict right,
+                __global const ulong* restrict right_batch,
                 __global {dtype}* restrict output)
         {{
             // x := output axis 0
             // y := reduce axis
             // z := output axis 1
             // w := matrix number
+            //
+            // the left and right matrices of each output matrix are looked up in the
+            // `left_batch` and `right_batch` offsets, since either may be broadcast
 
             const ulong x_tile = get_global_id(1);
             const ulong z_tile = get_global_id(2);
@@ -42,10 +129,10 @@ pub fn matmul<T: CDatatype>(context: &Context) -> Result<Program, Error> {
 
             const ulong x_offset = x_tile * {TILE_SIZE};
             const ulong z_offset = z_tile * {TILE_SIZE};
-            const ulong left_offset = w * dims.x * dims.y;
-            const ulong right_offset = w * dims.y * dims.z;
+            const ulong left_offset = left_batch[w] * dims.x * dims.y;
+            const ulong right_offset = right_batch[w] * dims.y * dims.z;
 
-            {dtype} tile[{TILE_SIZE}][{TILE_SIZE}];
+            {acc} tile[{TILE_SIZE}][{TILE_SIZE}];
 
             // initialize the local cache for the left and right tiles to zero
             {dtype} left_tile[{TILE_SIZE}][{TILE_SIZE}];
@@ -83,7 +170,7 @@ pub fn matmul<T: CDatatype>(context: &Context) -> Result<Program, Error> {
                     for (uint j = 0; j < {TILE_SIZE}; j++) {{
                         #pragma unroll
                         for (uint k = 0; k < {TILE_SIZE}; k++) {{
-                            tile[i][k] += left_tile[i][j] * right_tile[j][k];
+                            tile[i][k] += {product};
                         }}
                     }}
                 }}
@@ -103,8 +190,284 @@ pub fn matmul<T: CDatatype>(context: &Context) -> Result<Program, Error> {
             }}
         }}
         "#,
+        dtype = T::TYPE_STR,
+        acc = accumulator::<T>(),
+    );
+
+    context.cl_program(src)
+}
+
+pub fn lu<T: CDatatype>(context: &Context) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        // factor each n x n matrix into a unit lower triangular matrix L (below the diagonal)
+        // and an upper triangular matrix U (on and above the diagonal), with partial pivoting
+        __kernel void lu(
+                const ulong n,
+                __global const {dtype}* restrict input,
+                __global {dtype}* restrict factors,
+                __global ulong* restrict pivots,
+                __global uchar* restrict parity,
+                __global uchar* restrict singular)
+        {{
+            const ulong w = get_global_id(0);
+            const ulong offset = w * n * n;
+
+            for (ulong i = 0; i < n * n; i++) {{
+                factors[offset + i] = input[offset + i];
+            }}
+
+            for (ulong i = 0; i < n; i++) {{
+                pivots[(w * n) + i] = i;
+            }}
+
+            uchar odd = 0;
+
+            for (ulong k = 0; k < n; k++) {{
+                ulong p = k;
+                {dtype} max = fabs(factors[offset + (k * n) + k]);
+
+                for (ulong i = k + 1; i < n; i++) {{
+                    const {dtype} value = fabs(factors[offset + (i * n) + k]);
+                    if (value > max) {{
+                        max = value;
+                        p = i;
+                    }}
+                }}
+
+                if (max == 0) {{
+                    singular[0] = 1;
+                    continue;
+                }}
+
+                if (p != k) {{
+                    for (ulong j = 0; j < n; j++) {{
+                        const {dtype} tmp = factors[offset + (k * n) + j];
+                        factors[offset + (k * n) + j] = factors[offset + (p * n) + j];
+                        factors[offset + (p * n) + j] = tmp;
+                    }}
+
+                    const ulong tmp = pivots[(w * n) + k];
+                    pivots[(w * n) + k] = pivots[(w * n) + p];
+                    pivots[(w * n) + p] = tmp;
+
+                    odd = !odd;
+                }}
+
+                const {dtype} pivot = factors[offset + (k * n) + k];
+
+                for (ulong i = k + 1; i < n; i++) {{
+                    const {dtype} l = factors[offset + (i * n) + k] / pivot;
+                    factors[offset + (i * n) + k] = l;
+
+                    for (ulong j = k + 1; j < n; j++) {{
+                        factors[offset + (i * n) + j] -= l * factors[offset + (k * n) + j];
+                    }}
+                }}
+            }}
+
+            parity[w] = odd;
+        }}
+
+        // read the permutation P (0), lower factor L (1), or upper factor U (2) such that A = P L U
+        __kernel void lu_factor(
+                const ulong n,
+                const uchar factor,
+                __global const {dtype}* restrict factors,
+                __global const ulong* restrict pivots,
+                __global {dtype}* restrict output)
+        {{
+            const ulong w = get_global_id(0);
+            const ulong i = get_global_id(1);
+            const ulong j = get_global_id(2);
+            const ulong offset = (w * n * n) + (i * n) + j;
+
+            {dtype} value;
+            if (factor == 0) {{
+                value = pivots[(w * n) + j] == i ? 1 : 0;
+            }} else if (factor == 1) {{
+                value = i > j ? factors[offset] : (i == j ? 1 : 0);
+            }} else {{
+                value = i <= j ? factors[offset] : 0;
+            }}
+
+            output[offset] = value;
+        }}
+
+        // read the determinant (0), its sign (1), or the log of its absolute value (2)
+        __kernel void lu_det(
+                const ulong n,
+                const uchar part,
+                __global const {dtype}* restrict factors,
+                __global const uchar* restrict parity,
+                __global {dtype}* restrict output)
+        {{
+            const ulong w = get_global_id(0);
+
+            {dtype} det = parity[w] ? -1 : 1;
+            {dtype} sign = det;
+            {dtype} logabs = 0;
+
+            for (ulong i = 0; i < n; i++) {{
+                const {dtype} u = factors[(w * n * n) + (i * n) + i];
+                det *= u;
+                sign = u < 0 ? -sign : (u == 0 ? 0 : sign);
+                logabs += log(fabs(u));
+            }}
+
+            if (part == 0) {{
+                output[w] = det;
+            }} else if (part == 1) {{
+                output[w] = sign;
+            }} else {{
+                output[w] = logabs;
+            }}
+        }}
+
+        {solve}
+
+        {inverse}
+        "#,
+        dtype = T::TYPE_STR,
+        solve = lu_solve(
+            "lu_solve",
+            T::TYPE_STR,
+            r#"
+                const ulong k,
+                __global const ulong* restrict left_batch,
+                __global const {dtype}* restrict right,
+                __global const ulong* restrict right_batch,"#,
+            "left_batch[w]",
+            "right[(right_batch[w] * n * k) + (pivots[(matrix * n) + i] * k) + j]",
+        ),
+        inverse = lu_solve(
+            "lu_inverse",
+            T::TYPE_STR,
+            "",
+            "w",
+            "pivots[(matrix * n) + i] == j ? 1 : 0",
+        ),
+    );
+
+    context.cl_program(src)
+}
+
+// a kernel to solve for column `j` of each output matrix by forward and back substitution,
+// given the LU factors of each `matrix` and a `load` expression for the permuted right-hand side
+fn lu_solve(name: &str, dtype: &str, args: &str, matrix: &str, load: &str) -> String {
+    let args = args.replace("{dtype}", dtype);
+    // the inverse has no right-hand side, so its output matrices are square
+    let k = if args.is_empty() {
+        "const ulong k = n;"
+    } else {
+        ""
+    };
+
+    format!(
+        r#"
+        __kernel void {name}(
+                const ulong n,
+                __global const {dtype}* restrict factors,
+                __global const ulong* restrict pivots,{args}
+                __global {dtype}* restrict output)
+        {{
+            {k}
+            const ulong w = get_global_id(0);
+            const ulong j = get_global_id(1);
+            const ulong matrix = {matrix};
+            const ulong lu = matrix * n * n;
+            const ulong offset = w * n * k;
+
+            for (ulong i = 0; i < n; i++) {{
+                output[offset + (i * k) + j] = {load};
+            }}
+
+            for (ulong i = 0; i < n; i++) {{
+                {dtype} x = output[offset + (i * k) + j];
+                for (ulong m = 0; m < i; m++) {{
+                    x -= factors[lu + (i * n) + m] * output[offset + (m * k) + j];
+                }}
+                output[offset + (i * k) + j] = x;
+            }}
+
+            for (ulong i = n; i-- > 0;) {{
+                {dtype} x = output[offset + (i * k) + j];
+                for (ulong m = i + 1; m < n; m++) {{
+                    x -= factors[lu + (i * n) + m] * output[offset + (m * k) + j];
+                }}
+                output[offset + (i * k) + j] = x / factors[lu + (i * n) + i];
+            }}
+        }}
+        "#
+    )
+}
+
+pub fn outer<T: CDatatype>(context: &Context) -> Result<Program, Error> {
+    let (preamble, product) = product::<T>("left[(w * m) + i]", "right[(w * n) + j]");
+
+    let src = format!(
+        r#"
+        {preamble}
+
+        __kernel void outer(
+                const ulong m,
+                const ulong n,
+                __global const {dtype}* restrict left,
+                __global const {dtype}* restrict right,
+                __global {dtype}* restrict output)
+        {{
+            const ulong offset = get_global_id(0);
+            const ulong w = offset / (m * n);
+            const ulong i = (offset / n) % m;
+            const ulong j = offset % n;
+
+            output[offset] = {product};
+        }}
+        "#,
+        dtype = T::TYPE_STR
+    );
+
+    context.cl_program(src)
+}
+
+pub fn triangle<T: CDatatype>(context: &Context) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        // keep the elements of each m x n matrix on and below (or, if upper, on and above)
+        // the k-th diagonal, and set every other element to zero
+        __kernel void triangle(
+                const ulong m,
+                const ulong n,
+                const long k,
+                const uchar upper,
+                __global const {dtype}* restrict input,
+                __global {dtype}* restrict output)
+        {{
+            const ulong offset = get_global_id(0);
+            const long diagonal = (long) (offset % n) - (long) ((offset / n) % m);
+            const bool keep = upper ? diagonal >= k : diagonal <= k;
+
+            if (keep) {{
+                output[offset] = input[offset];
+            }} else {{
+                output[offset] = 0;
+            }}
+        }}
+        "#,
         dtype = T::TYPE_STR
     );
 
-    Program::builder().source(src).build(context.cl_context())
+    context.cl_program(src)
+}
+
+// the preamble and expression to multiply `left` by `right`,
+// which must use a helper function if `T` is a complex number
+fn product<T: CDatatype>(left: &str, right: &str) -> (String, String) {
+    #[cfg(feature = "complex")]
+    if super::complex::is_complex::<T>() {
+        let preamble = super::complex::complex_mul(T::TYPE_STR);
+        return (preamble, format!("mul({left}, {right})"));
+    }
+
+    (String::new(), format!("{left} * {right}"))
 }
@@ -1,15 +1,27 @@
 use std::fmt;
 
+use crate::CDatatype;
+
+mod cache;
+#[cfg(feature = "complex")]
+mod complex;
 mod construct;
 mod elementwise;
+#[cfg(feature = "fft")]
+mod fft;
 mod gather;
 mod linalg;
 mod reduce;
 mod slice;
 mod view;
 
+pub use cache::ProgramCache;
+#[cfg(feature = "complex")]
+pub use complex::*;
 pub use construct::*;
 pub use elementwise::*;
+#[cfg(feature = "fft")]
+pub use fft::*;
 pub use gather::*;
 pub use linalg::*;
 pub use reduce::*;
@@ -19,6 +31,47 @@ pub use view::*;
 pub(crate) const TILE_SIZE: usize = 8;
 pub(crate) const WG_SIZE: usize = 64;
 
+// the expression to compare `left` with `right`, which are both of type `T`
+fn compare<T: CDatatype>(left: &str, cmp: &str, right: &str) -> String {
+    #[cfg(feature = "complex")]
+    if complex::is_complex::<T>() {
+        return complex::compare_complex(left, cmp, right);
+    }
+
+    format!("{left} {cmp} {right}")
+}
+
+// the type in which to accumulate a sum or product of values of type `T`,
+// since a half-precision accumulator stops incrementing by one at 2048
+fn accumulator<T: CDatatype>() -> &'static str {
+    if T::CL_FP16 {
+        T::Float::TYPE_STR
+    } else {
+        T::TYPE_STR
+    }
+}
+
+// the number of bytes of local memory in which to accumulate `len` values of type `T`
+pub(crate) fn accumulator_bytes<T: CDatatype>(len: usize) -> usize {
+    let size = if T::CL_FP16 {
+        std::mem::size_of::<T::Float>()
+    } else {
+        std::mem::size_of::<T>()
+    };
+
+    len * size
+}
+
+// the expression for the real part of `x` of type `T`
+fn real<T: CDatatype>(x: &str) -> String {
+    #[cfg(feature = "complex")]
+    if complex::is_complex::<T>() {
+        return format!("{x}.x");
+    }
+
+    x.to_string()
+}
+
 struct ArrayFormat<'a, T> {
     arr: &'a [T],
 }
@@ -1,26 +1,51 @@
 use ocl::{Buffer, Error, Kernel, Program, Queue};
-use rayon::prelude::*;
 
-use crate::{div_ceil, CDatatype};
+use crate::{div_ceil, CDatatype, Context};
 
-use super::WG_SIZE;
+use super::{accumulator, accumulator_bytes, compare, WG_SIZE};
 
-pub fn reduce_all<T: CDatatype>(queue: Queue, input: &Buffer<T>) -> Result<bool, Error> {
+// the in-place reduce ops on accumulators of values of type `T`
+fn reduce_ops<T: CDatatype>() -> String {
+    #[cfg(feature = "complex")]
+    if super::complex::is_complex::<T>() {
+        return super::complex::complex_reduce(T::TYPE_STR);
+    }
+
+    format!(
+        r#"
+        inline void add({dtype}* left, const {dtype} right) {{
+            *left += right;
+        }}
+
+        inline void mul({dtype}* left, const {dtype} right) {{
+            *left *= right;
+        }}
+        "#,
+        dtype = accumulator::<T>()
+    )
+}
+
+pub fn reduce_all<T: CDatatype>(
+    context: &Context,
+    queue: Queue,
+    input: &Buffer<T>,
+) -> Result<bool, Error> {
     let src = format!(
         r#"
         __kernel void reduce_all(
                 __global uchar* flag,
                 __global const {dtype}* input)
         {{
-            if (input[get_global_id(0)] == 0) {{
+            if ({zero}) {{
                 flag[0] = 0;
             }}
         }}
         "#,
-        dtype = T::TYPE_STR
+        dtype = T::TYPE_STR,
+        zero = compare::<T>("input[get_global_id(0)]", "==", "0"),
     );
 
-    let program = Program::builder().source(src).build(&queue.context())?;
+    let program = context.cl_program(src)?;
 
     let mut result = vec![1u8];
     let flag: Buffer<u8> = Buffer::builder().queue(queue.clone()).len(1).build()?;
@@ -44,22 +69,27 @@ pub fn reduce_all<T: CDatatype>(queue: Queue, input: &Buffer<T>) -> Result<bool,
     Ok(result == [1])
 }
 
-pub fn reduce_any<T: CDatatype>(queue: Queue, input: &Buffer<T>) -> Result<bool, Error> {
+pub fn reduce_any<T: CDatatype>(
+    context: &Context,
+    queue: Queue,
+    input: &Buffer<T>,
+) -> Result<bool, Error> {
     let src = format!(
         r#"
         __kernel void reduce_any(
                 __global uchar* flag,
                 __global const {dtype}* input)
         {{
-            if (input[get_global_id(0)] != 0) {{
+            if ({nonzero}) {{
                 flag[0] = 1;
             }}
         }}
         "#,
-        dtype = T::TYPE_STR
+        dtype = T::TYPE_STR,
+        nonzero = compare::<T>("input[get_global_id(0)]", "!=", "0"),
     );
 
-    let program = Program::builder().source(src).build(&queue.context())?;
+    let program = context.cl_program(src)?;
 
     let mut result = vec![0u8];
     let flag: Buffer<u8> = Buffer::builder().queue(queue.clone()).len(1).build()?;
@@ -83,13 +113,15 @@ pub fn reduce_any<T: CDatatype>(queue: Queue, input: &Buffer<T>) -> Result<bool,
     Ok(result == [1])
 }
 
+/// Reduce the `input` on the device until it's small enough to finish reducing on the host,
+/// and return the partial reductions which remain.
 pub fn reduce<T: CDatatype>(
     init: T,
     reduce: &'static str,
+    context: &Context,
     queue: Queue,
     input: &Buffer<T>,
-    collector: impl Fn(T, T) -> T + Send + Sync,
-) -> Result<T, Error> {
+) -> Result<Vec<T>, Error> {
     const MIN_SIZE: usize = 65_536;
 
     let min_size = MIN_SIZE * num_cpus::get();
@@ -97,24 +129,18 @@ pub fn reduce<T: CDatatype>(
     if input.len() < min_size {
         let mut result = vec![init; input.len()];
         input.read(&mut result).enq()?;
-        return Ok(result.into_par_iter().reduce(|| init, collector));
+        return Ok(result);
     }
 
     let src = format!(
         r#"
-        inline void add({dtype}* left, const {dtype} right) {{
-            *left += right;
-        }}
-
-        inline void mul({dtype}* left, const {dtype} right) {{
-            *left *= right;
-        }}
+        {reduce_ops}
 
         __kernel void reduce(
                 const ulong size,
                 __global const {dtype}* input,
                 __global {dtype}* output,
-                __local {dtype}* partials)
+                __local {acc}* partials)
         {{
             const ulong offset = get_global_id(0);
             const uint group_size = get_local_size(0);
@@ -141,10 +167,12 @@ pub fn reduce<T: CDatatype>(
             }}
         }}
         "#,
-        dtype = T::TYPE_STR
+        dtype = T::TYPE_STR,
+        acc = accumulator::<T>(),
+        reduce_ops = reduce_ops::<T>(),
     );
 
-    let program = Program::builder().source(src).build(&queue.context())?;
+    let program = context.cl_program(src)?;
 
     let mut buffer = {
         let output = Buffer::builder()
@@ -162,7 +190,7 @@ pub fn reduce<T: CDatatype>(
             .arg(input.len() as u64)
             .arg(input)
             .arg(&output)
-            .arg_local::<T>(WG_SIZE)
+            .arg_local::<u8>(accumulator_bytes::<T>(WG_SIZE))
             .build()?;
 
         unsafe { kernel.enq()? };
@@ -188,7 +216,7 @@ pub fn reduce<T: CDatatype>(
             .arg(input.len() as u64)
             .arg(&input)
             .arg(&output)
-            .arg_local::<T>(WG_SIZE)
+            .arg_local::<u8>(accumulator_bytes::<T>(WG_SIZE))
             .build()?;
 
         unsafe { kernel.enq()? }
@@ -201,17 +229,121 @@ pub fn reduce<T: CDatatype>(
 
     queue.finish()?;
 
-    Ok(result.into_par_iter().reduce(|| init, collector))
+    Ok(result)
+}
+
+pub fn normalize<T: CDatatype>(
+    context: &Context,
+    running: bool,
+    weight: bool,
+    bias: bool,
+) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        __kernel void moments(
+                const ulong stride,
+                __global const {dtype}* restrict input,
+                __global {dtype}* restrict mean,
+                __global {dtype}* restrict var,
+                __local {acc}* counts,
+                __local {acc}* means,
+                __local {acc}* m2s)
+        {{
+            const ulong group = get_group_id(0);
+            const uint b = get_local_id(0);
+            const uint group_size = get_local_size(0);
+            const ulong start = group * stride;
+
+            // accumulate a partial mean and variance (Welford's algorithm)
+            {acc} n = 0;
+            {acc} mu = 0;
+            {acc} m2 = 0;
+
+            for (ulong i = b; i < stride; i += group_size) {{
+                const {acc} x = input[start + i];
+                n += 1;
+                const {acc} delta = x - mu;
+                mu += delta / n;
+                m2 += delta * (x - mu);
+            }}
+
+            counts[b] = n;
+            means[b] = mu;
+            m2s[b] = m2;
+
+            // merge the partial moments over local memory in parallel
+            for (uint offset = group_size >> 1; offset > 0; offset = offset >> 1) {{
+                barrier(CLK_LOCAL_MEM_FENCE);
+
+                if (b < offset) {{
+                    const {acc} n_a = counts[b];
+                    const {acc} n_b = counts[b + offset];
+
+                    if (n_b > 0) {{
+                        const {acc} n_ab = n_a + n_b;
+                        const {acc} delta = means[b + offset] - means[b];
+                        means[b] += delta * (n_b / n_ab);
+                        m2s[b] += m2s[b + offset] + delta * delta * (n_a * n_b / n_ab);
+                        counts[b] = n_ab;
+                    }}
+                }}
+            }}
+
+            if (b == 0) {{
+                mean[group] = means[0];
+                var[group] = m2s[0] / counts[0];
+            }}
+        }}
+
+        __kernel void normalize(
+                const ulong stride,
+                const ulong param_stride,
+                const ulong param_len,
+                const {dtype} eps,
+                __global const {dtype}* restrict input,
+                __global const {dtype}* restrict mean,
+                __global const {dtype}* restrict var,
+                {weight_arg}
+                {bias_arg}
+                __global {dtype}* restrict output)
+        {{
+            const ulong offset = get_global_id(0);
+            const ulong p = (offset / param_stride) % param_len;
+            const ulong s = {stats};
+
+            const {acc} normalized = (input[offset] - mean[s]) / sqrt(var[s] + eps);
+            output[offset] = normalized{scale}{shift};
+        }}
+        "#,
+        dtype = T::TYPE_STR,
+        acc = accumulator::<T>(),
+        weight_arg = if weight {
+            format!("__global const {}* restrict weight,", T::TYPE_STR)
+        } else {
+            String::default()
+        },
+        bias_arg = if bias {
+            format!("__global const {}* restrict bias,", T::TYPE_STR)
+        } else {
+            String::default()
+        },
+        stats = if running { "p" } else { "offset / stride" },
+        scale = if weight { " * weight[p]" } else { "" },
+        shift = if bias { " + bias[p]" } else { "" },
+    );
+
+    context.cl_program(src)
 }
 
 pub fn reduce_axis<T: CDatatype>(
     init: T,
     reduce: &'static str,
-    queue: Queue,
+    queue: &crate::Queue,
+    cl_queue: Queue,
     input: &Buffer<T>,
     shape: &[usize],
     mut stride: usize,
-) -> Result<Buffer<T>, Error> {
+) -> Result<Buffer<T>, crate::Error> {
     assert!(input.len() > 0);
     assert!(stride > 0);
     assert_eq!(input.len(), shape.iter().product());
@@ -221,31 +353,33 @@ pub fn reduce_axis<T: CDatatype>(
     debug_assert!(output_size > 0);
 
     if stride < WG_SIZE {
-        return fold_axis(init, reduce, queue.clone(), input, stride, 1);
+        return fold_axis(init, reduce, queue, cl_queue, input, stride, 1);
     }
 
     let log = (stride as f32).log(WG_SIZE as f32).fract();
     let target_dim = WG_SIZE.pow(log as u32);
-    let mut buffer = fold_axis(init, reduce, queue.clone(), input, stride, target_dim)?;
+    let mut buffer = fold_axis(
+        init,
+        reduce,
+        queue,
+        cl_queue.clone(),
+        input,
+        stride,
+        target_dim,
+    )?;
 
     stride = target_dim;
     debug_assert_eq!(output_size * stride, buffer.len());
 
     let src = format!(
         r#"
-        inline void add({dtype}* left, const {dtype} right) {{
-            *left += right;
-        }}
+        {reduce_ops}
 
-        inline void mul({dtype}* left, const {dtype} right) {{
-            *left *= right;
-        }}
-
-        __kernel void reduce(
+        __kernel void reduce_axis(
                 {dtype} init,
                 __global const {dtype}* input,
                 __global {dtype}* output,
-                __local {dtype}* partials)
+                __local {acc}* partials)
         {{
             const ulong offset = get_global_id(0);
             const uint reduce_dim = get_local_size(0);
@@ -271,14 +405,16 @@ pub fn reduce_axis<T: CDatatype>(
             }}
         }}
         "#,
-        dtype = T::TYPE_STR
+        dtype = T::TYPE_STR,
+        acc = accumulator::<T>(),
+        reduce_ops = reduce_ops::<T>(),
     );
 
-    let program = Program::builder().source(src).build(&queue.context())?;
+    let program = queue.context().cl_program(src)?;
 
     while buffer.len() > output_size {
         let output = Buffer::builder()
-            .queue(queue.clone())
+            .queue(cl_queue.clone())
             .len(buffer.len() / stride)
             .build()?;
 
@@ -292,16 +428,16 @@ pub fn reduce_axis<T: CDatatype>(
         let kernel = Kernel::builder()
             .name("reduce_axis")
             .program(&program)
-            .queue(queue.clone())
+            .queue(cl_queue.clone())
             .local_work_size(wg_size)
             .global_work_size(buffer.len())
             .arg(init)
             .arg(&buffer)
             .arg(&output)
-            .arg_local::<T>(wg_size)
+            .arg_local::<u8>(accumulator_bytes::<T>(wg_size))
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { queue.enqueue_kernel(&kernel)? }
 
         buffer = output;
         stride /= wg_size;
@@ -314,24 +450,19 @@ pub fn reduce_axis<T: CDatatype>(
 fn fold_axis<T: CDatatype>(
     init: T,
     reduce: &'static str,
-    queue: Queue,
+    queue: &crate::Queue,
+    cl_queue: Queue,
     input: &Buffer<T>,
     reduce_dim: usize,
     target_dim: usize,
-) -> Result<Buffer<T>, Error> {
+) -> Result<Buffer<T>, crate::Error> {
     debug_assert_eq!(input.len() % reduce_dim, 0);
 
     let output_size = (input.len() / reduce_dim) * target_dim;
 
     let src = format!(
         r#"
-        inline void add({dtype}* left, const {dtype} right) {{
-            *left += right;
-        }}
-
-        inline void mul({dtype}* left, const {dtype} right) {{
-            *left *= right;
-        }}
+        {reduce_ops}
 
         __kernel void fold_axis(
             const ulong reduce_dim,
@@ -352,7 +483,7 @@ fn fold_axis<T: CDatatype>(
             // the global offset in the input basis
             const ulong i_offset = (a * reduce_dim) + b;
 
-            {dtype} reduced = init;
+            {acc} reduced = init;
 
             for (uint stride = i_offset; stride < (a + 1) * reduce_dim; stride += target_dim) {{
                 {reduce}(&reduced, input[stride]);
@@ -362,19 +493,21 @@ fn fold_axis<T: CDatatype>(
         }}
         "#,
         dtype = T::TYPE_STR,
+        acc = accumulator::<T>(),
+        reduce_ops = reduce_ops::<T>(),
     );
 
-    let program = Program::builder().source(src).build(&queue.context())?;
+    let program = queue.context().cl_program(src)?;
 
     let output = Buffer::builder()
-        .queue(queue.clone())
+        .queue(cl_queue.clone())
         .len(output_size)
         .build()?;
 
     let kernel = Kernel::builder()
         .name("fold_axis")
         .program(&program)
-        .queue(queue.clone())
+        .queue(cl_queue.clone())
         .global_work_size(output_size)
         .arg(reduce_dim as u64)
         .arg(target_dim as u64)
@@ -383,7 +516,7 @@ fn fold_axis<T: CDatatype>(
         .arg(&output)
         .build()?;
 
-    unsafe { kernel.enq()? }
+    unsafe { queue.enqueue_kernel(&kernel)? }
 
     Ok(output)
 }
@@ -149,7 +149,7 @@ pub fn read_slice<T: CDatatype>(
         max_indices = bounds.max_indices(),
     );
 
-    Program::builder().source(src).build(context.cl_context())
+    context.cl_program(src)
 }
 
 pub fn write_to_slice<T: CDatatype>(
@@ -242,7 +242,7 @@ pub fn write_to_slice<T: CDatatype>(
         max_indices = bounds.max_indices(),
     );
 
-    Program::builder().source(src).build(context.cl_context())
+    context.cl_program(src)
 }
 
 pub fn write_value_to_slice<T: CDatatype>(
@@ -335,5 +335,5 @@ pub fn write_value_to_slice<T: CDatatype>(
         max_indices = bounds.max_indices(),
     );
 
-    Program::builder().source(src).build(context.cl_context())
+    context.cl_program(src)
 }
@@ -59,5 +59,5 @@ where
         ndim = shape.len(),
     );
 
-    Program::builder().source(src).build(context.cl_context())
+    context.cl_program(src)
 }
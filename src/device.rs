@@ -0,0 +1,277 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// The class of an OpenCL [`Device`]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DeviceKind {
+    CPU,
+    GPU,
+    Accelerator,
+}
+
+/// A description of an OpenCL device and its capabilities
+#[derive(Clone, Debug)]
+pub struct Device {
+    kind: DeviceKind,
+    name: String,
+    vendor: String,
+    compute_units: u32,
+    global_mem_size: u64,
+    max_work_group_size: usize,
+    fp16: bool,
+    queues: Arc<AtomicUsize>,
+    #[cfg(feature = "opencl")]
+    cl_device: ocl::Device,
+}
+
+impl Device {
+    #[cfg(feature = "opencl")]
+    pub(crate) fn new(cl_device: ocl::Device) -> Result<Self, ocl::Error> {
+        use ocl::enums::{DeviceInfo, DeviceInfoResult};
+
+        let kind = match cl_device.info(DeviceInfo::Type)? {
+            DeviceInfoResult::Type(device_type) if device_type.contains(ocl::DeviceType::GPU) => {
+                DeviceKind::GPU
+            }
+            DeviceInfoResult::Type(device_type)
+                if device_type.contains(ocl::DeviceType::ACCELERATOR) =>
+            {
+                DeviceKind::Accelerator
+            }
+            _ => DeviceKind::CPU,
+        };
+
+        let compute_units = match cl_device.info(DeviceInfo::MaxComputeUnits)? {
+            DeviceInfoResult::MaxComputeUnits(compute_units) => compute_units,
+            _ => 1,
+        };
+
+        let global_mem_size = match cl_device.info(DeviceInfo::GlobalMemSize)? {
+            DeviceInfoResult::GlobalMemSize(size) => size,
+            _ => 0,
+        };
+
+        let fp16 = match cl_device.info(DeviceInfo::Extensions)? {
+            DeviceInfoResult::Extensions(extensions) => extensions
+                .split_whitespace()
+                .any(|ext| ext == "cl_khr_fp16"),
+            _ => false,
+        };
+
+        Ok(Self {
+            kind,
+            name: cl_device.name()?,
+            vendor: cl_device.vendor()?,
+            compute_units,
+            global_mem_size,
+            max_work_group_size: cl_device.max_wg_size()?,
+            fp16,
+            queues: Arc::new(AtomicUsize::new(0)),
+            cl_device,
+        })
+    }
+
+    /// The class of this device.
+    pub fn kind(&self) -> DeviceKind {
+        self.kind
+    }
+
+    /// The name of this device.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The vendor of this device.
+    pub fn vendor(&self) -> &str {
+        &self.vendor
+    }
+
+    /// The number of parallel compute units of this device.
+    pub fn compute_units(&self) -> u32 {
+        self.compute_units
+    }
+
+    /// The size of the global memory of this device, in bytes.
+    pub fn global_mem_size(&self) -> u64 {
+        self.global_mem_size
+    }
+
+    /// The maximum number of work-items in a work-group on this device.
+    pub fn max_work_group_size(&self) -> usize {
+        self.max_work_group_size
+    }
+
+    /// Whether this device can compute on half-precision floats (the `cl_khr_fp16` extension).
+    pub fn fp16(&self) -> bool {
+        self.fp16
+    }
+
+    /// The number of [`crate::Queue`]s currently using this device.
+    pub fn load(&self) -> usize {
+        self.queues.load(Ordering::Relaxed)
+    }
+
+    #[cfg(feature = "opencl")]
+    pub(crate) fn cl_device(&self) -> ocl::Device {
+        self.cl_device
+    }
+
+    // count a queue using this device until the returned guard is dropped
+    #[cfg(feature = "opencl")]
+    pub(crate) fn acquire(&self) -> DeviceLoad {
+        self.queues.fetch_add(1, Ordering::Relaxed);
+        DeviceLoad {
+            queues: self.queues.clone(),
+        }
+    }
+}
+
+/// A guard which counts a queue using a [`Device`] for as long as it's held
+#[cfg(feature = "opencl")]
+pub(crate) struct DeviceLoad {
+    queues: Arc<AtomicUsize>,
+}
+
+#[cfg(feature = "opencl")]
+impl Clone for DeviceLoad {
+    fn clone(&self) -> Self {
+        self.queues.fetch_add(1, Ordering::Relaxed);
+        Self {
+            queues: self.queues.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl Drop for DeviceLoad {
+    fn drop(&mut self) {
+        self.queues.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+// return `true` if the given `name` or `vendor` contains `query`, ignoring case
+pub(crate) fn matches(query: &str, name: &str, vendor: &str) -> bool {
+    let query = query.to_lowercase();
+    name.to_lowercase().contains(&query) || vendor.to_lowercase().contains(&query)
+}
+
+/// A policy to select the device on which to enqueue an array operation
+pub trait DevicePolicy: Send + Sync {
+    /// Return the index of the device in `devices` on which to enqueue an operation
+    /// whose output has `size_hint` elements, or `None` to enqueue it on the host.
+    fn select(&self, devices: &[Device], size_hint: usize) -> Option<usize>;
+}
+
+/// A [`DevicePolicy`] which enqueues every operation on the host
+#[derive(Copy, Clone, Debug, Default)]
+pub struct HostOnly;
+
+impl DevicePolicy for HostOnly {
+    fn select(&self, _devices: &[Device], _size_hint: usize) -> Option<usize> {
+        None
+    }
+}
+
+/// A [`DevicePolicy`] which enqueues every operation on the device with the given index,
+/// or on the host if there is no such device
+#[derive(Copy, Clone, Debug)]
+pub struct Pinned {
+    index: usize,
+}
+
+impl Pinned {
+    /// Construct a new [`Pinned`] policy for the device at `index`.
+    pub fn new(index: usize) -> Self {
+        Self { index }
+    }
+}
+
+impl DevicePolicy for Pinned {
+    fn select(&self, devices: &[Device], _size_hint: usize) -> Option<usize> {
+        if self.index < devices.len() {
+            Some(self.index)
+        } else {
+            None
+        }
+    }
+}
+
+/// A [`DevicePolicy`] which enqueues an operation on a CPU if it's smaller than `gpu_min`,
+/// on a GPU if it's smaller than `acc_min`, and otherwise on an accelerator,
+/// falling back to the next smaller class of device if there is none available.
+///
+/// Operations are distributed across the devices of each class in round-robin order.
+#[derive(Debug)]
+pub struct SizeThresholds {
+    gpu_min: usize,
+    acc_min: usize,
+    next: [AtomicUsize; 3],
+}
+
+impl SizeThresholds {
+    /// Construct a new [`SizeThresholds`] policy.
+    pub fn new(gpu_min: usize, acc_min: usize) -> Self {
+        Self {
+            gpu_min,
+            acc_min,
+            next: Default::default(),
+        }
+    }
+
+    /// The minimum size of an operation to enqueue on a GPU.
+    pub fn gpu_min(&self) -> usize {
+        self.gpu_min
+    }
+
+    /// The minimum size of an operation to enqueue on an accelerator.
+    pub fn acc_min(&self) -> usize {
+        self.acc_min
+    }
+
+    fn next(&self, devices: &[Device], kind: DeviceKind) -> Option<usize> {
+        let count = devices.iter().filter(|device| device.kind == kind).count();
+
+        if count == 0 {
+            return None;
+        }
+
+        let next = &self.next[kind as usize];
+        let n = next.fetch_add(1, Ordering::Relaxed) % count;
+
+        devices
+            .iter()
+            .enumerate()
+            .filter(|(_, device)| device.kind == kind)
+            .nth(n)
+            .map(|(i, _)| i)
+    }
+}
+
+impl DevicePolicy for SizeThresholds {
+    fn select(&self, devices: &[Device], size_hint: usize) -> Option<usize> {
+        let kinds: &[DeviceKind] = if size_hint < self.gpu_min {
+            &[DeviceKind::CPU]
+        } else if size_hint < self.acc_min {
+            &[DeviceKind::GPU, DeviceKind::CPU]
+        } else {
+            &[DeviceKind::Accelerator, DeviceKind::GPU, DeviceKind::CPU]
+        };
+
+        kinds.iter().find_map(|kind| self.next(devices, *kind))
+    }
+}
+
+/// A [`DevicePolicy`] which enqueues every operation on the device
+/// with the fewest queues currently using it
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LeastLoaded;
+
+impl DevicePolicy for LeastLoaded {
+    fn select(&self, devices: &[Device], _size_hint: usize) -> Option<usize> {
+        devices
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, device)| device.load())
+            .map(|(i, _)| i)
+    }
+}
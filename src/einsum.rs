@@ -0,0 +1,369 @@
+use std::collections::HashMap;
+
+use super::{Array, CDatatype, Error, MatrixMath, NDArray, NDArrayReduce, NDArrayTransform, Shape};
+
+/// Construct a tensor contraction of the given `operands` in Einstein summation notation,
+/// e.g. `"bij,bjk->bik"` for a batch matrix multiplication.
+///
+/// If the output subscripts are omitted (e.g. `"ij,jk"`), the output consists of the labels
+/// which appear exactly once, in alphabetical order. Labels repeated within a single operand
+/// select its diagonal, and labels omitted from the output are summed over.
+/// The contraction is lowered to transposes, reshapes, reductions, and matrix multiplications,
+/// contracting two operands at a time in the order which minimizes the size of each intermediate.
+pub fn einsum<T: CDatatype>(subscripts: &str, operands: Vec<Array<T>>) -> Result<Array<T>, Error> {
+    let (inputs, output) = parse_subscripts(subscripts, operands.len())?;
+
+    let mut dims = HashMap::<usize, usize>::new();
+    for (labels, operand) in inputs.iter().zip(&operands) {
+        if labels.len() != operand.ndim() {
+            return Err(Error::Bounds(format!(
+                "einsum subscripts {subscripts} do not match an operand with shape {:?}",
+                operand.shape()
+            )));
+        }
+
+        for (label, dim) in labels.iter().zip(operand.shape()) {
+            let expected = *dims.entry(*label).or_insert(*dim);
+            if expected != *dim {
+                return Err(Error::Bounds(format!(
+                    "einsum label {} has inconsistent dimensions {expected} and {dim}",
+                    char::from_u32(*label as u32).unwrap_or('?'),
+                )));
+            }
+        }
+    }
+
+    let mut operands = operands
+        .into_iter()
+        .zip(inputs)
+        .map(|(array, labels)| Operand { array, labels }.diagonals())
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    while operands.len() > 1 {
+        let (i, j) = contraction_order(&operands, &output, &dims);
+        debug_assert!(i < j);
+
+        let right = operands.remove(j);
+        let left = operands.remove(i);
+
+        let keep = |label: usize| {
+            output.contains(&label) || operands.iter().any(|op| op.labels.contains(&label))
+        };
+
+        let product = contract(left, right, keep, &dims)?;
+        operands.push(product);
+    }
+
+    let operand = operands.pop().expect("operand");
+    let operand = operand.sum(|label| output.contains(&label))?;
+    operand.permute(&output)
+}
+
+/// Contract the given `axes` of `left` with the given `axes` of `right`,
+/// resulting in an array with the remaining axes of `left` followed by those of `right`.
+pub(crate) fn tensordot<T: CDatatype>(
+    left: Array<T>,
+    right: Array<T>,
+    axes: (Vec<usize>, Vec<usize>),
+) -> Result<Array<T>, Error> {
+    let (left_axes, right_axes) = axes;
+
+    let valid = |axes: &[usize], ndim: usize| {
+        axes.iter().all(|x| *x < ndim) && (1..axes.len()).all(|i| !axes[..i].contains(&axes[i]))
+    };
+
+    if left_axes.len() != right_axes.len()
+        || !valid(&left_axes, left.ndim())
+        || !valid(&right_axes, right.ndim())
+        || left_axes
+            .iter()
+            .zip(&right_axes)
+            .any(|(l, r)| left.shape()[*l] != right.shape()[*r])
+    {
+        return Err(Error::Bounds(format!(
+            "invalid axes {left_axes:?} and {right_axes:?} for tensordot of {left:?} and {right:?}"
+        )));
+    }
+
+    let mut dims = HashMap::new();
+    dims.extend(left.shape().iter().copied().enumerate());
+
+    let left_labels = (0..left.ndim()).collect::<Vec<_>>();
+    let right_labels = (0..right.ndim())
+        .map(|x| match right_axes.iter().position(|y| *y == x) {
+            Some(i) => left_axes[i],
+            None => left.ndim() + x,
+        })
+        .collect::<Vec<_>>();
+
+    for (label, dim) in right_labels.iter().zip(right.shape()) {
+        dims.insert(*label, *dim);
+    }
+
+    let left = Operand {
+        array: left,
+        labels: left_labels,
+    };
+
+    let right = Operand {
+        array: right,
+        labels: right_labels,
+    };
+
+    let product = contract(left, right, |label| !left_axes.contains(&label), &dims)?;
+    debug_assert!(product.labels.windows(2).all(|w| w[0] < w[1]));
+    Ok(product.array)
+}
+
+// an array whose axes are labelled, where a scalar has no labels and a shape of [1]
+struct Operand<T: CDatatype> {
+    array: Array<T>,
+    labels: Vec<usize>,
+}
+
+impl<T: CDatatype> Operand<T> {
+    fn shape(labels: &[usize], dims: &HashMap<usize, usize>) -> Shape {
+        if labels.is_empty() {
+            vec![1]
+        } else {
+            labels.iter().map(|label| dims[label]).collect()
+        }
+    }
+
+    // replace each label repeated within this operand with the diagonal along its axes
+    fn diagonals(mut self) -> Result<Self, Error> {
+        while let Some((x, y)) = (0..self.labels.len()).find_map(|x| {
+            (x + 1..self.labels.len())
+                .find(|y| self.labels[*y] == self.labels[x])
+                .map(|y| (x, y))
+        }) {
+            let label = self.labels[x];
+
+            let mut permutation = (0..self.labels.len())
+                .filter(|i| *i != x && *i != y)
+                .collect::<Vec<_>>();
+
+            let mut labels = permutation
+                .iter()
+                .map(|i| self.labels[*i])
+                .collect::<Vec<_>>();
+
+            permutation.push(x);
+            permutation.push(y);
+            labels.push(label);
+
+            let array = self.array.transpose(Some(permutation))?;
+            self.array = array.diagonal(0).map(Array::from)?;
+            self.labels = labels;
+        }
+
+        Ok(self)
+    }
+
+    // transpose this operand so that its labels are in the given `order`
+    fn permute(self, order: &[usize]) -> Result<Array<T>, Error> {
+        debug_assert_eq!(order.len(), self.labels.len());
+
+        let permutation = order
+            .iter()
+            .map(|label| self.labels.iter().position(|l| l == label).expect("label"))
+            .collect::<Vec<_>>();
+
+        if permutation.iter().copied().enumerate().all(|(i, x)| i == x) {
+            Ok(self.array)
+        } else {
+            self.array.transpose(Some(permutation))
+        }
+    }
+
+    // sum over each axis whose label is not kept
+    fn sum<K: Fn(usize) -> bool>(self, keep: K) -> Result<Self, Error> {
+        let axes = self
+            .labels
+            .iter()
+            .enumerate()
+            .filter(|(_x, label)| !keep(**label))
+            .map(|(x, _label)| x)
+            .collect::<Vec<_>>();
+
+        if axes.is_empty() {
+            return Ok(self);
+        }
+
+        let labels = self
+            .labels
+            .iter()
+            .copied()
+            .filter(|label| keep(*label))
+            .collect();
+
+        let array = self.array.sum(axes, false).map(Array::from)?;
+        Ok(Self { array, labels })
+    }
+}
+
+// contract two operands with a (batched) matrix multiplication, summing over each label
+// which is not kept and keeping the batch labels, then the free labels of `left` and `right`
+fn contract<T: CDatatype, K: Fn(usize) -> bool>(
+    left: Operand<T>,
+    right: Operand<T>,
+    keep: K,
+    dims: &HashMap<usize, usize>,
+) -> Result<Operand<T>, Error> {
+    let left = left.sum(|label| keep(label) || right.labels.contains(&label))?;
+    let right = right.sum(|label| keep(label) || left.labels.contains(&label))?;
+
+    let (shared, free_left): (Vec<usize>, Vec<usize>) = left
+        .labels
+        .iter()
+        .copied()
+        .partition(|label| right.labels.contains(label));
+
+    let (batch, contracted): (Vec<usize>, Vec<usize>) =
+        shared.into_iter().partition(|label| keep(*label));
+
+    let free_right = right
+        .labels
+        .iter()
+        .copied()
+        .filter(|label| !left.labels.contains(label))
+        .collect::<Vec<_>>();
+
+    let batch_shape = batch.iter().map(|label| dims[label]).collect::<Vec<_>>();
+    let size = |labels: &[usize]| labels.iter().map(|label| dims[label]).product::<usize>();
+
+    let mut left_order = batch.clone();
+    left_order.extend_from_slice(&free_left);
+    left_order.extend_from_slice(&contracted);
+
+    let mut left_shape = batch_shape.clone();
+    left_shape.push(size(&free_left));
+    left_shape.push(size(&contracted));
+
+    let mut right_order = batch.clone();
+    right_order.extend_from_slice(&contracted);
+    right_order.extend_from_slice(&free_right);
+
+    let mut right_shape = batch_shape;
+    right_shape.push(size(&contracted));
+    right_shape.push(size(&free_right));
+
+    let left = left.permute(&left_order)?.reshape(left_shape)?;
+    let right = right.permute(&right_order)?.reshape(right_shape)?;
+
+    let mut labels = batch;
+    labels.extend(free_left);
+    labels.extend(free_right);
+
+    let shape = Operand::<T>::shape(&labels, dims);
+    let array = left.matmul(right).map(Array::from)?.reshape(shape)?;
+
+    Ok(Operand { array, labels })
+}
+
+// choose the next pair of operands to contract, greedily minimizing the size of the product
+fn contraction_order<T: CDatatype>(
+    operands: &[Operand<T>],
+    output: &[usize],
+    dims: &HashMap<usize, usize>,
+) -> (usize, usize) {
+    let mut order = (0, 1);
+    let mut min_size = usize::MAX;
+
+    for i in 0..operands.len() {
+        for j in (i + 1)..operands.len() {
+            let mut size = 1usize;
+            let mut labels = operands[i].labels.clone();
+            labels.extend(&operands[j].labels);
+            labels.sort();
+            labels.dedup();
+
+            for label in labels {
+                let kept = output.contains(&label)
+                    || operands
+                        .iter()
+                        .enumerate()
+                        .any(|(k, op)| k != i && k != j && op.labels.contains(&label));
+
+                if kept {
+                    size = size.saturating_mul(dims[&label]);
+                }
+            }
+
+            if size < min_size {
+                min_size = size;
+                order = (i, j);
+            }
+        }
+    }
+
+    order
+}
+
+// parse the subscripts into a list of labels for each operand and for the output
+fn parse_subscripts(
+    subscripts: &str,
+    num_operands: usize,
+) -> Result<(Vec<Vec<usize>>, Vec<usize>), Error> {
+    let invalid = |cause: &str| {
+        Error::Interface(format!("invalid einsum subscripts {subscripts:?}: {cause}"))
+    };
+
+    let labels = |spec: &str| {
+        spec.chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| {
+                if c.is_ascii_alphabetic() {
+                    Ok(c as usize)
+                } else {
+                    Err(invalid("labels must be ASCII letters"))
+                }
+            })
+            .collect::<Result<Vec<_>, Error>>()
+    };
+
+    let (inputs, output) = match subscripts.split_once("->") {
+        Some((inputs, output)) => (inputs, Some(output)),
+        None => (subscripts, None),
+    };
+
+    let inputs = inputs
+        .split(',')
+        .map(labels)
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    if inputs.len() != num_operands {
+        return Err(invalid(&format!(
+            "expected subscripts for {num_operands} operands but found {}",
+            inputs.len()
+        )));
+    }
+
+    let count = |label: usize| inputs.iter().flatten().filter(|l| **l == label).count();
+
+    let output = if let Some(output) = output {
+        let output = labels(output)?;
+
+        for (i, label) in output.iter().enumerate() {
+            if output[..i].contains(label) {
+                return Err(invalid("output labels must be unique"));
+            } else if count(*label) == 0 {
+                return Err(invalid("each output label must appear in an input"));
+            }
+        }
+
+        output
+    } else {
+        let mut output = inputs
+            .iter()
+            .flatten()
+            .copied()
+            .filter(|label| count(*label) == 1)
+            .collect::<Vec<_>>();
+
+        output.sort();
+        output
+    };
+
+    Ok((inputs, output))
+}
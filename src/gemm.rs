@@ -0,0 +1,314 @@
+//! A cache-blocked, register-tiled matrix multiplication for the host CPU.
+//!
+//! The loop structure follows the BLIS design: the right matrix is packed into panels of
+//! `KC` rows and `NR` columns which fit in the L2 cache, the left matrix into panels of `MC`
+//! rows and `KC` columns which fit in the L1 cache, and each `MR x NR` tile of the output
+//! is computed by a micro-kernel which keeps its accumulators in registers.
+
+use std::any::TypeId;
+
+use rayon::prelude::*;
+
+use super::CDatatype;
+
+// the depth of a packed panel
+const KC: usize = 256;
+// the maximum number of rows of the left matrix packed per task
+const MC: usize = 96;
+// the maximum number of columns of the right matrix packed at once
+const NC: usize = 4096;
+// the minimum number of multiply-adds per task, to amortize the cost of scheduling it
+const MIN_TASK_SIZE: usize = 1 << 16;
+
+type Kernel<T, const MR: usize, const NR: usize> = fn(usize, &[T], &[T], &mut [[T; NR]; MR]);
+
+/// Compute the product of the `a x b` matrix `left` and the `b x c` matrix `right`,
+/// both in row-major order, writing the `a x c` result to `output`.
+pub(crate) fn gemm<T: CDatatype>(left: &[T], right: &[T], output: &mut [T], dims: [usize; 3]) {
+    let [a, b, c] = dims;
+    debug_assert_eq!(left.len(), a * b);
+    debug_assert_eq!(right.len(), b * c);
+    debug_assert_eq!(output.len(), a * c);
+
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx") && is_x86_feature_detected!("fma") {
+        if let (Some(left), Some(right), Some(output)) = (cast(left), cast(right), cast_mut(output))
+        {
+            return blocked::<f32, 6, 16>(left, right, output, dims, x86::kernel_f32);
+        }
+
+        if let (Some(left), Some(right), Some(output)) = (cast(left), cast(right), cast_mut(output))
+        {
+            return blocked::<f64, 6, 8>(left, right, output, dims, x86::kernel_f64);
+        }
+    }
+
+    blocked::<T, 4, 8>(left, right, output, dims, kernel)
+}
+
+fn blocked<T: CDatatype, const MR: usize, const NR: usize>(
+    left: &[T],
+    right: &[T],
+    output: &mut [T],
+    dims: [usize; 3],
+    kernel: Kernel<T, MR, NR>,
+) {
+    let [a, b, c] = dims;
+
+    output.iter_mut().for_each(|o| *o = T::zero());
+
+    if a == 0 || b == 0 || c == 0 {
+        return;
+    }
+
+    // split the rows of the output into as many tasks as there are threads,
+    // as long as each task is large enough to be worth scheduling
+    let max_tasks = (a * b * c / MIN_TASK_SIZE).clamp(1, rayon::current_num_threads());
+    let mc = a.div_ceil(max_tasks).div_ceil(MR) * MR;
+    let mc = mc.min(MC);
+
+    // if there are too few row blocks to occupy every task (e.g. a single row),
+    // also split the columns of each row block into groups of column strips
+    let col_groups = (max_tasks / a.div_ceil(mc)).max(1);
+
+    for jc in (0..c).step_by(NC) {
+        let nc = NC.min(c - jc);
+
+        let strips = nc.div_ceil(NR);
+        let group_strips = strips.div_ceil(col_groups);
+        let group_width = group_strips * NR;
+
+        for pc in (0..b).step_by(KC) {
+            let kc = KC.min(b - pc);
+            let right = pack_right::<T, NR>(right, c, [pc, kc], [jc, nc]);
+
+            // each task computes the columns of one group of strips, for one block of rows
+            let mut tasks = Vec::with_capacity(a.div_ceil(mc) * col_groups);
+
+            for (i, block) in output.chunks_mut(mc * c).enumerate() {
+                let mut groups = (0..strips.div_ceil(group_strips))
+                    .map(|g| (i * mc, g * group_strips, Vec::with_capacity(mc)))
+                    .collect::<Vec<_>>();
+
+                for row in block.chunks_exact_mut(c) {
+                    let mut row = &mut row[jc..jc + nc];
+
+                    for (_, _, segments) in groups.iter_mut() {
+                        let (segment, rest) = row.split_at_mut(group_width.min(row.len()));
+                        segments.push(segment);
+                        row = rest;
+                    }
+                }
+
+                tasks.extend(groups);
+            }
+
+            let task = |(ic, js, mut output): (usize, usize, Vec<&mut [T]>)| {
+                let mc = output.len();
+                let left = pack_left::<T, MR>(left, b, [ic, mc], [pc, kc]);
+
+                let strips = right.chunks_exact(kc * NR).enumerate();
+                for (jr, right) in strips.skip(js).take(group_strips) {
+                    let cols = NR.min(nc - (jr * NR));
+                    let offset = (jr - js) * NR;
+
+                    for (ir, left) in left.chunks_exact(kc * MR).enumerate() {
+                        let rows = MR.min(mc - (ir * MR));
+
+                        let mut acc = [[T::zero(); NR]; MR];
+                        kernel(kc, left, right, &mut acc);
+
+                        for (i, acc) in acc.iter().take(rows).enumerate() {
+                            let row = &mut output[(ir * MR) + i][offset..offset + cols];
+
+                            for (o, x) in row.iter_mut().zip(acc) {
+                                *o = *o + *x;
+                            }
+                        }
+                    }
+                }
+            };
+
+            if tasks.len() > 1 {
+                tasks.into_par_iter().for_each(task);
+            } else {
+                tasks.into_iter().for_each(task);
+            }
+        }
+    }
+}
+
+// pack the `kc x nc` block of `right` at (`pc`, `jc`) into column strips of width `NR`,
+// each stored in row-major order and padded with zeros
+fn pack_right<T: CDatatype, const NR: usize>(
+    right: &[T],
+    c: usize,
+    [pc, kc]: [usize; 2],
+    [jc, nc]: [usize; 2],
+) -> Vec<T> {
+    let strips = nc.div_ceil(NR);
+    let mut packed = vec![T::zero(); strips * kc * NR];
+
+    let pack = |(s, strip): (usize, &mut [T])| {
+        let start = jc + (s * NR);
+        let cols = NR.min(nc - (s * NR));
+
+        for (p, row) in strip.chunks_exact_mut(NR).enumerate() {
+            let offset = ((pc + p) * c) + start;
+            row[..cols].copy_from_slice(&right[offset..offset + cols]);
+        }
+    };
+
+    if strips * kc * NR >= MIN_TASK_SIZE {
+        packed.par_chunks_mut(kc * NR).enumerate().for_each(pack);
+    } else {
+        packed.chunks_mut(kc * NR).enumerate().for_each(pack);
+    }
+
+    packed
+}
+
+// pack the `mc x kc` block of `left` at (`ic`, `pc`) into row panels of height `MR`,
+// each stored in column-major order and padded with zeros
+fn pack_left<T: CDatatype, const MR: usize>(
+    left: &[T],
+    b: usize,
+    [ic, mc]: [usize; 2],
+    [pc, kc]: [usize; 2],
+) -> Vec<T> {
+    let panels = mc.div_ceil(MR);
+    let mut packed = vec![T::zero(); panels * kc * MR];
+
+    for (r, panel) in packed.chunks_exact_mut(kc * MR).enumerate() {
+        let rows = MR.min(mc - (r * MR));
+
+        for i in 0..rows {
+            let offset = ((ic + (r * MR) + i) * b) + pc;
+
+            for (p, x) in left[offset..offset + kc].iter().enumerate() {
+                panel[(p * MR) + i] = *x;
+            }
+        }
+    }
+
+    packed
+}
+
+// compute an `MR x NR` tile of the output from a packed left panel and a packed right strip,
+// using fixed-size arrays so that the compiler can keep the accumulators in vector registers
+fn kernel<T: CDatatype, const MR: usize, const NR: usize>(
+    kc: usize,
+    left: &[T],
+    right: &[T],
+    acc: &mut [[T; NR]; MR],
+) {
+    debug_assert_eq!(left.len(), kc * MR);
+    debug_assert_eq!(right.len(), kc * NR);
+
+    for (l, r) in left.chunks_exact(MR).zip(right.chunks_exact(NR)) {
+        for (acc, l) in acc.iter_mut().zip(l) {
+            for (acc, r) in acc.iter_mut().zip(r) {
+                *acc = *acc + (*l * *r);
+            }
+        }
+    }
+}
+
+pub(crate) fn cast<T: 'static, U: 'static>(slice: &[T]) -> Option<&[U]> {
+    if TypeId::of::<T>() == TypeId::of::<U>() {
+        // SAFETY: T and U are the same type
+        Some(unsafe { std::slice::from_raw_parts(slice.as_ptr() as *const U, slice.len()) })
+    } else {
+        None
+    }
+}
+
+pub(crate) fn cast_mut<T: 'static, U: 'static>(slice: &mut [T]) -> Option<&mut [U]> {
+    if TypeId::of::<T>() == TypeId::of::<U>() {
+        // SAFETY: T and U are the same type
+        Some(unsafe { std::slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut U, slice.len()) })
+    } else {
+        None
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    // these kernels must only be called after checking that the CPU supports AVX and FMA
+
+    pub fn kernel_f32(kc: usize, left: &[f32], right: &[f32], acc: &mut [[f32; 16]; 6]) {
+        assert_eq!(left.len(), kc * 6);
+        assert_eq!(right.len(), kc * 16);
+
+        // SAFETY: the caller has checked for AVX and FMA support, and the bounds are checked above
+        unsafe { kernel_f32_avx(kc, left.as_ptr(), right.as_ptr(), acc) }
+    }
+
+    pub fn kernel_f64(kc: usize, left: &[f64], right: &[f64], acc: &mut [[f64; 8]; 6]) {
+        assert_eq!(left.len(), kc * 6);
+        assert_eq!(right.len(), kc * 8);
+
+        // SAFETY: the caller has checked for AVX and FMA support, and the bounds are checked above
+        unsafe { kernel_f64_avx(kc, left.as_ptr(), right.as_ptr(), acc) }
+    }
+
+    #[target_feature(enable = "avx,fma")]
+    unsafe fn kernel_f32_avx(
+        kc: usize,
+        mut left: *const f32,
+        mut right: *const f32,
+        acc: &mut [[f32; 16]; 6],
+    ) {
+        let mut c = [[_mm256_setzero_ps(); 2]; 6];
+
+        for _ in 0..kc {
+            let r0 = _mm256_loadu_ps(right);
+            let r1 = _mm256_loadu_ps(right.add(8));
+
+            for (i, c) in c.iter_mut().enumerate() {
+                let l = _mm256_broadcast_ss(&*left.add(i));
+                c[0] = _mm256_fmadd_ps(l, r0, c[0]);
+                c[1] = _mm256_fmadd_ps(l, r1, c[1]);
+            }
+
+            left = left.add(6);
+            right = right.add(16);
+        }
+
+        for (acc, c) in acc.iter_mut().zip(c) {
+            _mm256_storeu_ps(acc.as_mut_ptr(), c[0]);
+            _mm256_storeu_ps(acc.as_mut_ptr().add(8), c[1]);
+        }
+    }
+
+    #[target_feature(enable = "avx,fma")]
+    unsafe fn kernel_f64_avx(
+        kc: usize,
+        mut left: *const f64,
+        mut right: *const f64,
+        acc: &mut [[f64; 8]; 6],
+    ) {
+        let mut c = [[_mm256_setzero_pd(); 2]; 6];
+
+        for _ in 0..kc {
+            let r0 = _mm256_loadu_pd(right);
+            let r1 = _mm256_loadu_pd(right.add(4));
+
+            for (i, c) in c.iter_mut().enumerate() {
+                let l = _mm256_broadcast_sd(&*left.add(i));
+                c[0] = _mm256_fmadd_pd(l, r0, c[0]);
+                c[1] = _mm256_fmadd_pd(l, r1, c[1]);
+            }
+
+            left = left.add(6);
+            right = right.add(8);
+        }
+
+        for (acc, c) in acc.iter_mut().zip(c) {
+            _mm256_storeu_pd(acc.as_mut_ptr(), c[0]);
+            _mm256_storeu_pd(acc.as_mut_ptr().add(4), c[1]);
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! Half-precision floating point types which can be stored in host or OpenCL buffers
+
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+// `ocl::OclPrm` cannot be implemented for a foreign type, so each type from the `half` crate
+// is wrapped in a local newtype with the same memory layout
+macro_rules! half_float {
+    ($name:ident, $t:ty, $doc:expr) => {
+        #[doc = $doc]
+        #[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+        #[repr(transparent)]
+        pub struct $name(pub $t);
+
+        impl $name {
+            /// The largest finite value of this type
+            pub const MAX: Self = Self(<$t>::MAX);
+            /// The smallest finite value of this type
+            pub const MIN: Self = Self(<$t>::MIN);
+            /// One
+            pub const ONE: Self = Self(<$t>::ONE);
+            /// Zero
+            pub const ZERO: Self = Self(<$t>::ZERO);
+
+            /// Convert a single-precision float to the nearest value of this type.
+            pub fn from_f32(f: f32) -> Self {
+                Self(<$t>::from_f32(f))
+            }
+
+            /// Convert a double-precision float to the nearest value of this type.
+            pub fn from_f64(f: f64) -> Self {
+                Self(<$t>::from_f64(f))
+            }
+
+            /// Construct a value of this type from its raw bit pattern.
+            pub fn from_bits(bits: u16) -> Self {
+                Self(<$t>::from_bits(bits))
+            }
+
+            /// Convert this value to a single-precision float, without loss of precision.
+            pub fn to_f32(self) -> f32 {
+                self.0.to_f32()
+            }
+
+            /// Convert this value to a double-precision float, without loss of precision.
+            pub fn to_f64(self) -> f64 {
+                self.0.to_f64()
+            }
+
+            /// Return the raw bit pattern of this value.
+            pub fn to_bits(self) -> u16 {
+                self.0.to_bits()
+            }
+        }
+
+        impl From<$t> for $name {
+            fn from(value: $t) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for $t {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self(self.0 + rhs.0)
+            }
+        }
+
+        impl Div for $name {
+            type Output = Self;
+
+            fn div(self, rhs: Self) -> Self {
+                Self(self.0 / rhs.0)
+            }
+        }
+
+        impl Mul for $name {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self {
+                Self(self.0 * rhs.0)
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self(-self.0)
+            }
+        }
+
+        impl Rem for $name {
+            type Output = Self;
+
+            fn rem(self, rhs: Self) -> Self {
+                Self(self.0 % rhs.0)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self(self.0 - rhs.0)
+            }
+        }
+
+        impl Sum for $name {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                Self::from_f32(iter.map(Self::to_f32).sum())
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        #[cfg(feature = "opencl")]
+        unsafe impl ocl::OclPrm for $name {}
+    };
+}
+
+half_float!(
+    F16,
+    half::f16,
+    "An IEEE 754 half-precision floating point number"
+);
+
+half_float!(
+    BF16,
+    half::bf16,
+    "A bfloat16 floating point number, with the exponent range of an `f32`"
+);
@@ -7,17 +7,39 @@ extern crate ocl;
 use std::convert::identity;
 use std::fmt;
 use std::iter::Sum;
-use std::ops::{Add, Div, Mul, Range, Rem, Sub};
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Range, Rem, Sub};
+use std::sync::Arc;
+#[cfg(feature = "opencl")]
+use std::sync::Mutex;
 
 pub use array::*;
 pub use buffer::*;
+pub use device::{Device, DeviceKind, DevicePolicy, HostOnly, LeastLoaded, Pinned, SizeThresholds};
+pub use einsum::einsum;
+#[cfg(feature = "half")]
+pub use half_float::{BF16, F16};
+#[cfg(feature = "complex")]
+pub use num_complex::Complex;
 use ops::*;
+pub use pool::{BufferPool, PoolStats, Pooled, Recycle};
+#[cfg(feature = "fft")]
+use rustfft::FftNum;
+pub use shard::ShardedArray;
 
 mod array;
+#[cfg(feature = "blas")]
+mod blas;
 mod buffer;
 #[cfg(feature = "opencl")]
 mod cl_programs;
+mod device;
+mod einsum;
+mod gemm;
+#[cfg(feature = "half")]
+mod half_float;
 pub mod ops;
+mod pool;
+mod shard;
 
 /// N-dimensional array constructor op definitions
 pub mod construct {
@@ -25,11 +47,14 @@ pub mod construct {
 }
 
 const GPU_MIN_DEFAULT: usize = 1024;
+const PARALLEL_MIN_DEFAULT: usize = 1024;
 
 /// An array math error
 pub enum Error {
     Bounds(String),
     Interface(String),
+    OutOfMemory(String),
+    Singular(String),
     #[cfg(feature = "opencl")]
     OCL(ocl::Error),
 }
@@ -46,6 +71,8 @@ impl fmt::Debug for Error {
         match self {
             Self::Bounds(cause) => f.write_str(cause),
             Self::Interface(cause) => f.write_str(cause),
+            Self::OutOfMemory(cause) => f.write_str(cause),
+            Self::Singular(cause) => f.write_str(cause),
             #[cfg(feature = "opencl")]
             Self::OCL(cause) => cause.fmt(f),
         }
@@ -57,6 +84,8 @@ impl fmt::Display for Error {
         match self {
             Self::Bounds(cause) => f.write_str(cause),
             Self::Interface(cause) => f.write_str(cause),
+            Self::OutOfMemory(cause) => f.write_str(cause),
+            Self::Singular(cause) => f.write_str(cause),
             #[cfg(feature = "opencl")]
             Self::OCL(cause) => cause.fmt(f),
         }
@@ -71,6 +100,9 @@ pub type Shape = Vec<usize>;
 // TODO: is there a better way to implement the OclPrm trait bound?
 // TODO: rename to CType
 /// A type which supports hardware-accelerated arithmetic operations
+///
+/// This does not imply [`PartialOrd`], since complex numbers are not ordered:
+/// generic code which orders elements must require `PartialOrd` explicitly.
 #[cfg(feature = "opencl")]
 pub trait CDatatype:
     ocl::OclPrm
@@ -81,7 +113,6 @@ pub trait CDatatype:
     + Rem<Output = Self>
     + Sub<Output = Self>
     + PartialEq
-    + PartialOrd
     + Sum
     + Send
     + Sync
@@ -89,6 +120,14 @@ pub trait CDatatype:
 {
     const TYPE_STR: &'static str;
 
+    /// Whether OpenCL kernels can compute on this type, rather than only store it.
+    /// Ops on types which OpenCL can only store run on the host.
+    const CL_COMPUTE: bool = true;
+
+    /// Whether OpenCL kernels can only compute on this type with the `cl_khr_fp16` extension,
+    /// so that ops on this type run on the host unless every device supports it.
+    const CL_FP16: bool = false;
+
     type Float: Float;
     type Neg: CDatatype;
 
@@ -118,6 +157,58 @@ pub trait CDatatype:
 
     fn round(self) -> Self;
 
+    /// Compute the sum of the given `values`.
+    ///
+    /// Reduced-precision types override this to accumulate in a wider type.
+    fn sum_slice(values: &[Self]) -> Self {
+        // chunk the sum to encourage the compiler to vectorize
+        values
+            .chunks(8)
+            .map(|chunk| chunk.iter().copied().fold(Self::zero(), Add::add))
+            .fold(Self::zero(), Add::add)
+    }
+
+    /// Compute the sum of the given `values` in parallel,
+    /// splitting them into pieces of at least `min_len` chunks of eight values.
+    ///
+    /// Reduced-precision types override this to accumulate the partial sums in a wider type.
+    fn par_sum(values: &[Self], min_len: usize) -> Self {
+        use rayon::prelude::*;
+
+        values
+            .par_chunks(8)
+            .with_min_len(min_len)
+            .map(Self::sum_slice)
+            .reduce(Self::zero, Add::add)
+    }
+
+    /// Compute the dot product of the given vectors, which must have the same length.
+    ///
+    /// Reduced-precision types override this to accumulate in a wider type.
+    fn dot(left: &[Self], right: &[Self]) -> Self {
+        debug_assert_eq!(left.len(), right.len());
+
+        // chunk the dot product to encourage the compiler to vectorize
+        left.chunks(8)
+            .zip(right.chunks(8))
+            .map(|(lc, rc)| {
+                lc.iter()
+                    .copied()
+                    .zip(rc.iter().copied())
+                    .map(|(l, r)| l * r)
+                    .sum()
+            })
+            .sum()
+    }
+
+    /// Compute the product of the `a x b` matrix `left` and the `b x c` matrix `right`,
+    /// both in row-major order, writing the `a x c` result to `output`.
+    ///
+    /// Reduced-precision types override this to accumulate in a wider type.
+    fn gemm(left: &[Self], right: &[Self], output: &mut [Self], dims: [usize; 3]) {
+        gemm::gemm(left, right, output, dims)
+    }
+
     fn to_float(self) -> Self::Float;
 
     fn to_f64(self) -> f64;
@@ -125,6 +216,9 @@ pub trait CDatatype:
 
 #[cfg(not(feature = "opencl"))]
 /// A type which supports hardware-accelerated arithmetic operations
+///
+/// This does not imply [`PartialOrd`], since complex numbers are not ordered:
+/// generic code which orders elements must require `PartialOrd` explicitly.
 pub trait CDatatype:
     Copy
     + Add<Output = Self>
@@ -133,7 +227,6 @@ pub trait CDatatype:
     + Rem<Output = Self>
     + Sub<Output = Self>
     + PartialEq
-    + PartialOrd
     + Sum
     + Send
     + Sync
@@ -170,6 +263,58 @@ pub trait CDatatype:
 
     fn round(self) -> Self;
 
+    /// Compute the sum of the given `values`.
+    ///
+    /// Reduced-precision types override this to accumulate in a wider type.
+    fn sum_slice(values: &[Self]) -> Self {
+        // chunk the sum to encourage the compiler to vectorize
+        values
+            .chunks(8)
+            .map(|chunk| chunk.iter().copied().fold(Self::zero(), Add::add))
+            .fold(Self::zero(), Add::add)
+    }
+
+    /// Compute the sum of the given `values` in parallel,
+    /// splitting them into pieces of at least `min_len` chunks of eight values.
+    ///
+    /// Reduced-precision types override this to accumulate the partial sums in a wider type.
+    fn par_sum(values: &[Self], min_len: usize) -> Self {
+        use rayon::prelude::*;
+
+        values
+            .par_chunks(8)
+            .with_min_len(min_len)
+            .map(Self::sum_slice)
+            .reduce(Self::zero, Add::add)
+    }
+
+    /// Compute the dot product of the given vectors, which must have the same length.
+    ///
+    /// Reduced-precision types override this to accumulate in a wider type.
+    fn dot(left: &[Self], right: &[Self]) -> Self {
+        debug_assert_eq!(left.len(), right.len());
+
+        // chunk the dot product to encourage the compiler to vectorize
+        left.chunks(8)
+            .zip(right.chunks(8))
+            .map(|(lc, rc)| {
+                lc.iter()
+                    .copied()
+                    .zip(rc.iter().copied())
+                    .map(|(l, r)| l * r)
+                    .sum()
+            })
+            .sum()
+    }
+
+    /// Compute the product of the `a x b` matrix `left` and the `b x c` matrix `right`,
+    /// both in row-major order, writing the `a x c` result to `output`.
+    ///
+    /// Reduced-precision types override this to accumulate in a wider type.
+    fn gemm(left: &[Self], right: &[Self], output: &mut [Self], dims: [usize; 3]) {
+        gemm::gemm(left, right, output, dims)
+    }
+
     fn to_float(self) -> Self::Float;
 
     fn to_f64(self) -> f64;
@@ -364,6 +509,294 @@ c_type!(
     identity
 );
 
+#[cfg(feature = "half")]
+macro_rules! half_type {
+    ($t:ty, $ct:expr, $cl_compute:expr, $cl_fp16:expr) => {
+        impl CDatatype for $t {
+            const TYPE_STR: &'static str = $ct;
+            #[cfg(feature = "opencl")]
+            const CL_COMPUTE: bool = $cl_compute;
+            #[cfg(feature = "opencl")]
+            const CL_FP16: bool = $cl_fp16;
+
+            type Float = f32;
+            type Neg = $t;
+
+            fn max() -> Self {
+                <$t>::MAX
+            }
+
+            fn min() -> Self {
+                <$t>::MIN
+            }
+
+            fn one() -> Self {
+                <$t>::ONE
+            }
+
+            fn zero() -> Self {
+                <$t>::ZERO
+            }
+
+            fn from_float(float: Self::Float) -> Self {
+                <$t>::from_f32(float)
+            }
+
+            fn from_f64(f: f64) -> Self {
+                <$t>::from_f64(f)
+            }
+
+            fn abs(self) -> Self {
+                <$t>::from_f32(self.to_f32().abs())
+            }
+
+            fn neg(self) -> Self::Neg {
+                -self
+            }
+
+            fn round(self) -> Self {
+                <$t>::from_f32(self.to_f32().round())
+            }
+
+            fn sum_slice(values: &[Self]) -> Self {
+                <$t>::from_f32(values.iter().copied().map(<$t>::to_f32).sum())
+            }
+
+            fn par_sum(values: &[Self], min_len: usize) -> Self {
+                use rayon::prelude::*;
+
+                let sum = values
+                    .par_chunks(8)
+                    .with_min_len(min_len)
+                    .map(|chunk| chunk.iter().copied().map(<$t>::to_f32).sum::<f32>())
+                    .sum();
+
+                <$t>::from_f32(sum)
+            }
+
+            fn dot(left: &[Self], right: &[Self]) -> Self {
+                debug_assert_eq!(left.len(), right.len());
+
+                let product = left
+                    .iter()
+                    .zip(right)
+                    .map(|(l, r)| l.to_f32() * r.to_f32())
+                    .sum();
+
+                <$t>::from_f32(product)
+            }
+
+            fn gemm(left: &[Self], right: &[Self], output: &mut [Self], dims: [usize; 3]) {
+                let [_a, b, c] = dims;
+
+                let mut right_t = vec![Self::zero(); b * c];
+                transpose::transpose(right, &mut right_t, c, b);
+
+                let product = left
+                    .chunks_exact(b)
+                    .flat_map(|row| right_t.chunks_exact(b).map(|col| Self::dot(row, col)));
+
+                for (o, x) in output.iter_mut().zip(product) {
+                    *o = x;
+                }
+            }
+
+            fn to_float(self) -> Self::Float {
+                self.to_f32()
+            }
+
+            fn to_f64(self) -> f64 {
+                <$t>::to_f64(self)
+            }
+        }
+    };
+}
+
+// `f16` is defined by each OpenCL program as `half` if every device of its context supports
+// the cl_khr_fp16 extension, or else as a `ushort` which can only be stored, not computed on
+#[cfg(feature = "half")]
+half_type!(F16, "f16", true, true);
+
+// OpenCL has no native bfloat16 type, so `bf16` is defined by each OpenCL program as a `ushort`
+// which can only be stored, not computed on
+#[cfg(feature = "half")]
+half_type!(BF16, "bf16", false, false);
+
+#[cfg(feature = "complex")]
+macro_rules! complex_type {
+    ($t:ty, $ct:expr) => {
+        impl CDatatype for Complex<$t> {
+            const TYPE_STR: &'static str = $ct;
+
+            type Float = $t;
+            type Neg = Self;
+
+            fn max() -> Self {
+                Complex::new(<$t>::MAX, <$t>::MAX)
+            }
+
+            fn min() -> Self {
+                Complex::new(<$t>::MIN, <$t>::MIN)
+            }
+
+            fn one() -> Self {
+                Complex::new(1., 0.)
+            }
+
+            fn zero() -> Self {
+                Complex::new(0., 0.)
+            }
+
+            fn from_float(float: Self::Float) -> Self {
+                Complex::new(float, 0.)
+            }
+
+            fn from_f64(f: f64) -> Self {
+                Complex::new(f as $t, 0.)
+            }
+
+            fn abs(self) -> Self {
+                Complex::new(self.norm(), 0.)
+            }
+
+            fn neg(self) -> Self::Neg {
+                -self
+            }
+
+            fn round(self) -> Self {
+                Complex::new(self.re.round(), self.im.round())
+            }
+
+            // like a cast to a real number, these discard the imaginary part
+
+            fn to_float(self) -> Self::Float {
+                self.re
+            }
+
+            fn to_f64(self) -> f64 {
+                self.re as f64
+            }
+        }
+    };
+}
+
+#[cfg(feature = "complex")]
+complex_type!(f32, "float2");
+
+#[cfg(feature = "complex")]
+complex_type!(f64, "double2");
+
+/// Bitwise operations on a scalar integer value
+pub trait Integer:
+    CDatatype + PartialOrd + BitAnd<Output = Self> + BitOr<Output = Self> + BitXor<Output = Self>
+{
+    /// Invert the bits of this value.
+    fn bitnot(self) -> Self;
+
+    /// Count the number of bits set in this value.
+    fn popcount(self) -> Self;
+
+    /// Shift the bits of this value left by `n`, modulo its bit width.
+    fn shift_left(self, n: Self) -> Self;
+
+    /// Shift the bits of this value right by `n`, modulo its bit width.
+    fn shift_right(self, n: Self) -> Self;
+
+    /// Add `other` to this value, wrapping around at the bounds of this type.
+    fn wrapping_add(self, other: Self) -> Self;
+
+    /// Subtract `other` from this value, wrapping around at the bounds of this type.
+    fn wrapping_sub(self, other: Self) -> Self;
+
+    /// Multiply this value by `other`, wrapping around at the bounds of this type.
+    fn wrapping_mul(self, other: Self) -> Self;
+
+    /// Add `other` to this value, saturating at the bounds of this type.
+    fn saturating_add(self, other: Self) -> Self;
+
+    /// Subtract `other` from this value, saturating at the bounds of this type.
+    fn saturating_sub(self, other: Self) -> Self;
+
+    /// Multiply this value by `other`, saturating at the bounds of this type.
+    fn saturating_mul(self, other: Self) -> Self;
+
+    /// Add `other` to this value, or return `None` on overflow.
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /// Subtract `other` from this value, or return `None` on overflow.
+    fn checked_sub(self, other: Self) -> Option<Self>;
+
+    /// Multiply this value by `other`, or return `None` on overflow.
+    fn checked_mul(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! integer_type {
+    ($t:ty) => {
+        impl Integer for $t {
+            fn bitnot(self) -> Self {
+                !self
+            }
+
+            fn popcount(self) -> Self {
+                self.count_ones() as $t
+            }
+
+            fn shift_left(self, n: Self) -> Self {
+                self.wrapping_shl(n as u32)
+            }
+
+            fn shift_right(self, n: Self) -> Self {
+                self.wrapping_shr(n as u32)
+            }
+
+            fn wrapping_add(self, other: Self) -> Self {
+                <$t>::wrapping_add(self, other)
+            }
+
+            fn wrapping_sub(self, other: Self) -> Self {
+                <$t>::wrapping_sub(self, other)
+            }
+
+            fn wrapping_mul(self, other: Self) -> Self {
+                <$t>::wrapping_mul(self, other)
+            }
+
+            fn saturating_add(self, other: Self) -> Self {
+                <$t>::saturating_add(self, other)
+            }
+
+            fn saturating_sub(self, other: Self) -> Self {
+                <$t>::saturating_sub(self, other)
+            }
+
+            fn saturating_mul(self, other: Self) -> Self {
+                <$t>::saturating_mul(self, other)
+            }
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                <$t>::checked_sub(self, other)
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+        }
+    };
+}
+
+integer_type!(u8);
+integer_type!(u16);
+integer_type!(u32);
+integer_type!(u64);
+integer_type!(i8);
+integer_type!(i16);
+integer_type!(i32);
+integer_type!(i64);
+
 /// Logarithm-related operations on a scalar value
 pub trait Log {
     /// Compute the natural log of this value.
@@ -375,6 +808,12 @@ pub trait Log {
     /// Compute `e^self`.
     fn exp(self) -> Self;
 
+    /// Compute `e^self - 1` in a way that is accurate even if `self` is close to zero.
+    fn expm1(self) -> Self;
+
+    /// Compute `ln(1 + self)` in a way that is accurate even if `self` is close to zero.
+    fn log1p(self) -> Self;
+
     /// Compute the exponent of this value to the given power.
     fn pow(self, exp: Self) -> Self;
 }
@@ -392,6 +831,14 @@ impl Log for f32 {
         f32::exp(self)
     }
 
+    fn expm1(self) -> Self {
+        f32::exp_m1(self)
+    }
+
+    fn log1p(self) -> Self {
+        f32::ln_1p(self)
+    }
+
     fn pow(self, n: f32) -> Self {
         f32::powf(self, n)
     }
@@ -410,6 +857,14 @@ impl Log for f64 {
         f64::exp(self)
     }
 
+    fn expm1(self) -> Self {
+        f64::exp_m1(self)
+    }
+
+    fn log1p(self) -> Self {
+        f64::ln_1p(self)
+    }
+
     fn pow(self, n: f64) -> Self {
         f64::powf(self, n)
     }
@@ -441,6 +896,9 @@ pub trait Trig {
     /// Compute the arctangent of this value.
     fn atan(self) -> Self;
 
+    /// Compute the four-quadrant arctangent of this value (`y`) and `x`.
+    fn atan2(self, x: Self) -> Self;
+
     /// Compute the hyperbolic tangent of this value.
     fn tanh(self) -> Self;
 }
@@ -478,6 +936,10 @@ impl Trig for f32 {
         f32::atan(self)
     }
 
+    fn atan2(self, x: f32) -> Self {
+        f32::atan2(self, x)
+    }
+
     fn tanh(self) -> Self {
         f32::tanh(self)
     }
@@ -516,21 +978,59 @@ impl Trig for f64 {
         f64::atan(self)
     }
 
+    fn atan2(self, x: f64) -> Self {
+        f64::atan2(self, x)
+    }
+
     fn tanh(self) -> Self {
         f64::tanh(self)
     }
 }
 
 /// Float-specific operations on a scalar floating point value
-pub trait Float: CDatatype + Log + Trig {
+pub trait Float: CDatatype + PartialOrd + Log + Trig {
+    /// Return the smallest integer greater than or equal to this value.
+    fn ceil(self) -> Self;
+
+    /// Compute the error function of this value.
+    fn erf(self) -> Self;
+
+    /// Return the largest integer less than or equal to this value.
+    fn floor(self) -> Self;
+
+    /// Compute the length of the hypotenuse of a right triangle with legs `self` and `other`.
+    fn hypot(self, other: Self) -> Self;
+
     /// Return `1` if this value is infinite, otherwise `0`.
     fn is_inf(self) -> u8;
 
     /// Return `1` if this value is not a number, otherwise `0`.
     fn is_nan(self) -> u8;
+
+    /// Compute the square root of this value.
+    fn sqrt(self) -> Self;
+
+    /// Return the integer part of this value.
+    fn trunc(self) -> Self;
 }
 
 impl Float for f32 {
+    fn ceil(self) -> Self {
+        f32::ceil(self)
+    }
+
+    fn erf(self) -> Self {
+        ops::erf(self as f64) as f32
+    }
+
+    fn floor(self) -> Self {
+        f32::floor(self)
+    }
+
+    fn hypot(self, other: f32) -> Self {
+        f32::hypot(self, other)
+    }
+
     fn is_inf(self) -> u8 {
         if f32::is_infinite(self) {
             1
@@ -546,9 +1046,33 @@ impl Float for f32 {
             0
         }
     }
+
+    fn sqrt(self) -> Self {
+        f32::sqrt(self)
+    }
+
+    fn trunc(self) -> Self {
+        f32::trunc(self)
+    }
 }
 
 impl Float for f64 {
+    fn ceil(self) -> Self {
+        f64::ceil(self)
+    }
+
+    fn erf(self) -> Self {
+        ops::erf(self)
+    }
+
+    fn floor(self) -> Self {
+        f64::floor(self)
+    }
+
+    fn hypot(self, other: f64) -> Self {
+        f64::hypot(self, other)
+    }
+
     fn is_inf(self) -> u8 {
         if f64::is_infinite(self) {
             1
@@ -564,99 +1088,413 @@ impl Float for f64 {
             0
         }
     }
+
+    fn sqrt(self) -> Self {
+        f64::sqrt(self)
+    }
+
+    fn trunc(self) -> Self {
+        f64::trunc(self)
+    }
 }
 
-#[cfg(feature = "opencl")]
-#[derive(Clone, Default)]
-struct DeviceList {
-    devices: Vec<ocl::Device>,
-    next: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+/// A runtime descriptor of a [`CDatatype`], for use with a [`DynArray`]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum DType {
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64,
+    #[cfg(feature = "half")]
+    F16,
+    #[cfg(feature = "half")]
+    BF16,
+    F32,
+    F64,
+    #[cfg(feature = "complex")]
+    C64,
+    #[cfg(feature = "complex")]
+    C128,
 }
 
-#[cfg(feature = "opencl")]
-impl DeviceList {
-    fn is_empty(&self) -> bool {
-        self.devices.is_empty()
+impl DType {
+    /// Return `true` if this is a complex data type.
+    pub fn is_complex(&self) -> bool {
+        match self {
+            #[cfg(feature = "complex")]
+            Self::C64 | Self::C128 => true,
+            _ => false,
+        }
     }
 
-    fn next(&self) -> Option<ocl::Device> {
-        if self.devices.is_empty() {
-            None
-        } else {
-            let idx = self.next.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-            self.devices.get(idx % self.devices.len()).copied()
+    /// Return `true` if this is a real floating point data type.
+    pub fn is_float(&self) -> bool {
+        match self {
+            #[cfg(feature = "half")]
+            Self::F16 | Self::BF16 => true,
+            Self::F32 | Self::F64 => true,
+            _ => false,
         }
     }
-}
 
-#[cfg(feature = "opencl")]
-impl From<Vec<ocl::Device>> for DeviceList {
-    fn from(devices: Vec<ocl::Device>) -> Self {
-        Self {
-            devices,
-            next: std::sync::Arc::new(Default::default()),
-        }
+    /// Return `true` if this is a signed data type.
+    pub fn is_signed(&self) -> bool {
+        !matches!(self, Self::U8 | Self::U16 | Self::U32 | Self::U64)
     }
-}
 
-#[cfg(feature = "opencl")]
-impl FromIterator<ocl::Device> for DeviceList {
-    fn from_iter<T: IntoIterator<Item = ocl::Device>>(iter: T) -> Self {
-        Self::from(iter.into_iter().collect::<Vec<ocl::Device>>())
+    /// Return the size of a single element of this data type, in bytes.
+    pub fn size(&self) -> usize {
+        match self {
+            Self::U8 | Self::I8 => 1,
+            Self::U16 | Self::I16 => 2,
+            #[cfg(feature = "half")]
+            Self::F16 | Self::BF16 => 2,
+            Self::U32 | Self::I32 | Self::F32 => 4,
+            Self::U64 | Self::I64 | Self::F64 => 8,
+            #[cfg(feature = "complex")]
+            Self::C64 => 8,
+            #[cfg(feature = "complex")]
+            Self::C128 => 16,
+        }
     }
-}
 
-#[derive(Clone, Default)]
-/// An OpenCL platform
-pub struct Platform {
-    #[cfg(feature = "opencl")]
-    cl_cpus: DeviceList,
-    #[cfg(feature = "opencl")]
-    cl_gpus: DeviceList,
-    #[cfg(feature = "opencl")]
-    cl_accs: DeviceList,
-    #[cfg(feature = "opencl")]
-    cl_platform: ocl::Platform,
-}
+    /// Return the smallest data type which can represent every value of both `self` and `other`,
+    /// following NumPy's type promotion rules.
+    ///
+    /// As in NumPy, promoting a 64-bit unsigned integer with a signed integer results in [`DType::F64`].
+    /// The two 16-bit float types promote to [`DType::F32`].
+    pub fn promote(self, other: Self) -> Self {
+        if self == other {
+            return self;
+        }
 
-impl Platform {
-    #[cfg(feature = "opencl")]
-    fn has_gpu(&self) -> bool {
-        !self.cl_gpus.is_empty()
+        #[cfg(feature = "complex")]
+        if self.is_complex() || other.is_complex() {
+            return match self.real().promote(other.real()) {
+                Self::F64 => Self::C128,
+                _ => Self::C64,
+            };
+        }
+
+        match (self.is_float(), other.is_float()) {
+            (true, true) if self.size() == other.size() => Self::F32,
+            (true, true) if self.size() > other.size() => self,
+            (true, true) => other,
+            (true, false) => Self::promote_float(self, other),
+            (false, true) => Self::promote_float(other, self),
+            (false, false) => match (self.is_signed(), other.is_signed()) {
+                (true, false) => Self::promote_signed(self, other),
+                (false, true) => Self::promote_signed(other, self),
+                _ if self.size() > other.size() => self,
+                _ => other,
+            },
+        }
     }
 
-    #[cfg(feature = "opencl")]
-    fn next_cpu(&self) -> Option<ocl::Device> {
-        self.cl_cpus.next()
+    // the smallest float type which can exactly represent every value of the given integer type
+    fn promote_float(float: Self, int: Self) -> Self {
+        let size = match int.size() {
+            1 => 2,
+            2 => 4,
+            _ => 8,
+        };
+
+        if float.size() >= size {
+            float
+        } else if size == 4 {
+            Self::F32
+        } else {
+            Self::F64
+        }
     }
 
-    #[cfg(feature = "opencl")]
-    fn next_gpu(&self) -> Option<ocl::Device> {
-        self.cl_gpus.next()
+    fn promote_signed(signed: Self, unsigned: Self) -> Self {
+        if signed.size() > unsigned.size() {
+            signed
+        } else {
+            match unsigned.size() {
+                1 => Self::I16,
+                2 => Self::I32,
+                4 => Self::I64,
+                _ => Self::F64,
+            }
+        }
     }
 
-    #[cfg(feature = "opencl")]
-    fn next_acc(&self) -> Option<ocl::Device> {
-        self.cl_accs.next()
+    #[cfg(feature = "complex")]
+    fn real(self) -> Self {
+        match self {
+            Self::C64 => Self::F32,
+            Self::C128 => Self::F64,
+            real => real,
+        }
     }
 }
 
-#[cfg(feature = "opencl")]
-impl TryFrom<ocl::Platform> for Platform {
-    type Error = ocl::Error;
+/// Type promotion from `Self` and `Rhs` to a common [`CDatatype`],
+/// following the same rules as [`DType::promote`]
+pub trait Promote<Rhs: CDatatype>: CDatatype {
+    /// The promoted data type
+    type Out: CDatatype;
 
-    fn try_from(cl_platform: ocl::Platform) -> Result<Self, Self::Error> {
-        let cl_cpus = ocl::Device::list(cl_platform, Some(ocl::DeviceType::CPU))?;
-        let cl_gpus = ocl::Device::list(cl_platform, Some(ocl::DeviceType::GPU))?;
-        let cl_accs = ocl::Device::list(cl_platform, Some(ocl::DeviceType::ACCELERATOR))?;
+    /// Convert this value to the promoted data type.
+    fn promote(self) -> Self::Out;
 
-        Ok(Self {
-            cl_cpus: cl_cpus.into(),
-            cl_gpus: cl_gpus.into(),
-            cl_accs: cl_accs.into(),
-            cl_platform,
+    /// Convert a right-hand value to the promoted data type.
+    fn promote_rhs(rhs: Rhs) -> Self::Out;
+}
+
+macro_rules! promote {
+    ($conv:ident: $l:ty; $($r:ty => $o:ty),*) => {
+        $(
+            impl Promote<$r> for $l {
+                type Out = $o;
+
+                fn promote(self) -> $o {
+                    promote!(@$conv self, $o)
+                }
+
+                fn promote_rhs(rhs: $r) -> $o {
+                    promote!(@$conv rhs, $o)
+                }
+            }
+        )*
+    };
+    (@as $x:expr, $o:ty) => {
+        $x as $o
+    };
+    (@f64 $x:expr, $o:ty) => {
+        <$o as CDatatype>::from_f64(CDatatype::to_f64($x))
+    };
+    (@complex $x:expr, $o:ty) => {
+        ToComplex::to_complex($x)
+    };
+}
+
+// unlike `CDatatype::from_f64`, this preserves the imaginary part of a complex value
+#[cfg(feature = "complex")]
+trait ToComplex<F> {
+    fn to_complex(self) -> Complex<F>;
+}
+
+#[cfg(feature = "complex")]
+impl<F: Float, T: Float> ToComplex<F> for Complex<T> {
+    fn to_complex(self) -> Complex<F> {
+        Complex::new(F::from_f64(self.re.to_f64()), F::from_f64(self.im.to_f64()))
+    }
+}
+
+#[cfg(feature = "complex")]
+macro_rules! to_complex {
+    ($($t:ty),*) => {
+        $(
+            impl<F: Float> ToComplex<F> for $t {
+                fn to_complex(self) -> Complex<F> {
+                    Complex::new(F::from_f64(self.to_f64()), F::zero())
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "complex")]
+to_complex!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+#[cfg(all(feature = "complex", feature = "half"))]
+to_complex!(F16, BF16);
+
+promote!(as: u8; u8 => u8, u16 => u16, u32 => u32, u64 => u64, i8 => i16, i16 => i16, i32 => i32, i64 => i64, f32 => f32, f64 => f64);
+promote!(as: u16; u8 => u16, u16 => u16, u32 => u32, u64 => u64, i8 => i32, i16 => i32, i32 => i32, i64 => i64, f32 => f32, f64 => f64);
+promote!(as: u32; u8 => u32, u16 => u32, u32 => u32, u64 => u64, i8 => i64, i16 => i64, i32 => i64, i64 => i64, f32 => f64, f64 => f64);
+promote!(as: u64; u8 => u64, u16 => u64, u32 => u64, u64 => u64, i8 => f64, i16 => f64, i32 => f64, i64 => f64, f32 => f64, f64 => f64);
+promote!(as: i8; u8 => i16, u16 => i32, u32 => i64, u64 => f64, i8 => i8, i16 => i16, i32 => i32, i64 => i64, f32 => f32, f64 => f64);
+promote!(as: i16; u8 => i16, u16 => i32, u32 => i64, u64 => f64, i8 => i16, i16 => i16, i32 => i32, i64 => i64, f32 => f32, f64 => f64);
+promote!(as: i32; u8 => i32, u16 => i32, u32 => i64, u64 => f64, i8 => i32, i16 => i32, i32 => i32, i64 => i64, f32 => f64, f64 => f64);
+promote!(as: i64; u8 => i64, u16 => i64, u32 => i64, u64 => f64, i8 => i64, i16 => i64, i32 => i64, i64 => i64, f32 => f64, f64 => f64);
+promote!(as: f32; u8 => f32, u16 => f32, u32 => f64, u64 => f64, i8 => f32, i16 => f32, i32 => f64, i64 => f64, f32 => f32, f64 => f64);
+promote!(as: f64; u8 => f64, u16 => f64, u32 => f64, u64 => f64, i8 => f64, i16 => f64, i32 => f64, i64 => f64, f32 => f64, f64 => f64);
+
+#[cfg(feature = "half")]
+promote!(f64: F16; u8 => F16, u16 => f32, u32 => f64, u64 => f64, i8 => F16, i16 => f32, i32 => f64, i64 => f64, f32 => f32, f64 => f64, F16 => F16, BF16 => f32);
+#[cfg(feature = "half")]
+promote!(f64: BF16; u8 => BF16, u16 => f32, u32 => f64, u64 => f64, i8 => BF16, i16 => f32, i32 => f64, i64 => f64, f32 => f32, f64 => f64, F16 => f32, BF16 => BF16);
+#[cfg(feature = "half")]
+promote!(f64: u8; F16 => F16, BF16 => BF16);
+#[cfg(feature = "half")]
+promote!(f64: u16; F16 => f32, BF16 => f32);
+#[cfg(feature = "half")]
+promote!(f64: u32; F16 => f64, BF16 => f64);
+#[cfg(feature = "half")]
+promote!(f64: u64; F16 => f64, BF16 => f64);
+#[cfg(feature = "half")]
+promote!(f64: i8; F16 => F16, BF16 => BF16);
+#[cfg(feature = "half")]
+promote!(f64: i16; F16 => f32, BF16 => f32);
+#[cfg(feature = "half")]
+promote!(f64: i32; F16 => f64, BF16 => f64);
+#[cfg(feature = "half")]
+promote!(f64: i64; F16 => f64, BF16 => f64);
+#[cfg(feature = "half")]
+promote!(f64: f32; F16 => f32, BF16 => f32);
+#[cfg(feature = "half")]
+promote!(f64: f64; F16 => f64, BF16 => f64);
+
+#[cfg(feature = "complex")]
+promote!(complex: Complex<f32>; u8 => Complex<f32>, u16 => Complex<f32>, u32 => Complex<f64>, u64 => Complex<f64>, i8 => Complex<f32>, i16 => Complex<f32>, i32 => Complex<f64>, i64 => Complex<f64>, f32 => Complex<f32>, f64 => Complex<f64>, Complex<f32> => Complex<f32>, Complex<f64> => Complex<f64>);
+#[cfg(feature = "complex")]
+promote!(complex: Complex<f64>; u8 => Complex<f64>, u16 => Complex<f64>, u32 => Complex<f64>, u64 => Complex<f64>, i8 => Complex<f64>, i16 => Complex<f64>, i32 => Complex<f64>, i64 => Complex<f64>, f32 => Complex<f64>, f64 => Complex<f64>, Complex<f32> => Complex<f64>, Complex<f64> => Complex<f64>);
+#[cfg(feature = "complex")]
+promote!(complex: u8; Complex<f32> => Complex<f32>, Complex<f64> => Complex<f64>);
+#[cfg(feature = "complex")]
+promote!(complex: u16; Complex<f32> => Complex<f32>, Complex<f64> => Complex<f64>);
+#[cfg(feature = "complex")]
+promote!(complex: u32; Complex<f32> => Complex<f64>, Complex<f64> => Complex<f64>);
+#[cfg(feature = "complex")]
+promote!(complex: u64; Complex<f32> => Complex<f64>, Complex<f64> => Complex<f64>);
+#[cfg(feature = "complex")]
+promote!(complex: i8; Complex<f32> => Complex<f32>, Complex<f64> => Complex<f64>);
+#[cfg(feature = "complex")]
+promote!(complex: i16; Complex<f32> => Complex<f32>, Complex<f64> => Complex<f64>);
+#[cfg(feature = "complex")]
+promote!(complex: i32; Complex<f32> => Complex<f64>, Complex<f64> => Complex<f64>);
+#[cfg(feature = "complex")]
+promote!(complex: i64; Complex<f32> => Complex<f64>, Complex<f64> => Complex<f64>);
+#[cfg(feature = "complex")]
+promote!(complex: f32; Complex<f32> => Complex<f32>, Complex<f64> => Complex<f64>);
+#[cfg(feature = "complex")]
+promote!(complex: f64; Complex<f32> => Complex<f64>, Complex<f64> => Complex<f64>);
+
+#[cfg(all(feature = "complex", feature = "half"))]
+promote!(complex: Complex<f32>; F16 => Complex<f32>, BF16 => Complex<f32>);
+#[cfg(all(feature = "complex", feature = "half"))]
+promote!(complex: Complex<f64>; F16 => Complex<f64>, BF16 => Complex<f64>);
+#[cfg(all(feature = "complex", feature = "half"))]
+promote!(complex: F16; Complex<f32> => Complex<f32>, Complex<f64> => Complex<f64>);
+#[cfg(all(feature = "complex", feature = "half"))]
+promote!(complex: BF16; Complex<f32> => Complex<f32>, Complex<f64> => Complex<f64>);
+
+#[derive(Clone, Debug, Default)]
+/// An OpenCL platform and its available devices
+pub struct Platform {
+    name: String,
+    vendor: String,
+    devices: Vec<Device>,
+    #[cfg(feature = "opencl")]
+    cl_platform: ocl::Platform,
+}
+
+impl Platform {
+    #[cfg(feature = "opencl")]
+    /// List the available OpenCL platforms.
+    pub fn list() -> Result<Vec<Self>, Error> {
+        ocl::Platform::list()
+            .into_iter()
+            .map(|cl_platform| Self::try_from(cl_platform).map_err(Error::from))
+            .collect()
+    }
+
+    #[cfg(not(feature = "opencl"))]
+    /// List the available OpenCL platforms.
+    /// This is always empty since the "opencl" feature flag is disabled.
+    pub fn list() -> Result<Vec<Self>, Error> {
+        Ok(Vec::new())
+    }
+
+    /// Return the first available OpenCL platform whose name or vendor contains `query`,
+    /// ignoring case.
+    pub fn find(query: &str) -> Result<Self, Error> {
+        Self::list()?
+            .into_iter()
+            .find(|platform| device::matches(query, &platform.name, &platform.vendor))
+            .ok_or_else(|| {
+                Error::Interface(format!("there is no OpenCL platform matching {query}"))
+            })
+    }
+
+    #[cfg(feature = "opencl")]
+    /// Construct a [`Platform`] with only the given `devices` of `cl_platform`,
+    /// for example a set of sub-devices partitioned from a single CPU.
+    pub fn from_devices(
+        cl_platform: ocl::Platform,
+        devices: Vec<ocl::Device>,
+    ) -> Result<Self, ocl::Error> {
+        Ok(Self {
+            name: cl_platform.name()?,
+            vendor: cl_platform.vendor()?,
+            devices: devices
+                .into_iter()
+                .map(Device::new)
+                .collect::<Result<_, _>>()?,
+            cl_platform,
         })
     }
+
+    /// Restrict this [`Platform`] to its devices whose name or vendor contains `query`,
+    /// ignoring case.
+    pub fn with_devices_matching(mut self, query: &str) -> Result<Self, Error> {
+        self.devices
+            .retain(|device| device::matches(query, device.name(), device.vendor()));
+
+        if self.devices.is_empty() {
+            Err(Error::Interface(format!(
+                "platform {} has no device matching {query}",
+                self.name
+            )))
+        } else {
+            Ok(self)
+        }
+    }
+
+    /// The name of this platform.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The vendor of this platform.
+    pub fn vendor(&self) -> &str {
+        &self.vendor
+    }
+
+    /// Borrow the devices available on this platform.
+    pub fn devices(&self) -> &[Device] {
+        &self.devices
+    }
+
+    // return `true` if every device of this platform supports half-precision arithmetic
+    #[cfg(feature = "opencl")]
+    fn fp16(&self) -> bool {
+        !self.devices.is_empty() && self.devices.iter().all(Device::fp16)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn has_gpu(&self) -> bool {
+        self.devices
+            .iter()
+            .any(|device| device.kind() == DeviceKind::GPU)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl TryFrom<ocl::Platform> for Platform {
+    type Error = ocl::Error;
+
+    fn try_from(cl_platform: ocl::Platform) -> Result<Self, Self::Error> {
+        let mut devices = Vec::new();
+
+        for device_type in [
+            ocl::DeviceType::CPU,
+            ocl::DeviceType::GPU,
+            ocl::DeviceType::ACCELERATOR,
+        ] {
+            devices.extend(ocl::Device::list(cl_platform, Some(device_type))?);
+        }
+
+        Self::from_devices(cl_platform, devices)
+    }
 }
 
 #[derive(Clone)]
@@ -664,10 +1502,14 @@ impl TryFrom<ocl::Platform> for Platform {
 /// An execution context
 pub struct Context {
     platform: Platform,
-    gpu_min: usize,
-    acc_min: usize,
+    policy: Arc<dyn DevicePolicy>,
+    thread_pool: Option<Arc<rayon::ThreadPool>>,
+    parallel_min: usize,
+    buffer_pool: Arc<BufferPool>,
     #[cfg(feature = "opencl")]
     cl_context: ocl::Context,
+    #[cfg(feature = "opencl")]
+    cl_programs: cl_programs::ProgramCache,
 }
 
 impl Context {
@@ -685,9 +1527,12 @@ impl Context {
 
         Ok(Self {
             platform,
-            gpu_min: GPU_MIN_DEFAULT,
-            acc_min,
+            policy: Arc::new(SizeThresholds::new(GPU_MIN_DEFAULT, acc_min)),
+            thread_pool: None,
+            parallel_min: PARALLEL_MIN_DEFAULT,
+            buffer_pool: Default::default(),
             cl_context,
+            cl_programs: Default::default(),
         })
     }
 
@@ -695,31 +1540,48 @@ impl Context {
     /// Construct a default [`Context`] with the host device.
     pub fn default() -> Result<Self, Error> {
         Ok(Self {
-            platform: Platform {},
-            gpu_min: GPU_MIN_DEFAULT,
-            acc_min: GPU_MIN_DEFAULT,
+            platform: Platform::default(),
+            policy: Arc::new(SizeThresholds::new(GPU_MIN_DEFAULT, GPU_MIN_DEFAULT)),
+            thread_pool: None,
+            parallel_min: PARALLEL_MIN_DEFAULT,
+            buffer_pool: Default::default(),
         })
     }
 
     #[cfg(feature = "opencl")]
-    /// Construct a default [`Context`] with the given configuration.
+    /// Construct a default [`Context`] with the given configuration,
+    /// which selects devices using a [`SizeThresholds`] policy.
     pub fn new(gpu_min: usize, acc_min: usize, platform: Option<Platform>) -> Result<Self, Error> {
         let platform = if let Some(platform) = platform {
             platform
         } else {
             let cl_platform = ocl::Platform::first()?;
-            Platform::try_from(cl_platform.clone())?
+            Platform::try_from(cl_platform)?
         };
 
-        let cl_context = ocl::Context::builder()
-            .platform(platform.cl_platform.clone())
-            .build()?;
+        let mut cl_context = ocl::Context::builder();
+        cl_context.platform(platform.cl_platform);
+
+        let devices = platform
+            .devices()
+            .iter()
+            .map(Device::cl_device)
+            .collect::<Vec<_>>();
+
+        if !devices.is_empty() {
+            cl_context.devices(&devices);
+        }
+
+        let cl_context = cl_context.build()?;
 
         Ok(Self {
             platform,
-            gpu_min,
-            acc_min,
+            policy: Arc::new(SizeThresholds::new(gpu_min, acc_min)),
+            thread_pool: None,
+            parallel_min: PARALLEL_MIN_DEFAULT,
+            buffer_pool: Default::default(),
             cl_context,
+            cl_programs: Default::default(),
         })
     }
 
@@ -735,30 +1597,147 @@ impl Context {
 
         Ok(Self {
             platform,
-            gpu_min,
-            acc_min,
+            policy: Arc::new(SizeThresholds::new(gpu_min, acc_min)),
+            thread_pool: None,
+            parallel_min: PARALLEL_MIN_DEFAULT,
+            buffer_pool: Default::default(),
         })
     }
 
+    /// Select the device on which to enqueue each operation using the given `policy`.
+    pub fn with_policy<P: DevicePolicy + 'static>(mut self, policy: P) -> Self {
+        self.policy = Arc::new(policy);
+        self
+    }
+
+    /// Run the CPU operations of this [`Context`] on the given `thread_pool`
+    /// instead of the global [`rayon`] thread pool, for example to limit their CPU usage.
+    ///
+    /// Use [`rayon::ThreadPoolBuilder`] to configure the number of threads, their names,
+    /// and their CPU affinity (with [`rayon::ThreadPoolBuilder::start_handler`]).
+    pub fn with_thread_pool(mut self, thread_pool: rayon::ThreadPool) -> Self {
+        self.thread_pool = Some(Arc::new(thread_pool));
+        self
+    }
+
+    /// Run the CPU operations of this [`Context`] on a new thread pool with `num_threads` threads.
+    pub fn with_num_threads(self, num_threads: usize) -> Result<Self, Error> {
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .thread_name(|i| format!("ha-ndarray-{i}"))
+            .build()
+            .map_err(|cause| Error::Interface(format!("failed to build a thread pool: {cause}")))?;
+
+        Ok(self.with_thread_pool(thread_pool))
+    }
+
+    /// Process an array with fewer than `parallel_min` elements sequentially on the CPU,
+    /// to avoid the overhead of splitting a small amount of work across threads.
+    pub fn with_parallel_min(mut self, parallel_min: usize) -> Self {
+        self.parallel_min = parallel_min;
+        self
+    }
+
+    /// Limit the memory in use or cached by the [`BufferPool`] of this [`Context`] to `budget` bytes.
+    /// An op whose output buffer would exceed the budget returns [`Error::OutOfMemory`].
+    pub fn with_memory_budget(mut self, budget: usize) -> Self {
+        self.buffer_pool = Arc::new(BufferPool::new(Some(budget)));
+        self
+    }
+
+    #[cfg(feature = "opencl")]
+    /// Persist the binaries of the OpenCL programs compiled by this [`Context`] in `dir`,
+    /// so that they can be loaded instead of compiled by a later [`Context`] with the same `dir`.
+    ///
+    /// Persistence is best-effort: a binary which can't be written or loaded is compiled from source.
+    pub fn with_program_dir<P: Into<std::path::PathBuf>>(mut self, dir: P) -> Self {
+        self.cl_programs.set_dir(dir.into());
+        self
+    }
+
+    #[cfg(feature = "opencl")]
+    /// Keep at most `capacity` of the OpenCL programs compiled by this [`Context`] in memory
+    /// (256 by default), evicting the least recently used program first.
+    pub fn with_program_cache_capacity(mut self, capacity: usize) -> Self {
+        self.cl_programs.set_capacity(capacity);
+        self
+    }
+
+    /// Borrow the [`Platform`] of this [`Context`].
+    pub fn platform(&self) -> &Platform {
+        &self.platform
+    }
+
+    /// Borrow the [`BufferPool`] which the ops of this [`Context`] draw their output buffers from.
+    pub fn buffer_pool(&self) -> &Arc<BufferPool> {
+        &self.buffer_pool
+    }
+
+    /// The number of threads available to the CPU operations of this [`Context`].
+    pub fn num_threads(&self) -> usize {
+        if let Some(thread_pool) = &self.thread_pool {
+            thread_pool.current_num_threads()
+        } else {
+            rayon::current_num_threads()
+        }
+    }
+
+    // run `op` on the thread pool of this context
+    fn install<R: Send, F: FnOnce() -> R + Send>(&self, op: F) -> R {
+        if let Some(thread_pool) = &self.thread_pool {
+            thread_pool.install(op)
+        } else {
+            op()
+        }
+    }
+
+    // run `op` asynchronously on the thread pool of this context
+    #[cfg(feature = "futures")]
+    fn spawn<F: FnOnce() + Send + 'static>(&self, op: F) {
+        if let Some(thread_pool) = &self.thread_pool {
+            thread_pool.spawn(op)
+        } else {
+            rayon::spawn(op)
+        }
+    }
+
     #[cfg(feature = "opencl")]
     fn cl_context(&self) -> &ocl::Context {
         &self.cl_context
     }
 
+    // compile the program with the given source, or return it from the cache
+    // if this context has already compiled it
     #[cfg(feature = "opencl")]
-    fn select_device(&self, size_hint: usize) -> Option<ocl::Device> {
-        if size_hint < self.gpu_min {
-            self.platform.next_cpu()
-        } else if size_hint < self.acc_min {
-            self.platform
-                .next_gpu()
-                .or_else(|| self.platform.next_cpu())
+    fn cl_program(&self, src: String) -> Result<ocl::Program, ocl::Error> {
+        #[cfg(feature = "half")]
+        let src = if self.platform.fp16() {
+            format!(
+                "#pragma OPENCL EXTENSION cl_khr_fp16 : enable\n\
+                typedef half f16;\n\
+                typedef ushort bf16;\n\
+                {src}"
+            )
         } else {
-            self.platform
-                .next_acc()
-                .or_else(|| self.platform.next_gpu())
-                .or_else(|| self.platform.next_cpu())
-        }
+            format!("typedef ushort f16;\ntypedef ushort bf16;\n{src}")
+        };
+
+        self.cl_programs.get_or_build(&self.cl_context, src)
+    }
+
+    // return `true` if the OpenCL kernels of this context can compute on the data type `T`
+    #[cfg(feature = "opencl")]
+    fn cl_compute<T: CDatatype>(&self) -> bool {
+        T::CL_COMPUTE && (!T::CL_FP16 || self.platform.fp16())
+    }
+
+    #[cfg(feature = "opencl")]
+    fn select_device(&self, size_hint: usize) -> Option<&Device> {
+        let devices = self.platform.devices();
+
+        self.policy
+            .select(devices, size_hint)
+            .and_then(|i| devices.get(i))
     }
 }
 
@@ -768,6 +1747,13 @@ pub struct Queue {
     context: Context,
     #[cfg(feature = "opencl")]
     cl_queue: Option<ocl::Queue>,
+    // held only to count this queue in the load of its device
+    #[cfg(feature = "opencl")]
+    #[allow(unused)]
+    load: Option<device::DeviceLoad>,
+    // the events which the next command enqueued by an op with this queue must wait for
+    #[cfg(feature = "opencl")]
+    wait_list: Arc<Mutex<ocl::EventList>>,
 }
 
 impl Queue {
@@ -776,25 +1762,36 @@ impl Queue {
             context,
             #[cfg(feature = "opencl")]
             cl_queue: None,
+            #[cfg(feature = "opencl")]
+            load: None,
+            #[cfg(feature = "opencl")]
+            wait_list: Arc::new(Mutex::new(ocl::EventList::new())),
         }
     }
 
     #[cfg(feature = "opencl")]
-    /// Construct a new [`Queue`] with the given [`Context`].
-    /// If `size_hint` is large, a new OpenCL context may be initialized.
+    /// Construct a new [`Queue`] with the given [`Context`],
+    /// on the device selected by its [`DevicePolicy`] for an operation of size `size_hint`.
     pub fn new(context: Context, size_hint: usize) -> Result<Self, Error> {
-        if let Some(device) = context.select_device(size_hint) {
-            let cl_queue = ocl::Queue::new(context.cl_context(), device, None)?;
-
-            Ok(Self {
-                context,
-                cl_queue: Some(cl_queue),
-            })
+        if let Some(device) = context.select_device(size_hint).cloned() {
+            Self::with_device(context, &device)
         } else {
             Ok(Self::default(context))
         }
     }
 
+    #[cfg(feature = "opencl")]
+    fn with_device(context: Context, device: &Device) -> Result<Self, Error> {
+        let cl_queue = ocl::Queue::new(context.cl_context(), device.cl_device(), None)?;
+
+        Ok(Self {
+            context,
+            cl_queue: Some(cl_queue),
+            load: Some(device.acquire()),
+            wait_list: Arc::new(Mutex::new(ocl::EventList::new())),
+        })
+    }
+
     #[cfg(not(feature = "opencl"))]
     /// Construct a new host [`Queue`] with the given [`Context`].
     /// `size_hint` will be ignored since the "opencl" feature flag is disabled.
@@ -816,24 +1813,95 @@ impl Queue {
         &self.context
     }
 
+    #[cfg(feature = "opencl")]
+    // enqueue a command by calling `enqueue` with the wait list of this queue and the event
+    // to set for the new command, which the next command will wait for, so that the commands
+    // which compute an array are chained by OpenCL events even if they're on different queues
+    fn chain<F>(&self, enqueue: F) -> Result<(), Error>
+    where
+        F: FnOnce(&ocl::EventList, &mut ocl::Event) -> ocl::Result<()>,
+    {
+        let mut wait_list = self.wait_list.lock().expect("wait list");
+        let mut event = ocl::Event::empty();
+        enqueue(&wait_list, &mut event)?;
+        wait_list.clear();
+        wait_list.push(event);
+        Ok(())
+    }
+
+    #[cfg(feature = "opencl")]
+    // enqueue the given `kernel` after the last command enqueued by an op with this queue
+    //
+    // safety: the same as `ocl::Kernel::enq`, i.e. the kernel's arguments must be valid
+    unsafe fn enqueue_kernel(&self, kernel: &ocl::Kernel) -> Result<(), Error> {
+        self.chain(|wait_list, event| unsafe { kernel.cmd().ewait(wait_list).enew(event).enq() })
+    }
+
+    #[cfg(feature = "opencl")]
+    // make the next command enqueued by an op with this queue wait for the commands enqueued
+    // with `other`, e.g. to compute the source of a slice on a different queue
+    fn wait_for(&self, other: &Self) {
+        let other = other.wait_list();
+        let mut wait_list = self.wait_list.lock().expect("wait list");
+
+        for event in other.as_slice() {
+            wait_list.push(event.clone());
+        }
+    }
+
+    #[cfg(feature = "opencl")]
+    // the events of the last commands enqueued by ops with this queue
+    fn wait_list(&self) -> ocl::EventList {
+        self.wait_list.lock().expect("wait list").clone()
+    }
+
+    // the minimum number of items of a parallel iterator over chunks of `chunk_size` elements
+    // to process in a single task, so that a small array is processed sequentially
+    fn min_len(&self, chunk_size: usize) -> usize {
+        Ord::max(
+            self.context.parallel_min.div_ceil(Ord::max(chunk_size, 1)),
+            1,
+        )
+    }
+
+    // draw an empty host buffer with a capacity of at least `len` elements
+    fn host_buffer<T: CDatatype>(&self, len: usize) -> Result<Pooled<Vec<T>>, Error> {
+        self.context.buffer_pool.host_buffer(len)
+    }
+
+    // draw a host buffer of `len` copies of `value`
+    fn host_buffer_of<T: CDatatype>(&self, len: usize, value: T) -> Result<Pooled<Vec<T>>, Error> {
+        let mut buffer = self.host_buffer(len)?;
+        buffer.resize(len, value);
+        Ok(buffer)
+    }
+
+    #[cfg(feature = "opencl")]
+    // draw an uninitialized buffer of `len` elements on the device of `cl_queue`
+    fn cl_buffer<T: CDatatype>(
+        &self,
+        cl_queue: ocl::Queue,
+        len: usize,
+    ) -> Result<Pooled<ocl::Buffer<T>>, Error> {
+        self.context.buffer_pool.cl_buffer(cl_queue, len)
+    }
+
+    // count a buffer allocated outside the buffer pool against its budget
+    #[cfg(any(feature = "fft", feature = "opencl"))]
+    fn adopt<B: Recycle>(&self, buffer: B) -> Result<Pooled<B>, Error> {
+        self.context.buffer_pool.adopt(buffer)
+    }
+
     #[allow(unused)]
     fn split(&self, size_hint: usize) -> Result<Self, Error> {
         #[cfg(feature = "opencl")]
-        let cl_queue = if let Some(left_queue) = &self.cl_queue {
+        if self.cl_queue.is_some() {
             if let Some(device) = self.context.select_device(size_hint) {
-                ocl::Queue::new(&left_queue.context(), device, None).map(Some)?
-            } else {
-                None
+                return Self::with_device(self.context.clone(), device);
             }
-        } else {
-            None
-        };
+        }
 
-        Ok(Self {
-            context: self.context.clone(),
-            #[cfg(feature = "opencl")]
-            cl_queue,
-        })
+        Ok(Self::default(self.context.clone()))
     }
 }
 
@@ -894,12 +1962,128 @@ pub trait NDArrayRead: NDArray + fmt::Debug + Sized {
         converter.to_slice()
     }
 
+    #[cfg(feature = "blas")]
+    /// Read the value of this [`NDArray`] in main memory as a batch of matrices,
+    /// and whether each matrix is stored in transposed (column-major) order.
+    fn to_host_matrices(
+        &self,
+        queue: &Queue,
+    ) -> Result<(SliceConverter<Self::DType>, bool), Error> {
+        self.to_host(queue).map(|matrices| (matrices, false))
+    }
+
     #[cfg(feature = "opencl")]
     /// Read the value of this [`NDArray`] as a [`CLConverter`] in OpenCL memory
     fn to_cl_buffer(&self, queue: &Queue) -> Result<CLConverter<Self::DType>, Error> {
         let converter = self.read(queue)?;
         converter.to_cl(queue)
     }
+
+    #[cfg(feature = "futures")]
+    /// Read the value of this [`NDArray`] without blocking the calling thread,
+    /// e.g. a thread which runs an async executor.
+    ///
+    /// This array is read on the `rayon` thread pool, and the returned future resolves when the
+    /// result is ready. On an OpenCL device, that's when the commands enqueued to compute it
+    /// have completed, which is signalled by an event callback rather than by blocking a thread.
+    ///
+    /// The commands enqueued by ops are chained by OpenCL event wait lists, so each one waits
+    /// for the events of the commands before it, even those on another device queue.
+    /// An op which must read an intermediate result back to the host (e.g. to check for
+    /// overflow) still blocks the `rayon` worker which enqueues it until that result is ready.
+    ///
+    /// To continue using this array after reading it, read a clone of it.
+    fn read_async(
+        self,
+        queue: &Queue,
+    ) -> impl std::future::Future<Output = Result<Buffer<Self::DType>, Error>> + Send
+    where
+        Self: Send + 'static,
+    {
+        let queue = queue.clone();
+        let context = queue.context().clone();
+        let (tx, rx) = futures::channel::oneshot::channel();
+
+        context.spawn(move || {
+            let buffer = match self.read(&queue).and_then(|buffer| buffer.into_buffer()) {
+                Ok(buffer) => buffer,
+                Err(cause) => {
+                    // the caller may have dropped the future if it no longer needs the result
+                    let _ = tx.send(Err(cause));
+                    return;
+                }
+            };
+
+            #[cfg(feature = "opencl")]
+            if let Buffer::CL(cl_buffer) = &buffer {
+                let cl_queue = cl_buffer.default_queue().expect("OpenCL queue").clone();
+                return complete_on_marker(&cl_queue, &queue.wait_list(), buffer, tx);
+            }
+
+            let _ = tx.send(Ok(buffer));
+        });
+
+        async move {
+            rx.await.unwrap_or_else(|_| {
+                Err(Error::Interface(
+                    "an asynchronous read was cancelled before it completed".to_string(),
+                ))
+            })
+        }
+    }
+}
+
+#[cfg(all(feature = "futures", feature = "opencl"))]
+type AsyncRead<T> = (
+    Buffer<T>,
+    futures::channel::oneshot::Sender<Result<Buffer<T>, Error>>,
+);
+
+// a marker at the end of the `buffer`'s queue which waits for the `wait_list` of the last
+// commands enqueued by ops completes after every command enqueued to compute the `buffer`,
+// at which point its callback sends it
+#[cfg(all(feature = "futures", feature = "opencl"))]
+fn complete_on_marker<T: CDatatype>(
+    cl_queue: &ocl::Queue,
+    wait_list: &ocl::EventList,
+    buffer: Buffer<T>,
+    tx: futures::channel::oneshot::Sender<Result<Buffer<T>, Error>>,
+) {
+    extern "C" fn complete<T: CDatatype>(
+        _event: ocl::ffi::cl_event,
+        status: i32,
+        read: *mut ocl::ffi::c_void,
+    ) {
+        // safety: `read` was leaked from a `Box<AsyncRead<T>>` by `complete_on_marker`,
+        // and OpenCL calls this callback exactly once
+        let (buffer, tx) = *unsafe { Box::from_raw(read as *mut AsyncRead<T>) };
+
+        let result = if status == ocl::core::CommandExecutionStatus::Complete as i32 {
+            Ok(buffer)
+        } else {
+            Err(Error::Interface(format!(
+                "OpenCL commands to compute an array failed with status {status}"
+            )))
+        };
+
+        let _ = tx.send(result);
+    }
+
+    let marker = match cl_queue.enqueue_marker(Some(wait_list)) {
+        Ok(marker) => marker,
+        Err(cause) => {
+            let _ = tx.send(Err(cause.into()));
+            return;
+        }
+    };
+
+    let read = Box::into_raw(Box::new((buffer, tx)));
+
+    // safety: the callback takes back ownership of `read`, which is reclaimed here if it's not set
+    if let Err(cause) = unsafe { marker.set_callback(complete::<T>, read as *mut _) } {
+        let (_buffer, tx) = *unsafe { Box::from_raw(read) };
+        let _ = tx.send(Err(ocl::Error::from(cause).into()));
+    }
 }
 
 /// Access methods for a mutable [`NDArray`]
@@ -988,14 +2172,20 @@ pub trait NDArrayUnary: NDArray + Sized {
     }
 
     /// Construct an exponentiation operation.
-    fn exp(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error> {
+    fn exp(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         let shape = self.shape().to_vec();
         let op = ArrayUnary::exp(self)?;
         Ok(ArrayOp::new(shape, op))
     }
 
     /// Construct a natural logarithm operation.
-    fn ln(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error> {
+    fn ln(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         let shape = self.shape().to_vec();
         let op = ArrayUnary::ln(self)?;
         Ok(ArrayOp::new(shape, op))
@@ -1014,6 +2204,96 @@ pub trait NDArrayUnary: NDArray + Sized {
         let op = ArrayUnary::round(self)?;
         Ok(ArrayOp::new(shape, op))
     }
+
+    /// Construct a ceiling operation.
+    fn ceil(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::ceil(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an error function operation.
+    fn erf(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::erf(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an `e^x - 1` operation.
+    fn expm1(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::expm1(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a floor operation.
+    fn floor(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::floor(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an `ln(1 + x)` operation.
+    fn log1p(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::log1p(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a reciprocal square root operation.
+    fn rsqrt(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::rsqrt(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a sign operation.
+    fn sign(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::sign(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a square root operation.
+    fn sqrt(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::sqrt(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a truncation operation.
+    fn trunc(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::trunc(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
 }
 
 impl<A: NDArray> NDArrayUnary for A {}
@@ -1082,12 +2362,84 @@ pub trait NDArrayMath: NDArray + Sized {
         Ok(ArrayOp::new(shape, op))
     }
 
-    /// Construct an array logarithm operation with the given `base`.
-    fn log<O>(self, base: O) -> Result<ArrayOp<ArrayDualFloat<Self::DType, Self, O>>, Error>
+    /// Construct an addition operation with the given `rhs`, promoting both to a common data type.
+    fn add_promoted<O>(
+        self,
+        rhs: O,
+    ) -> Result<ArrayOp<ArrayDualPromoted<Self::DType, O::DType, Self, O>>, Error>
     where
-        O: NDArray<DType = <Self::DType as CDatatype>::Float> + Sized,
+        O: NDArray + Sized,
+        Self::DType: Promote<O::DType>,
     {
-        let shape = check_shape(self.shape(), base.shape())?;
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDualPromoted::add(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a division operation with the given `rhs`, promoting both to a common data type,
+    /// which will enter undefined behavior if `rhs` contains zeros.
+    fn div_promoted<O>(
+        self,
+        rhs: O,
+    ) -> Result<ArrayOp<ArrayDualPromoted<Self::DType, O::DType, Self, O>>, Error>
+    where
+        O: NDArray + Sized,
+        Self::DType: Promote<O::DType>,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDualPromoted::div(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an array multiplication operation with the given `rhs`, promoting both to a common data type.
+    fn mul_promoted<O>(
+        self,
+        rhs: O,
+    ) -> Result<ArrayOp<ArrayDualPromoted<Self::DType, O::DType, Self, O>>, Error>
+    where
+        O: NDArray + Sized,
+        Self::DType: Promote<O::DType>,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDualPromoted::mul(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an array modulo operation with the given `rhs`, promoting both to a common data type.
+    fn rem_promoted<O>(
+        self,
+        rhs: O,
+    ) -> Result<ArrayOp<ArrayDualPromoted<Self::DType, O::DType, Self, O>>, Error>
+    where
+        O: NDArray + Sized,
+        Self::DType: Promote<O::DType>,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDualPromoted::rem(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an array subtraction operation with the given `rhs`, promoting both to a common data type.
+    fn sub_promoted<O>(
+        self,
+        rhs: O,
+    ) -> Result<ArrayOp<ArrayDualPromoted<Self::DType, O::DType, Self, O>>, Error>
+    where
+        O: NDArray + Sized,
+        Self::DType: Promote<O::DType>,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDualPromoted::sub(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an array logarithm operation with the given `base`.
+    fn log<O>(self, base: O) -> Result<ArrayOp<ArrayDualFloat<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = <Self::DType as CDatatype>::Float> + Sized,
+        Self::DType: PartialOrd,
+    {
+        let shape = check_shape(self.shape(), base.shape())?;
         let op = ArrayDualFloat::log(self, base)?;
         Ok(ArrayOp::new(shape, op))
     }
@@ -1096,11 +2448,56 @@ pub trait NDArrayMath: NDArray + Sized {
     fn pow<O>(self, exp: O) -> Result<ArrayOp<ArrayDualFloat<Self::DType, Self, O>>, Error>
     where
         O: NDArray<DType = <Self::DType as CDatatype>::Float> + Sized,
+        Self::DType: PartialOrd,
     {
         let shape = check_shape(self.shape(), exp.shape())?;
         let op = ArrayDualFloat::pow(self, exp)?;
         Ok(ArrayOp::new(shape, op))
     }
+
+    /// Construct an elementwise maximum operation with the given `rhs`.
+    fn maximum<O>(self, rhs: O) -> Result<ArrayOp<ArrayDual<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = Self::DType> + Sized,
+        Self::DType: PartialOrd,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDual::maximum(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an elementwise minimum operation with the given `rhs`.
+    fn minimum<O>(self, rhs: O) -> Result<ArrayOp<ArrayDual<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = Self::DType> + Sized,
+        Self::DType: PartialOrd,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDual::minimum(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a four-quadrant arctangent operation of this array (`y`) and `x`.
+    fn atan2<O>(self, x: O) -> Result<ArrayOp<ArrayDualFloat<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = <Self::DType as CDatatype>::Float> + Sized,
+        Self::DType: PartialOrd,
+    {
+        let shape = check_shape(self.shape(), x.shape())?;
+        let op = ArrayDualFloat::atan2(self, x)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a hypotenuse operation with the given `rhs`.
+    fn hypot<O>(self, rhs: O) -> Result<ArrayOp<ArrayDualFloat<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = <Self::DType as CDatatype>::Float> + Sized,
+        Self::DType: PartialOrd,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDualFloat::hypot(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
 }
 
 impl<A: NDArray> NDArrayMath for A {}
@@ -1161,50 +2558,643 @@ pub trait NDArrayMathScalar: NDArray + Sized {
         Ok(ArrayOp::new(shape, op))
     }
 
-    /// Construct a scalar logarithm operation.
-    fn log_scalar(
-        self,
-        base: <Self::DType as CDatatype>::Float,
-    ) -> Result<ArrayOp<ArrayScalarFloat<Self::DType, Self>>, Error> {
+    /// Construct a scalar logarithm operation.
+    fn log_scalar(
+        self,
+        base: <Self::DType as CDatatype>::Float,
+    ) -> Result<ArrayOp<ArrayScalarFloat<Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalarFloat::log(self, base)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a scalar exponentiation operation.
+    fn pow_scalar(
+        self,
+        exp: <Self::DType as CDatatype>::Float,
+    ) -> Result<ArrayOp<ArrayScalarFloat<Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalarFloat::pow(self, exp)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a scalar maximum operation.
+    fn maximum_scalar(
+        self,
+        rhs: Self::DType,
+    ) -> Result<ArrayOp<ArrayScalar<Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalar::maximum(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a scalar minimum operation.
+    fn minimum_scalar(
+        self,
+        rhs: Self::DType,
+    ) -> Result<ArrayOp<ArrayScalar<Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalar::minimum(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an operation to clamp the elements of this array to the range `[min, max]`.
+    fn clamp(
+        self,
+        min: Self::DType,
+        max: Self::DType,
+    ) -> Result<ArrayOp<ArrayClamp<Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
+        let shape = self.shape().to_vec();
+        let op = ArrayClamp::new(self, min, max)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+}
+
+impl<A: NDArray> NDArrayMathScalar for A {}
+
+/// Float-specific array methods
+pub trait NDArrayNumeric: NDArray + Sized
+where
+    Self::DType: Float,
+{
+    /// Test which elements of this array are infinite.
+    fn is_inf(self) -> Result<ArrayOp<ArrayUnary<Self::DType, u8, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::inf(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Test which elements of this array are not-a-number.
+    fn is_nan(self) -> Result<ArrayOp<ArrayUnary<Self::DType, u8, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::nan(self).expect("op");
+        Ok(ArrayOp::new(shape, op))
+    }
+}
+
+impl<A: NDArray> NDArrayNumeric for A where A::DType: Float {}
+
+/// Neural network activation functions
+pub trait NDArrayActivation: NDArray + Sized
+where
+    Self::DType: Float,
+{
+    /// Construct an exponential linear unit (ELU) activation with the given `alpha`.
+    fn elu(self, alpha: Self::DType) -> Result<ArrayOp<ArrayScalar<Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalar::elu(self, alpha)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an exact Gaussian error linear unit (GELU) activation.
+    fn gelu(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::gelu(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a Gaussian error linear unit (GELU) activation using the `tanh` approximation.
+    fn gelu_tanh(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::gelu_tanh(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a hard `tanh` activation, which clamps each element to `[-1, 1]`.
+    fn hardtanh(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::hardtanh(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a leaky rectified linear unit activation with the negative slope `alpha`.
+    fn leaky_relu(
+        self,
+        alpha: Self::DType,
+    ) -> Result<ArrayOp<ArrayScalar<Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalar::leaky_relu(self, alpha)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a rectified linear unit (ReLU) activation.
+    fn relu(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::relu(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a logistic sigmoid activation.
+    fn sigmoid(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::sigmoid(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a sigmoid linear unit (SiLU) activation.
+    fn silu(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::silu(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a softplus activation.
+    fn softplus(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::softplus(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+}
+
+impl<A: NDArray> NDArrayActivation for A where A::DType: Float {}
+
+/// Array bitwise methods, for integer data types
+pub trait NDArrayBitwise: NDArray + Sized
+where
+    Self::DType: Integer,
+{
+    /// Construct a bitwise and operation with the given `rhs`.
+    fn bitand<O>(self, rhs: O) -> Result<ArrayOp<ArrayDual<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = Self::DType> + Sized,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDual::bitand(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a bitwise or operation with the given `rhs`.
+    fn bitor<O>(self, rhs: O) -> Result<ArrayOp<ArrayDual<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = Self::DType> + Sized,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDual::bitor(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a bitwise exclusive or operation with the given `rhs`.
+    fn bitxor<O>(self, rhs: O) -> Result<ArrayOp<ArrayDual<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = Self::DType> + Sized,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDual::bitxor(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a left shift operation with the given `rhs`.
+    fn shl<O>(self, rhs: O) -> Result<ArrayOp<ArrayDual<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = Self::DType> + Sized,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDual::shl(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a right shift operation with the given `rhs`.
+    fn shr<O>(self, rhs: O) -> Result<ArrayOp<ArrayDual<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = Self::DType> + Sized,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDual::shr(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a bitwise and operation with the given scalar `rhs`.
+    fn bitand_scalar(
+        self,
+        rhs: Self::DType,
+    ) -> Result<ArrayOp<ArrayScalar<Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalar::bitand(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a bitwise or operation with the given scalar `rhs`.
+    fn bitor_scalar(
+        self,
+        rhs: Self::DType,
+    ) -> Result<ArrayOp<ArrayScalar<Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalar::bitor(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a bitwise exclusive or operation with the given scalar `rhs`.
+    fn bitxor_scalar(
+        self,
+        rhs: Self::DType,
+    ) -> Result<ArrayOp<ArrayScalar<Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalar::bitxor(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a left shift operation with the given scalar `rhs`.
+    fn shl_scalar(
+        self,
+        rhs: Self::DType,
+    ) -> Result<ArrayOp<ArrayScalar<Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalar::shl(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a right shift operation with the given scalar `rhs`.
+    fn shr_scalar(
+        self,
+        rhs: Self::DType,
+    ) -> Result<ArrayOp<ArrayScalar<Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalar::shr(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a bitwise not operation.
+    fn bitnot(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::bitnot(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an operation to count the bits set in each element.
+    fn popcount(self) -> Result<ArrayOp<ArrayUnary<Self::DType, Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::popcount(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+}
+
+impl<A: NDArray> NDArrayBitwise for A where A::DType: Integer {}
+
+/// Integer arithmetic operations with well-defined overflow behavior
+pub trait NDArrayIntegerMath: NDArray + Sized
+where
+    Self::DType: Integer,
+{
+    /// Construct an addition operation with the given `rhs`,
+    /// which wraps around at the bounds of the data type.
+    fn wrapping_add<O>(self, rhs: O) -> Result<ArrayOp<ArrayDual<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = Self::DType> + Sized,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDual::wrapping_add(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a subtraction operation with the given `rhs`,
+    /// which wraps around at the bounds of the data type.
+    fn wrapping_sub<O>(self, rhs: O) -> Result<ArrayOp<ArrayDual<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = Self::DType> + Sized,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDual::wrapping_sub(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a multiplication operation with the given `rhs`,
+    /// which wraps around at the bounds of the data type.
+    fn wrapping_mul<O>(self, rhs: O) -> Result<ArrayOp<ArrayDual<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = Self::DType> + Sized,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDual::wrapping_mul(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an addition operation with the given `rhs`,
+    /// which saturates at the bounds of the data type.
+    fn saturating_add<O>(self, rhs: O) -> Result<ArrayOp<ArrayDual<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = Self::DType> + Sized,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDual::saturating_add(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a subtraction operation with the given `rhs`,
+    /// which saturates at the bounds of the data type.
+    fn saturating_sub<O>(self, rhs: O) -> Result<ArrayOp<ArrayDual<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = Self::DType> + Sized,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDual::saturating_sub(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a multiplication operation with the given `rhs`,
+    /// which saturates at the bounds of the data type.
+    fn saturating_mul<O>(self, rhs: O) -> Result<ArrayOp<ArrayDual<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = Self::DType> + Sized,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDual::saturating_mul(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an addition operation with the given `rhs`,
+    /// which will return an error when read if any element overflows.
+    fn checked_add<O>(
+        self,
+        rhs: O,
+    ) -> Result<ArrayOp<ArrayDualChecked<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = Self::DType> + Sized,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDualChecked::add(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a subtraction operation with the given `rhs`,
+    /// which will return an error when read if any element overflows.
+    fn checked_sub<O>(
+        self,
+        rhs: O,
+    ) -> Result<ArrayOp<ArrayDualChecked<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = Self::DType> + Sized,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDualChecked::sub(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a multiplication operation with the given `rhs`,
+    /// which will return an error when read if any element overflows.
+    fn checked_mul<O>(
+        self,
+        rhs: O,
+    ) -> Result<ArrayOp<ArrayDualChecked<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = Self::DType> + Sized,
+    {
+        let shape = check_shape(self.shape(), rhs.shape())?;
+        let op = ArrayDualChecked::mul(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a wrapping scalar addition operation.
+    fn wrapping_add_scalar(
+        self,
+        rhs: Self::DType,
+    ) -> Result<ArrayOp<ArrayScalar<Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalar::wrapping_add(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a wrapping scalar subtraction operation.
+    fn wrapping_sub_scalar(
+        self,
+        rhs: Self::DType,
+    ) -> Result<ArrayOp<ArrayScalar<Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalar::wrapping_sub(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a wrapping scalar multiplication operation.
+    fn wrapping_mul_scalar(
+        self,
+        rhs: Self::DType,
+    ) -> Result<ArrayOp<ArrayScalar<Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalar::wrapping_mul(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a saturating scalar addition operation.
+    fn saturating_add_scalar(
+        self,
+        rhs: Self::DType,
+    ) -> Result<ArrayOp<ArrayScalar<Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalar::saturating_add(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a saturating scalar subtraction operation.
+    fn saturating_sub_scalar(
+        self,
+        rhs: Self::DType,
+    ) -> Result<ArrayOp<ArrayScalar<Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalar::saturating_sub(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a saturating scalar multiplication operation.
+    fn saturating_mul_scalar(
+        self,
+        rhs: Self::DType,
+    ) -> Result<ArrayOp<ArrayScalar<Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalar::saturating_mul(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a checked scalar addition operation,
+    /// which will return an error when read if any element overflows.
+    fn checked_add_scalar(
+        self,
+        rhs: Self::DType,
+    ) -> Result<ArrayOp<ArrayScalarChecked<Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalarChecked::add(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a checked scalar subtraction operation,
+    /// which will return an error when read if any element overflows.
+    fn checked_sub_scalar(
+        self,
+        rhs: Self::DType,
+    ) -> Result<ArrayOp<ArrayScalarChecked<Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalarChecked::sub(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a checked scalar multiplication operation,
+    /// which will return an error when read if any element overflows.
+    fn checked_mul_scalar(
+        self,
+        rhs: Self::DType,
+    ) -> Result<ArrayOp<ArrayScalarChecked<Self::DType, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayScalarChecked::mul(self, rhs)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+}
+
+impl<A: NDArray> NDArrayIntegerMath for A where A::DType: Integer {}
+
+/// Array methods for complex data types, whose real and imaginary parts are of type `F`
+#[cfg(feature = "complex")]
+pub trait NDArrayComplex<F: Float>: NDArray<DType = Complex<F>> + Sized
+where
+    Complex<F>: CDatatype,
+{
+    /// Construct an operation to compute the phase angle of each element, in radians.
+    fn angle(self) -> Result<ArrayOp<ArrayUnary<Complex<F>, F, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::angle(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a complex conjugate operation.
+    fn conj(self) -> Result<ArrayOp<ArrayUnary<Complex<F>, Complex<F>, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::conj(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an operation to extract the imaginary part of each element.
+    fn imag(self) -> Result<ArrayOp<ArrayUnary<Complex<F>, F, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::imag(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an operation to compute the absolute value (modulus) of each element.
+    ///
+    /// Unlike [`NDArrayUnary::abs`], the output data type is real.
+    #[doc(alias = "abs")]
+    fn norm(self) -> Result<ArrayOp<ArrayUnary<Complex<F>, F, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::norm(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an operation to extract the real part of each element.
+    fn real(self) -> Result<ArrayOp<ArrayUnary<Complex<F>, F, Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayUnary::real(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+}
+
+#[cfg(feature = "complex")]
+impl<F: Float, A: NDArray<DType = Complex<F>>> NDArrayComplex<F> for A where Complex<F>: CDatatype {}
+
+/// Fourier transform methods for complex arrays
+#[cfg(feature = "fft")]
+pub trait NDArrayFFT<F>: NDArray<DType = Complex<F>> + Sized
+where
+    F: Float + FftNum,
+    Complex<F>: CDatatype,
+{
+    /// Construct a fast Fourier transform along the given `axis`.
+    fn fft(self, axis: usize) -> Result<ArrayOp<ArrayFFT<Self>>, Error> {
+        self.fftn(vec![axis])
+    }
+
+    /// Construct a fast Fourier transform over the last two axes.
+    fn fft2(self) -> Result<ArrayOp<ArrayFFT<Self>>, Error> {
+        let axes = last_two_axes(self.ndim())?;
+        self.fftn(axes)
+    }
+
+    /// Construct a fast Fourier transform over each of the given `axes`.
+    fn fftn(self, axes: Vec<usize>) -> Result<ArrayOp<ArrayFFT<Self>>, Error> {
+        let shape = self.shape().to_vec();
+        let op = ArrayFFT::forward(self, axes)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an inverse fast Fourier transform along the given `axis`.
+    fn ifft(self, axis: usize) -> Result<ArrayOp<ArrayFFT<Self>>, Error> {
+        self.ifftn(vec![axis])
+    }
+
+    /// Construct an inverse fast Fourier transform over the last two axes.
+    fn ifft2(self) -> Result<ArrayOp<ArrayFFT<Self>>, Error> {
+        let axes = last_two_axes(self.ndim())?;
+        self.ifftn(axes)
+    }
+
+    /// Construct an inverse fast Fourier transform over each of the given `axes`.
+    fn ifftn(self, axes: Vec<usize>) -> Result<ArrayOp<ArrayFFT<Self>>, Error> {
         let shape = self.shape().to_vec();
-        let op = ArrayScalarFloat::log(self, base)?;
+        let op = ArrayFFT::inverse(self, axes)?;
         Ok(ArrayOp::new(shape, op))
     }
 
-    /// Construct a scalar exponentiation operation.
-    fn pow_scalar(
-        self,
-        exp: <Self::DType as CDatatype>::Float,
-    ) -> Result<ArrayOp<ArrayScalarFloat<Self::DType, Self>>, Error> {
-        let shape = self.shape().to_vec();
-        let op = ArrayScalarFloat::pow(self, exp)?;
+    /// Construct the inverse of [`NDArrayRealFFT::rfft`] along the given `axis`,
+    /// with output length `n`, which defaults to `2 * (m - 1)` given `m` input terms.
+    fn irfft(self, axis: usize, n: Option<usize>) -> Result<ArrayOp<ArrayIRFFT<Self>>, Error> {
+        let mut shape = self.shape().to_vec();
+
+        let n = match (n, shape.get(axis)) {
+            (Some(n), _) => n,
+            (None, Some(m)) => 2 * m.saturating_sub(1),
+            (None, None) => 0,
+        };
+
+        let op = ArrayIRFFT::new(self, axis, n)?;
+        shape[axis] = n;
         Ok(ArrayOp::new(shape, op))
     }
 }
 
-impl<A: NDArray> NDArrayMathScalar for A {}
-
-/// Float-specific array methods
-pub trait NDArrayNumeric: NDArray + Sized
+#[cfg(feature = "fft")]
+impl<F, A> NDArrayFFT<F> for A
 where
-    Self::DType: Float,
+    F: Float + FftNum,
+    Complex<F>: CDatatype,
+    A: NDArray<DType = Complex<F>>,
 {
-    /// Test which elements of this array are infinite.
-    fn is_inf(self) -> Result<ArrayOp<ArrayUnary<Self::DType, u8, Self>>, Error> {
-        let shape = self.shape().to_vec();
-        let op = ArrayUnary::inf(self)?;
-        Ok(ArrayOp::new(shape, op))
-    }
+}
 
-    /// Test which elements of this array are not-a-number.
-    fn is_nan(self) -> Result<ArrayOp<ArrayUnary<Self::DType, u8, Self>>, Error> {
-        let shape = self.shape().to_vec();
-        let op = ArrayUnary::nan(self).expect("op");
+/// Fourier transform methods for real arrays
+#[cfg(feature = "fft")]
+pub trait NDArrayRealFFT: NDArray + Sized
+where
+    Self::DType: Float + FftNum,
+    Complex<Self::DType>: CDatatype,
+{
+    /// Construct a fast Fourier transform of this real array along the given `axis`,
+    /// which outputs only the `n / 2 + 1` non-negative frequency terms.
+    fn rfft(self, axis: usize) -> Result<ArrayOp<ArrayRFFT<Self>>, Error> {
+        let mut shape = self.shape().to_vec();
+        let op = ArrayRFFT::new(self, axis)?;
+        shape[axis] = (shape[axis] / 2) + 1;
         Ok(ArrayOp::new(shape, op))
     }
 }
 
-impl<A: NDArray> NDArrayNumeric for A where A::DType: Float {}
+#[cfg(feature = "fft")]
+impl<A: NDArray> NDArrayRealFFT for A
+where
+    A::DType: Float + FftNum,
+    Complex<A::DType>: CDatatype,
+{
+}
 
 /// Array trigonometry methods
 pub trait NDArrayTrig: NDArray + Sized {
@@ -1212,6 +3202,8 @@ pub trait NDArrayTrig: NDArray + Sized {
     fn asin(
         self,
     ) -> Result<ArrayOp<ArrayUnary<Self::DType, <Self::DType as CDatatype>::Float, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
     {
         let shape = self.shape().to_vec();
         let op = ArrayUnary::asin(self)?;
@@ -1222,6 +3214,8 @@ pub trait NDArrayTrig: NDArray + Sized {
     fn sin(
         self,
     ) -> Result<ArrayOp<ArrayUnary<Self::DType, <Self::DType as CDatatype>::Float, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
     {
         let shape = self.shape().to_vec();
         let op = ArrayUnary::sin(self)?;
@@ -1232,6 +3226,8 @@ pub trait NDArrayTrig: NDArray + Sized {
     fn sinh(
         self,
     ) -> Result<ArrayOp<ArrayUnary<Self::DType, <Self::DType as CDatatype>::Float, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
     {
         let shape = self.shape().to_vec();
         let op = ArrayUnary::sinh(self)?;
@@ -1242,6 +3238,8 @@ pub trait NDArrayTrig: NDArray + Sized {
     fn acos(
         self,
     ) -> Result<ArrayOp<ArrayUnary<Self::DType, <Self::DType as CDatatype>::Float, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
     {
         let shape = self.shape().to_vec();
         let op = ArrayUnary::acos(self)?;
@@ -1252,6 +3250,8 @@ pub trait NDArrayTrig: NDArray + Sized {
     fn cos(
         self,
     ) -> Result<ArrayOp<ArrayUnary<Self::DType, <Self::DType as CDatatype>::Float, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
     {
         let shape = self.shape().to_vec();
         let op = ArrayUnary::cos(self)?;
@@ -1262,6 +3262,8 @@ pub trait NDArrayTrig: NDArray + Sized {
     fn cosh(
         self,
     ) -> Result<ArrayOp<ArrayUnary<Self::DType, <Self::DType as CDatatype>::Float, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
     {
         let shape = self.shape().to_vec();
         let op = ArrayUnary::cosh(self)?;
@@ -1272,6 +3274,8 @@ pub trait NDArrayTrig: NDArray + Sized {
     fn atan(
         self,
     ) -> Result<ArrayOp<ArrayUnary<Self::DType, <Self::DType as CDatatype>::Float, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
     {
         let shape = self.shape().to_vec();
         let op = ArrayUnary::atan(self)?;
@@ -1282,6 +3286,8 @@ pub trait NDArrayTrig: NDArray + Sized {
     fn tan(
         self,
     ) -> Result<ArrayOp<ArrayUnary<Self::DType, <Self::DType as CDatatype>::Float, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
     {
         let shape = self.shape().to_vec();
         let op = ArrayUnary::tan(self)?;
@@ -1292,6 +3298,8 @@ pub trait NDArrayTrig: NDArray + Sized {
     fn tanh(
         self,
     ) -> Result<ArrayOp<ArrayUnary<Self::DType, <Self::DType as CDatatype>::Float, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
     {
         let shape = self.shape().to_vec();
         let op = ArrayUnary::tanh(self)?;
@@ -1323,28 +3331,40 @@ pub trait NDArrayCompare<O: NDArray<DType = Self::DType>>: NDArray + Sized {
     }
 
     /// Construct a greater-than comparison with the `other` array.
-    fn gt(self, other: O) -> Result<ArrayOp<ArrayCompare<Self::DType, Self, O>>, Error> {
+    fn gt(self, other: O) -> Result<ArrayOp<ArrayCompare<Self::DType, Self, O>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         let shape = check_shape(self.shape(), other.shape())?;
         let op = ArrayCompare::gt(self, other)?;
         Ok(ArrayOp::new(shape, op))
     }
 
     /// Construct an equal-or-greater-than comparison with the `other` array.
-    fn ge(self, other: O) -> Result<ArrayOp<ArrayCompare<Self::DType, Self, O>>, Error> {
+    fn ge(self, other: O) -> Result<ArrayOp<ArrayCompare<Self::DType, Self, O>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         let shape = check_shape(self.shape(), other.shape())?;
         let op = ArrayCompare::ge(self, other)?;
         Ok(ArrayOp::new(shape, op))
     }
 
     /// Construct an equal-or-less-than comparison with the `other` array.
-    fn lt(self, other: O) -> Result<ArrayOp<ArrayCompare<Self::DType, Self, O>>, Error> {
+    fn lt(self, other: O) -> Result<ArrayOp<ArrayCompare<Self::DType, Self, O>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         let shape = check_shape(self.shape(), other.shape())?;
         let op = ArrayCompare::lt(self, other)?;
         Ok(ArrayOp::new(shape, op))
     }
 
     /// Construct an equal-or-less-than comparison with the `other` array.
-    fn le(self, other: O) -> Result<ArrayOp<ArrayCompare<Self::DType, Self, O>>, Error> {
+    fn le(self, other: O) -> Result<ArrayOp<ArrayCompare<Self::DType, Self, O>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         let shape = check_shape(self.shape(), other.shape())?;
         let op = ArrayCompare::le(self, other)?;
         Ok(ArrayOp::new(shape, op))
@@ -1356,6 +3376,41 @@ pub trait NDArrayCompare<O: NDArray<DType = Self::DType>>: NDArray + Sized {
         let op = ArrayCompare::ne(self, other)?;
         Ok(ArrayOp::new(shape, op))
     }
+
+    /// Construct an approximate equality comparison with the `other` array,
+    /// which is true where `|self - other| <= atol + rtol * |other|`
+    /// (or where both are `NaN`, if `equal_nan` is set).
+    fn isclose(
+        self,
+        other: O,
+        rtol: Self::DType,
+        atol: Self::DType,
+        equal_nan: bool,
+    ) -> Result<ArrayOp<ArrayCompare<Self::DType, Self, O>>, Error>
+    where
+        Self::DType: Float,
+    {
+        let shape = check_shape(self.shape(), other.shape())?;
+        let op = ArrayCompare::isclose(self, other, rtol, atol, equal_nan)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Return `true` if every element of this array is approximately equal to the `other`.
+    /// See [`NDArrayCompare::isclose`].
+    fn allclose(
+        self,
+        other: O,
+        rtol: Self::DType,
+        atol: Self::DType,
+        equal_nan: bool,
+    ) -> Result<bool, Error>
+    where
+        Self: NDArrayRead,
+        Self::DType: Float,
+        O: NDArrayRead,
+    {
+        self.isclose(other, rtol, atol, equal_nan)?.all()
+    }
 }
 
 impl<A: NDArray, O: NDArray> NDArrayCompare<O> for A where O: NDArray<DType = A::DType> {}
@@ -1376,7 +3431,10 @@ pub trait NDArrayCompareScalar: NDArray + Sized {
     fn gt_scalar(
         self,
         other: Self::DType,
-    ) -> Result<ArrayOp<ArrayCompareScalar<Self::DType, Self>>, Error> {
+    ) -> Result<ArrayOp<ArrayCompareScalar<Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         let shape = self.shape().to_vec();
         let op = ArrayCompareScalar::gt(self, other)?;
         Ok(ArrayOp::new(shape, op))
@@ -1386,7 +3444,10 @@ pub trait NDArrayCompareScalar: NDArray + Sized {
     fn ge_scalar(
         self,
         other: Self::DType,
-    ) -> Result<ArrayOp<ArrayCompareScalar<Self::DType, Self>>, Error> {
+    ) -> Result<ArrayOp<ArrayCompareScalar<Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         let shape = self.shape().to_vec();
         let op = ArrayCompareScalar::ge(self, other)?;
         Ok(ArrayOp::new(shape, op))
@@ -1396,7 +3457,10 @@ pub trait NDArrayCompareScalar: NDArray + Sized {
     fn lt_scalar(
         self,
         other: Self::DType,
-    ) -> Result<ArrayOp<ArrayCompareScalar<Self::DType, Self>>, Error> {
+    ) -> Result<ArrayOp<ArrayCompareScalar<Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         let shape = self.shape().to_vec();
         let op = ArrayCompareScalar::lt(self, other)?;
         Ok(ArrayOp::new(shape, op))
@@ -1406,7 +3470,10 @@ pub trait NDArrayCompareScalar: NDArray + Sized {
     fn le_scalar(
         self,
         other: Self::DType,
-    ) -> Result<ArrayOp<ArrayCompareScalar<Self::DType, Self>>, Error> {
+    ) -> Result<ArrayOp<ArrayCompareScalar<Self::DType, Self>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         let shape = self.shape().to_vec();
         let op = ArrayCompareScalar::le(self, other)?;
         Ok(ArrayOp::new(shape, op))
@@ -1430,77 +3497,330 @@ impl<A: NDArray> NDArrayCompareScalar for A {}
 
 /// Matrix operations
 pub trait MatrixMath: NDArray + fmt::Debug {
-    /// Construct an operation to read the diagonal of this matrix or batch of matrices.
-    fn diagonal(self) -> Result<ArrayOp<MatDiag<Self>>, Error>
+    /// Construct an operation to compute the lower triangular Cholesky factor `L`
+    /// of this symmetric positive-definite matrix or batch of matrices, such that `A = L L^T`.
+    ///
+    /// Only the lower triangle of this matrix is read.
+    /// Reading the result returns an [`Error::Singular`] if any matrix is not positive-definite.
+    fn cholesky(self) -> Result<ArrayOp<MatDecomposition<Self::DType, Self>>, Error>
     where
         Self: Sized,
+        Self::DType: Float,
     {
-        if self.ndim() >= 2 && self.shape()[self.ndim() - 1] == self.shape()[self.ndim() - 2] {
-            let shape = self.shape().iter().take(self.ndim() - 1).copied().collect();
-            let op = MatDiag::new(self)?;
-            Ok(ArrayOp::new(shape, op))
-        } else {
-            Err(Error::Bounds(format!(
-                "diagonal requires a square matrix, not {:?}",
-                self
-            )))
-        }
+        let op = MatDecomposition::cholesky(self)?;
+        Ok(ArrayOp::new(op.shape().to_vec(), op))
+    }
+
+    /// Construct an operation to compute the determinant of this square matrix
+    /// or batch of square matrices.
+    fn det(self) -> Result<ArrayOp<MatDet<Self::DType, Self>>, Error>
+    where
+        Self: Sized,
+        Self::DType: Float,
+    {
+        let shape = batch_shape(self.shape());
+        let op = MatDet::new(self, DetPart::Det)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an operation to build a square diagonal matrix from this vector,
+    /// or a batch of diagonal matrices from this batch of vectors.
+    fn diag_embed(self) -> Result<ArrayOp<MatDiagEmbed<Self>>, Error>
+    where
+        Self: Sized,
+    {
+        let op = MatDiagEmbed::new(self)?;
+        Ok(ArrayOp::new(op.shape(), op))
+    }
+
+    /// Construct an operation to read a diagonal of this matrix or batch of matrices.
+    ///
+    /// An `offset` of zero reads the main diagonal, a positive `offset` reads a diagonal
+    /// above the main diagonal, and a negative `offset` reads a diagonal below it.
+    fn diagonal(self, offset: isize) -> Result<ArrayOp<MatDiag<Self>>, Error>
+    where
+        Self: Sized,
+    {
+        let op = MatDiag::new(self, offset)?;
+        Ok(ArrayOp::new(op.shape().to_vec(), op))
+    }
+
+    /// Construct operations to compute the eigenvalues, in ascending order, and the eigenvectors
+    /// (as columns) of this symmetric matrix or batch of symmetric matrices.
+    ///
+    /// Only the lower triangle of this matrix is read.
+    /// Each of the two operations computes the decomposition when it's read.
+    #[allow(clippy::type_complexity)]
+    fn eigh(
+        self,
+    ) -> Result<
+        (
+            ArrayOp<MatDecomposition<Self::DType, Self>>,
+            ArrayOp<MatDecomposition<Self::DType, Self>>,
+        ),
+        Error,
+    >
+    where
+        Self: Clone,
+        Self::DType: Float,
+    {
+        let values = MatDecomposition::eigenvalues(self.clone())?;
+        let vectors = MatDecomposition::eigenvectors(self)?;
+
+        Ok((
+            ArrayOp::new(values.shape().to_vec(), values),
+            ArrayOp::new(vectors.shape().to_vec(), vectors),
+        ))
+    }
+
+    /// Construct an operation to compute the inverse of this square matrix
+    /// or batch of square matrices.
+    ///
+    /// Reading the result returns an [`Error::Singular`] if any matrix is singular.
+    fn inverse(self) -> Result<ArrayOp<MatInverse<Self::DType, Self>>, Error>
+    where
+        Self: Sized,
+        Self::DType: Float,
+    {
+        let shape = self.shape().to_vec();
+        let op = MatInverse::new(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an operation to compute the Kronecker product of this matrix with `other`,
+    /// or of each matrix in this batch with the corresponding matrix in `other`.
+    fn kron<O>(self, other: O) -> Result<ArrayOp<MatKron<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = Self::DType> + fmt::Debug,
+        Self: Sized,
+    {
+        let op = MatKron::new(self, other)?;
+        Ok(ArrayOp::new(op.shape().to_vec(), op))
+    }
+
+    /// Construct operations to read the permutation matrix `P`, the unit lower triangular
+    /// matrix `L`, and the upper triangular matrix `U` of the LU decomposition with partial
+    /// pivoting `A = P L U` of this square matrix or batch of square matrices.
+    ///
+    /// Each of the three operations computes the decomposition when it's read.
+    #[allow(clippy::type_complexity)]
+    fn lu(
+        self,
+    ) -> Result<
+        (
+            ArrayOp<MatLU<Self::DType, Self>>,
+            ArrayOp<MatLU<Self::DType, Self>>,
+            ArrayOp<MatLU<Self::DType, Self>>,
+        ),
+        Error,
+    >
+    where
+        Self: Clone,
+        Self::DType: Float,
+    {
+        let shape = self.shape().to_vec();
+        let p = MatLU::new(self.clone(), LUFactor::Permutation)?;
+        let l = MatLU::new(self.clone(), LUFactor::Lower)?;
+        let u = MatLU::new(self, LUFactor::Upper)?;
+
+        Ok((
+            ArrayOp::new(shape.to_vec(), p),
+            ArrayOp::new(shape.to_vec(), l),
+            ArrayOp::new(shape, u),
+        ))
     }
 
     /// Construct an operation to multiply this matrix or batch of matrices with the `other`.
+    ///
+    /// As in NumPy, the batch dimensions of both operands are broadcast together,
+    /// and a 1-dimensional operand is treated as a row vector (on the left) or a column vector
+    /// (on the right), whose axis is removed from the shape of the product.
     fn matmul<O>(self, other: O) -> Result<ArrayOp<MatMul<Self::DType, Self, O>>, Error>
     where
         O: NDArray<DType = Self::DType> + fmt::Debug,
         Self: Sized,
     {
-        if self.ndim() < 2 || other.ndim() < 2 {
-            return Err(Error::Bounds(format!(
-                "invalid matrices for matmul: {:?} and {:?}",
-                self, other
-            )));
-        }
+        let op = MatMul::new(self, other)?;
+        Ok(ArrayOp::new(op.shape(), op))
+    }
 
-        let ndim = self.ndim();
-        let prefix = &self.shape()[..ndim - 2];
+    /// Construct an operation to compute the outer product of this vector with `other`,
+    /// or of each vector in this batch with the corresponding vector in `other`.
+    fn outer<O>(self, other: O) -> Result<ArrayOp<MatOuter<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = Self::DType> + fmt::Debug,
+        Self: Sized,
+    {
+        let op = MatOuter::new(self, other)?;
+        Ok(ArrayOp::new(op.shape(), op))
+    }
 
-        if other.ndim() != ndim {
-            return Err(Error::Bounds(format!(
-                "matrix multiply expects at least two dimensions but found shapes {:?} and {:?}",
-                self.shape(),
-                other.shape()
-            )));
-        } else if &other.shape()[..ndim - 2] != prefix {
-            return Err(Error::Bounds(format!(
-                "matrix multiply requires the same batch shape, not {:?} and {:?}",
-                prefix,
-                &other.shape()[..ndim - 2]
-            )));
-        }
+    /// Construct operations to compute the orthonormal matrix `Q` and the upper triangular
+    /// matrix `R` of the reduced QR decomposition `A = Q R` of this matrix or batch of matrices.
+    ///
+    /// If this matrix is `m x n` then `Q` is `m x k` and `R` is `k x n`, where `k = min(m, n)`.
+    /// Each of the two operations computes the decomposition when it's read.
+    #[allow(clippy::type_complexity)]
+    fn qr(
+        self,
+    ) -> Result<
+        (
+            ArrayOp<MatDecomposition<Self::DType, Self>>,
+            ArrayOp<MatDecomposition<Self::DType, Self>>,
+        ),
+        Error,
+    >
+    where
+        Self: Clone,
+        Self::DType: Float,
+    {
+        let q = MatDecomposition::qr_q(self.clone())?;
+        let r = MatDecomposition::qr_r(self)?;
 
-        let a = self.shape()[ndim - 2];
-        let b = self.shape()[ndim - 1];
-        let c = other.shape()[ndim - 1];
+        Ok((
+            ArrayOp::new(q.shape().to_vec(), q),
+            ArrayOp::new(r.shape().to_vec(), r),
+        ))
+    }
 
-        if other.shape()[ndim - 2] != b {
-            return Err(Error::Bounds(format!(
-                "invalid dimensions for matrix multiply: {:?} and {:?}",
-                self.shape(),
-                other.shape()
-            )));
-        }
+    /// Construct operations to compute the sign and the natural log of the absolute value
+    /// of the determinant of this square matrix or batch of square matrices.
+    ///
+    /// The sign of the determinant of a singular matrix is zero, and its log is negative infinity.
+    #[allow(clippy::type_complexity)]
+    fn slogdet(
+        self,
+    ) -> Result<
+        (
+            ArrayOp<MatDet<Self::DType, Self>>,
+            ArrayOp<MatDet<Self::DType, Self>>,
+        ),
+        Error,
+    >
+    where
+        Self: Clone,
+        Self::DType: Float,
+    {
+        let shape = batch_shape(self.shape());
+        let sign = MatDet::new(self.clone(), DetPart::Sign)?;
+        let logabsdet = MatDet::new(self, DetPart::LogAbsDet)?;
+        Ok((
+            ArrayOp::new(shape.to_vec(), sign),
+            ArrayOp::new(shape, logabsdet),
+        ))
+    }
+
+    /// Construct an operation to solve the linear system `A X = B` for `X`,
+    /// where `A` is this square matrix or batch of square matrices and `B` is the `other`.
+    ///
+    /// As in NumPy, `B` is a vector if it has one dimension and a matrix otherwise,
+    /// and the batch dimensions of `A` and `B` are broadcast together.
+    /// Reading the result returns an [`Error::Singular`] if any matrix in `A` is singular.
+    fn solve<O>(self, other: O) -> Result<ArrayOp<MatSolve<Self::DType, Self, O>>, Error>
+    where
+        O: NDArray<DType = Self::DType> + fmt::Debug,
+        Self: Sized,
+        Self::DType: Float,
+    {
+        let op = MatSolve::new(self, other)?;
+        Ok(ArrayOp::new(op.shape(), op))
+    }
+
+    /// Construct operations to compute the left singular vectors `U` (as columns),
+    /// the singular values `S` in descending order, and the right singular vectors `V^T` (as rows)
+    /// of the singular value decomposition `A = U diag(S) V^T` of this matrix or batch of matrices.
+    ///
+    /// If this matrix is `m x n` then `S` has `k = min(m, n)` values, and `U` and `V^T`
+    /// are `m x m` and `n x n` if `full_matrices` is `true`, or `m x k` and `k x n` otherwise.
+    /// Each of the three operations computes the decomposition when it's read.
+    #[allow(clippy::type_complexity)]
+    fn svd(
+        self,
+        full_matrices: bool,
+    ) -> Result<
+        (
+            ArrayOp<MatDecomposition<Self::DType, Self>>,
+            ArrayOp<MatDecomposition<Self::DType, Self>>,
+            ArrayOp<MatDecomposition<Self::DType, Self>>,
+        ),
+        Error,
+    >
+    where
+        Self: Clone,
+        Self::DType: Float,
+    {
+        let u = MatDecomposition::svd_u(self.clone(), full_matrices)?;
+        let s = MatDecomposition::svd_s(self.clone())?;
+        let vh = MatDecomposition::svd_vh(self, full_matrices)?;
 
-        let mut shape = Vec::with_capacity(ndim);
-        shape.extend_from_slice(prefix);
-        shape.push(a);
-        shape.push(c);
+        Ok((
+            ArrayOp::new(u.shape().to_vec(), u),
+            ArrayOp::new(s.shape().to_vec(), s),
+            ArrayOp::new(vh.shape().to_vec(), vh),
+        ))
+    }
 
-        let op = MatMul::new(self, other)?;
+    /// Construct a contraction of the given `axes` of this array with those of the `other`,
+    /// resulting in an array with the remaining axes of this array followed by those of the `other`.
+    fn tensordot<O>(
+        self,
+        other: O,
+        axes: (Vec<usize>, Vec<usize>),
+    ) -> Result<Array<Self::DType>, Error>
+    where
+        O: NDArray<DType = Self::DType> + fmt::Debug,
+        Self: Sized,
+        Array<Self::DType>: From<Self> + From<O>,
+    {
+        einsum::tensordot(self.into(), other.into(), axes)
+    }
+
+    /// Construct an operation to compute the sum of the main diagonal of this matrix
+    /// or of each matrix in this batch.
+    fn trace(self) -> Result<ArrayOp<MatTrace<Self>>, Error>
+    where
+        Self: Sized,
+    {
+        let shape = batch_shape(self.shape());
+        let op = MatTrace::new(self)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an operation to read the lower triangle of this matrix or batch of matrices,
+    /// i.e. the `k`th diagonal and every element below it, with every other element set to zero.
+    fn tril(self, k: isize) -> Result<ArrayOp<MatTriangle<Self>>, Error>
+    where
+        Self: Sized,
+    {
+        let shape = self.shape().to_vec();
+        let op = MatTriangle::lower(self, k)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an operation to read the upper triangle of this matrix or batch of matrices,
+    /// i.e. the `k`th diagonal and every element above it, with every other element set to zero.
+    fn triu(self, k: isize) -> Result<ArrayOp<MatTriangle<Self>>, Error>
+    where
+        Self: Sized,
+    {
+        let shape = self.shape().to_vec();
+        let op = MatTriangle::upper(self, k)?;
         Ok(ArrayOp::new(shape, op))
     }
 }
 
 impl<A: NDArray + fmt::Debug> MatrixMath for A {}
 
+// the shape of the output of a matrix reduction, like a determinant
+#[inline]
+fn batch_shape(shape: &[usize]) -> Shape {
+    if shape.len() > 2 {
+        shape[..shape.len() - 2].to_vec()
+    } else {
+        vec![1]
+    }
+}
+
 /// Boolean array reduce operations
 pub trait NDArrayReduceBoolean: NDArrayRead {
     /// Return `true` if this array contains only non-zero elements.
@@ -1523,14 +3843,20 @@ impl<A: NDArrayRead> NDArrayReduceBoolean for A {}
 /// Array reduce operations
 pub trait NDArrayReduceAll: NDArrayRead {
     /// Return the maximum element in this array.
-    fn max_all(&self) -> Result<Self::DType, Error> {
+    fn max_all(&self) -> Result<Self::DType, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         let queue = Queue::new(self.context().clone(), self.size())?;
         let buffer = self.read(&queue)?;
         buffer.max(&queue)
     }
 
     /// Return the minimum element in this array.
-    fn min_all(&self) -> Result<Self::DType, Error> {
+    fn min_all(&self) -> Result<Self::DType, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         let queue = Queue::new(self.context().clone(), self.size())?;
         let buffer = self.read(&queue)?;
         buffer.min(&queue)
@@ -1563,7 +3889,10 @@ where
         self,
         mut axes: Vec<usize>,
         keepdims: bool,
-    ) -> Result<ArrayOp<ArrayReduceAxes<Self::DType, Array<Self::DType>>>, Error> {
+    ) -> Result<ArrayOp<ArrayReduceAxes<Self::DType, Array<Self::DType>>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         axes.sort();
         axes.dedup();
 
@@ -1579,7 +3908,10 @@ where
         self,
         mut axes: Vec<usize>,
         keepdims: bool,
-    ) -> Result<ArrayOp<ArrayReduceAxes<Self::DType, Array<Self::DType>>>, Error> {
+    ) -> Result<ArrayOp<ArrayReduceAxes<Self::DType, Array<Self::DType>>>, Error>
+    where
+        Self::DType: PartialOrd,
+    {
         axes.sort();
         axes.dedup();
 
@@ -1630,6 +3962,101 @@ where
 {
 }
 
+/// Array normalization operations
+pub trait NDArrayNorm: NDArrayRead
+where
+    Self::DType: Float,
+{
+    /// Construct a layer normalization operation over the given trailing `axes`,
+    /// with an optional elementwise `weight` and `bias` of the normalized shape.
+    fn layer_norm(
+        self,
+        mut axes: Vec<usize>,
+        eps: Self::DType,
+        weight: Option<Array<Self::DType>>,
+        bias: Option<Array<Self::DType>>,
+    ) -> Result<ArrayOp<ArrayNorm<Self::DType, Self>>, Error> {
+        axes.sort();
+        axes.dedup();
+
+        let ndim = self.ndim();
+        let trailing = axes.len() <= ndim && axes.iter().copied().eq(ndim - axes.len()..ndim);
+
+        if axes.is_empty() || !trailing {
+            return Err(Error::Bounds(format!(
+                "layer normalization requires trailing axes of {:?}, not {:?}",
+                self, axes
+            )));
+        }
+
+        let normalized_shape = &self.shape()[ndim - axes.len()..];
+        check_norm_param(weight.as_ref(), normalized_shape)?;
+        check_norm_param(bias.as_ref(), normalized_shape)?;
+
+        let shape = self.shape().to_vec();
+        let stride = normalized_shape.iter().product();
+        let op = ArrayNorm::layer(self, stride, eps, weight, bias)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct an (inference-mode) batch normalization operation over an array with shape
+    /// `[N, C, ...]` using the given per-channel `running_mean` and `running_var`,
+    /// with an optional per-channel `weight` and `bias`.
+    fn batch_norm(
+        self,
+        running_mean: Array<Self::DType>,
+        running_var: Array<Self::DType>,
+        eps: Self::DType,
+        weight: Option<Array<Self::DType>>,
+        bias: Option<Array<Self::DType>>,
+    ) -> Result<ArrayOp<ArrayNorm<Self::DType, Self>>, Error> {
+        if self.ndim() < 2 {
+            return Err(Error::Bounds(format!(
+                "batch normalization requires an array with shape [N, C, ...], not {:?}",
+                self
+            )));
+        }
+
+        let channels = [self.shape()[1]];
+        check_norm_param(Some(&running_mean), &channels)?;
+        check_norm_param(Some(&running_var), &channels)?;
+        check_norm_param(weight.as_ref(), &channels)?;
+        check_norm_param(bias.as_ref(), &channels)?;
+
+        let shape = self.shape().to_vec();
+        let op = ArrayNorm::batch(self, running_mean, running_var, eps, weight, bias)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+
+    /// Construct a group normalization operation over an array with shape `[N, C, ...]`
+    /// whose `C` channels are divided into `num_groups` groups,
+    /// with an optional per-channel `weight` and `bias`.
+    fn group_norm(
+        self,
+        num_groups: usize,
+        eps: Self::DType,
+        weight: Option<Array<Self::DType>>,
+        bias: Option<Array<Self::DType>>,
+    ) -> Result<ArrayOp<ArrayNorm<Self::DType, Self>>, Error> {
+        if self.ndim() < 2 || num_groups == 0 || !self.shape()[1].is_multiple_of(num_groups) {
+            return Err(Error::Bounds(format!(
+                "cannot divide the channels of {:?} into {} groups",
+                self, num_groups
+            )));
+        }
+
+        let channels = [self.shape()[1]];
+        check_norm_param(weight.as_ref(), &channels)?;
+        check_norm_param(bias.as_ref(), &channels)?;
+
+        let shape = self.shape().to_vec();
+        let op = ArrayNorm::group(self, num_groups, eps, weight, bias)?;
+        Ok(ArrayOp::new(shape, op))
+    }
+}
+
+impl<A: NDArrayRead> NDArrayNorm for A where A::DType: Float {}
+
 /// Conditional selection (boolean logic) methods
 pub trait NDArrayWhere: NDArray<DType = u8> + fmt::Debug {
     /// Construct a boolean selection operation.
@@ -1797,6 +4224,29 @@ fn check_shape(left: &[usize], right: &[usize]) -> Result<Shape, Error> {
     }
 }
 
+#[inline]
+fn check_norm_param<T: CDatatype>(param: Option<&Array<T>>, shape: &[usize]) -> Result<(), Error> {
+    match param {
+        Some(param) if param.shape() != shape => Err(Error::Bounds(format!(
+            "expected a normalization parameter with shape {:?}, not {:?}",
+            shape, param
+        ))),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(feature = "fft")]
+#[inline]
+fn last_two_axes(ndim: usize) -> Result<Vec<usize>, Error> {
+    if ndim >= 2 {
+        Ok(vec![ndim - 2, ndim - 1])
+    } else {
+        Err(Error::Bounds(format!(
+            "a 2-dimensional FFT requires at least 2 dimensions, not {ndim}"
+        )))
+    }
+}
+
 #[cfg(feature = "opencl")]
 #[inline]
 fn div_ceil(num: usize, denom: usize) -> usize {
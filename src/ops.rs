@@ -4,17 +4,26 @@ use std::cmp::{PartialEq, PartialOrd};
 use std::f32::consts::PI;
 use std::fmt;
 use std::marker::PhantomData;
-use std::ops::{Add, Div, Mul, Rem, Sub};
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Rem, Sub};
 use std::sync::Arc;
 
 use rand::Rng;
 use rayon::prelude::*;
+#[cfg(feature = "fft")]
+use rustfft::{FftNum, FftPlanner};
 
+#[cfg(feature = "blas")]
+use super::blas;
 #[cfg(feature = "opencl")]
 use super::cl_programs;
+#[cfg(feature = "opencl")]
+use super::CLConverter;
+#[cfg(feature = "complex")]
+use super::Complex;
 use super::{
-    offset_of, strides_for, Buffer, CDatatype, Context, Error, Float, Log, NDArray, NDArrayMath,
-    NDArrayRead, NDArrayTransform, Queue, Shape, SliceConverter, Trig,
+    broadcast_shape, offset_of, strides_for, Array, BufferConverter, CDatatype, Context, Error,
+    Float, Integer, Log, NDArray, NDArrayMath, NDArrayRead, NDArrayTransform, Pooled, Promote,
+    Queue, Shape, SliceConverter, Trig,
 };
 
 /// An n-dimensional array [`Op`]
@@ -26,22 +35,35 @@ pub trait Op: Send + Sync {
     fn context(&self) -> &Context;
 
     /// Enqueue this [`Op`] for execution.
-    fn enqueue(&self, queue: &Queue) -> Result<Buffer<Self::Out>, Error> {
+    /// The output buffer is returned to the [`BufferPool`](crate::BufferPool) of the `queue`'s context when dropped.
+    fn enqueue(&self, queue: &Queue) -> Result<BufferConverter<'static, Self::Out>, Error> {
         // TODO: there must be a better way to do this
         #[cfg(feature = "opencl")]
-        if queue.cl_queue.is_some() {
-            return self.enqueue_cl(queue).map(Buffer::CL);
+        if queue.cl_queue.is_some() && self.cl_compute() {
+            return self
+                .enqueue_cl(queue)
+                .map(|buffer| CLConverter::Pooled(buffer).into());
         }
 
-        self.enqueue_cpu(queue).map(Buffer::Host)
+        queue
+            .context()
+            .install(|| self.enqueue_cpu(queue))
+            .map(|buffer| SliceConverter::Pooled(buffer).into())
     }
 
     /// Enqueue this [`Op`] on the host CPU.
-    fn enqueue_cpu(&self, queue: &Queue) -> Result<Vec<Self::Out>, Error>;
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error>;
 
     /// Enqueue this [`Op`] on an OpenCL device.
     #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<Self::Out>, Error>;
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error>;
+
+    /// Return `false` if OpenCL can't compute on the data types of this [`Op`],
+    /// in which case it's enqueued on the host even if the queue has an OpenCL device.
+    #[cfg(feature = "opencl")]
+    fn cl_compute(&self) -> bool {
+        self.context().cl_compute::<Self::Out>()
+    }
 
     /// Read the result of this [`Op`] at a single `coord`.
     fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error>;
@@ -54,15 +76,20 @@ impl<O: Op + ?Sized> Op for Arc<O> {
         (**self).context()
     }
 
-    fn enqueue_cpu(&self, queue: &Queue) -> Result<Vec<Self::Out>, Error> {
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
         (**self).enqueue_cpu(queue)
     }
 
     #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<Self::Out>, Error> {
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
         (**self).enqueue_cl(queue)
     }
 
+    #[cfg(feature = "opencl")]
+    fn cl_compute(&self) -> bool {
+        (**self).cl_compute()
+    }
+
     fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
         (**self).read_value(coord)
     }
@@ -75,15 +102,20 @@ impl<O: Op + ?Sized> Op for Box<O> {
         (**self).context()
     }
 
-    fn enqueue_cpu(&self, queue: &Queue) -> Result<Vec<Self::Out>, Error> {
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
         (**self).enqueue_cpu(queue)
     }
 
     #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<Self::Out>, Error> {
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
         (**self).enqueue_cl(queue)
     }
 
+    #[cfg(feature = "opencl")]
+    fn cl_compute(&self) -> bool {
+        (**self).cl_compute()
+    }
+
     fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
         (**self).read_value(coord)
     }
@@ -102,7 +134,7 @@ pub struct Range<T> {
     cl_op: ocl::Program,
 }
 
-impl<T: CDatatype + fmt::Display> Range<T> {
+impl<T: CDatatype + PartialOrd + fmt::Display> Range<T> {
     /// Initialize a new [`Range`] constructor.
     pub fn new(start: T, stop: T, shape: Shape) -> Result<Self, Error> {
         let context = Context::default()?;
@@ -139,29 +171,29 @@ impl<T: CDatatype> Op for Range<T> {
         &self.context
     }
 
-    fn enqueue_cpu(&self, _queue: &Queue) -> Result<Vec<Self::Out>, Error> {
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
         let start = self.start.to_f64();
         let size = self.shape.iter().product::<usize>();
 
-        let buffer = (0..size)
+        let mut buffer = queue.host_buffer(size)?;
+
+        (0..size)
             .into_par_iter()
+            .with_min_len(queue.min_len(1))
             .map(|i| i as f64)
             .map(|i| i * self.step)
             .map(|o| start.to_f64() + o)
             .map(T::from_f64)
-            .collect();
+            .collect_into_vec(&mut buffer);
 
         Ok(buffer)
     }
 
     #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<Self::Out>, Error> {
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
         let cl_queue = queue.cl_queue.as_ref().expect("queue");
 
-        let buffer = ocl::Buffer::builder()
-            .queue(cl_queue.clone())
-            .len(self.shape.iter().product::<usize>())
-            .build()?;
+        let buffer = queue.cl_buffer(cl_queue.clone(), self.shape.iter().product::<usize>())?;
 
         let kernel = ocl::Kernel::builder()
             .name("range")
@@ -169,10 +201,10 @@ impl<T: CDatatype> Op for Range<T> {
             .program(&self.cl_op)
             .global_work_size(buffer.len())
             .arg(self.step)
-            .arg(&buffer)
+            .arg(&*buffer)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { queue.enqueue_kernel(&kernel)? }
 
         Ok(buffer)
     }
@@ -195,10 +227,14 @@ impl<T: CDatatype> Op for Range<T> {
 }
 
 /// A random normal constructor
+///
+/// Each value is computed from a counter-based random number generator, given the seed
+/// of this constructor and its offset, so a value can be read without generating the others.
 #[derive(Clone)]
 pub struct RandomNormal {
     context: Context,
     size: usize,
+    seed: u32,
     #[cfg(feature = "opencl")]
     cl_op: ocl::Program,
 }
@@ -218,10 +254,26 @@ impl RandomNormal {
         Ok(Self {
             context,
             size,
+            seed: rand::thread_rng().gen(),
             #[cfg(feature = "opencl")]
             cl_op,
         })
     }
+
+    // the Box-Muller transform of the pair of uniform random numbers which includes `offset`
+    fn normal(&self, offset: usize) -> f32 {
+        let pair = offset - (offset % 2);
+        let u1 = random(self.seed, pair);
+        let u2 = random(self.seed, pair + 1);
+        let r = (u1.ln() * -2.).sqrt();
+        let theta = 2. * PI * u2;
+
+        if offset % 2 == 0 {
+            r * theta.cos()
+        } else {
+            r * theta.sin()
+        }
+    }
 }
 
 impl Op for RandomNormal {
@@ -231,50 +283,26 @@ impl Op for RandomNormal {
         &self.context
     }
 
-    fn enqueue_cpu(&self, _queue: &Queue) -> Result<Vec<Self::Out>, Error> {
-        let mut u = vec![
-            0.0f32;
-            if self.size % 2 == 0 {
-                self.size
-            } else {
-                self.size + 1
-            }
-        ];
-
-        rand::thread_rng().fill(&mut u[..]);
-
-        let mut output = u
-            .par_chunks_exact(2)
-            .map(|u| {
-                let [u1, u2]: [f32; 2] = u.try_into().expect("u");
-                let r = (u1.ln() * -2.).sqrt();
-                let theta = 2. * PI * u2;
-                [r * theta.cos(), r * theta.sin()]
-            })
-            .flatten()
-            .collect::<Vec<f32>>();
-
-        if output.len() > self.size {
-            output.pop();
-        }
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let mut output = queue.host_buffer(self.size)?;
 
-        debug_assert_eq!(output.len(), self.size);
+        (0..self.size)
+            .into_par_iter()
+            .with_min_len(queue.min_len(1))
+            .map(|offset| self.normal(offset))
+            .collect_into_vec(&mut output);
 
         Ok(output)
     }
 
     #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<Self::Out>, Error> {
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
         use crate::div_ceil;
         use cl_programs::WG_SIZE;
 
         let cl_queue = queue.cl_queue.as_ref().expect("queue");
-        let seed: u32 = rand::thread_rng().gen();
 
-        let buffer = ocl::Buffer::builder()
-            .queue(cl_queue.clone())
-            .len(WG_SIZE * div_ceil(self.size, WG_SIZE))
-            .build()?;
+        let buffer = queue.cl_buffer(cl_queue.clone(), WG_SIZE * div_ceil(self.size, WG_SIZE))?;
 
         let kernel = ocl::Kernel::builder()
             .name("random_normal")
@@ -282,32 +310,70 @@ impl Op for RandomNormal {
             .program(&self.cl_op)
             .global_work_size(buffer.len())
             .local_work_size(WG_SIZE)
-            .arg(u64::try_from(seed).expect("seed"))
-            .arg(&buffer)
+            .arg(u64::from(self.seed))
+            .arg(&*buffer)
             .arg_local::<f32>(WG_SIZE)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { queue.enqueue_kernel(&kernel)? }
 
         if buffer.len() == self.size {
             Ok(buffer)
         } else {
-            let output = ocl::Buffer::builder()
-                .queue(cl_queue.clone())
-                .len(self.size)
-                .build()?;
+            let output = queue.cl_buffer(cl_queue.clone(), self.size)?;
 
-            buffer.copy(&output, Some(0), Some(self.size)).enq()?;
+            queue.chain(|wait, event| {
+                let copy = buffer.copy(&*output, Some(0), Some(self.size));
+                copy.ewait(wait).enew(event).enq()
+            })?;
 
             Ok(output)
         }
     }
 
-    fn read_value(&self, _coord: &[usize]) -> Result<Self::Out, Error> {
-        Err(Error::Bounds(format!(
-            "cannot read an individual value of a random normal distribution"
-        )))
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        match coord {
+            [offset] if *offset < self.size => Ok(self.normal(*offset)),
+            _ => Err(Error::Bounds(format!(
+                "random normal constructor of size {} does not contain {:?}",
+                self.size, coord
+            ))),
+        }
+    }
+}
+
+// the resolution of a uniform random number, shared with the OpenCL `random` function
+pub(crate) const RANDOM_RESOLUTION: f32 = 1. / u32::MAX as f32;
+
+// a uniform random number in the range [0, 1] at the given `offset` of a sequence,
+// computed by the same counter-based algorithm as the OpenCL `random` function
+fn random(seed: u32, offset: usize) -> f32 {
+    // PCG hash by Melissa E. O'Neill: https://www.pcg-random.org/
+    fn pcg_hash(seed: u32) -> u32 {
+        let state = seed.wrapping_mul(747796405).wrapping_add(2891336453);
+        let word = ((state >> ((state >> 28) + 4)) ^ state).wrapping_mul(277803737);
+        (word >> 22) ^ word
     }
+
+    // Xorshift algorithm by George Marsaglia: https://www.jstatsoft.org/article/view/v008i14
+    fn xorshift(mut state: u32) -> u32 {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state
+    }
+
+    let offset = offset as u64;
+    let mut state = xorshift((offset.wrapping_add(u64::from(seed)) & u64::from(u32::MAX)) as u32);
+
+    // i.e. while i < log2(offset), without rounding
+    let mut i = 32;
+    while i < 64 && (1u64 << i) < offset {
+        state = xorshift(state);
+        i += 1;
+    }
+
+    pcg_hash(state) as f32 * RANDOM_RESOLUTION
 }
 
 /// A random uniform constructor
@@ -347,23 +413,20 @@ impl Op for RandomUniform {
         &self.context
     }
 
-    fn enqueue_cpu(&self, _queue: &Queue) -> Result<Vec<Self::Out>, Error> {
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
         let size = self.shape.iter().product();
-        let mut data = vec![0.; size];
+        let mut data = queue.host_buffer_of(size, 0.)?;
         rand::thread_rng().fill(&mut data[..]);
         Ok(data)
     }
 
     #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<Self::Out>, Error> {
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
         let seed: u32 = rand::thread_rng().gen();
         let cl_queue = queue.cl_queue.as_ref().expect("queue");
 
         let size = self.shape.iter().product::<usize>();
-        let output = ocl::Buffer::builder()
-            .queue(cl_queue.clone())
-            .len(size)
-            .build()?;
+        let output = queue.cl_buffer(cl_queue.clone(), size)?;
 
         let kernel = ocl::Kernel::builder()
             .name("random_uniform")
@@ -371,10 +434,10 @@ impl Op for RandomUniform {
             .program(&self.cl_op)
             .global_work_size(output.len())
             .arg(u64::try_from(seed).expect("seed"))
-            .arg(&output)
+            .arg(&*output)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { queue.enqueue_kernel(&kernel)? }
 
         Ok(output)
     }
@@ -443,6 +506,22 @@ impl<T: CDatatype, L: NDArray, R: NDArray> ArrayDual<T, L, R> {
         Self::new(left, right, Div::div, "div")
     }
 
+    /// Initialize an elementwise maximum [`Op`].
+    pub fn maximum(left: L, right: R) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(left, right, maximum, "maximum")
+    }
+
+    /// Initialize an elementwise minimum [`Op`].
+    pub fn minimum(left: L, right: R) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(left, right, minimum, "minimum")
+    }
+
     /// Initialize a multiplication [`Op`].
     pub fn mul(left: L, right: R) -> Result<Self, Error> {
         Self::new(left, right, Mul::mul, "mul")
@@ -459,6 +538,101 @@ impl<T: CDatatype, L: NDArray, R: NDArray> ArrayDual<T, L, R> {
     }
 }
 
+impl<T: Integer, L: NDArray, R: NDArray> ArrayDual<T, L, R> {
+    #[allow(unused_variables)]
+    fn bitwise(
+        left: L,
+        right: R,
+        cpu_op: fn(T, T) -> T,
+        cl_op: &'static str,
+    ) -> Result<Self, Error> {
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::elementwise_bitwise::<T>(cl_op, left.context())?;
+
+        Ok(Self {
+            left,
+            right,
+            cpu_op,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
+
+    /// Initialize a bitwise and [`Op`].
+    pub fn bitand(left: L, right: R) -> Result<Self, Error> {
+        Self::bitwise(left, right, BitAnd::bitand, "&")
+    }
+
+    /// Initialize a bitwise or [`Op`].
+    pub fn bitor(left: L, right: R) -> Result<Self, Error> {
+        Self::bitwise(left, right, BitOr::bitor, "|")
+    }
+
+    /// Initialize a bitwise exclusive or [`Op`].
+    pub fn bitxor(left: L, right: R) -> Result<Self, Error> {
+        Self::bitwise(left, right, BitXor::bitxor, "^")
+    }
+
+    /// Initialize a left shift [`Op`].
+    pub fn shl(left: L, right: R) -> Result<Self, Error> {
+        Self::bitwise(left, right, T::shift_left, "<<")
+    }
+
+    /// Initialize a right shift [`Op`].
+    pub fn shr(left: L, right: R) -> Result<Self, Error> {
+        Self::bitwise(left, right, T::shift_right, ">>")
+    }
+
+    #[allow(unused_variables)]
+    fn integer(
+        left: L,
+        right: R,
+        cpu_op: fn(T, T) -> T,
+        cl_op: &'static str,
+    ) -> Result<Self, Error> {
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::elementwise_integer::<T>(cl_op, left.context())?;
+
+        Ok(Self {
+            left,
+            right,
+            cpu_op,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
+
+    /// Initialize a wrapping addition [`Op`].
+    pub fn wrapping_add(left: L, right: R) -> Result<Self, Error> {
+        Self::integer(left, right, T::wrapping_add, "wrapping_add")
+    }
+
+    /// Initialize a wrapping subtraction [`Op`].
+    pub fn wrapping_sub(left: L, right: R) -> Result<Self, Error> {
+        Self::integer(left, right, T::wrapping_sub, "wrapping_sub")
+    }
+
+    /// Initialize a wrapping multiplication [`Op`].
+    pub fn wrapping_mul(left: L, right: R) -> Result<Self, Error> {
+        Self::integer(left, right, T::wrapping_mul, "wrapping_mul")
+    }
+
+    /// Initialize a saturating addition [`Op`].
+    pub fn saturating_add(left: L, right: R) -> Result<Self, Error> {
+        Self::integer(left, right, T::saturating_add, "saturating_add")
+    }
+
+    /// Initialize a saturating subtraction [`Op`].
+    pub fn saturating_sub(left: L, right: R) -> Result<Self, Error> {
+        Self::integer(left, right, T::saturating_sub, "saturating_sub")
+    }
+
+    /// Initialize a saturating multiplication [`Op`].
+    pub fn saturating_mul(left: L, right: R) -> Result<Self, Error> {
+        Self::integer(left, right, T::saturating_mul, "saturating_mul")
+    }
+}
+
 impl<T: CDatatype, L: NDArrayRead<DType = T>, R: NDArrayRead<DType = T>> Op for ArrayDual<T, L, R> {
     type Out = T;
 
@@ -466,23 +640,25 @@ impl<T: CDatatype, L: NDArrayRead<DType = T>, R: NDArrayRead<DType = T>> Op for
         self.left.context()
     }
 
-    fn enqueue_cpu(&self, queue: &Queue) -> Result<Vec<T>, Error> {
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<T>>, Error> {
         let (left, right) = try_join_read(&self.left, &self.right, queue)?;
         debug_assert_eq!(left.len(), right.len());
 
-        let output = left
-            .as_ref()
+        let mut output = queue.host_buffer(left.len())?;
+
+        left.as_ref()
             .par_iter()
             .copied()
             .zip(right.as_ref().par_iter().copied())
+            .with_min_len(queue.min_len(1))
             .map(|(l, r)| (self.cpu_op)(l, r))
-            .collect();
+            .collect_into_vec(&mut output);
 
         Ok(output)
     }
 
     #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<T>, Error> {
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<T>>, Error> {
         let right_queue = queue.split(self.right.size())?;
         let right = self.right.to_cl_buffer(&right_queue)?;
         let left = self.left.to_cl_buffer(queue)?;
@@ -490,10 +666,7 @@ impl<T: CDatatype, L: NDArrayRead<DType = T>, R: NDArrayRead<DType = T>> Op for
 
         let cl_queue = left.as_ref().default_queue().expect("left queue").clone();
 
-        let output = ocl::Buffer::builder()
-            .queue(cl_queue.clone())
-            .len(left.len())
-            .build()?;
+        let output = queue.cl_buffer(cl_queue.clone(), left.len())?;
 
         let kernel = ocl::Kernel::builder()
             .name("elementwise_dual")
@@ -502,10 +675,10 @@ impl<T: CDatatype, L: NDArrayRead<DType = T>, R: NDArrayRead<DType = T>> Op for
             .global_work_size(left.len())
             .arg(left.as_ref())
             .arg(right.as_ref())
-            .arg(&output)
+            .arg(&*output)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { queue.enqueue_kernel(&kernel)? }
 
         Ok(output)
     }
@@ -520,26 +693,26 @@ impl<T: CDatatype, L: NDArrayRead<DType = T>, R: NDArrayRead<DType = T>> Op for
     }
 }
 
-/// A dual floating-point array [`Op`]
+/// A dual integer array [`Op`] which returns an error on overflow
 #[derive(Clone)]
-pub struct ArrayDualFloat<T: CDatatype, L, R> {
+pub struct ArrayDualChecked<T, L, R> {
     left: L,
     right: R,
-    cpu_op: fn(T, T::Float) -> T,
+    cpu_op: fn(T, T) -> Option<T>,
     #[cfg(feature = "opencl")]
     cl_op: ocl::Program,
 }
 
-impl<T: CDatatype, L: NDArray<DType = T>, R: NDArray<DType = T::Float>> ArrayDualFloat<T, L, R> {
+impl<T: Integer, L: NDArray, R: NDArray> ArrayDualChecked<T, L, R> {
     #[allow(unused_variables)]
     fn new(
         left: L,
         right: R,
-        cpu_op: fn(T, T::Float) -> T,
+        cpu_op: fn(T, T) -> Option<T>,
         cl_op: &'static str,
     ) -> Result<Self, Error> {
         #[cfg(feature = "opencl")]
-        let cl_op = cl_programs::elementwise_dual::<T, T::Float>(cl_op, left.context())?;
+        let cl_op = cl_programs::elementwise_checked::<T>(cl_op, left.context())?;
 
         Ok(Self {
             left,
@@ -550,32 +723,27 @@ impl<T: CDatatype, L: NDArray<DType = T>, R: NDArray<DType = T::Float>> ArrayDua
         })
     }
 
-    /// Initialize a new logarithm [`Op`].
-    pub fn log(left: L, right: R) -> Result<Self, Error> {
-        Self::new(
-            left,
-            right,
-            |l, r| T::from_float(l.to_float().log(r)),
-            "log_",
-        )
+    /// Initialize a checked addition [`Op`].
+    pub fn add(left: L, right: R) -> Result<Self, Error> {
+        Self::new(left, right, T::checked_add, "add")
     }
 
-    /// Initialize a new exponentiation [`Op`].
-    pub fn pow(left: L, right: R) -> Result<Self, Error> {
-        Self::new(
-            left,
-            right,
-            |l, r| T::from_float(l.to_float().pow(r)),
-            "pow_",
-        )
+    /// Initialize a checked subtraction [`Op`].
+    pub fn sub(left: L, right: R) -> Result<Self, Error> {
+        Self::new(left, right, T::checked_sub, "sub")
+    }
+
+    /// Initialize a checked multiplication [`Op`].
+    pub fn mul(left: L, right: R) -> Result<Self, Error> {
+        Self::new(left, right, T::checked_mul, "mul")
     }
 }
 
-impl<T, L, R> Op for ArrayDualFloat<T, L, R>
+impl<T, L, R> Op for ArrayDualChecked<T, L, R>
 where
-    T: CDatatype,
+    T: Integer,
     L: NDArrayRead<DType = T>,
-    R: NDArrayRead<DType = T::Float>,
+    R: NDArrayRead<DType = T>,
 {
     type Out = T;
 
@@ -583,22 +751,27 @@ where
         self.left.context()
     }
 
-    fn enqueue_cpu(&self, queue: &Queue) -> Result<Vec<Self::Out>, Error> {
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<T>>, Error> {
         let (left, right) = try_join_read(&self.left, &self.right, queue)?;
+        debug_assert_eq!(left.len(), right.len());
 
-        let output = left
-            .as_ref()
-            .par_iter()
-            .copied()
+        let mut output = queue.host_buffer_of(left.len(), T::zero())?;
+
+        output
+            .par_iter_mut()
+            .zip(left.as_ref().par_iter().copied())
             .zip(right.as_ref().par_iter().copied())
-            .map(|(l, r)| (self.cpu_op)(l, r))
-            .collect();
+            .with_min_len(queue.min_len(1))
+            .try_for_each(|((o, l), r)| -> Result<(), Error> {
+                *o = (self.cpu_op)(l, r).ok_or_else(|| overflow(l, r))?;
+                Ok(())
+            })?;
 
         Ok(output)
     }
 
     #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<Self::Out>, Error> {
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<T>>, Error> {
         let right_queue = queue.split(self.right.size())?;
         let right = self.right.to_cl_buffer(&right_queue)?;
         let left = self.left.to_cl_buffer(queue)?;
@@ -606,22 +779,24 @@ where
 
         let cl_queue = left.as_ref().default_queue().expect("left queue").clone();
 
-        let output = ocl::Buffer::builder()
-            .queue(cl_queue.clone())
-            .len(left.len())
-            .build()?;
+        let output = queue.cl_buffer(cl_queue.clone(), left.len())?;
+
+        let flag = cl_buffer_of(queue, cl_queue.clone(), 1, 0u8)?;
 
         let kernel = ocl::Kernel::builder()
-            .name("elementwise_dual")
+            .name("elementwise_dual_checked")
             .program(&self.cl_op)
             .queue(cl_queue)
             .global_work_size(left.len())
             .arg(left.as_ref())
             .arg(right.as_ref())
-            .arg(&output)
+            .arg(&*output)
+            .arg(&*flag)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { queue.enqueue_kernel(&kernel)? }
+
+        check_overflow(&flag)?;
 
         Ok(output)
     }
@@ -632,159 +807,214 @@ where
             || self.right.read_value(coord),
         )?;
 
-        Ok((self.cpu_op)(left, right))
+        (self.cpu_op)(left, right).ok_or_else(|| overflow(left, right))
     }
 }
 
-/// An array [`Op`] with a scalar argument
+/// A dual array [`Op`] whose operands are promoted to a common data type (see [`Promote`])
 #[derive(Clone)]
-pub struct ArrayScalar<T, A> {
-    array: A,
-    scalar: T,
-    cpu_op: fn(T, T) -> T,
+pub struct ArrayDualPromoted<LT: Promote<RT>, RT: CDatatype, L, R> {
+    left: L,
+    right: R,
+    cpu_op: fn(LT::Out, LT::Out) -> LT::Out,
     #[cfg(feature = "opencl")]
     cl_op: ocl::Program,
 }
 
-impl<T: CDatatype, A: NDArray<DType = T>> ArrayScalar<T, A> {
+impl<LT, RT, L, R> ArrayDualPromoted<LT, RT, L, R>
+where
+    LT: Promote<RT>,
+    RT: CDatatype,
+    L: NDArray<DType = LT>,
+    R: NDArray<DType = RT>,
+{
     #[allow(unused_variables)]
-    fn new(array: A, scalar: T, cpu_op: fn(T, T) -> T, cl_op: &'static str) -> Result<Self, Error> {
+    fn new(
+        left: L,
+        right: R,
+        cpu_op: fn(LT::Out, LT::Out) -> LT::Out,
+        cl_op: &'static str,
+    ) -> Result<Self, Error> {
         #[cfg(feature = "opencl")]
-        let cl_op = cl_programs::elementwise_scalar::<T, T>(cl_op, array.context())?;
+        let cl_op =
+            cl_programs::elementwise_dual_promoted::<LT, RT, LT::Out>(cl_op, left.context())?;
 
         Ok(Self {
-            array,
-            scalar,
+            left,
+            right,
             cpu_op,
             #[cfg(feature = "opencl")]
             cl_op,
         })
     }
 
-    /// Initialize a new scalar addition [`Op`].
-    pub fn add(left: A, right: T) -> Result<Self, Error> {
+    /// Initialize a promoted addition [`Op`].
+    pub fn add(left: L, right: R) -> Result<Self, Error> {
         Self::new(left, right, Add::add, "add")
     }
 
-    /// Initialize a new scalar division [`Op`].
-    pub fn div(left: A, right: T) -> Result<Self, Error> {
+    /// Initialize a promoted division [`Op`] with undefined behavior if `right` contains zeros.
+    pub fn div(left: L, right: R) -> Result<Self, Error> {
         Self::new(left, right, Div::div, "div")
     }
 
-    /// Initialize a new scalar multiplication [`Op`].
-    pub fn mul(left: A, right: T) -> Result<Self, Error> {
+    /// Initialize a promoted multiplication [`Op`].
+    pub fn mul(left: L, right: R) -> Result<Self, Error> {
         Self::new(left, right, Mul::mul, "mul")
     }
 
-    /// Initialize a new scalar modulo [`Op`].
-    pub fn rem(left: A, right: T) -> Result<Self, Error> {
+    /// Initialize a promoted modulo [`Op`].
+    pub fn rem(left: L, right: R) -> Result<Self, Error> {
         Self::new(left, right, Rem::rem, "rem")
     }
 
-    /// Initialize a new scalar subtraction [`Op`].
-    pub fn sub(left: A, right: T) -> Result<Self, Error> {
+    /// Initialize a promoted subtraction [`Op`].
+    pub fn sub(left: L, right: R) -> Result<Self, Error> {
         Self::new(left, right, Sub::sub, "sub")
     }
 }
 
-impl<T: CDatatype, A: NDArrayRead<DType = T>> Op for ArrayScalar<T, A> {
-    type Out = A::DType;
+impl<LT, RT, L, R> Op for ArrayDualPromoted<LT, RT, L, R>
+where
+    LT: Promote<RT>,
+    RT: CDatatype,
+    L: NDArrayRead<DType = LT>,
+    R: NDArrayRead<DType = RT>,
+{
+    type Out = LT::Out;
 
     fn context(&self) -> &Context {
-        self.array.context()
+        self.left.context()
     }
 
-    fn enqueue_cpu(&self, queue: &Queue) -> Result<Vec<Self::Out>, Error> {
-        let left = self.array.to_host(queue)?;
-        let right = self.scalar;
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let (left, right) = try_join_read(&self.left, &self.right, queue)?;
+        debug_assert_eq!(left.len(), right.len());
 
-        let output = left
-            .as_ref()
+        let mut output = queue.host_buffer(left.len())?;
+
+        left.as_ref()
             .par_iter()
             .copied()
-            .map(|l| (self.cpu_op)(l, right))
-            .collect();
+            .zip(right.as_ref().par_iter().copied())
+            .with_min_len(queue.min_len(1))
+            .map(|(l, r)| (self.cpu_op)(l.promote(), LT::promote_rhs(r)))
+            .collect_into_vec(&mut output);
 
         Ok(output)
     }
 
     #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<Self::Out>, Error> {
-        let left = self.array.to_cl_buffer(queue)?;
-        let right = self.scalar;
-        let cl_queue = left.as_ref().default_queue().expect("queue").clone();
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let right_queue = queue.split(self.right.size())?;
+        let right = self.right.to_cl_buffer(&right_queue)?;
+        let left = self.left.to_cl_buffer(queue)?;
+        debug_assert_eq!(left.len(), right.len());
 
-        let output = ocl::Buffer::builder()
-            .queue(cl_queue.clone())
-            .len(left.len())
-            .build()?;
+        let cl_queue = left.as_ref().default_queue().expect("left queue").clone();
+
+        let output = queue.cl_buffer(cl_queue.clone(), left.len())?;
 
         let kernel = ocl::Kernel::builder()
-            .name("elementwise_scalar")
+            .name("elementwise_dual")
             .program(&self.cl_op)
             .queue(cl_queue)
             .global_work_size(left.len())
             .arg(left.as_ref())
-            .arg(right)
-            .arg(&output)
+            .arg(right.as_ref())
+            .arg(&*output)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { queue.enqueue_kernel(&kernel)? }
 
         Ok(output)
     }
 
+    #[cfg(feature = "opencl")]
+    fn cl_compute(&self) -> bool {
+        let context = self.context();
+        context.cl_compute::<LT>()
+            && context.cl_compute::<RT>()
+            && context.cl_compute::<Self::Out>()
+    }
+
     fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
-        let left = self.array.read_value(coord)?;
-        let right = self.scalar;
-        Ok((self.cpu_op)(left, right))
+        let (left, right) = try_join(
+            || self.left.read_value(coord),
+            || self.right.read_value(coord),
+        )?;
+
+        Ok((self.cpu_op)(left.promote(), LT::promote_rhs(right)))
     }
 }
 
-/// An array [`Op`] with a scalar floating-point argument
+/// A dual floating-point array [`Op`]
 #[derive(Clone)]
-pub struct ArrayScalarFloat<T: CDatatype, A> {
-    array: A,
-    scalar: T::Float,
+pub struct ArrayDualFloat<T: CDatatype, L, R> {
+    left: L,
+    right: R,
     cpu_op: fn(T, T::Float) -> T,
     #[cfg(feature = "opencl")]
     cl_op: ocl::Program,
 }
 
-impl<T: CDatatype, A: NDArray> ArrayScalarFloat<T, A> {
+impl<T, L, R> ArrayDualFloat<T, L, R>
+where
+    T: CDatatype + PartialOrd,
+    L: NDArray<DType = T>,
+    R: NDArray<DType = T::Float>,
+{
     #[allow(unused_variables)]
     fn new(
-        array: A,
-        scalar: T::Float,
+        left: L,
+        right: R,
         cpu_op: fn(T, T::Float) -> T,
         cl_op: &'static str,
     ) -> Result<Self, Error> {
         #[cfg(feature = "opencl")]
-        let cl_op = cl_programs::elementwise_scalar::<T::Float, T>(cl_op, array.context())?;
+        let cl_op = cl_programs::elementwise_dual::<T, T::Float>(cl_op, left.context())?;
 
         Ok(Self {
-            array,
-            scalar,
+            left,
+            right,
             cpu_op,
             #[cfg(feature = "opencl")]
             cl_op,
         })
     }
-}
 
-impl<T: CDatatype, A: NDArray> ArrayScalarFloat<T, A> {
-    /// Initialize a new logarithm [`Op`] with a scalar base.
-    pub fn log(left: A, right: T::Float) -> Result<Self, Error> {
+    /// Initialize a new logarithm [`Op`].
+    pub fn log(left: L, right: R) -> Result<Self, Error> {
         Self::new(
             left,
             right,
             |l, r| T::from_float(l.to_float().log(r)),
-            "log",
+            "log_",
         )
     }
 
-    /// Initialize a new exponentiation [`Op`] with a scalar exponent.
-    pub fn pow(left: A, right: T::Float) -> Result<Self, Error> {
+    /// Initialize a new four-quadrant arctangent [`Op`] of `left / right`.
+    pub fn atan2(left: L, right: R) -> Result<Self, Error> {
+        Self::new(
+            left,
+            right,
+            |l, r| T::from_float(l.to_float().atan2(r)),
+            "atan2_",
+        )
+    }
+
+    /// Initialize a new hypotenuse [`Op`].
+    pub fn hypot(left: L, right: R) -> Result<Self, Error> {
+        Self::new(
+            left,
+            right,
+            |l, r| T::from_float(l.to_float().hypot(r)),
+            "hypot_",
+        )
+    }
+
+    /// Initialize a new exponentiation [`Op`].
+    pub fn pow(left: L, right: R) -> Result<Self, Error> {
         Self::new(
             left,
             right,
@@ -794,851 +1024,3567 @@ impl<T: CDatatype, A: NDArray> ArrayScalarFloat<T, A> {
     }
 }
 
-impl<T: CDatatype, A: NDArrayRead<DType = T>> Op for ArrayScalarFloat<T, A> {
+impl<T, L, R> Op for ArrayDualFloat<T, L, R>
+where
+    T: CDatatype,
+    L: NDArrayRead<DType = T>,
+    R: NDArrayRead<DType = T::Float>,
+{
     type Out = T;
 
     fn context(&self) -> &Context {
-        self.array.context()
+        self.left.context()
     }
 
-    fn enqueue_cpu(&self, queue: &Queue) -> Result<Vec<Self::Out>, Error> {
-        let left = self.array.to_host(queue)?;
-        let right = self.scalar;
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let (left, right) = try_join_read(&self.left, &self.right, queue)?;
 
-        let output = left
-            .as_ref()
+        let mut output = queue.host_buffer(left.len())?;
+
+        left.as_ref()
             .par_iter()
             .copied()
-            .map(|l| (self.cpu_op)(l, right))
-            .collect();
+            .zip(right.as_ref().par_iter().copied())
+            .with_min_len(queue.min_len(1))
+            .map(|(l, r)| (self.cpu_op)(l, r))
+            .collect_into_vec(&mut output);
 
         Ok(output)
     }
 
     #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<Self::Out>, Error> {
-        let left = self.array.to_cl_buffer(queue)?;
-        let right = self.scalar;
-        let cl_queue = left.as_ref().default_queue().expect("queue").clone();
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let right_queue = queue.split(self.right.size())?;
+        let right = self.right.to_cl_buffer(&right_queue)?;
+        let left = self.left.to_cl_buffer(queue)?;
+        debug_assert_eq!(left.len(), right.len());
 
-        let output = ocl::Buffer::builder()
-            .queue(cl_queue.clone())
-            .len(left.len())
-            .build()?;
+        let cl_queue = left.as_ref().default_queue().expect("left queue").clone();
+
+        let output = queue.cl_buffer(cl_queue.clone(), left.len())?;
 
         let kernel = ocl::Kernel::builder()
-            .name("elementwise_scalar")
+            .name("elementwise_dual")
             .program(&self.cl_op)
             .queue(cl_queue)
             .global_work_size(left.len())
             .arg(left.as_ref())
-            .arg(right)
-            .arg(&output)
+            .arg(right.as_ref())
+            .arg(&*output)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { queue.enqueue_kernel(&kernel)? }
 
         Ok(output)
     }
 
     fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
-        let left = self.array.read_value(coord)?;
-        let right = self.scalar;
+        let (left, right) = try_join(
+            || self.left.read_value(coord),
+            || self.right.read_value(coord),
+        )?;
+
         Ok((self.cpu_op)(left, right))
     }
 }
 
-// linear algebra
-
-/// A matrix diagonal read [`Op`]
+/// An array [`Op`] with a scalar argument
 #[derive(Clone)]
-pub struct MatDiag<A> {
-    source: A,
+pub struct ArrayScalar<T, A> {
+    array: A,
+    scalar: T,
+    cpu_op: fn(T, T) -> T,
     #[cfg(feature = "opencl")]
     cl_op: ocl::Program,
 }
 
-impl<A: NDArray> MatDiag<A> {
-    /// Initialize a new matrix diagonal read [`Op`].
-    pub fn new(source: A) -> Result<Self, Error> {
-        debug_assert!(source.ndim() >= 2);
-        debug_assert_eq!(
-            source.shape()[source.ndim() - 1],
-            source.shape()[source.ndim() - 2]
-        );
-
+impl<T: CDatatype, A: NDArray<DType = T>> ArrayScalar<T, A> {
+    #[allow(unused_variables)]
+    fn new(array: A, scalar: T, cpu_op: fn(T, T) -> T, cl_op: &'static str) -> Result<Self, Error> {
         #[cfg(feature = "opencl")]
-        let cl_op = cl_programs::diagonal::<A::DType>(source.context())?;
+        let cl_op = cl_programs::elementwise_scalar::<T, T>(cl_op, array.context())?;
 
         Ok(Self {
-            source,
+            array,
+            scalar,
+            cpu_op,
             #[cfg(feature = "opencl")]
             cl_op,
         })
     }
-}
-
-impl<A: NDArrayRead> Op for MatDiag<A> {
-    type Out = A::DType;
 
-    fn context(&self) -> &Context {
-        self.source.context()
+    /// Initialize a new scalar addition [`Op`].
+    pub fn add(left: A, right: T) -> Result<Self, Error> {
+        Self::new(left, right, Add::add, "add")
     }
 
-    fn enqueue_cpu(&self, queue: &Queue) -> Result<Vec<Self::Out>, Error> {
-        let dim = *self.source.shape().last().expect("dim");
-
-        let input = self.source.to_host(queue)?;
-        let mut output = Vec::with_capacity(self.source.size() / dim);
+    /// Initialize a new scalar division [`Op`].
+    pub fn div(left: A, right: T) -> Result<Self, Error> {
+        Self::new(left, right, Div::div, "div")
+    }
 
-        let diagonals = input
-            .as_ref()
-            .par_chunks_exact(dim * dim)
-            .map(|matrix| (0..dim).into_par_iter().map(|i| matrix[(i * dim) + i]))
-            .flatten();
+    /// Initialize a new scalar maximum [`Op`].
+    pub fn maximum(left: A, right: T) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(left, right, maximum, "maximum")
+    }
 
-        output.par_extend(diagonals);
+    /// Initialize a new scalar minimum [`Op`].
+    pub fn minimum(left: A, right: T) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(left, right, minimum, "minimum")
+    }
 
-        Ok(output)
+    /// Initialize a new scalar multiplication [`Op`].
+    pub fn mul(left: A, right: T) -> Result<Self, Error> {
+        Self::new(left, right, Mul::mul, "mul")
     }
 
-    #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<Self::Out>, Error> {
-        let dim = *self.source.shape().last().expect("dim");
-        let batch_size = self
-            .source
-            .shape()
-            .iter()
-            .take(self.source.ndim() - 2)
-            .product();
+    /// Initialize a new scalar modulo [`Op`].
+    pub fn rem(left: A, right: T) -> Result<Self, Error> {
+        Self::new(left, right, Rem::rem, "rem")
+    }
 
-        let input = self.source.to_cl_buffer(queue)?;
-        let cl_queue = input.as_ref().default_queue().expect("queue");
+    /// Initialize a new scalar subtraction [`Op`].
+    pub fn sub(left: A, right: T) -> Result<Self, Error> {
+        Self::new(left, right, Sub::sub, "sub")
+    }
+}
 
-        let output = ocl::Buffer::builder()
-            .queue(cl_queue.clone())
-            .len(input.len() / dim)
-            .build()?;
+impl<T: Integer, A: NDArray<DType = T>> ArrayScalar<T, A> {
+    #[allow(unused_variables)]
+    fn bitwise(
+        array: A,
+        scalar: T,
+        cpu_op: fn(T, T) -> T,
+        cl_op: &'static str,
+    ) -> Result<Self, Error> {
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::scalar_bitwise::<T>(cl_op, array.context())?;
 
-        let kernel = ocl::Kernel::builder()
-            .name("diagonal")
-            .program(&self.cl_op)
-            .queue(cl_queue.clone())
-            .global_work_size((batch_size, dim))
-            .arg(input.as_ref())
-            .arg(&output)
-            .build()?;
+        Ok(Self {
+            array,
+            scalar,
+            cpu_op,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
 
-        unsafe { kernel.enq()? };
+    /// Initialize a new scalar bitwise and [`Op`].
+    pub fn bitand(left: A, right: T) -> Result<Self, Error> {
+        Self::bitwise(left, right, BitAnd::bitand, "&")
+    }
 
-        Ok(output)
+    /// Initialize a new scalar bitwise or [`Op`].
+    pub fn bitor(left: A, right: T) -> Result<Self, Error> {
+        Self::bitwise(left, right, BitOr::bitor, "|")
     }
 
-    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
-        let mut source_coord = Vec::with_capacity(coord.len() + 1);
-        source_coord.extend_from_slice(coord);
-        source_coord.push(coord[coord.len() - 1]);
-        self.source.read_value(&source_coord)
+    /// Initialize a new scalar bitwise exclusive or [`Op`].
+    pub fn bitxor(left: A, right: T) -> Result<Self, Error> {
+        Self::bitwise(left, right, BitXor::bitxor, "^")
     }
-}
 
-/// A matrix multiplication [`Op`]
-#[derive(Clone)]
-pub struct MatMul<T, L, R> {
-    left: L,
-    right: R,
-    dtype: PhantomData<T>,
-    #[cfg(feature = "opencl")]
-    cl_op: ocl::Program,
-}
+    /// Initialize a new scalar left shift [`Op`].
+    pub fn shl(left: A, right: T) -> Result<Self, Error> {
+        Self::bitwise(left, right, T::shift_left, "<<")
+    }
 
-impl<T, L, R> MatMul<T, L, R>
-where
-    T: CDatatype,
-    L: NDArray<DType = T>,
-    R: NDArray<DType = T>,
-{
-    /// Initialize a new matrix multiplication [`Op`].
-    pub fn new(left: L, right: R) -> Result<Self, Error> {
-        debug_assert!(left.ndim() >= 2);
-        debug_assert!(right.ndim() >= 2);
+    /// Initialize a new scalar right shift [`Op`].
+    pub fn shr(left: A, right: T) -> Result<Self, Error> {
+        Self::bitwise(left, right, T::shift_right, ">>")
+    }
 
+    #[allow(unused_variables)]
+    fn integer(
+        array: A,
+        scalar: T,
+        cpu_op: fn(T, T) -> T,
+        cl_op: &'static str,
+    ) -> Result<Self, Error> {
         #[cfg(feature = "opencl")]
-        let cl_op = cl_programs::matmul::<T>(left.context())?;
+        let cl_op = cl_programs::elementwise_integer::<T>(cl_op, array.context())?;
 
         Ok(Self {
-            left,
-            right,
-            dtype: PhantomData,
+            array,
+            scalar,
+            cpu_op,
             #[cfg(feature = "opencl")]
             cl_op,
         })
     }
 
-    fn dims(&self) -> [usize; 4] {
-        let ndim = self.left.ndim();
-        debug_assert_eq!(ndim, self.right.ndim());
+    /// Initialize a new scalar wrapping addition [`Op`].
+    pub fn wrapping_add(left: A, right: T) -> Result<Self, Error> {
+        Self::integer(left, right, T::wrapping_add, "wrapping_add")
+    }
 
-        let num_matrices = self.left.shape().iter().take(ndim - 2).product();
-        debug_assert_eq!(
-            num_matrices,
-            self.right.shape().iter().take(ndim - 2).product()
-        );
+    /// Initialize a new scalar wrapping subtraction [`Op`].
+    pub fn wrapping_sub(left: A, right: T) -> Result<Self, Error> {
+        Self::integer(left, right, T::wrapping_sub, "wrapping_sub")
+    }
 
-        let a = *self.left.shape().iter().rev().nth(1).expect("a");
-        let b = *self.left.shape().last().expect("b");
-        let c = *self.right.shape().last().expect("c");
-        debug_assert_eq!(b, self.right.shape()[ndim - 2]);
-        debug_assert_eq!(num_matrices * a * b, self.left.size());
-        debug_assert_eq!(num_matrices * b * c, self.right.size());
+    /// Initialize a new scalar wrapping multiplication [`Op`].
+    pub fn wrapping_mul(left: A, right: T) -> Result<Self, Error> {
+        Self::integer(left, right, T::wrapping_mul, "wrapping_mul")
+    }
 
-        [num_matrices, a, b, c]
+    /// Initialize a new scalar saturating addition [`Op`].
+    pub fn saturating_add(left: A, right: T) -> Result<Self, Error> {
+        Self::integer(left, right, T::saturating_add, "saturating_add")
     }
-}
 
-impl<T, L, R> Op for MatMul<T, L, R>
-where
-    T: CDatatype,
-    L: NDArrayRead<DType = T> + NDArrayTransform,
-    R: NDArrayRead<DType = T> + NDArrayTransform,
-    L::Slice: NDArrayMath,
-{
-    type Out = T;
+    /// Initialize a new scalar saturating subtraction [`Op`].
+    pub fn saturating_sub(left: A, right: T) -> Result<Self, Error> {
+        Self::integer(left, right, T::saturating_sub, "saturating_sub")
+    }
 
-    fn context(&self) -> &Context {
-        self.left.context()
+    /// Initialize a new scalar saturating multiplication [`Op`].
+    pub fn saturating_mul(left: A, right: T) -> Result<Self, Error> {
+        Self::integer(left, right, T::saturating_mul, "saturating_mul")
     }
+}
 
-    fn enqueue_cpu(&self, queue: &Queue) -> Result<Vec<Self::Out>, Error> {
-        let [num_matrices, a, b, c] = self.dims();
+impl<T: Float, A: NDArray<DType = T>> ArrayScalar<T, A> {
+    /// Initialize a new exponential linear unit [`Op`] with the given `alpha`.
+    pub fn elu(array: A, alpha: T) -> Result<Self, Error> {
+        fn elu<T: Float>(x: T, alpha: T) -> T {
+            if x > T::zero() {
+                x
+            } else {
+                alpha * (x.exp() - T::one())
+            }
+        }
 
-        let (left, right) = try_join_read(&self.left, &self.right, queue)?;
+        Self::new(array, alpha, elu, "elu")
+    }
 
-        let left = left.as_ref();
-        let right = right.as_ref();
+    /// Initialize a new leaky rectified linear unit [`Op`] with the given negative slope `alpha`.
+    pub fn leaky_relu(array: A, alpha: T) -> Result<Self, Error> {
+        fn leaky_relu<T: Float>(x: T, alpha: T) -> T {
+            if x > T::zero() {
+                x
+            } else {
+                alpha * x
+            }
+        }
 
-        // transpose the right matrices
-        let right_size = b * c;
-        let right_matrices = (0..num_matrices).into_par_iter().map(|n| {
-            let start = n * right_size;
-            let stop = start + right_size;
-            let mut right_t = vec![T::zero(); right_size];
-            transpose::transpose(&right[start..stop], &mut right_t[..], c, b);
-            right_t
-        });
+        Self::new(array, alpha, leaky_relu, "leaky_relu")
+    }
+}
 
-        let left_size = a * b;
-        let left_matrices = left.par_chunks_exact(left_size);
+impl<T: CDatatype, A: NDArrayRead<DType = T>> Op for ArrayScalar<T, A> {
+    type Out = A::DType;
 
-        let output_size = a * c;
-        let mut output = Vec::<T>::with_capacity(num_matrices * output_size);
-        let output_matrices = left_matrices
-            .zip(right_matrices)
-            .map(|(lm, rm)| {
-                let mut out = Vec::<T>::with_capacity(output_size);
-
-                let product = lm
-                    .par_chunks_exact(b)
-                    .map(|row| {
-                        rm.par_chunks_exact(b).map(move |col| {
-                            // chunk the dot product to encourage the compiler to vectorize
-                            let col = col.chunks(8).map(|cc| cc.into_iter().copied());
-
-                            row.chunks(8)
-                                .zip(col)
-                                .map(|(rc, cc)| {
-                                    rc.into_iter().copied().zip(cc).map(|(r, c)| r * c).sum()
-                                })
-                                .sum::<T>()
-                        })
-                    })
-                    .flatten();
+    fn context(&self) -> &Context {
+        self.array.context()
+    }
 
-                out.par_extend(product);
-                out
-            })
-            .flatten();
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let left = self.array.to_host(queue)?;
+        let right = self.scalar;
 
-        output.par_extend(output_matrices);
+        let mut output = queue.host_buffer(left.len())?;
 
-        debug_assert_eq!(output.len(), num_matrices * output_size);
+        left.as_ref()
+            .par_iter()
+            .copied()
+            .with_min_len(queue.min_len(1))
+            .map(|l| (self.cpu_op)(l, right))
+            .collect_into_vec(&mut output);
 
         Ok(output)
     }
 
     #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<Self::Out>, Error> {
-        use crate::div_ceil;
-        use cl_programs::TILE_SIZE;
-
-        let [num_matrices, a, b, c] = self.dims();
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let left = self.array.to_cl_buffer(queue)?;
+        let right = self.scalar;
+        let cl_queue = left.as_ref().default_queue().expect("queue").clone();
 
-        let right_queue = queue.split(self.right.size())?;
-        let right = self.right.to_cl_buffer(&right_queue)?;
-        let left = self.left.to_cl_buffer(queue)?;
+        let output = queue.cl_buffer(cl_queue.clone(), left.len())?;
 
-        let cl_queue = left.as_ref().default_queue().expect("left queue");
+        let kernel = ocl::Kernel::builder()
+            .name("elementwise_scalar")
+            .program(&self.cl_op)
+            .queue(cl_queue)
+            .global_work_size(left.len())
+            .arg(left.as_ref())
+            .arg(right)
+            .arg(&*output)
+            .build()?;
 
-        assert!(num_matrices > 0);
-        assert_eq!(num_matrices * a * b, left.len());
-        assert_eq!(num_matrices * b * c, right.len());
+        unsafe { queue.enqueue_kernel(&kernel)? }
 
-        let dims = [a as u64, b as u64, c as u64, num_matrices as u64];
-
-        let output = ocl::Buffer::builder()
-            .queue(cl_queue.clone())
-            .len(a * c * num_matrices)
-            .build()?;
-
-        let kernel = ocl::Kernel::builder()
-            .name("matmul")
-            .program(&self.cl_op)
-            .queue(cl_queue.clone())
-            .global_work_size((num_matrices, div_ceil(a, TILE_SIZE), div_ceil(c, TILE_SIZE)))
-            .arg(ocl::core::Ulong4::from(dims))
-            .arg(div_ceil(b, TILE_SIZE))
-            .arg(left.as_ref())
-            .arg(right.as_ref())
-            .arg(&output)
-            .build()?;
-
-        unsafe { kernel.enq()? }
-
-        Ok(output)
-    }
+        Ok(output)
+    }
 
     fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
-        Err(Error::Bounds(format!(
-            "reading the value at {coord:?} from a maxtrix multiplication is not implemented"
-        )))
+        let left = self.array.read_value(coord)?;
+        let right = self.scalar;
+        Ok((self.cpu_op)(left, right))
     }
 }
 
-// comparison
-
-/// An array comparison [`Op`]
+/// An integer array [`Op`] with a scalar argument which returns an error on overflow
 #[derive(Clone)]
-pub struct ArrayBoolean<T, L, R> {
-    left: L,
-    right: R,
-    cpu_op: fn(T, T) -> bool,
+pub struct ArrayScalarChecked<T, A> {
+    array: A,
+    scalar: T,
+    cpu_op: fn(T, T) -> Option<T>,
     #[cfg(feature = "opencl")]
     cl_op: ocl::Program,
 }
 
-impl<T: CDatatype, L: NDArray<DType = T>, R: NDArray<DType = T>> ArrayBoolean<T, L, R> {
+impl<T: Integer, A: NDArray<DType = T>> ArrayScalarChecked<T, A> {
     #[allow(unused_variables)]
     fn new(
-        left: L,
-        right: R,
-        cpu_op: fn(T, T) -> bool,
+        array: A,
+        scalar: T,
+        cpu_op: fn(T, T) -> Option<T>,
         cl_op: &'static str,
     ) -> Result<Self, Error> {
-        debug_assert_eq!(left.shape(), right.shape());
-
         #[cfg(feature = "opencl")]
-        let cl_op = cl_programs::elementwise_boolean::<T>(cl_op, left.context())?;
+        let cl_op = cl_programs::elementwise_checked::<T>(cl_op, array.context())?;
 
         Ok(Self {
-            left,
-            right,
+            array,
+            scalar,
             cpu_op,
             #[cfg(feature = "opencl")]
             cl_op,
         })
     }
 
-    /// Initialize a new boolean and [`Op`].
-    pub fn and(left: L, right: R) -> Result<Self, Error> {
-        fn and<T: CDatatype>(l: T, r: T) -> bool {
-            (l != T::zero()) && (r != T::zero())
-        }
-
-        Self::new(left, right, and, "&&")
+    /// Initialize a new scalar checked addition [`Op`].
+    pub fn add(left: A, right: T) -> Result<Self, Error> {
+        Self::new(left, right, T::checked_add, "add")
     }
 
-    /// Initialize a new boolean or [`Op`].
-    pub fn or(left: L, right: R) -> Result<Self, Error> {
-        fn or<T: CDatatype>(l: T, r: T) -> bool {
-            (l != T::zero()) || (r != T::zero())
-        }
-
-        Self::new(left, right, or, "||")
+    /// Initialize a new scalar checked subtraction [`Op`].
+    pub fn sub(left: A, right: T) -> Result<Self, Error> {
+        Self::new(left, right, T::checked_sub, "sub")
     }
 
-    /// Initialize a new boolean xor [`Op`].
-    pub fn xor(left: L, right: R) -> Result<Self, Error> {
-        fn xor<T: CDatatype>(l: T, r: T) -> bool {
-            (l != T::zero()) ^ (r != T::zero())
-        }
-
-        Self::new(left, right, xor, "^")
+    /// Initialize a new scalar checked multiplication [`Op`].
+    pub fn mul(left: A, right: T) -> Result<Self, Error> {
+        Self::new(left, right, T::checked_mul, "mul")
     }
 }
 
-impl<T: CDatatype, L, R> Op for ArrayBoolean<T, L, R>
-where
-    L: NDArrayRead<DType = T>,
-    R: NDArrayRead<DType = T>,
-{
-    type Out = u8;
+impl<T: Integer, A: NDArrayRead<DType = T>> Op for ArrayScalarChecked<T, A> {
+    type Out = T;
 
     fn context(&self) -> &Context {
-        self.left.context()
+        self.array.context()
     }
 
-    fn enqueue_cpu(&self, queue: &Queue) -> Result<Vec<Self::Out>, Error> {
-        let (left, right) = try_join_read(&self.left, &self.right, queue)?;
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let left = self.array.to_host(queue)?;
+        let right = self.scalar;
 
-        let output = left
-            .as_ref()
-            .par_iter()
-            .copied()
-            .zip(right.as_ref().par_iter().copied())
-            .map(|(l, r)| (self.cpu_op)(l, r))
-            .map(|cmp| if cmp { 1 } else { 0 })
-            .collect();
+        let mut output = queue.host_buffer_of(left.len(), T::zero())?;
+
+        output
+            .par_iter_mut()
+            .zip(left.as_ref().par_iter().copied())
+            .with_min_len(queue.min_len(1))
+            .try_for_each(|(o, l)| -> Result<(), Error> {
+                *o = (self.cpu_op)(l, right).ok_or_else(|| overflow(l, right))?;
+                Ok(())
+            })?;
 
         Ok(output)
     }
 
     #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<Self::Out>, Error> {
-        let right_queue = queue.split(self.right.size())?;
-        let right = self.right.to_cl_buffer(&right_queue)?;
-
-        let left = self.left.to_cl_buffer(queue)?;
-        debug_assert_eq!(left.len(), right.len());
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let left = self.array.to_cl_buffer(queue)?;
+        let right = self.scalar;
+        let cl_queue = left.as_ref().default_queue().expect("queue").clone();
 
-        let cl_queue = left.as_ref().default_queue().expect("queue");
+        let output = queue.cl_buffer(cl_queue.clone(), left.len())?;
 
-        let output = ocl::Buffer::builder()
-            .queue(cl_queue.clone())
-            .len(left.len())
-            .build()?;
+        let flag = cl_buffer_of(queue, cl_queue.clone(), 1, 0u8)?;
 
         let kernel = ocl::Kernel::builder()
-            .name("elementwise_boolean")
+            .name("elementwise_scalar_checked")
             .program(&self.cl_op)
-            .queue(cl_queue.clone())
-            .global_work_size(output.len())
+            .queue(cl_queue)
+            .global_work_size(left.len())
             .arg(left.as_ref())
-            .arg(right.as_ref())
-            .arg(&output)
+            .arg(right)
+            .arg(&*output)
+            .arg(&*flag)
             .build()?;
 
-        unsafe { kernel.enq()? };
+        unsafe { queue.enqueue_kernel(&kernel)? }
+
+        check_overflow(&flag)?;
 
         Ok(output)
     }
 
     fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
-        let (left, right) = try_join(
-            || self.left.read_value(coord),
-            || self.right.read_value(coord),
-        )?;
-
-        if (self.cpu_op)(left, right) {
-            Ok(1)
-        } else {
-            Ok(0)
-        }
+        let left = self.array.read_value(coord)?;
+        let right = self.scalar;
+        (self.cpu_op)(left, right).ok_or_else(|| overflow(left, right))
     }
 }
 
-/// A boolean array [`Op`] with a scalar argument
+/// An array [`Op`] with a scalar floating-point argument
 #[derive(Clone)]
-pub struct ArrayBooleanScalar<L, T> {
-    left: L,
-    right: T,
-    cpu_op: fn(T, T) -> bool,
+pub struct ArrayScalarFloat<T: CDatatype, A> {
+    array: A,
+    scalar: T::Float,
+    cpu_op: fn(T, T::Float) -> T,
     #[cfg(feature = "opencl")]
     cl_op: ocl::Program,
 }
 
-impl<L: NDArray, T: CDatatype> ArrayBooleanScalar<L, T> {
+impl<T: CDatatype, A: NDArray> ArrayScalarFloat<T, A> {
     #[allow(unused_variables)]
     fn new(
-        left: L,
-        right: T,
-        cpu_op: fn(T, T) -> bool,
+        array: A,
+        scalar: T::Float,
+        cpu_op: fn(T, T::Float) -> T,
         cl_op: &'static str,
     ) -> Result<Self, Error> {
         #[cfg(feature = "opencl")]
-        let cl_op = cl_programs::scalar_boolean::<T>(cl_op, left.context())?;
+        let cl_op = cl_programs::elementwise_scalar::<T::Float, T>(cl_op, array.context())?;
 
         Ok(Self {
-            left,
-            right,
+            array,
+            scalar,
             cpu_op,
             #[cfg(feature = "opencl")]
             cl_op,
         })
     }
+}
 
-    /// Initialize a new boolean and [`Op`] with a scalar argument.
-    pub fn and(left: L, right: T) -> Result<Self, Error> {
-        fn and<T: CDatatype>(l: T, r: T) -> bool {
-            (l != T::zero()) && (r != T::zero())
-        }
-
-        Self::new(left, right, and, "&&")
-    }
-
-    /// Initialize a new boolean or [`Op`] with a scalar argument.
-    pub fn or(left: L, right: T) -> Result<Self, Error> {
-        fn or<T: CDatatype>(l: T, r: T) -> bool {
-            (l != T::zero()) || (r != T::zero())
-        }
-
-        Self::new(left, right, or, "||")
+impl<T: CDatatype + PartialOrd, A: NDArray> ArrayScalarFloat<T, A> {
+    /// Initialize a new logarithm [`Op`] with a scalar base.
+    pub fn log(left: A, right: T::Float) -> Result<Self, Error> {
+        Self::new(
+            left,
+            right,
+            |l, r| T::from_float(l.to_float().log(r)),
+            "log",
+        )
     }
 
-    /// Initialize a new boolean xor [`Op`] with a scalar argument.
-    pub fn xor(left: L, right: T) -> Result<Self, Error> {
-        fn xor<T: CDatatype>(l: T, r: T) -> bool {
-            (l != T::zero()) ^ (r != T::zero())
-        }
-
-        Self::new(left, right, xor, "^")
+    /// Initialize a new exponentiation [`Op`] with a scalar exponent.
+    pub fn pow(left: A, right: T::Float) -> Result<Self, Error> {
+        Self::new(
+            left,
+            right,
+            |l, r| T::from_float(l.to_float().pow(r)),
+            "pow_",
+        )
     }
 }
 
-impl<L: NDArrayRead<DType = T>, T: CDatatype> Op for ArrayBooleanScalar<L, T> {
-    type Out = u8;
+impl<T: CDatatype, A: NDArrayRead<DType = T>> Op for ArrayScalarFloat<T, A> {
+    type Out = T;
 
     fn context(&self) -> &Context {
-        self.left.context()
+        self.array.context()
     }
 
-    fn enqueue_cpu(&self, queue: &Queue) -> Result<Vec<Self::Out>, Error> {
-        let left = self.left.to_host(queue)?;
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let left = self.array.to_host(queue)?;
+        let right = self.scalar;
 
-        let output = left
-            .as_ref()
+        let mut output = queue.host_buffer(left.len())?;
+
+        left.as_ref()
             .par_iter()
             .copied()
-            .map(|l| (self.cpu_op)(l, self.right))
-            .map(|cmp| if cmp { 1 } else { 0 })
-            .collect();
+            .with_min_len(queue.min_len(1))
+            .map(|l| (self.cpu_op)(l, right))
+            .collect_into_vec(&mut output);
 
         Ok(output)
     }
 
     #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<Self::Out>, Error> {
-        let left = self.left.to_cl_buffer(queue)?;
-        let cl_queue = left.as_ref().default_queue().expect("queue");
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let left = self.array.to_cl_buffer(queue)?;
+        let right = self.scalar;
+        let cl_queue = left.as_ref().default_queue().expect("queue").clone();
 
-        let output = ocl::Buffer::builder()
-            .queue(cl_queue.clone())
-            .len(left.len())
-            .build()?;
+        let output = queue.cl_buffer(cl_queue.clone(), left.len())?;
 
         let kernel = ocl::Kernel::builder()
-            .name("scalar_boolean")
+            .name("elementwise_scalar")
             .program(&self.cl_op)
-            .queue(cl_queue.clone())
-            .global_work_size(output.len())
+            .queue(cl_queue)
+            .global_work_size(left.len())
             .arg(left.as_ref())
-            .arg(self.right)
-            .arg(&output)
+            .arg(right)
+            .arg(&*output)
             .build()?;
 
-        unsafe { kernel.enq()? };
+        unsafe { queue.enqueue_kernel(&kernel)? }
 
         Ok(output)
     }
 
     fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
-        let left = self.left.read_value(coord)?;
-        let right = self.right;
-
-        if (self.cpu_op)(left, right) {
-            Ok(1)
-        } else {
-            Ok(0)
-        }
+        let left = self.array.read_value(coord)?;
+        let right = self.scalar;
+        Ok((self.cpu_op)(left, right))
     }
 }
 
-/// An array comparison [`Op`]
+/// An array [`Op`] to clamp each element to a range `[min, max]`
 #[derive(Clone)]
-pub struct ArrayCompare<T, L, R> {
-    left: L,
-    right: R,
-    cpu_op: fn(&T, &T) -> bool,
+pub struct ArrayClamp<T, A> {
+    array: A,
+    min: T,
+    max: T,
     #[cfg(feature = "opencl")]
     cl_op: ocl::Program,
 }
 
-impl<T: CDatatype, L: NDArray<DType = T>, R: NDArray<DType = T>> ArrayCompare<T, L, R> {
-    #[allow(unused_variables)]
-    fn new(
-        left: L,
-        right: R,
-        cpu_op: fn(&T, &T) -> bool,
-        cl_op: &'static str,
-    ) -> Result<Self, Error> {
-        debug_assert_eq!(left.shape(), right.shape());
+impl<T: CDatatype + PartialOrd, A: NDArray<DType = T>> ArrayClamp<T, A> {
+    /// Initialize a new [`Op`] to clamp the elements of the given `array` to `[min, max]`.
+    pub fn new(array: A, min: T, max: T) -> Result<Self, Error> {
+        if min > max {
+            return Err(Error::Bounds(format!(
+                "invalid range for clamp: [{}, {}]",
+                min.to_f64(),
+                max.to_f64()
+            )));
+        }
 
         #[cfg(feature = "opencl")]
-        let cl_op = cl_programs::elementwise_cmp::<T>(cl_op, left.context())?;
+        let cl_op = cl_programs::elementwise_clamp::<T>(array.context())?;
 
         Ok(Self {
-            left,
-            right,
-            cpu_op,
+            array,
+            min,
+            max,
             #[cfg(feature = "opencl")]
             cl_op,
         })
     }
+}
 
-    /// Initialize a new equality comparison [`Op`].
-    pub fn eq(left: L, right: R) -> Result<Self, Error> {
-        Self::new(left, right, PartialEq::eq, "==")
-    }
+impl<T: CDatatype + PartialOrd, A: NDArrayRead<DType = T>> Op for ArrayClamp<T, A> {
+    type Out = T;
 
-    /// Initialize a new greater-than comparison [`Op`].
-    pub fn gt(left: L, right: R) -> Result<Self, Error> {
-        Self::new(left, right, PartialOrd::gt, ">")
+    fn context(&self) -> &Context {
+        self.array.context()
     }
 
-    /// Initialize a new equal-or-greater-than comparison [`Op`].
-    pub fn ge(left: L, right: R) -> Result<Self, Error> {
-        Self::new(left, right, PartialOrd::ge, ">=")
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let input = self.array.to_host(queue)?;
+        let mut output = queue.host_buffer(input.len())?;
+
+        input
+            .as_ref()
+            .par_iter()
+            .copied()
+            .with_min_len(queue.min_len(1))
+            .map(|n| minimum(maximum(n, self.min), self.max))
+            .collect_into_vec(&mut output);
+
+        Ok(output)
     }
 
-    /// Initialize a new less-than comparison [`Op`].
-    pub fn lt(left: L, right: R) -> Result<Self, Error> {
-        Self::new(left, right, PartialOrd::lt, "<")
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let input = self.array.to_cl_buffer(queue)?;
+        let cl_queue = input.as_ref().default_queue().expect("queue").clone();
+
+        let output = queue.cl_buffer(cl_queue.clone(), input.len())?;
+
+        let kernel = ocl::Kernel::builder()
+            .name("elementwise_clamp")
+            .program(&self.cl_op)
+            .queue(cl_queue)
+            .global_work_size(input.len())
+            .arg(input.as_ref())
+            .arg(self.min)
+            .arg(self.max)
+            .arg(&*output)
+            .build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? }
+
+        Ok(output)
     }
 
-    /// Initialize a new equal-or-less-than comparison [`Op`].
-    pub fn le(left: L, right: R) -> Result<Self, Error> {
-        Self::new(left, right, PartialOrd::le, "<=")
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        let n = self.array.read_value(coord)?;
+        Ok(minimum(maximum(n, self.min), self.max))
     }
+}
 
-    /// Initialize a new not-equal comparison [`Op`].
-    pub fn ne(left: L, right: R) -> Result<Self, Error> {
-        Self::new(left, right, PartialEq::ne, "!=")
+// linear algebra
+
+/// A matrix diagonal read [`Op`]
+///
+/// A positive `offset` reads a diagonal above the main diagonal, and a negative `offset`
+/// a diagonal below it.
+#[derive(Clone)]
+pub struct MatDiag<A> {
+    source: A,
+    shape: Shape,
+    start: [usize; 2],
+    #[cfg(feature = "opencl")]
+    cl_op: ocl::Program,
+}
+
+impl<A: NDArray> MatDiag<A> {
+    /// Initialize a new matrix diagonal read [`Op`].
+    pub fn new(source: A, offset: isize) -> Result<Self, Error> {
+        let [m, n] = matrix_dims(source.shape())?;
+
+        let start = if offset < 0 {
+            [offset.unsigned_abs(), 0]
+        } else {
+            [0, offset as usize]
+        };
+
+        let len = m.saturating_sub(start[0]).min(n.saturating_sub(start[1]));
+
+        if len == 0 {
+            return Err(Error::Bounds(format!(
+                "a {m}x{n} matrix has no diagonal at offset {offset}"
+            )));
+        }
+
+        let mut shape = matrix_batch(source.shape()).to_vec();
+        shape.push(len);
+
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::diagonal::<A::DType>(source.context())?;
+
+        Ok(Self {
+            source,
+            shape,
+            start,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
+
+    /// Return the shape of the diagonal(s) read by this [`Op`].
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
     }
 }
 
-impl<T, L, R> Op for ArrayCompare<T, L, R>
-where
-    T: CDatatype,
-    L: NDArrayRead<DType = T>,
-    R: NDArrayRead<DType = T>,
-{
-    type Out = u8;
+impl<A: NDArrayRead> Op for MatDiag<A> {
+    type Out = A::DType;
 
     fn context(&self) -> &Context {
-        self.left.context()
+        self.source.context()
     }
 
-    fn enqueue_cpu(&self, queue: &Queue) -> Result<Vec<Self::Out>, Error> {
-        let (left, right) = try_join_read(&self.left, &self.right, queue)?;
-        debug_assert_eq!(left.len(), right.len());
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let [m, n] = matrix_dims(self.source.shape())?;
+        let len = *self.shape.last().expect("len");
+        let [row, col] = self.start;
+
+        let input = self.source.to_host(queue)?;
+        let mut output = queue.host_buffer(self.shape.iter().product())?;
 
-        let output = left
+        let diagonals = input
             .as_ref()
-            .par_iter()
-            .zip(right.as_ref().par_iter())
-            .map(|(l, r)| (self.cpu_op)(l, r))
-            .map(|cmp| if cmp { 1 } else { 0 })
-            .collect();
+            .par_chunks_exact(m * n)
+            .with_min_len(queue.min_len(m * n))
+            .map(|matrix| {
+                (0..len)
+                    .into_par_iter()
+                    .map(move |i| matrix[((row + i) * n) + col + i])
+            })
+            .flatten();
+
+        output.par_extend(diagonals);
 
         Ok(output)
     }
 
     #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<Self::Out>, Error> {
-        let right_queue = queue.split(self.right.size())?;
-        let right = self.right.to_cl_buffer(&right_queue)?;
-        let left = self.left.to_cl_buffer(queue)?;
-        debug_assert_eq!(left.len(), right.len());
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let [m, n] = matrix_dims(self.source.shape())?;
+        let len = *self.shape.last().expect("len");
+        let [row, col] = self.start;
 
-        let cl_queue = left.as_ref().default_queue().expect("left queue").clone();
+        let input = self.source.to_cl_buffer(queue)?;
+        let cl_queue = input.as_ref().default_queue().expect("queue");
 
-        let output = ocl::Buffer::builder()
-            .queue(cl_queue.clone())
-            .len(left.len())
-            .build()?;
+        let output = queue.cl_buffer(cl_queue.clone(), self.shape.iter().product::<usize>())?;
 
         let kernel = ocl::Kernel::builder()
-            .name("elementwise_cmp")
+            .name("diagonal")
             .program(&self.cl_op)
-            .queue(cl_queue)
-            .global_work_size(output.len())
-            .arg(left.as_ref())
-            .arg(right.as_ref())
-            .arg(&output)
+            .queue(cl_queue.clone())
+            .global_work_size((input.len() / (m * n), len))
+            .arg(m as u64)
+            .arg(n as u64)
+            .arg(row as u64)
+            .arg(col as u64)
+            .arg(input.as_ref())
+            .arg(&*output)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { queue.enqueue_kernel(&kernel)? };
 
         Ok(output)
     }
 
     fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
-        let (left, right) = try_join(
-            || self.left.read_value(coord),
-            || self.right.read_value(coord),
-        )?;
-
-        if (self.cpu_op)(&left, &right) {
-            Ok(1)
-        } else {
-            Ok(0)
-        }
+        let i = coord[coord.len() - 1];
+        let mut source_coord = Vec::with_capacity(coord.len() + 1);
+        source_coord.extend_from_slice(&coord[..coord.len() - 1]);
+        source_coord.push(self.start[0] + i);
+        source_coord.push(self.start[1] + i);
+        self.source.read_value(&source_coord)
     }
 }
 
-/// An array comparison [`Op`] with a scalar argument
+/// An [`Op`] to construct a square diagonal matrix from a vector, or a batch of vectors
 #[derive(Clone)]
-pub struct ArrayCompareScalar<T, A> {
-    array: A,
-    scalar: T,
-    cpu_op: fn(&T, &T) -> bool,
+pub struct MatDiagEmbed<A> {
+    source: A,
     #[cfg(feature = "opencl")]
     cl_op: ocl::Program,
 }
 
-impl<T: CDatatype, A: NDArray> ArrayCompareScalar<T, A> {
-    #[allow(unused_variables)]
-    fn new(
-        array: A,
-        scalar: T,
-        cpu_op: fn(&T, &T) -> bool,
-        cl_op: &'static str,
-    ) -> Result<Self, Error> {
+impl<A: NDArray> MatDiagEmbed<A> {
+    /// Initialize a new diagonal matrix construction [`Op`].
+    pub fn new(source: A) -> Result<Self, Error> {
         #[cfg(feature = "opencl")]
-        let cl_op = cl_programs::scalar_cmp::<T>(cl_op, array.context())?;
+        let cl_op = cl_programs::diagonal::<A::DType>(source.context())?;
 
         Ok(Self {
-            array,
-            scalar,
-            cpu_op,
+            source,
             #[cfg(feature = "opencl")]
             cl_op,
         })
     }
 
-    /// Initialize a new equality comparison [`Op`].
-    pub fn eq(array: A, scalar: T) -> Result<Self, Error> {
-        Self::new(array, scalar, PartialEq::eq, "==")
+    /// Return the shape of the matrix or batch of matrices constructed by this [`Op`].
+    pub fn shape(&self) -> Shape {
+        let mut shape = self.source.shape().to_vec();
+        shape.push(*shape.last().expect("n"));
+        shape
     }
+}
 
-    /// Initialize a new greater-than comparison [`Op`].
-    pub fn gt(array: A, scalar: T) -> Result<Self, Error> {
-        Self::new(array, scalar, PartialOrd::gt, ">")
+impl<A: NDArrayRead> Op for MatDiagEmbed<A> {
+    type Out = A::DType;
+
+    fn context(&self) -> &Context {
+        self.source.context()
     }
 
-    /// Initialize a new equal-or-greater-than comparison [`Op`].
-    pub fn ge(array: A, scalar: T) -> Result<Self, Error> {
-        Self::new(array, scalar, PartialOrd::ge, ">=")
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let n = *self.source.shape().last().expect("n");
+        let input = self.source.to_host(queue)?;
+
+        let mut output = queue.host_buffer_of(self.source.size() * n, A::DType::zero())?;
+
+        output
+            .par_chunks_exact_mut(n * n)
+            .zip(input.as_ref().par_chunks_exact(n))
+            .with_min_len(queue.min_len(n * n))
+            .for_each(|(matrix, vector)| {
+                for (i, x) in vector.iter().enumerate() {
+                    matrix[(i * n) + i] = *x;
+                }
+            });
+
+        Ok(output)
     }
 
-    /// Initialize a new less-than comparison [`Op`].
-    pub fn lt(array: A, scalar: T) -> Result<Self, Error> {
-        Self::new(array, scalar, PartialOrd::lt, "<")
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let n = *self.source.shape().last().expect("n");
+        let input = self.source.to_cl_buffer(queue)?;
+        let cl_queue = input.as_ref().default_queue().expect("queue");
+
+        let output = queue.cl_buffer(cl_queue.clone(), input.len() * n)?;
+
+        let kernel = ocl::Kernel::builder()
+            .name("diag_embed")
+            .program(&self.cl_op)
+            .queue(cl_queue.clone())
+            .global_work_size(input.len() * n)
+            .arg(n as u64)
+            .arg(input.as_ref())
+            .arg(&*output)
+            .build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? };
+
+        Ok(output)
     }
 
-    /// Initialize a new equal-or-less-than comparison [`Op`].
-    pub fn le(array: A, scalar: T) -> Result<Self, Error> {
-        Self::new(array, scalar, PartialOrd::le, "<=")
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        let ndim = coord.len();
+
+        if coord[ndim - 2] == coord[ndim - 1] {
+            self.source.read_value(&coord[..ndim - 1])
+        } else {
+            Ok(A::DType::zero())
+        }
     }
+}
 
-    /// Initialize a new not-equal comparison [`Op`].
-    pub fn ne(array: A, scalar: T) -> Result<Self, Error> {
-        Self::new(array, scalar, PartialEq::ne, "!=")
+/// An [`Op`] to compute the sum of the main diagonal of a matrix, or a batch of matrices
+#[derive(Clone)]
+pub struct MatTrace<A> {
+    source: A,
+    #[cfg(feature = "opencl")]
+    cl_op: ocl::Program,
+}
+
+impl<A: NDArray> MatTrace<A> {
+    /// Initialize a new matrix trace [`Op`].
+    pub fn new(source: A) -> Result<Self, Error> {
+        matrix_dims(source.shape())?;
+
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::diagonal::<A::DType>(source.context())?;
+
+        Ok(Self {
+            source,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
     }
 }
 
-impl<T: CDatatype, A: NDArrayRead<DType = T>> Op for ArrayCompareScalar<T, A> {
-    type Out = u8;
+impl<A: NDArrayRead> Op for MatTrace<A> {
+    type Out = A::DType;
 
     fn context(&self) -> &Context {
-        self.array.context()
+        self.source.context()
     }
 
-    fn enqueue_cpu(&self, queue: &Queue) -> Result<Vec<Self::Out>, Error> {
-        let input = self.array.to_host(queue)?;
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let [m, n] = matrix_dims(self.source.shape())?;
+        let input = self.source.to_host(queue)?;
+
+        let mut output = queue.host_buffer(input.len() / (m * n))?;
 
-        let output = input
+        input
             .as_ref()
-            .par_iter()
-            .map(|n| (self.cpu_op)(n, &self.scalar))
-            .map(|cmp| if cmp { 1 } else { 0 })
-            .collect();
+            .par_chunks_exact(m * n)
+            .with_min_len(queue.min_len(m * n))
+            .map(|matrix| {
+                (0..m.min(n))
+                    .map(|i| matrix[(i * n) + i])
+                    .fold(A::DType::zero(), |sum, x| sum + x)
+            })
+            .collect_into_vec(&mut output);
 
         Ok(output)
     }
 
     #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<Self::Out>, Error> {
-        let input = self.array.to_cl_buffer(queue)?;
-        let cl_queue = input.as_ref().default_queue().expect("queue").clone();
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let [m, n] = matrix_dims(self.source.shape())?;
+        let input = self.source.to_cl_buffer(queue)?;
+        let cl_queue = input.as_ref().default_queue().expect("queue");
 
-        let output = ocl::Buffer::builder()
-            .queue(cl_queue.clone())
-            .len(input.len())
-            .build()?;
+        let output = queue.cl_buffer(cl_queue.clone(), input.len() / (m * n))?;
 
         let kernel = ocl::Kernel::builder()
-            .name("scalar_cmp")
+            .name("trace")
             .program(&self.cl_op)
-            .queue(cl_queue)
-            .global_work_size(input.len())
+            .queue(cl_queue.clone())
+            .global_work_size(input.len() / (m * n))
+            .arg(m as u64)
+            .arg(n as u64)
             .arg(input.as_ref())
-            .arg(self.scalar)
-            .arg(&output)
+            .arg(&*output)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { queue.enqueue_kernel(&kernel)? };
 
         Ok(output)
     }
 
     fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
-        let left = self.array.read_value(coord)?;
-        let right = self.scalar;
+        let [m, n] = matrix_dims(self.source.shape())?;
+        let batch = &coord[..self.source.ndim() - 2];
 
-        if (self.cpu_op)(&left, &right) {
-            Ok(1)
-        } else {
-            Ok(0)
+        let mut source_coord = Vec::with_capacity(self.source.ndim());
+        let mut sum = A::DType::zero();
+
+        for i in 0..m.min(n) {
+            source_coord.clear();
+            source_coord.extend_from_slice(batch);
+            source_coord.extend([i, i]);
+            sum = sum + self.source.read_value(&source_coord)?;
         }
+
+        Ok(sum)
     }
 }
 
-// reduction
-
-/// An array reduction [`Op`]
-#[derive(Copy, Clone)]
-pub struct ArrayReduceAxes<T, A> {
+/// An [`Op`] to mask the lower or upper triangle of a matrix, or a batch of matrices
+///
+/// The lower triangle is the `k`th diagonal and every element below it,
+/// and the upper triangle is the `k`th diagonal and every element above it.
+/// Every other element is set to zero.
+#[derive(Clone)]
+pub struct MatTriangle<A> {
     source: A,
-    stride: usize,
-    id: T,
-    cpu_op: fn(T, T) -> T,
-    #[allow(unused)]
-    cl_op: &'static str,
+    k: isize,
+    upper: bool,
+    #[cfg(feature = "opencl")]
+    cl_op: ocl::Program,
 }
 
-impl<T: CDatatype, A: NDArray<DType = T>> ArrayReduceAxes<T, A> {
-    fn new(source: A, stride: usize, id: T, cpu_op: fn(T, T) -> T, cl_op: &'static str) -> Self {
-        Self {
+impl<A: NDArray> MatTriangle<A> {
+    /// Initialize a new [`Op`] to read the lower triangle of a matrix.
+    pub fn lower(source: A, k: isize) -> Result<Self, Error> {
+        Self::new(source, k, false)
+    }
+
+    /// Initialize a new [`Op`] to read the upper triangle of a matrix.
+    pub fn upper(source: A, k: isize) -> Result<Self, Error> {
+        Self::new(source, k, true)
+    }
+
+    fn new(source: A, k: isize, upper: bool) -> Result<Self, Error> {
+        matrix_dims(source.shape())?;
+
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::triangle::<A::DType>(source.context())?;
+
+        Ok(Self {
             source,
-            stride,
-            id,
-            cpu_op,
+            k,
+            upper,
+            #[cfg(feature = "opencl")]
             cl_op,
-        }
+        })
     }
 
-    /// Initialize a new reduce-max [`Op`].
-    pub fn max(source: A, stride: usize) -> Self {
+    // return `true` if the element at row `i` and column `j` is in this triangle
+    fn contains(&self, i: usize, j: usize) -> bool {
+        let diagonal = j as isize - i as isize;
+
+        if self.upper {
+            diagonal >= self.k
+        } else {
+            diagonal <= self.k
+        }
+    }
+}
+
+impl<A: NDArrayRead> Op for MatTriangle<A> {
+    type Out = A::DType;
+
+    fn context(&self) -> &Context {
+        self.source.context()
+    }
+
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let [m, n] = matrix_dims(self.source.shape())?;
+        let input = self.source.to_host(queue)?;
+
+        let mut output = queue.host_buffer(input.len())?;
+
+        input
+            .as_ref()
+            .par_iter()
+            .with_min_len(queue.min_len(1))
+            .enumerate()
+            .map(|(offset, x)| {
+                if self.contains((offset / n) % m, offset % n) {
+                    *x
+                } else {
+                    A::DType::zero()
+                }
+            })
+            .collect_into_vec(&mut output);
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let [m, n] = matrix_dims(self.source.shape())?;
+        let input = self.source.to_cl_buffer(queue)?;
+        let cl_queue = input.as_ref().default_queue().expect("queue");
+
+        let output = queue.cl_buffer(cl_queue.clone(), input.len())?;
+
+        let kernel = ocl::Kernel::builder()
+            .name("triangle")
+            .program(&self.cl_op)
+            .queue(cl_queue.clone())
+            .global_work_size(input.len())
+            .arg(m as u64)
+            .arg(n as u64)
+            .arg(self.k as i64)
+            .arg(u8::from(self.upper))
+            .arg(input.as_ref())
+            .arg(&*output)
+            .build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? };
+
+        Ok(output)
+    }
+
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        let ndim = coord.len();
+
+        if self.contains(coord[ndim - 2], coord[ndim - 1]) {
+            self.source.read_value(coord)
+        } else {
+            Ok(A::DType::zero())
+        }
+    }
+}
+
+/// An [`Op`] to compute the outer product of two vectors, or two batches of vectors
+#[derive(Clone)]
+pub struct MatOuter<T, L, R> {
+    left: L,
+    right: R,
+    dtype: PhantomData<T>,
+    #[cfg(feature = "opencl")]
+    cl_op: ocl::Program,
+}
+
+impl<T, L, R> MatOuter<T, L, R>
+where
+    T: CDatatype,
+    L: NDArray<DType = T>,
+    R: NDArray<DType = T>,
+{
+    /// Initialize a new outer product [`Op`].
+    pub fn new(left: L, right: R) -> Result<Self, Error> {
+        let (lbatch, rbatch) = (
+            &left.shape()[..left.ndim() - 1],
+            &right.shape()[..right.ndim() - 1],
+        );
+
+        if lbatch != rbatch {
+            return Err(Error::Bounds(format!(
+                "the outer product of {:?} and {:?} requires the same batch dimensions",
+                left.shape(),
+                right.shape()
+            )));
+        }
+
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::outer::<T>(left.context())?;
+
+        Ok(Self {
+            left,
+            right,
+            dtype: PhantomData,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
+
+    /// Return the shape of the matrix or batch of matrices computed by this [`Op`].
+    pub fn shape(&self) -> Shape {
+        let mut shape = self.left.shape().to_vec();
+        shape.push(*self.right.shape().last().expect("n"));
+        shape
+    }
+}
+
+impl<T, L, R> Op for MatOuter<T, L, R>
+where
+    T: CDatatype,
+    L: NDArrayRead<DType = T>,
+    R: NDArrayRead<DType = T>,
+{
+    type Out = T;
+
+    fn context(&self) -> &Context {
+        self.left.context()
+    }
+
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let m = *self.left.shape().last().expect("m");
+        let n = *self.right.shape().last().expect("n");
+
+        let left = self.left.to_host(queue)?;
+        let right = self.right.to_host(queue)?;
+
+        let mut output = queue.host_buffer_of(self.left.size() * n, T::zero())?;
+
+        output
+            .par_chunks_exact_mut(m * n)
+            .zip(left.as_ref().par_chunks_exact(m))
+            .zip(right.as_ref().par_chunks_exact(n))
+            .with_min_len(queue.min_len(m * n))
+            .for_each(|((output, left), right)| {
+                for (row, l) in output.chunks_exact_mut(n).zip(left) {
+                    for (o, r) in row.iter_mut().zip(right) {
+                        *o = *l * *r;
+                    }
+                }
+            });
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let m = *self.left.shape().last().expect("m");
+        let n = *self.right.shape().last().expect("n");
+
+        let left = self.left.to_cl_buffer(queue)?;
+        let right = self.right.to_cl_buffer(queue)?;
+        let cl_queue = left.as_ref().default_queue().expect("queue").clone();
+
+        let output = queue.cl_buffer(cl_queue.clone(), left.len() * n)?;
+
+        let kernel = ocl::Kernel::builder()
+            .name("outer")
+            .program(&self.cl_op)
+            .queue(cl_queue)
+            .global_work_size(left.len() * n)
+            .arg(m as u64)
+            .arg(n as u64)
+            .arg(left.as_ref())
+            .arg(right.as_ref())
+            .arg(&*output)
+            .build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? }
+
+        Ok(output)
+    }
+
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        let ndim = coord.len();
+        let batch = &coord[..ndim - 2];
+
+        let mut left_coord = batch.to_vec();
+        left_coord.push(coord[ndim - 2]);
+
+        let mut right_coord = batch.to_vec();
+        right_coord.push(coord[ndim - 1]);
+
+        let left = self.left.read_value(&left_coord)?;
+        let right = self.right.read_value(&right_coord)?;
+        Ok(left * right)
+    }
+}
+
+/// An [`Op`] to compute the Kronecker product of two matrices, or two batches of matrices
+///
+/// The Kronecker product of an `m x n` matrix `A` and a `p x q` matrix `B` is the
+/// `mp x nq` block matrix whose block at `(i, j)` is `A[i, j] B`.
+#[derive(Clone)]
+pub struct MatKron<T, L, R> {
+    left: L,
+    right: R,
+    shape: Shape,
+    dtype: PhantomData<T>,
+    #[cfg(feature = "opencl")]
+    cl_op: ocl::Program,
+}
+
+impl<T, L, R> MatKron<T, L, R>
+where
+    T: CDatatype,
+    L: NDArray<DType = T>,
+    R: NDArray<DType = T>,
+{
+    /// Initialize a new Kronecker product [`Op`].
+    pub fn new(left: L, right: R) -> Result<Self, Error> {
+        let [m, n] = matrix_dims(left.shape())?;
+        let [p, q] = matrix_dims(right.shape())?;
+
+        let batch = matrix_batch(left.shape());
+
+        if batch != matrix_batch(right.shape()) {
+            return Err(Error::Bounds(format!(
+                "the Kronecker product of {:?} and {:?} requires the same batch dimensions",
+                left.shape(),
+                right.shape()
+            )));
+        }
+
+        let mut shape = batch.to_vec();
+        shape.extend([m * p, n * q]);
+
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::kron::<T>(left.context())?;
+
+        Ok(Self {
+            left,
+            right,
+            shape,
+            dtype: PhantomData,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
+
+    /// Return the shape of the matrix or batch of matrices computed by this [`Op`].
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+}
+
+impl<T, L, R> Op for MatKron<T, L, R>
+where
+    T: CDatatype,
+    L: NDArrayRead<DType = T>,
+    R: NDArrayRead<DType = T>,
+{
+    type Out = T;
+
+    fn context(&self) -> &Context {
+        self.left.context()
+    }
+
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let [m, n] = matrix_dims(self.left.shape())?;
+        let [p, q] = matrix_dims(self.right.shape())?;
+
+        let left = self.left.to_host(queue)?;
+        let right = self.right.to_host(queue)?;
+
+        let mut output = queue.host_buffer_of(self.shape.iter().product(), T::zero())?;
+
+        output
+            .par_chunks_exact_mut(m * n * p * q)
+            .zip(left.as_ref().par_chunks_exact(m * n))
+            .zip(right.as_ref().par_chunks_exact(p * q))
+            .with_min_len(queue.min_len(m * n * p * q))
+            .for_each(|((output, left), right)| {
+                for (i, row) in output.chunks_exact_mut(n * q).enumerate() {
+                    let (left, right) = (&left[(i / p) * n..], &right[(i % p) * q..]);
+
+                    for (j, o) in row.iter_mut().enumerate() {
+                        *o = left[j / q] * right[j % q];
+                    }
+                }
+            });
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let [m, n] = matrix_dims(self.left.shape())?;
+        let [p, q] = matrix_dims(self.right.shape())?;
+
+        let left = self.left.to_cl_buffer(queue)?;
+        let right = self.right.to_cl_buffer(queue)?;
+        let cl_queue = left.as_ref().default_queue().expect("queue").clone();
+
+        let size = self.shape.iter().product::<usize>();
+
+        let output = queue.cl_buffer(cl_queue.clone(), size)?;
+
+        let kernel = ocl::Kernel::builder()
+            .name("kron")
+            .program(&self.cl_op)
+            .queue(cl_queue)
+            .global_work_size(size)
+            .arg(ocl::core::Ulong4::from([
+                m as u64, n as u64, p as u64, q as u64,
+            ]))
+            .arg(left.as_ref())
+            .arg(right.as_ref())
+            .arg(&*output)
+            .build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? }
+
+        Ok(output)
+    }
+
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        let [p, q] = matrix_dims(self.right.shape())?;
+        let ndim = coord.len();
+        let (i, j) = (coord[ndim - 2], coord[ndim - 1]);
+
+        let mut left_coord = coord[..ndim - 2].to_vec();
+        left_coord.extend([i / p, j / q]);
+
+        let mut right_coord = coord[..ndim - 2].to_vec();
+        right_coord.extend([i % p, j % q]);
+
+        let left = self.left.read_value(&left_coord)?;
+        let right = self.right.read_value(&right_coord)?;
+        Ok(left * right)
+    }
+}
+
+/// A matrix multiplication [`Op`]
+///
+/// As in NumPy, the batch dimensions of the operands are broadcast together,
+/// and a 1-dimensional `left` or `right` operand is treated as a row or column vector.
+/// A broadcast operand is not copied: each output matrix reads its operand matrices
+/// at offsets computed from the broadcast strides of their batch dimensions.
+#[derive(Clone)]
+pub struct MatMul<T, L, R> {
+    left: L,
+    right: R,
+    batch: Shape,
+    dtype: PhantomData<T>,
+    #[cfg(feature = "opencl")]
+    cl_op: ocl::Program,
+}
+
+impl<T, L, R> MatMul<T, L, R>
+where
+    T: CDatatype,
+    L: NDArray<DType = T>,
+    R: NDArray<DType = T>,
+{
+    /// Initialize a new matrix multiplication [`Op`].
+    pub fn new(left: L, right: R) -> Result<Self, Error> {
+        let invalid = || {
+            Error::Bounds(format!(
+                "invalid dimensions for matrix multiply: {:?} and {:?}",
+                left.shape(),
+                right.shape()
+            ))
+        };
+
+        if left.ndim() == 0 || right.ndim() == 0 {
+            return Err(invalid());
+        }
+
+        let b = left.shape()[left.ndim() - 1];
+        if right.shape()[right.ndim().saturating_sub(2)] != b {
+            return Err(invalid());
+        }
+
+        let batch = broadcast_batch(left.shape(), right.shape())?;
+
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::matmul::<T>(left.context())?;
+
+        Ok(Self {
+            left,
+            right,
+            batch,
+            dtype: PhantomData,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
+
+    /// The shape of the output of this [`Op`].
+    pub fn shape(&self) -> Shape {
+        let mut shape = self.batch.clone();
+
+        if self.left.ndim() > 1 {
+            shape.push(self.left.shape()[self.left.ndim() - 2]);
+        }
+
+        if self.right.ndim() > 1 {
+            shape.push(self.right.shape()[self.right.ndim() - 1]);
+        }
+
+        if shape.is_empty() {
+            vec![1]
+        } else {
+            shape
+        }
+    }
+
+    fn dims(&self) -> [usize; 4] {
+        let num_matrices = self.batch.iter().product();
+
+        let a = if self.left.ndim() > 1 {
+            self.left.shape()[self.left.ndim() - 2]
+        } else {
+            1
+        };
+
+        let b = *self.left.shape().last().expect("b");
+
+        let c = if self.right.ndim() > 1 {
+            *self.right.shape().last().expect("c")
+        } else {
+            1
+        };
+
+        debug_assert_eq!(
+            a * b * matrix_batch(self.left.shape()).iter().product::<usize>(),
+            self.left.size()
+        );
+
+        debug_assert_eq!(
+            b * c * matrix_batch(self.right.shape()).iter().product::<usize>(),
+            self.right.size()
+        );
+
+        [num_matrices, a, b, c]
+    }
+
+    fn batch_offsets(&self) -> (Vec<usize>, Vec<usize>) {
+        batch_offsets(&self.batch, self.left.shape(), self.right.shape())
+    }
+}
+
+#[cfg(feature = "blas")]
+impl<T, L, R> MatMul<T, L, R>
+where
+    T: CDatatype,
+    L: NDArrayRead<DType = T>,
+    R: NDArrayRead<DType = T>,
+{
+    // multiply each pair of matrices with BLAS, which reads a transposed view
+    // of either operand in place instead of reordering it
+    fn enqueue_blas(&self, queue: &Queue) -> Result<Pooled<Vec<T>>, Error> {
+        let [num_matrices, a, b, c] = self.dims();
+        let (left_offsets, right_offsets) = self.batch_offsets();
+
+        let ((left, left_t), (right, right_t)) = try_join(
+            || self.left.to_host_matrices(queue),
+            || self.right.to_host_matrices(queue),
+        )?;
+
+        let left = left.as_ref();
+        let right = right.as_ref();
+
+        let output_size = a * c;
+        let mut output = queue.host_buffer_of(num_matrices * output_size, T::zero())?;
+
+        for (output, (l, r)) in output
+            .chunks_exact_mut(output_size)
+            .zip(left_offsets.into_iter().zip(right_offsets))
+        {
+            let left = &left[l * a * b..(l + 1) * a * b];
+            let right = &right[r * b * c..(r + 1) * b * c];
+            blas::gemm(left, left_t, right, right_t, output, [a, b, c])?;
+        }
+
+        Ok(output)
+    }
+}
+
+impl<T, L, R> Op for MatMul<T, L, R>
+where
+    T: CDatatype,
+    L: NDArrayRead<DType = T> + NDArrayTransform,
+    R: NDArrayRead<DType = T> + NDArrayTransform,
+    L::Slice: NDArrayMath,
+{
+    type Out = T;
+
+    fn context(&self) -> &Context {
+        self.left.context()
+    }
+
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        #[cfg(feature = "blas")]
+        if blas::supports::<T>() {
+            return self.enqueue_blas(queue);
+        }
+
+        let [num_matrices, a, b, c] = self.dims();
+        let (left_offsets, right_offsets) = self.batch_offsets();
+
+        let (left, right) = try_join_read(&self.left, &self.right, queue)?;
+
+        let left = left.as_ref();
+        let right = right.as_ref();
+
+        let left_matrices = left.chunks_exact(a * b).collect::<Vec<_>>();
+        let right_matrices = right.chunks_exact(b * c).collect::<Vec<_>>();
+
+        let output_size = a * c;
+        let mut output = queue.host_buffer_of(num_matrices * output_size, T::zero())?;
+
+        output
+            .par_chunks_mut(output_size)
+            .zip(left_offsets.into_par_iter().zip(right_offsets))
+            .with_min_len(queue.min_len(output_size))
+            .for_each(|(output, (l, r))| {
+                T::gemm(left_matrices[l], right_matrices[r], output, [a, b, c])
+            });
+
+        debug_assert_eq!(output.len(), num_matrices * output_size);
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        use crate::div_ceil;
+        use cl_programs::TILE_SIZE;
+
+        let [num_matrices, a, b, c] = self.dims();
+        let (left_offsets, right_offsets) = self.batch_offsets();
+
+        let right_queue = queue.split(self.right.size())?;
+        let right = self.right.to_cl_buffer(&right_queue)?;
+        let left = self.left.to_cl_buffer(queue)?;
+
+        let cl_queue = left.as_ref().default_queue().expect("left queue");
+
+        assert!(num_matrices > 0);
+        assert_eq!(left.len() % (a * b), 0);
+        assert_eq!(right.len() % (b * c), 0);
+
+        let dims = [a as u64, b as u64, c as u64, num_matrices as u64];
+
+        let offsets = |offsets: Vec<usize>| {
+            let offsets = offsets.into_iter().map(|i| i as u64).collect::<Vec<_>>();
+            cl_buffer_from(queue, cl_queue.clone(), &offsets)
+        };
+
+        let left_offsets = offsets(left_offsets)?;
+        let right_offsets = offsets(right_offsets)?;
+
+        let output = queue.cl_buffer(cl_queue.clone(), a * c * num_matrices)?;
+
+        let kernel = ocl::Kernel::builder()
+            .name("matmul")
+            .program(&self.cl_op)
+            .queue(cl_queue.clone())
+            .global_work_size((num_matrices, div_ceil(a, TILE_SIZE), div_ceil(c, TILE_SIZE)))
+            .arg(ocl::core::Ulong4::from(dims))
+            .arg(div_ceil(b, TILE_SIZE))
+            .arg(left.as_ref())
+            .arg(&*left_offsets)
+            .arg(right.as_ref())
+            .arg(&*right_offsets)
+            .arg(&*output)
+            .build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? }
+
+        Ok(output)
+    }
+
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        let shape = self.shape();
+
+        if coord.len() != shape.len() || coord.iter().zip(&shape).any(|(i, dim)| i >= dim) {
+            return Err(Error::Bounds(format!(
+                "matrix product with shape {shape:?} does not contain {coord:?}"
+            )));
+        }
+
+        let batch = &coord[..self.batch.len()];
+        let mut matrix = &coord[self.batch.len()..];
+
+        // each operand coordinate ends with the index on the reduce axis, which is updated below
+        let mut left_coord = operand_batch(batch, matrix_batch(self.left.shape()));
+        if self.left.ndim() > 1 {
+            left_coord.push(matrix[0]);
+            matrix = &matrix[1..];
+        }
+        left_coord.push(0);
+
+        let mut right_coord = operand_batch(batch, matrix_batch(self.right.shape()));
+        right_coord.push(0);
+        if self.right.ndim() > 1 {
+            right_coord.push(matrix[0]);
+        }
+
+        let b = *self.left.shape().last().expect("b");
+        let j_left = self.left.ndim() - 1;
+        let j_right = matrix_batch(self.right.shape()).len();
+
+        let mut sum = T::zero();
+
+        for j in 0..b {
+            left_coord[j_left] = j;
+            right_coord[j_right] = j;
+
+            let left = self.left.read_value(&left_coord)?;
+            let right = self.right.read_value(&right_coord)?;
+            sum = sum + (left * right);
+        }
+
+        Ok(sum)
+    }
+}
+
+// linear algebra
+
+/// The factor of an LU decomposition `A = P L U` read by a [`MatLU`] [`Op`]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LUFactor {
+    /// The permutation matrix `P`
+    Permutation,
+    /// The unit lower triangular matrix `L`
+    Lower,
+    /// The upper triangular matrix `U`
+    Upper,
+}
+
+/// An [`Op`] to read one factor of the LU decomposition with partial pivoting `A = P L U`
+/// of a square matrix or batch of square matrices
+#[derive(Clone)]
+pub struct MatLU<T, A> {
+    source: A,
+    factor: LUFactor,
+    dtype: PhantomData<T>,
+    #[cfg(feature = "opencl")]
+    cl_op: ocl::Program,
+}
+
+impl<T: Float, A: NDArray<DType = T>> MatLU<T, A> {
+    /// Initialize a new [`Op`] to read the given `factor` of an LU decomposition.
+    pub fn new(source: A, factor: LUFactor) -> Result<Self, Error> {
+        check_square(source.shape())?;
+
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::lu::<T>(source.context())?;
+
+        Ok(Self {
+            source,
+            factor,
+            dtype: PhantomData,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
+}
+
+impl<T: Float, A: NDArrayRead<DType = T>> Op for MatLU<T, A> {
+    type Out = T;
+
+    fn context(&self) -> &Context {
+        self.source.context()
+    }
+
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let n = *self.source.shape().last().expect("n");
+        let input = self.source.to_host(queue)?;
+        let mut output = queue.host_buffer_of(self.source.size(), T::zero())?;
+
+        output
+            .par_chunks_mut(n * n)
+            .zip(input.as_ref().par_chunks_exact(n * n))
+            .with_min_len(queue.min_len(n * n))
+            .for_each(|(output, matrix)| LU::factor(matrix, n).read(self.factor, output));
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let n = *self.source.shape().last().expect("n");
+        let input = self.source.to_cl_buffer(queue)?;
+        let lu = lu_cl(&self.cl_op, queue, input.as_ref(), n)?;
+        let cl_queue = input.as_ref().default_queue().expect("queue").clone();
+
+        let output = queue.cl_buffer(cl_queue.clone(), input.len())?;
+
+        let factor: u8 = match self.factor {
+            LUFactor::Permutation => 0,
+            LUFactor::Lower => 1,
+            LUFactor::Upper => 2,
+        };
+
+        let kernel = ocl::Kernel::builder()
+            .name("lu_factor")
+            .program(&self.cl_op)
+            .queue(cl_queue)
+            .global_work_size((input.len() / (n * n), n, n))
+            .arg(n as u64)
+            .arg(factor)
+            .arg(&*lu.factors)
+            .arg(&*lu.pivots)
+            .arg(&*output)
+            .build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? }
+
+        Ok(output)
+    }
+
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        let ndim = coord.len();
+        let n = self.source.shape()[ndim - 1];
+        let matrix = read_matrix(&self.source, &coord[..ndim - 2])?;
+
+        let mut output = vec![T::zero(); n * n];
+        LU::factor(&matrix, n).read(self.factor, &mut output);
+        Ok(output[(coord[ndim - 2] * n) + coord[ndim - 1]])
+    }
+}
+
+/// The part of a determinant read by a [`MatDet`] [`Op`]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DetPart {
+    /// The determinant itself
+    Det,
+    /// The sign of the determinant, which is zero if the matrix is singular
+    Sign,
+    /// The natural log of the absolute value of the determinant
+    LogAbsDet,
+}
+
+/// An [`Op`] to compute the determinant of a square matrix or batch of square matrices
+#[derive(Clone)]
+pub struct MatDet<T, A> {
+    source: A,
+    part: DetPart,
+    dtype: PhantomData<T>,
+    #[cfg(feature = "opencl")]
+    cl_op: ocl::Program,
+}
+
+impl<T: Float, A: NDArray<DType = T>> MatDet<T, A> {
+    /// Initialize a new [`Op`] to read the given `part` of a determinant.
+    pub fn new(source: A, part: DetPart) -> Result<Self, Error> {
+        check_square(source.shape())?;
+
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::lu::<T>(source.context())?;
+
+        Ok(Self {
+            source,
+            part,
+            dtype: PhantomData,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
+}
+
+impl<T: Float, A: NDArrayRead<DType = T>> Op for MatDet<T, A> {
+    type Out = T;
+
+    fn context(&self) -> &Context {
+        self.source.context()
+    }
+
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let n = *self.source.shape().last().expect("n");
+        let input = self.source.to_host(queue)?;
+
+        let mut output = queue.host_buffer(input.len() / (n * n))?;
+
+        input
+            .as_ref()
+            .par_chunks_exact(n * n)
+            .with_min_len(queue.min_len(n * n))
+            .map(|matrix| LU::factor(matrix, n).det(self.part))
+            .collect_into_vec(&mut output);
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let n = *self.source.shape().last().expect("n");
+        let input = self.source.to_cl_buffer(queue)?;
+        let lu = lu_cl(&self.cl_op, queue, input.as_ref(), n)?;
+        let cl_queue = input.as_ref().default_queue().expect("queue").clone();
+        let num_matrices = input.len() / (n * n);
+
+        let output = queue.cl_buffer(cl_queue.clone(), num_matrices)?;
+
+        let part: u8 = match self.part {
+            DetPart::Det => 0,
+            DetPart::Sign => 1,
+            DetPart::LogAbsDet => 2,
+        };
+
+        let kernel = ocl::Kernel::builder()
+            .name("lu_det")
+            .program(&self.cl_op)
+            .queue(cl_queue)
+            .global_work_size(num_matrices)
+            .arg(n as u64)
+            .arg(part)
+            .arg(&*lu.factors)
+            .arg(&*lu.parity)
+            .arg(&*output)
+            .build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? }
+
+        Ok(output)
+    }
+
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        let n = *self.source.shape().last().expect("n");
+        let batch = &coord[..self.source.ndim() - 2];
+        let matrix = read_matrix(&self.source, batch)?;
+        Ok(LU::factor(&matrix, n).det(self.part))
+    }
+}
+
+/// An [`Op`] to compute the inverse of a square matrix or batch of square matrices
+#[derive(Clone)]
+pub struct MatInverse<T, A> {
+    source: A,
+    dtype: PhantomData<T>,
+    #[cfg(feature = "opencl")]
+    cl_op: ocl::Program,
+}
+
+impl<T: Float, A: NDArray<DType = T>> MatInverse<T, A> {
+    /// Initialize a new matrix inverse [`Op`].
+    pub fn new(source: A) -> Result<Self, Error> {
+        check_square(source.shape())?;
+
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::lu::<T>(source.context())?;
+
+        Ok(Self {
+            source,
+            dtype: PhantomData,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
+}
+
+impl<T: Float, A: NDArrayRead<DType = T>> Op for MatInverse<T, A> {
+    type Out = T;
+
+    fn context(&self) -> &Context {
+        self.source.context()
+    }
+
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let n = *self.source.shape().last().expect("n");
+        let input = self.source.to_host(queue)?;
+
+        let factors = input
+            .as_ref()
+            .par_chunks_exact(n * n)
+            .with_min_len(queue.min_len(n * n))
+            .map(|matrix| LU::factor(matrix, n))
+            .collect::<Vec<_>>();
+
+        if factors.iter().any(|lu| lu.singular) {
+            return Err(singular(self.source.shape()));
+        }
+
+        let mut output = queue.host_buffer_of(self.source.size(), T::zero())?;
+
+        output
+            .par_chunks_mut(n * n)
+            .zip(factors)
+            .with_min_len(queue.min_len(n * n))
+            .for_each(|(output, lu)| lu.solve(n, identity, output));
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let n = *self.source.shape().last().expect("n");
+        let input = self.source.to_cl_buffer(queue)?;
+        let lu = lu_cl(&self.cl_op, queue, input.as_ref(), n)?;
+        check_singular(&lu.singular, self.source.shape())?;
+
+        let cl_queue = input.as_ref().default_queue().expect("queue").clone();
+
+        let output = queue.cl_buffer(cl_queue.clone(), input.len())?;
+
+        let kernel = ocl::Kernel::builder()
+            .name("lu_inverse")
+            .program(&self.cl_op)
+            .queue(cl_queue)
+            .global_work_size((input.len() / (n * n), n))
+            .arg(n as u64)
+            .arg(&*lu.factors)
+            .arg(&*lu.pivots)
+            .arg(&*output)
+            .build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? }
+
+        Ok(output)
+    }
+
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        let ndim = coord.len();
+        let n = self.source.shape()[ndim - 1];
+        let matrix = read_matrix(&self.source, &coord[..ndim - 2])?;
+
+        let lu = LU::factor(&matrix, n);
+        if lu.singular {
+            return Err(singular(self.source.shape()));
+        }
+
+        let mut output = vec![T::zero(); n * n];
+        lu.solve(n, identity, &mut output);
+        Ok(output[(coord[ndim - 2] * n) + coord[ndim - 1]])
+    }
+}
+
+/// An [`Op`] to solve the linear system `A X = B` for `X`, given a square matrix `A`
+/// and a matrix or vector `B`, or a batch of them
+///
+/// As in [`MatMul`], the batch dimensions of `A` and `B` are broadcast together.
+#[derive(Clone)]
+pub struct MatSolve<T, L, R> {
+    left: L,
+    right: R,
+    batch: Shape,
+    dtype: PhantomData<T>,
+    #[cfg(feature = "opencl")]
+    cl_op: ocl::Program,
+}
+
+impl<T, L, R> MatSolve<T, L, R>
+where
+    T: Float,
+    L: NDArray<DType = T>,
+    R: NDArray<DType = T>,
+{
+    /// Initialize a new linear solve [`Op`].
+    pub fn new(left: L, right: R) -> Result<Self, Error> {
+        check_square(left.shape())?;
+
+        let n = left.shape()[left.ndim() - 1];
+        if right.ndim() == 0 || right.shape()[right.ndim().saturating_sub(2)] != n {
+            return Err(Error::Bounds(format!(
+                "cannot solve a linear system with shapes {:?} and {:?}",
+                left.shape(),
+                right.shape()
+            )));
+        }
+
+        let batch = broadcast_batch(left.shape(), right.shape())?;
+
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::lu::<T>(left.context())?;
+
+        Ok(Self {
+            left,
+            right,
+            batch,
+            dtype: PhantomData,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
+
+    /// The shape of the output of this [`Op`].
+    pub fn shape(&self) -> Shape {
+        let mut shape = self.batch.clone();
+        shape.extend_from_slice(&self.right.shape()[matrix_batch(self.right.shape()).len()..]);
+        shape
+    }
+
+    fn dims(&self) -> [usize; 2] {
+        let n = *self.left.shape().last().expect("n");
+
+        let k = if self.right.ndim() > 1 {
+            *self.right.shape().last().expect("k")
+        } else {
+            1
+        };
+
+        [n, k]
+    }
+}
+
+impl<T, L, R> Op for MatSolve<T, L, R>
+where
+    T: Float,
+    L: NDArrayRead<DType = T>,
+    R: NDArrayRead<DType = T>,
+{
+    type Out = T;
+
+    fn context(&self) -> &Context {
+        self.left.context()
+    }
+
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let [n, k] = self.dims();
+        let (left_offsets, right_offsets) =
+            batch_offsets(&self.batch, self.left.shape(), self.right.shape());
+
+        let (left, right) = try_join_read(&self.left, &self.right, queue)?;
+        let right = right.as_ref();
+
+        // factor each distinct left matrix once, even if it's broadcast
+        let factors = left
+            .as_ref()
+            .par_chunks_exact(n * n)
+            .with_min_len(queue.min_len(n * n))
+            .map(|matrix| LU::factor(matrix, n))
+            .collect::<Vec<_>>();
+
+        if factors.iter().any(|lu| lu.singular) {
+            return Err(singular(self.left.shape()));
+        }
+
+        let mut output = queue.host_buffer_of(left_offsets.len() * n * k, T::zero())?;
+
+        output
+            .par_chunks_mut(n * k)
+            .zip(left_offsets.into_par_iter().zip(right_offsets))
+            .with_min_len(queue.min_len(n * k))
+            .for_each(|(output, (l, r))| {
+                let right = &right[r * n * k..(r + 1) * n * k];
+                factors[l].solve(k, |i, j| right[(i * k) + j], output)
+            });
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let [n, k] = self.dims();
+        let (left_offsets, right_offsets) =
+            batch_offsets(&self.batch, self.left.shape(), self.right.shape());
+
+        let right_queue = queue.split(self.right.size())?;
+        let right = self.right.to_cl_buffer(&right_queue)?;
+        let left = self.left.to_cl_buffer(queue)?;
+
+        let lu = lu_cl(&self.cl_op, queue, left.as_ref(), n)?;
+        check_singular(&lu.singular, self.left.shape())?;
+
+        let cl_queue = left.as_ref().default_queue().expect("queue").clone();
+
+        let offsets = |offsets: Vec<usize>| {
+            let offsets = offsets.into_iter().map(|i| i as u64).collect::<Vec<_>>();
+            cl_buffer_from(queue, cl_queue.clone(), &offsets)
+        };
+
+        let num_matrices = left_offsets.len();
+        let left_offsets = offsets(left_offsets)?;
+        let right_offsets = offsets(right_offsets)?;
+
+        let output = queue.cl_buffer(cl_queue.clone(), num_matrices * n * k)?;
+
+        let kernel = ocl::Kernel::builder()
+            .name("lu_solve")
+            .program(&self.cl_op)
+            .queue(cl_queue)
+            .global_work_size((num_matrices, k))
+            .arg(n as u64)
+            .arg(&*lu.factors)
+            .arg(&*lu.pivots)
+            .arg(k as u64)
+            .arg(&*left_offsets)
+            .arg(right.as_ref())
+            .arg(&*right_offsets)
+            .arg(&*output)
+            .build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? }
+
+        Ok(output)
+    }
+
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        let [n, k] = self.dims();
+        let batch = &coord[..self.batch.len()];
+
+        let left = read_matrix(
+            &self.left,
+            &operand_batch(batch, matrix_batch(self.left.shape())),
+        )?;
+
+        let lu = LU::factor(&left, n);
+        if lu.singular {
+            return Err(singular(self.left.shape()));
+        }
+
+        let right = if self.right.ndim() > 1 {
+            read_matrix(
+                &self.right,
+                &operand_batch(batch, matrix_batch(self.right.shape())),
+            )?
+        } else {
+            (0..n)
+                .map(|i| self.right.read_value(&[i]))
+                .collect::<Result<Vec<_>, Error>>()?
+        };
+
+        let mut output = vec![T::zero(); n * k];
+        lu.solve(k, |i, j| right[(i * k) + j], &mut output);
+
+        let i = coord[self.batch.len()];
+        let j = if self.right.ndim() > 1 {
+            coord[self.batch.len() + 1]
+        } else {
+            0
+        };
+
+        Ok(output[(i * k) + j])
+    }
+}
+
+// the minimum number of values to update in parallel during an LU decomposition
+const LU_PARALLEL_SIZE: usize = 1 << 14;
+
+// the LU decomposition with partial pivoting of a single `n x n` matrix
+struct LU<T> {
+    n: usize,
+    // L below the diagonal (with an implicit unit diagonal) and U on and above it
+    factors: Vec<T>,
+    // the row of the source matrix at each row of the factors
+    pivots: Vec<usize>,
+    odd: bool,
+    singular: bool,
+}
+
+impl<T: Float> LU<T> {
+    fn factor(matrix: &[T], n: usize) -> Self {
+        debug_assert_eq!(matrix.len(), n * n);
+
+        let mut factors = matrix.to_vec();
+        let mut pivots = (0..n).collect::<Vec<_>>();
+        let mut odd = false;
+        let mut singular = false;
+
+        for k in 0..n {
+            let (p, max) = ((k + 1)..n).fold((k, factors[(k * n) + k].abs()), |(p, max), i| {
+                let value = factors[(i * n) + k].abs();
+                if value > max {
+                    (i, value)
+                } else {
+                    (p, max)
+                }
+            });
+
+            if max == T::zero() {
+                singular = true;
+                continue;
+            }
+
+            if p != k {
+                for j in 0..n {
+                    factors.swap((k * n) + j, (p * n) + j);
+                }
+
+                pivots.swap(k, p);
+                odd = !odd;
+            }
+
+            let (pivot_rows, rows) = factors.split_at_mut((k + 1) * n);
+            let pivot_row = &pivot_rows[k * n..];
+            let pivot = pivot_row[k];
+
+            let eliminate = |row: &mut [T]| {
+                let l = row[k] / pivot;
+                row[k] = l;
+
+                for (x, u) in row[k + 1..].iter_mut().zip(&pivot_row[k + 1..]) {
+                    *x = *x - (l * *u);
+                }
+            };
+
+            if rows.len() * (n - k) >= LU_PARALLEL_SIZE {
+                rows.par_chunks_mut(n).for_each(eliminate);
+            } else {
+                rows.chunks_mut(n).for_each(eliminate);
+            }
+        }
+
+        Self {
+            n,
+            factors,
+            pivots,
+            odd,
+            singular,
+        }
+    }
+
+    fn read(&self, factor: LUFactor, output: &mut [T]) {
+        let n = self.n;
+
+        for (offset, x) in output.iter_mut().enumerate() {
+            let (i, j) = (offset / n, offset % n);
+
+            *x = match factor {
+                LUFactor::Permutation if self.pivots[j] == i => T::one(),
+                LUFactor::Lower if i == j => T::one(),
+                LUFactor::Lower if i > j => self.factors[offset],
+                LUFactor::Upper if i <= j => self.factors[offset],
+                _ => T::zero(),
+            };
+        }
+    }
+
+    fn det(&self, part: DetPart) -> T {
+        let n = self.n;
+        let mut det = if self.odd {
+            T::zero() - T::one()
+        } else {
+            T::one()
+        };
+
+        let mut sign = det;
+        let mut logabs = T::zero();
+
+        for i in 0..n {
+            let u = self.factors[(i * n) + i];
+            det = det * u;
+            logabs = logabs + u.abs().ln();
+
+            if u < T::zero() {
+                sign = T::zero() - sign;
+            } else if u == T::zero() {
+                sign = T::zero();
+            }
+        }
+
+        match part {
+            DetPart::Det => det,
+            DetPart::Sign => sign,
+            DetPart::LogAbsDet => logabs,
+        }
+    }
+
+    // solve `A X = B` for the `n x k` matrix `X`, where `rhs(i, j)` reads `B[i, j]`
+    fn solve<B: Fn(usize, usize) -> T>(&self, k: usize, rhs: B, output: &mut [T]) {
+        debug_assert!(!self.singular);
+        debug_assert_eq!(output.len(), self.n * k);
+
+        let n = self.n;
+
+        for (i, row) in output.chunks_exact_mut(k).enumerate() {
+            for (j, x) in row.iter_mut().enumerate() {
+                *x = rhs(self.pivots[i], j);
+            }
+        }
+
+        // forward substitution with the unit lower triangular factor
+        for i in 1..n {
+            let (solved, rows) = output.split_at_mut(i * k);
+
+            for (m, solved) in solved.chunks_exact(k).enumerate() {
+                let l = self.factors[(i * n) + m];
+
+                for (x, y) in rows[..k].iter_mut().zip(solved) {
+                    *x = *x - (l * *y);
+                }
+            }
+        }
+
+        // back substitution with the upper triangular factor
+        for i in (0..n).rev() {
+            let (rows, solved) = output.split_at_mut((i + 1) * k);
+            let row = &mut rows[i * k..];
+
+            for (m, solved) in solved.chunks_exact(k).enumerate() {
+                let u = self.factors[(i * n) + i + 1 + m];
+
+                for (x, y) in row.iter_mut().zip(solved) {
+                    *x = *x - (u * *y);
+                }
+            }
+
+            let u = self.factors[(i * n) + i];
+            row.iter_mut().for_each(|x| *x = *x / u);
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+struct LUBuffers<T: CDatatype> {
+    factors: Pooled<ocl::Buffer<T>>,
+    pivots: Pooled<ocl::Buffer<u64>>,
+    parity: Pooled<ocl::Buffer<u8>>,
+    singular: Pooled<ocl::Buffer<u8>>,
+}
+
+// factor each `n x n` matrix of the `input` on the device
+#[cfg(feature = "opencl")]
+fn lu_cl<T: CDatatype>(
+    program: &ocl::Program,
+    queue: &Queue,
+    input: &ocl::Buffer<T>,
+    n: usize,
+) -> Result<LUBuffers<T>, Error> {
+    let cl_queue = input.default_queue().expect("queue").clone();
+    let num_matrices = input.len() / (n * n);
+
+    let factors = queue.cl_buffer(cl_queue.clone(), input.len())?;
+    let pivots = queue.cl_buffer(cl_queue.clone(), num_matrices * n)?;
+    let parity = queue.cl_buffer(cl_queue.clone(), num_matrices)?;
+    let singular = cl_buffer_of(queue, cl_queue.clone(), 1, 0u8)?;
+
+    let kernel = ocl::Kernel::builder()
+        .name("lu")
+        .program(program)
+        .queue(cl_queue)
+        .global_work_size(num_matrices)
+        .arg(n as u64)
+        .arg(input)
+        .arg(&*factors)
+        .arg(&*pivots)
+        .arg(&*parity)
+        .arg(&*singular)
+        .build()?;
+
+    unsafe { queue.enqueue_kernel(&kernel)? }
+
+    Ok(LUBuffers {
+        factors,
+        pivots,
+        parity,
+        singular,
+    })
+}
+
+#[inline]
+#[cfg(feature = "opencl")]
+fn check_singular(flag: &ocl::Buffer<u8>, shape: &[usize]) -> Result<(), Error> {
+    let mut singular = [0u8];
+    flag.read(&mut singular[..]).enq()?;
+
+    if singular[0] == 0 {
+        Ok(())
+    } else {
+        Err(self::singular(shape))
+    }
+}
+
+fn check_square(shape: &[usize]) -> Result<(), Error> {
+    let ndim = shape.len();
+
+    if ndim >= 2 && shape[ndim - 1] == shape[ndim - 2] && shape[ndim - 1] > 0 {
+        Ok(())
+    } else {
+        Err(Error::Bounds(format!(
+            "expected a square matrix or batch of square matrices, not {shape:?}"
+        )))
+    }
+}
+
+#[inline]
+fn identity<T: CDatatype>(i: usize, j: usize) -> T {
+    if i == j {
+        T::one()
+    } else {
+        T::zero()
+    }
+}
+
+// the batch coordinate of an operand broadcast to the given output `batch` coordinate
+fn operand_batch(batch: &[usize], operand: &[usize]) -> Vec<usize> {
+    let offset = batch.len() - operand.len();
+
+    operand
+        .iter()
+        .zip(&batch[offset..])
+        .map(|(dim, i)| if *dim == 1 { 0 } else { *i })
+        .collect()
+}
+
+// read the matrix at the given `batch` coordinate of the `source`
+fn read_matrix<A: NDArrayRead>(source: &A, batch: &[usize]) -> Result<Vec<A::DType>, Error> {
+    let ndim = source.ndim();
+    let [m, n] = [source.shape()[ndim - 2], source.shape()[ndim - 1]];
+
+    (0..m * n)
+        .map(|offset| {
+            let mut coord = batch.to_vec();
+            coord.push(offset / n);
+            coord.push(offset % n);
+            source.read_value(&coord)
+        })
+        .collect()
+}
+
+fn singular(shape: &[usize]) -> Error {
+    Error::Singular(format!("a matrix in {shape:?} is singular"))
+}
+
+// matrix decompositions
+
+// compute one factor of the decomposition of a single matrix with the given dimensions
+type Decompose = fn(&[f64], [usize; 2], &mut [f64]) -> Result<(), Error>;
+
+/// An [`Op`] to read one factor of a decomposition of a matrix or batch of matrices,
+/// like a QR decomposition or a singular value decomposition
+///
+/// Decompositions are computed in double precision on the host.
+/// Each factor computes the whole decomposition of every matrix when it's read,
+/// and [`Op::read_value`] computes the whole decomposition of the matrix at the given coordinate,
+/// so to read every element, read the whole factor rather than each element.
+#[derive(Clone)]
+pub struct MatDecomposition<T, A> {
+    source: A,
+    shape: Shape,
+    cpu_op: Decompose,
+    dtype: PhantomData<T>,
+}
+
+impl<T: Float, A: NDArray<DType = T>> MatDecomposition<T, A> {
+    fn new(source: A, matrix_shape: &[usize], cpu_op: Decompose) -> Result<Self, Error> {
+        let mut shape = matrix_batch(source.shape()).to_vec();
+        shape.extend_from_slice(matrix_shape);
+
+        Ok(Self {
+            source,
+            shape,
+            cpu_op,
+            dtype: PhantomData,
+        })
+    }
+
+    /// Initialize a new [`Op`] to read the lower triangular Cholesky factor `L`
+    /// of a symmetric positive-definite matrix `A = L L^T`, using only its lower triangle.
+    pub fn cholesky(source: A) -> Result<Self, Error> {
+        check_square(source.shape())?;
+        let n = source.shape()[source.ndim() - 1];
+        Self::new(source, &[n, n], cholesky)
+    }
+
+    /// Initialize a new [`Op`] to read the eigenvalues, in ascending order,
+    /// of a symmetric matrix, using only its lower triangle.
+    pub fn eigenvalues(source: A) -> Result<Self, Error> {
+        check_square(source.shape())?;
+        let n = source.shape()[source.ndim() - 1];
+        Self::new(source, &[n], eigenvalues)
+    }
+
+    /// Initialize a new [`Op`] to read the eigenvectors (as columns) of a symmetric matrix,
+    /// using only its lower triangle.
+    pub fn eigenvectors(source: A) -> Result<Self, Error> {
+        check_square(source.shape())?;
+        let n = source.shape()[source.ndim() - 1];
+        Self::new(source, &[n, n], eigenvectors)
+    }
+
+    /// Initialize a new [`Op`] to read the `m x k` orthonormal factor `Q`
+    /// of the reduced QR decomposition of an `m x n` matrix, where `k = min(m, n)`.
+    pub fn qr_q(source: A) -> Result<Self, Error> {
+        let [m, n] = matrix_dims(source.shape())?;
+        Self::new(source, &[m, m.min(n)], qr_q)
+    }
+
+    /// Initialize a new [`Op`] to read the `k x n` upper triangular factor `R`
+    /// of the reduced QR decomposition of an `m x n` matrix, where `k = min(m, n)`.
+    pub fn qr_r(source: A) -> Result<Self, Error> {
+        let [m, n] = matrix_dims(source.shape())?;
+        Self::new(source, &[m.min(n), n], qr_r)
+    }
+
+    /// Initialize a new [`Op`] to read the left singular vectors `U` (as columns)
+    /// of an `m x n` matrix, which are `m x m` if `full_matrices` is `true`
+    /// and `m x min(m, n)` otherwise.
+    pub fn svd_u(source: A, full_matrices: bool) -> Result<Self, Error> {
+        let [m, n] = matrix_dims(source.shape())?;
+
+        if full_matrices {
+            Self::new(source, &[m, m], svd_u_full)
+        } else {
+            Self::new(source, &[m, m.min(n)], svd_u)
+        }
+    }
+
+    /// Initialize a new [`Op`] to read the `min(m, n)` singular values,
+    /// in descending order, of an `m x n` matrix.
+    pub fn svd_s(source: A) -> Result<Self, Error> {
+        let [m, n] = matrix_dims(source.shape())?;
+        Self::new(source, &[m.min(n)], svd_s)
+    }
+
+    /// Initialize a new [`Op`] to read the right singular vectors `V^T` (as rows)
+    /// of an `m x n` matrix, which are `n x n` if `full_matrices` is `true`
+    /// and `min(m, n) x n` otherwise.
+    pub fn svd_vh(source: A, full_matrices: bool) -> Result<Self, Error> {
+        let [m, n] = matrix_dims(source.shape())?;
+
+        if full_matrices {
+            Self::new(source, &[n, n], svd_vh_full)
+        } else {
+            Self::new(source, &[m.min(n), n], svd_vh)
+        }
+    }
+
+    /// The shape of the output of this [`Op`].
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    fn dims(&self) -> [usize; 2] {
+        let ndim = self.source.ndim();
+        [self.source.shape()[ndim - 2], self.source.shape()[ndim - 1]]
+    }
+
+    fn matrix_size(&self) -> usize {
+        self.shape[matrix_batch(self.source.shape()).len()..]
+            .iter()
+            .product()
+    }
+}
+
+impl<T: Float, A: NDArrayRead<DType = T>> Op for MatDecomposition<T, A> {
+    type Out = T;
+
+    fn context(&self) -> &Context {
+        self.source.context()
+    }
+
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let [m, n] = self.dims();
+        let matrix_size = self.matrix_size();
+        let input = self.source.to_host(queue)?;
+        let mut output = queue.host_buffer_of(self.shape.iter().product(), T::zero())?;
+
+        output
+            .par_chunks_mut(matrix_size)
+            .zip(input.as_ref().par_chunks_exact(m * n))
+            .with_min_len(queue.min_len(matrix_size))
+            .map(|(output, matrix)| {
+                let matrix = matrix.iter().copied().map(T::to_f64).collect::<Vec<_>>();
+                let mut factor = vec![0.; matrix_size];
+                (self.cpu_op)(&matrix, [m, n], &mut factor)?;
+
+                for (x, f) in output.iter_mut().zip(factor) {
+                    *x = T::from_f64(f);
+                }
+
+                Ok(())
+            })
+            .collect::<Result<(), Error>>()?;
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        enqueue_on_host(self, queue)
+    }
+
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        let [m, n] = self.dims();
+        let batch = &coord[..matrix_batch(self.source.shape()).len()];
+        let matrix = read_matrix(&self.source, batch)?;
+        let matrix = matrix.into_iter().map(T::to_f64).collect::<Vec<_>>();
+
+        let mut factor = vec![0.; self.matrix_size()];
+        (self.cpu_op)(&matrix, [m, n], &mut factor)?;
+
+        let matrix_coord = &coord[batch.len()..];
+        let matrix_shape = &self.shape[batch.len()..];
+        Ok(T::from_f64(factor[offset_of(matrix_coord, matrix_shape)]))
+    }
+}
+
+// the maximum number of sweeps of a Jacobi eigenvalue or singular value algorithm
+const JACOBI_SWEEPS: usize = 100;
+
+fn cholesky(a: &[f64], [n, _n]: [usize; 2], l: &mut [f64]) -> Result<(), Error> {
+    for j in 0..n {
+        let sum = (0..j).map(|k| l[(j * n) + k] * l[(j * n) + k]).sum::<f64>();
+        let diagonal = a[(j * n) + j] - sum;
+
+        if diagonal.is_nan() || diagonal <= 0. {
+            return Err(Error::Singular(
+                "the Cholesky decomposition requires a positive-definite matrix".to_string(),
+            ));
+        }
+
+        let diagonal = diagonal.sqrt();
+        l[(j * n) + j] = diagonal;
+
+        for i in (j + 1)..n {
+            let sum = (0..j).map(|k| l[(i * n) + k] * l[(j * n) + k]).sum::<f64>();
+            l[(i * n) + j] = (a[(i * n) + j] - sum) / diagonal;
+        }
+    }
+
+    Ok(())
+}
+
+fn eigenvalues(a: &[f64], [n, _n]: [usize; 2], output: &mut [f64]) -> Result<(), Error> {
+    let (values, _vectors) = eigh(a, n);
+    output.copy_from_slice(&values);
+    Ok(())
+}
+
+fn eigenvectors(a: &[f64], [n, _n]: [usize; 2], output: &mut [f64]) -> Result<(), Error> {
+    let (_values, vectors) = eigh(a, n);
+    write_columns(&vectors, output);
+    Ok(())
+}
+
+fn qr_q(a: &[f64], [m, n]: [usize; 2], output: &mut [f64]) -> Result<(), Error> {
+    let (q, _r) = qr(a, m, n);
+    write_columns(&q, output);
+    Ok(())
+}
+
+fn qr_r(a: &[f64], [m, n]: [usize; 2], output: &mut [f64]) -> Result<(), Error> {
+    let (_q, r) = qr(a, m, n);
+    output.copy_from_slice(&r);
+    Ok(())
+}
+
+fn svd_u(a: &[f64], [m, n]: [usize; 2], output: &mut [f64]) -> Result<(), Error> {
+    let (u, _s, _v) = svd(a, m, n);
+    write_columns(&complete_basis(u, m, m.min(n)), output);
+    Ok(())
+}
+
+fn svd_u_full(a: &[f64], [m, n]: [usize; 2], output: &mut [f64]) -> Result<(), Error> {
+    let (u, _s, _v) = svd(a, m, n);
+    write_columns(&complete_basis(u, m, m), output);
+    Ok(())
+}
+
+fn svd_s(a: &[f64], [m, n]: [usize; 2], output: &mut [f64]) -> Result<(), Error> {
+    let (_u, s, _v) = svd(a, m, n);
+    output.copy_from_slice(&s);
+    Ok(())
+}
+
+fn svd_vh(a: &[f64], [m, n]: [usize; 2], output: &mut [f64]) -> Result<(), Error> {
+    let (_u, _s, v) = svd(a, m, n);
+    write_rows(&complete_basis(v, n, m.min(n)), output);
+    Ok(())
+}
+
+fn svd_vh_full(a: &[f64], [m, n]: [usize; 2], output: &mut [f64]) -> Result<(), Error> {
+    let (_u, _s, v) = svd(a, m, n);
+    write_rows(&complete_basis(v, n, n), output);
+    Ok(())
+}
+
+// the eigenvalues, in ascending order, and the eigenvectors of the symmetric `n x n` matrix
+// whose lower triangle is given, computed with the cyclic Jacobi eigenvalue algorithm
+fn eigh(a: &[f64], n: usize) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let mut a = (0..n * n)
+        .map(|offset| {
+            let (i, j) = (offset / n, offset % n);
+            a[(i.max(j) * n) + i.min(j)]
+        })
+        .collect::<Vec<_>>();
+
+    let mut v = (0..n * n)
+        .map(|offset| if offset % (n + 1) == 0 { 1. } else { 0. })
+        .collect::<Vec<_>>();
+
+    let norm = a.iter().map(|x| x * x).sum::<f64>();
+
+    for _sweep in 0..JACOBI_SWEEPS {
+        let off_diagonal = (0..n)
+            .flat_map(|i| (0..n).filter(move |j| *j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[(i * n) + j] * a[(i * n) + j])
+            .sum::<f64>();
+
+        if off_diagonal <= f64::EPSILON * f64::EPSILON * norm {
+            break;
+        }
+
+        for p in 0..n {
+            for q in (p + 1)..n {
+                let apq = a[(p * n) + q];
+                if apq == 0. {
+                    continue;
+                }
+
+                let (c, s) = jacobi_rotation(a[(p * n) + p], a[(q * n) + q], apq);
+
+                for k in 0..n {
+                    let (akp, akq) = (a[(k * n) + p], a[(k * n) + q]);
+                    a[(k * n) + p] = (c * akp) - (s * akq);
+                    a[(k * n) + q] = (s * akp) + (c * akq);
+                }
+
+                for k in 0..n {
+                    let (apk, aqk) = (a[(p * n) + k], a[(q * n) + k]);
+                    a[(p * n) + k] = (c * apk) - (s * aqk);
+                    a[(q * n) + k] = (s * apk) + (c * aqk);
+                }
+
+                for k in 0..n {
+                    let (vkp, vkq) = (v[(k * n) + p], v[(k * n) + q]);
+                    v[(k * n) + p] = (c * vkp) - (s * vkq);
+                    v[(k * n) + q] = (s * vkp) + (c * vkq);
+                }
+            }
+        }
+    }
+
+    let mut order = (0..n).collect::<Vec<_>>();
+    order.sort_by(|i, j| a[(i * n) + i].total_cmp(&a[(j * n) + j]));
+
+    let values = order.iter().map(|i| a[(i * n) + i]).collect();
+    let vectors = order
+        .iter()
+        .map(|j| (0..n).map(|i| v[(i * n) + j]).collect())
+        .collect();
+
+    (values, vectors)
+}
+
+// the (cosine, sine) of the Jacobi rotation which zeroes the off-diagonal entry
+// of the symmetric 2x2 matrix `[[app, apq], [apq, aqq]]`
+#[inline]
+fn jacobi_rotation(app: f64, aqq: f64, apq: f64) -> (f64, f64) {
+    let theta = (aqq - app) / (2. * apq);
+    let t = theta.signum() / (theta.abs() + theta.hypot(1.));
+    let c = 1. / t.hypot(1.);
+    (c, t * c)
+}
+
+// the reduced QR decomposition of the `m x n` matrix `a`, computed with Householder reflections,
+// as the `k` orthonormal columns of Q and the row-major `k x n` matrix R, where `k = min(m, n)`
+fn qr(a: &[f64], m: usize, n: usize) -> (Vec<Vec<f64>>, Vec<f64>) {
+    let k = m.min(n);
+    let mut r = a.to_vec();
+    let mut reflections = Vec::with_capacity(k);
+
+    for j in 0..k {
+        let mut v = (j..m).map(|i| r[(i * n) + j]).collect::<Vec<_>>();
+        let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        v[0] += if v[0] < 0. { -norm } else { norm };
+
+        let v_norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if v_norm == 0. {
+            reflections.push(None);
+            continue;
+        }
+
+        v.iter_mut().for_each(|x| *x /= v_norm);
+
+        for c in j..n {
+            let d = v
+                .iter()
+                .enumerate()
+                .map(|(i, x)| x * r[((j + i) * n) + c])
+                .sum::<f64>();
+            for (i, x) in v.iter().enumerate() {
+                r[((j + i) * n) + c] -= 2. * d * x;
+            }
+        }
+
+        reflections.push(Some(v));
+    }
+
+    // Q is the product of the reflections applied to the first k columns of the identity
+    let mut q = (0..k)
+        .map(|c| {
+            (0..m)
+                .map(|i| if i == c { 1. } else { 0. })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    for (j, v) in reflections.into_iter().enumerate().rev() {
+        if let Some(v) = v {
+            for column in q.iter_mut() {
+                let d = v.iter().zip(&column[j..]).map(|(x, y)| x * y).sum::<f64>();
+                for (y, x) in column[j..].iter_mut().zip(&v) {
+                    *y -= 2. * d * x;
+                }
+            }
+        }
+    }
+
+    let r = (0..k * n)
+        .map(|offset| {
+            let (i, j) = (offset / n, offset % n);
+            if i > j {
+                0.
+            } else {
+                r[offset]
+            }
+        })
+        .collect();
+
+    (q, r)
+}
+
+// the singular value decomposition of the `m x n` matrix `a`, computed with the one-sided
+// Jacobi algorithm, as the left singular vectors, the `min(m, n)` singular values in descending
+// order, and the right singular vectors, where a singular vector is zero if its value is zero
+fn svd(a: &[f64], m: usize, n: usize) -> (Vec<Vec<f64>>, Vec<f64>, Vec<Vec<f64>>) {
+    if m < n {
+        let a_t = (0..m * n)
+            .map(|offset| a[((offset % m) * n) + (offset / m)])
+            .collect::<Vec<_>>();
+
+        let (u, s, v) = svd(&a_t, n, m);
+
+        // the left singular vectors of A^T are the right singular vectors of A
+        return (v, s, u);
+    }
+
+    // the columns of the working matrix W = A V
+    let mut w = (0..n)
+        .map(|j| (0..m).map(|i| a[(i * n) + j]).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    let mut v = (0..n)
+        .map(|j| {
+            (0..n)
+                .map(|i| if i == j { 1. } else { 0. })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let dot = |x: &[f64], y: &[f64]| x.iter().zip(y).map(|(x, y)| x * y).sum::<f64>();
+
+    for _sweep in 0..JACOBI_SWEEPS {
+        let mut rotated = false;
+
+        for p in 0..n {
+            for q in (p + 1)..n {
+                let alpha = dot(&w[p], &w[p]);
+                let beta = dot(&w[q], &w[q]);
+                let gamma = dot(&w[p], &w[q]);
+
+                if gamma.abs() <= f64::EPSILON * (alpha * beta).sqrt() || gamma == 0. {
+                    continue;
+                }
+
+                rotated = true;
+
+                let (c, s) = jacobi_rotation(alpha, beta, gamma);
+
+                for columns in [&mut w, &mut v] {
+                    let (left, right) = columns.split_at_mut(q);
+                    for (x, y) in left[p].iter_mut().zip(right[0].iter_mut()) {
+                        let (xp, xq) = (*x, *y);
+                        *x = (c * xp) - (s * xq);
+                        *y = (s * xp) + (c * xq);
+                    }
+                }
+            }
+        }
+
+        if !rotated {
+            break;
+        }
+    }
+
+    let s = w.iter().map(|w| dot(w, w).sqrt()).collect::<Vec<_>>();
+
+    let mut order = (0..n).collect::<Vec<_>>();
+    order.sort_by(|i, j| s[*j].total_cmp(&s[*i]));
+
+    let max = s.iter().copied().fold(0., f64::max);
+    let u = order
+        .iter()
+        .map(|j| {
+            if s[*j] > max * f64::EPSILON * (m as f64) {
+                w[*j].iter().map(|x| x / s[*j]).collect()
+            } else {
+                vec![0.; m]
+            }
+        })
+        .collect();
+
+    let v = order.iter().map(|j| v[*j].to_vec()).collect();
+    let s = order.into_iter().map(|j| s[j]).collect();
+
+    (u, s, v)
+}
+
+// fill each zero column in `columns`, and append columns, until there are `total` orthonormal
+// columns of length `dim`, using the Gram-Schmidt process on the standard basis
+fn complete_basis(mut columns: Vec<Vec<f64>>, dim: usize, total: usize) -> Vec<Vec<f64>> {
+    debug_assert!(total <= dim);
+
+    let is_unit = |x: &[f64]| x.iter().map(|x| x * x).sum::<f64>() > 0.5;
+
+    columns.truncate(total);
+    columns.resize(total, vec![0.; dim]);
+
+    let mut candidates = 0..dim;
+
+    for j in 0..total {
+        if is_unit(&columns[j]) {
+            continue;
+        }
+
+        for e in candidates.by_ref() {
+            let mut x = vec![0.; dim];
+            x[e] = 1.;
+
+            // orthogonalize twice for numerical stability
+            for _ in 0..2 {
+                for column in columns.iter().filter(|column| is_unit(column)) {
+                    let d = x.iter().zip(column).map(|(x, y)| x * y).sum::<f64>();
+                    x.iter_mut().zip(column).for_each(|(x, y)| *x -= d * y);
+                }
+            }
+
+            let norm = x.iter().map(|x| x * x).sum::<f64>().sqrt();
+            if norm > 1e-6 {
+                x.iter_mut().for_each(|x| *x /= norm);
+                columns[j] = x;
+                break;
+            }
+        }
+    }
+
+    columns
+}
+
+// write the given columns to `output` as a row-major matrix
+fn write_columns(columns: &[Vec<f64>], output: &mut [f64]) {
+    let num_columns = columns.len();
+
+    for (j, column) in columns.iter().enumerate() {
+        for (i, x) in column.iter().enumerate() {
+            output[(i * num_columns) + j] = *x;
+        }
+    }
+}
+
+// write the given rows to `output` as a row-major matrix
+fn write_rows(rows: &[Vec<f64>], output: &mut [f64]) {
+    for (output, row) in output.chunks_exact_mut(rows[0].len()).zip(rows) {
+        output.copy_from_slice(row);
+    }
+}
+
+// comparison
+
+/// An array comparison [`Op`]
+#[derive(Clone)]
+pub struct ArrayBoolean<T, L, R> {
+    left: L,
+    right: R,
+    cpu_op: fn(T, T) -> bool,
+    #[cfg(feature = "opencl")]
+    cl_op: ocl::Program,
+}
+
+impl<T: CDatatype, L: NDArray<DType = T>, R: NDArray<DType = T>> ArrayBoolean<T, L, R> {
+    #[allow(unused_variables)]
+    fn new(
+        left: L,
+        right: R,
+        cpu_op: fn(T, T) -> bool,
+        cl_op: &'static str,
+    ) -> Result<Self, Error> {
+        debug_assert_eq!(left.shape(), right.shape());
+
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::elementwise_boolean::<T>(cl_op, left.context())?;
+
+        Ok(Self {
+            left,
+            right,
+            cpu_op,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
+
+    /// Initialize a new boolean and [`Op`].
+    pub fn and(left: L, right: R) -> Result<Self, Error> {
+        fn and<T: CDatatype>(l: T, r: T) -> bool {
+            (l != T::zero()) && (r != T::zero())
+        }
+
+        Self::new(left, right, and, "&&")
+    }
+
+    /// Initialize a new boolean or [`Op`].
+    pub fn or(left: L, right: R) -> Result<Self, Error> {
+        fn or<T: CDatatype>(l: T, r: T) -> bool {
+            (l != T::zero()) || (r != T::zero())
+        }
+
+        Self::new(left, right, or, "||")
+    }
+
+    /// Initialize a new boolean xor [`Op`].
+    pub fn xor(left: L, right: R) -> Result<Self, Error> {
+        fn xor<T: CDatatype>(l: T, r: T) -> bool {
+            (l != T::zero()) ^ (r != T::zero())
+        }
+
+        Self::new(left, right, xor, "^")
+    }
+}
+
+impl<T: CDatatype, L, R> Op for ArrayBoolean<T, L, R>
+where
+    L: NDArrayRead<DType = T>,
+    R: NDArrayRead<DType = T>,
+{
+    type Out = u8;
+
+    fn context(&self) -> &Context {
+        self.left.context()
+    }
+
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let (left, right) = try_join_read(&self.left, &self.right, queue)?;
+
+        let mut output = queue.host_buffer(left.len())?;
+
+        left.as_ref()
+            .par_iter()
+            .copied()
+            .zip(right.as_ref().par_iter().copied())
+            .with_min_len(queue.min_len(1))
+            .map(|(l, r)| (self.cpu_op)(l, r))
+            .map(|cmp| if cmp { 1 } else { 0 })
+            .collect_into_vec(&mut output);
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let right_queue = queue.split(self.right.size())?;
+        let right = self.right.to_cl_buffer(&right_queue)?;
+
+        let left = self.left.to_cl_buffer(queue)?;
+        debug_assert_eq!(left.len(), right.len());
+
+        let cl_queue = left.as_ref().default_queue().expect("queue");
+
+        let output = queue.cl_buffer(cl_queue.clone(), left.len())?;
+
+        let kernel = ocl::Kernel::builder()
+            .name("elementwise_boolean")
+            .program(&self.cl_op)
+            .queue(cl_queue.clone())
+            .global_work_size(output.len())
+            .arg(left.as_ref())
+            .arg(right.as_ref())
+            .arg(&*output)
+            .build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? };
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn cl_compute(&self) -> bool {
+        self.context().cl_compute::<T>()
+    }
+
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        let (left, right) = try_join(
+            || self.left.read_value(coord),
+            || self.right.read_value(coord),
+        )?;
+
+        if (self.cpu_op)(left, right) {
+            Ok(1)
+        } else {
+            Ok(0)
+        }
+    }
+}
+
+/// A boolean array [`Op`] with a scalar argument
+#[derive(Clone)]
+pub struct ArrayBooleanScalar<L, T> {
+    left: L,
+    right: T,
+    cpu_op: fn(T, T) -> bool,
+    #[cfg(feature = "opencl")]
+    cl_op: ocl::Program,
+}
+
+impl<L: NDArray, T: CDatatype> ArrayBooleanScalar<L, T> {
+    #[allow(unused_variables)]
+    fn new(
+        left: L,
+        right: T,
+        cpu_op: fn(T, T) -> bool,
+        cl_op: &'static str,
+    ) -> Result<Self, Error> {
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::scalar_boolean::<T>(cl_op, left.context())?;
+
+        Ok(Self {
+            left,
+            right,
+            cpu_op,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
+
+    /// Initialize a new boolean and [`Op`] with a scalar argument.
+    pub fn and(left: L, right: T) -> Result<Self, Error> {
+        fn and<T: CDatatype>(l: T, r: T) -> bool {
+            (l != T::zero()) && (r != T::zero())
+        }
+
+        Self::new(left, right, and, "&&")
+    }
+
+    /// Initialize a new boolean or [`Op`] with a scalar argument.
+    pub fn or(left: L, right: T) -> Result<Self, Error> {
+        fn or<T: CDatatype>(l: T, r: T) -> bool {
+            (l != T::zero()) || (r != T::zero())
+        }
+
+        Self::new(left, right, or, "||")
+    }
+
+    /// Initialize a new boolean xor [`Op`] with a scalar argument.
+    pub fn xor(left: L, right: T) -> Result<Self, Error> {
+        fn xor<T: CDatatype>(l: T, r: T) -> bool {
+            (l != T::zero()) ^ (r != T::zero())
+        }
+
+        Self::new(left, right, xor, "^")
+    }
+}
+
+impl<L: NDArrayRead<DType = T>, T: CDatatype> Op for ArrayBooleanScalar<L, T> {
+    type Out = u8;
+
+    fn context(&self) -> &Context {
+        self.left.context()
+    }
+
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let left = self.left.to_host(queue)?;
+
+        let mut output = queue.host_buffer(left.len())?;
+
+        left.as_ref()
+            .par_iter()
+            .copied()
+            .with_min_len(queue.min_len(1))
+            .map(|l| (self.cpu_op)(l, self.right))
+            .map(|cmp| if cmp { 1 } else { 0 })
+            .collect_into_vec(&mut output);
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let left = self.left.to_cl_buffer(queue)?;
+        let cl_queue = left.as_ref().default_queue().expect("queue");
+
+        let output = queue.cl_buffer(cl_queue.clone(), left.len())?;
+
+        let kernel = ocl::Kernel::builder()
+            .name("scalar_boolean")
+            .program(&self.cl_op)
+            .queue(cl_queue.clone())
+            .global_work_size(output.len())
+            .arg(left.as_ref())
+            .arg(self.right)
+            .arg(&*output)
+            .build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? };
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn cl_compute(&self) -> bool {
+        self.context().cl_compute::<T>()
+    }
+
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        let left = self.left.read_value(coord)?;
+        let right = self.right;
+
+        if (self.cpu_op)(left, right) {
+            Ok(1)
+        } else {
+            Ok(0)
+        }
+    }
+}
+
+/// An array comparison [`Op`]
+#[derive(Clone)]
+pub struct ArrayCompare<T, L, R> {
+    left: L,
+    right: R,
+    cpu_op: fn(&T, &T) -> bool,
+    tolerance: Option<Tolerance<T>>,
+    #[cfg(feature = "opencl")]
+    cl_op: ocl::Program,
+}
+
+/// The tolerance of an approximate equality comparison
+#[derive(Copy, Clone)]
+struct Tolerance<T> {
+    rtol: T,
+    atol: T,
+    equal_nan: bool,
+}
+
+impl<T: CDatatype> Tolerance<T> {
+    fn is_close(&self, left: T, right: T) -> bool {
+        let (left, right) = (left.to_f64(), right.to_f64());
+
+        if left.is_nan() || right.is_nan() {
+            self.equal_nan && left.is_nan() && right.is_nan()
+        } else if left.is_infinite() || right.is_infinite() {
+            false
+        } else {
+            (left - right).abs() <= self.atol.to_f64() + (self.rtol.to_f64() * right.abs())
+        }
+    }
+}
+
+impl<T: CDatatype, L: NDArray<DType = T>, R: NDArray<DType = T>> ArrayCompare<T, L, R> {
+    #[allow(unused_variables)]
+    fn new(
+        left: L,
+        right: R,
+        cpu_op: fn(&T, &T) -> bool,
+        cl_op: &'static str,
+    ) -> Result<Self, Error> {
+        debug_assert_eq!(left.shape(), right.shape());
+
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::elementwise_cmp::<T>(cl_op, left.context())?;
+
+        Ok(Self {
+            left,
+            right,
+            cpu_op,
+            tolerance: None,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
+
+    #[inline]
+    fn compare(&self, left: &T, right: &T) -> bool {
+        if (self.cpu_op)(left, right) {
+            true
+        } else if let Some(tolerance) = &self.tolerance {
+            tolerance.is_close(*left, *right)
+        } else {
+            false
+        }
+    }
+
+    /// Initialize a new equality comparison [`Op`].
+    pub fn eq(left: L, right: R) -> Result<Self, Error> {
+        Self::new(left, right, PartialEq::eq, "==")
+    }
+
+    /// Initialize a new greater-than comparison [`Op`].
+    pub fn gt(left: L, right: R) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(left, right, PartialOrd::gt, ">")
+    }
+
+    /// Initialize a new equal-or-greater-than comparison [`Op`].
+    pub fn ge(left: L, right: R) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(left, right, PartialOrd::ge, ">=")
+    }
+
+    /// Initialize a new less-than comparison [`Op`].
+    pub fn lt(left: L, right: R) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(left, right, PartialOrd::lt, "<")
+    }
+
+    /// Initialize a new equal-or-less-than comparison [`Op`].
+    pub fn le(left: L, right: R) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(left, right, PartialOrd::le, "<=")
+    }
+
+    /// Initialize a new not-equal comparison [`Op`].
+    pub fn ne(left: L, right: R) -> Result<Self, Error> {
+        Self::new(left, right, PartialEq::ne, "!=")
+    }
+}
+
+impl<T: Float, L: NDArray<DType = T>, R: NDArray<DType = T>> ArrayCompare<T, L, R> {
+    /// Initialize a new approximate equality comparison [`Op`], which is true where
+    /// `|left - right| <= atol + rtol * |right|`, or where both are `NaN` if `equal_nan` is set.
+    pub fn isclose(left: L, right: R, rtol: T, atol: T, equal_nan: bool) -> Result<Self, Error> {
+        debug_assert_eq!(left.shape(), right.shape());
+
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::elementwise_isclose::<T>(
+            rtol.to_f64(),
+            atol.to_f64(),
+            equal_nan,
+            left.context(),
+        )?;
+
+        Ok(Self {
+            left,
+            right,
+            cpu_op: PartialEq::eq,
+            tolerance: Some(Tolerance {
+                rtol,
+                atol,
+                equal_nan,
+            }),
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
+}
+
+impl<T, L, R> Op for ArrayCompare<T, L, R>
+where
+    T: CDatatype,
+    L: NDArrayRead<DType = T>,
+    R: NDArrayRead<DType = T>,
+{
+    type Out = u8;
+
+    fn context(&self) -> &Context {
+        self.left.context()
+    }
+
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let (left, right) = try_join_read(&self.left, &self.right, queue)?;
+        debug_assert_eq!(left.len(), right.len());
+
+        let mut output = queue.host_buffer(left.len())?;
+
+        left.as_ref()
+            .par_iter()
+            .zip(right.as_ref().par_iter())
+            .with_min_len(queue.min_len(1))
+            .map(|(l, r)| self.compare(l, r))
+            .map(|cmp| if cmp { 1 } else { 0 })
+            .collect_into_vec(&mut output);
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let right_queue = queue.split(self.right.size())?;
+        let right = self.right.to_cl_buffer(&right_queue)?;
+        let left = self.left.to_cl_buffer(queue)?;
+        debug_assert_eq!(left.len(), right.len());
+
+        let cl_queue = left.as_ref().default_queue().expect("left queue").clone();
+
+        let output = queue.cl_buffer(cl_queue.clone(), left.len())?;
+
+        let kernel = ocl::Kernel::builder()
+            .name("elementwise_cmp")
+            .program(&self.cl_op)
+            .queue(cl_queue)
+            .global_work_size(output.len())
+            .arg(left.as_ref())
+            .arg(right.as_ref())
+            .arg(&*output)
+            .build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? }
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn cl_compute(&self) -> bool {
+        self.context().cl_compute::<T>()
+    }
+
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        let (left, right) = try_join(
+            || self.left.read_value(coord),
+            || self.right.read_value(coord),
+        )?;
+
+        if self.compare(&left, &right) {
+            Ok(1)
+        } else {
+            Ok(0)
+        }
+    }
+}
+
+/// An array comparison [`Op`] with a scalar argument
+#[derive(Clone)]
+pub struct ArrayCompareScalar<T, A> {
+    array: A,
+    scalar: T,
+    cpu_op: fn(&T, &T) -> bool,
+    #[cfg(feature = "opencl")]
+    cl_op: ocl::Program,
+}
+
+impl<T: CDatatype, A: NDArray> ArrayCompareScalar<T, A> {
+    #[allow(unused_variables)]
+    fn new(
+        array: A,
+        scalar: T,
+        cpu_op: fn(&T, &T) -> bool,
+        cl_op: &'static str,
+    ) -> Result<Self, Error> {
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::scalar_cmp::<T>(cl_op, array.context())?;
+
+        Ok(Self {
+            array,
+            scalar,
+            cpu_op,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
+
+    /// Initialize a new equality comparison [`Op`].
+    pub fn eq(array: A, scalar: T) -> Result<Self, Error> {
+        Self::new(array, scalar, PartialEq::eq, "==")
+    }
+
+    /// Initialize a new greater-than comparison [`Op`].
+    pub fn gt(array: A, scalar: T) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(array, scalar, PartialOrd::gt, ">")
+    }
+
+    /// Initialize a new equal-or-greater-than comparison [`Op`].
+    pub fn ge(array: A, scalar: T) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(array, scalar, PartialOrd::ge, ">=")
+    }
+
+    /// Initialize a new less-than comparison [`Op`].
+    pub fn lt(array: A, scalar: T) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(array, scalar, PartialOrd::lt, "<")
+    }
+
+    /// Initialize a new equal-or-less-than comparison [`Op`].
+    pub fn le(array: A, scalar: T) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(array, scalar, PartialOrd::le, "<=")
+    }
+
+    /// Initialize a new not-equal comparison [`Op`].
+    pub fn ne(array: A, scalar: T) -> Result<Self, Error> {
+        Self::new(array, scalar, PartialEq::ne, "!=")
+    }
+}
+
+impl<T: CDatatype, A: NDArrayRead<DType = T>> Op for ArrayCompareScalar<T, A> {
+    type Out = u8;
+
+    fn context(&self) -> &Context {
+        self.array.context()
+    }
+
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let input = self.array.to_host(queue)?;
+
+        let mut output = queue.host_buffer(input.len())?;
+
+        input
+            .as_ref()
+            .par_iter()
+            .with_min_len(queue.min_len(1))
+            .map(|n| (self.cpu_op)(n, &self.scalar))
+            .map(|cmp| if cmp { 1 } else { 0 })
+            .collect_into_vec(&mut output);
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let input = self.array.to_cl_buffer(queue)?;
+        let cl_queue = input.as_ref().default_queue().expect("queue").clone();
+
+        let output = queue.cl_buffer(cl_queue.clone(), input.len())?;
+
+        let kernel = ocl::Kernel::builder()
+            .name("scalar_cmp")
+            .program(&self.cl_op)
+            .queue(cl_queue)
+            .global_work_size(input.len())
+            .arg(input.as_ref())
+            .arg(self.scalar)
+            .arg(&*output)
+            .build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? }
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn cl_compute(&self) -> bool {
+        self.context().cl_compute::<T>()
+    }
+
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        let left = self.array.read_value(coord)?;
+        let right = self.scalar;
+
+        if (self.cpu_op)(&left, &right) {
+            Ok(1)
+        } else {
+            Ok(0)
+        }
+    }
+}
+
+// reduction
+
+/// An array reduction [`Op`]
+#[derive(Copy, Clone)]
+pub struct ArrayReduceAxes<T, A> {
+    source: A,
+    stride: usize,
+    id: T,
+    cpu_op: fn(T, T) -> T,
+    cpu_reduce: Option<fn(&[T]) -> T>,
+    #[allow(unused)]
+    cl_op: &'static str,
+}
+
+impl<T: CDatatype, A: NDArray<DType = T>> ArrayReduceAxes<T, A> {
+    fn new(source: A, stride: usize, id: T, cpu_op: fn(T, T) -> T, cl_op: &'static str) -> Self {
+        Self {
+            source,
+            stride,
+            id,
+            cpu_op,
+            cpu_reduce: None,
+            cl_op,
+        }
+    }
+
+    /// Initialize a new reduce-max [`Op`].
+    pub fn max(source: A, stride: usize) -> Self
+    where
+        T: PartialOrd,
+    {
         fn max<T: PartialOrd>(l: T, r: T) -> T {
             if r > l {
                 r
@@ -1647,108 +4593,1053 @@ impl<T: CDatatype, A: NDArray<DType = T>> ArrayReduceAxes<T, A> {
             }
         }
 
-        Self::new(source, stride, T::min(), max, "max")
+        Self::new(source, stride, T::min(), max, "max")
+    }
+
+    /// Initialize a new reduce-min [`Op`].
+    pub fn min(source: A, stride: usize) -> Self
+    where
+        T: PartialOrd,
+    {
+        fn min<T: PartialOrd>(l: T, r: T) -> T {
+            if r < l {
+                r
+            } else {
+                l
+            }
+        }
+
+        Self::new(source, stride, T::max(), min, "min")
+    }
+
+    /// Initialize a new product-reduce [`Op`].
+    pub fn product(source: A, stride: usize) -> Self {
+        Self::new(source, stride, T::one(), Mul::mul, "mul")
+    }
+
+    /// Initialize a new sum-reduce [`Op`].
+    pub fn sum(source: A, stride: usize) -> Self {
+        let mut op = Self::new(source, stride, T::zero(), Add::add, "add");
+        op.cpu_reduce = Some(T::sum_slice);
+        op
+    }
+}
+
+impl<T, A> Op for ArrayReduceAxes<T, A>
+where
+    T: CDatatype,
+    A: NDArrayRead<DType = T>,
+{
+    type Out = T;
+
+    fn context(&self) -> &Context {
+        self.source.context()
+    }
+
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let input = self.source.to_host(queue)?;
+        debug_assert!(!input.as_ref().is_empty());
+
+        let mut output = queue.host_buffer(input.len() / self.stride)?;
+
+        input
+            .as_ref()
+            .par_chunks_exact(self.stride)
+            .with_min_len(queue.min_len(self.stride))
+            .map(|chunk| {
+                if let Some(reduce) = self.cpu_reduce {
+                    return reduce(chunk);
+                }
+
+                // encourage the compiler to vectorize the reduction
+                let reduced = chunk
+                    .chunks(8)
+                    .map(|cc| {
+                        let reduced = cc
+                            .into_iter()
+                            .copied()
+                            .reduce(self.cpu_op)
+                            .expect("reduce chunk");
+
+                        reduced
+                    })
+                    .reduce(self.cpu_op)
+                    .expect("reduce");
+
+                reduced
+            })
+            .collect_into_vec(&mut output);
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let input = self.source.to_cl_buffer(queue)?;
+        let cl_queue = input.as_ref().default_queue().expect("queue").clone();
+        let output = cl_programs::reduce_axis(
+            A::DType::zero(),
+            self.cl_op,
+            queue,
+            cl_queue,
+            input.as_ref(),
+            self.source.shape(),
+            self.stride,
+        )?;
+
+        queue.adopt(output)
+    }
+
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        let start = offset_of(coord, self.source.shape());
+        let stop = start + self.stride;
+
+        let strides = strides_for(self.source.shape(), self.source.ndim());
+
+        (start..stop)
+            .into_par_iter()
+            .map(|offset| {
+                strides
+                    .iter()
+                    .zip(self.source.shape())
+                    .map(|(stride, dim)| (offset / stride) % dim)
+                    .collect::<Vec<usize>>()
+            })
+            .map(|source_coord| self.source.read_value(&source_coord))
+            .try_reduce(|| self.id, |r, v| Ok((self.cpu_op)(r, v)))
+    }
+}
+
+// normalization
+
+/// A fused normalization [`Op`] which computes its mean and variance in a single pass
+pub struct ArrayNorm<T: CDatatype, A> {
+    source: A,
+    stride: usize,
+    param_stride: usize,
+    param_len: usize,
+    eps: T,
+    running: Option<(Array<T>, Array<T>)>,
+    weight: Option<Array<T>>,
+    bias: Option<Array<T>>,
+    #[cfg(feature = "opencl")]
+    cl_op: ocl::Program,
+}
+
+impl<T: Float, A: NDArray<DType = T>> ArrayNorm<T, A> {
+    fn new(
+        source: A,
+        stride: usize,
+        (param_stride, param_len): (usize, usize),
+        eps: T,
+        running: Option<(Array<T>, Array<T>)>,
+        weight: Option<Array<T>>,
+        bias: Option<Array<T>>,
+    ) -> Result<Self, Error> {
+        debug_assert_eq!(source.size() % stride, 0);
+
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::normalize::<T>(
+            source.context(),
+            running.is_some(),
+            weight.is_some(),
+            bias.is_some(),
+        )?;
+
+        Ok(Self {
+            source,
+            stride,
+            param_stride,
+            param_len,
+            eps,
+            running,
+            weight,
+            bias,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
+
+    /// Initialize a new layer normalization [`Op`] over contiguous groups of `stride` elements.
+    /// The `weight` and `bias`, if any, must each have `stride` elements.
+    pub fn layer(
+        source: A,
+        stride: usize,
+        eps: T,
+        weight: Option<Array<T>>,
+        bias: Option<Array<T>>,
+    ) -> Result<Self, Error> {
+        Self::new(source, stride, (1, stride), eps, None, weight, bias)
+    }
+
+    /// Initialize a new group normalization [`Op`] over a source with shape `[N, C, ...]`.
+    /// The `weight` and `bias`, if any, must each have `C` elements.
+    pub fn group(
+        source: A,
+        num_groups: usize,
+        eps: T,
+        weight: Option<Array<T>>,
+        bias: Option<Array<T>>,
+    ) -> Result<Self, Error> {
+        let channels = source.shape()[1];
+        let param_stride = source.shape()[2..].iter().product();
+        let stride = (channels / num_groups) * param_stride;
+        Self::new(
+            source,
+            stride,
+            (param_stride, channels),
+            eps,
+            None,
+            weight,
+            bias,
+        )
+    }
+
+    /// Initialize a new batch normalization [`Op`] over a source with shape `[N, C, ...]`
+    /// using the given per-channel running `mean` and `var`.
+    pub fn batch(
+        source: A,
+        mean: Array<T>,
+        var: Array<T>,
+        eps: T,
+        weight: Option<Array<T>>,
+        bias: Option<Array<T>>,
+    ) -> Result<Self, Error> {
+        let channels = source.shape()[1];
+        let param_stride = source.shape()[2..].iter().product();
+        let stride = channels * param_stride;
+        let running = Some((mean, var));
+        let layout = (param_stride, channels);
+        Self::new(source, stride, layout, eps, running, weight, bias)
+    }
+
+    #[inline]
+    fn param_offset(&self, offset: usize) -> usize {
+        (offset / self.param_stride) % self.param_len
+    }
+
+    #[inline]
+    fn stats_offset(&self, offset: usize) -> usize {
+        if self.running.is_some() {
+            self.param_offset(offset)
+        } else {
+            offset / self.stride
+        }
+    }
+
+    #[inline]
+    fn normalize(&self, x: T, mean: T, var: T, weight: Option<T>, bias: Option<T>) -> T {
+        let rstd = T::pow(var + self.eps, T::from_f64(-0.5));
+        let normalized = (x - mean) * rstd;
+        let scaled = weight.map(|w| normalized * w).unwrap_or(normalized);
+        bias.map(|b| scaled + b).unwrap_or(scaled)
+    }
+}
+
+impl<T: Float, A: NDArrayRead<DType = T>> Op for ArrayNorm<T, A> {
+    type Out = T;
+
+    fn context(&self) -> &Context {
+        self.source.context()
+    }
+
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let input = self.source.to_host(queue)?;
+        let input = input.as_ref();
+
+        let weight = self.weight.as_ref().map(|w| w.to_host(queue)).transpose()?;
+        let bias = self.bias.as_ref().map(|b| b.to_host(queue)).transpose()?;
+
+        let (mean, var) = if let Some((mean, var)) = &self.running {
+            let (mean, var) = try_join_read(mean, var, queue)?;
+            (mean.into_vec(), var.into_vec())
+        } else {
+            input
+                .par_chunks_exact(self.stride)
+                .with_min_len(queue.min_len(self.stride))
+                .map(moments)
+                .unzip()
+        };
+
+        let mut output = queue.host_buffer(input.len())?;
+
+        input
+            .par_iter()
+            .copied()
+            .with_min_len(queue.min_len(1))
+            .enumerate()
+            .map(|(offset, x)| {
+                let p = self.param_offset(offset);
+                let s = self.stats_offset(offset);
+                let weight = weight.as_ref().map(|w| w.as_ref()[p]);
+                let bias = bias.as_ref().map(|b| b.as_ref()[p]);
+                self.normalize(x, mean[s], var[s], weight, bias)
+            })
+            .collect_into_vec(&mut output);
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        use cl_programs::WG_SIZE;
+
+        let input = self.source.to_cl_buffer(queue)?;
+        let cl_queue = input.as_ref().default_queue().expect("queue").clone();
+
+        let (mean, var) = if let Some((mean, var)) = &self.running {
+            (mean.to_cl_buffer(queue)?, var.to_cl_buffer(queue)?)
+        } else {
+            let num_groups = input.len() / self.stride;
+
+            let mean = queue.cl_buffer(cl_queue.clone(), num_groups)?;
+            let var = queue.cl_buffer(cl_queue.clone(), num_groups)?;
+
+            let kernel = ocl::Kernel::builder()
+                .name("moments")
+                .program(&self.cl_op)
+                .queue(cl_queue.clone())
+                .local_work_size(WG_SIZE)
+                .global_work_size(WG_SIZE * num_groups)
+                .arg(self.stride as u64)
+                .arg(input.as_ref())
+                .arg(&*mean)
+                .arg(&*var)
+                .arg_local::<u8>(cl_programs::accumulator_bytes::<T>(WG_SIZE))
+                .arg_local::<u8>(cl_programs::accumulator_bytes::<T>(WG_SIZE))
+                .arg_local::<u8>(cl_programs::accumulator_bytes::<T>(WG_SIZE))
+                .build()?;
+
+            unsafe { queue.enqueue_kernel(&kernel)? }
+
+            (CLConverter::Pooled(mean), CLConverter::Pooled(var))
+        };
+
+        let weight = self
+            .weight
+            .as_ref()
+            .map(|w| w.to_cl_buffer(queue))
+            .transpose()?;
+        let bias = self
+            .bias
+            .as_ref()
+            .map(|b| b.to_cl_buffer(queue))
+            .transpose()?;
+
+        let output = queue.cl_buffer(cl_queue.clone(), input.len())?;
+
+        let mut builder = ocl::Kernel::builder();
+
+        builder
+            .name("normalize")
+            .program(&self.cl_op)
+            .queue(cl_queue)
+            .global_work_size(input.len())
+            .arg(self.stride as u64)
+            .arg(self.param_stride as u64)
+            .arg(self.param_len as u64)
+            .arg(self.eps)
+            .arg(input.as_ref())
+            .arg(mean.as_ref())
+            .arg(var.as_ref());
+
+        if let Some(weight) = &weight {
+            builder.arg(weight.as_ref());
+        }
+
+        if let Some(bias) = &bias {
+            builder.arg(bias.as_ref());
+        }
+
+        let kernel = builder.arg(&*output).build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? }
+
+        Ok(output)
+    }
+
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        let offset = offset_of(coord, self.source.shape());
+        let x = self.source.read_value(coord)?;
+
+        let p = self.param_offset(offset);
+
+        let (mean, var) = if let Some((mean, var)) = &self.running {
+            (mean.read_value(&[p])?, var.read_value(&[p])?)
+        } else {
+            let start = (offset / self.stride) * self.stride;
+            let stop = start + self.stride;
+
+            let group = (start..stop)
+                .into_par_iter()
+                .map(|offset| coord_of(offset, self.source.shape()))
+                .map(|source_coord| self.source.read_value(&source_coord))
+                .collect::<Result<Vec<T>, Error>>()?;
+
+            moments(&group)
+        };
+
+        let weight = self
+            .weight
+            .as_ref()
+            .map(|w| w.read_value(&coord_of(p, w.shape())))
+            .transpose()?;
+
+        let bias = self
+            .bias
+            .as_ref()
+            .map(|b| b.read_value(&coord_of(p, b.shape())))
+            .transpose()?;
+
+        Ok(self.normalize(x, mean, var, weight, bias))
+    }
+}
+
+// fourier transforms
+
+/// A fast Fourier transform [`Op`] over one or more axes of a complex array
+#[cfg(feature = "fft")]
+#[derive(Clone)]
+pub struct ArrayFFT<A> {
+    source: A,
+    axes: Vec<usize>,
+    inverse: bool,
+    #[cfg(feature = "opencl")]
+    cl_op: ocl::Program,
+}
+
+#[cfg(feature = "fft")]
+impl<F, A> ArrayFFT<A>
+where
+    F: Float + FftNum,
+    Complex<F>: CDatatype,
+    A: NDArray<DType = Complex<F>>,
+{
+    /// Initialize a new (forward) FFT [`Op`] over the given `axes`.
+    pub fn forward(source: A, axes: Vec<usize>) -> Result<Self, Error> {
+        Self::new(source, axes, false)
+    }
+
+    /// Initialize a new inverse FFT [`Op`] over the given `axes`, normalized by `1 / n`.
+    pub fn inverse(source: A, axes: Vec<usize>) -> Result<Self, Error> {
+        Self::new(source, axes, true)
+    }
+
+    fn new(source: A, axes: Vec<usize>, inverse: bool) -> Result<Self, Error> {
+        if axes.is_empty() {
+            return Err(Error::Bounds(
+                "an FFT requires at least one axis".to_string(),
+            ));
+        }
+
+        for (i, axis) in axes.iter().enumerate() {
+            check_fft_axis(source.shape(), *axis)?;
+
+            if axes[..i].contains(axis) {
+                return Err(Error::Bounds(format!("duplicate FFT axis {axis}")));
+            }
+        }
+
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::fft::<Complex<F>>(source.context())?;
+
+        Ok(Self {
+            source,
+            axes,
+            inverse,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
     }
+}
 
-    /// Initialize a new reduce-min [`Op`].
-    pub fn min(source: A, stride: usize) -> Self {
-        fn min<T: PartialOrd>(l: T, r: T) -> T {
-            if r < l {
-                r
+#[cfg(feature = "fft")]
+impl<F, A> Op for ArrayFFT<A>
+where
+    F: Float + FftNum,
+    Complex<F>: CDatatype,
+    A: NDArrayRead<DType = Complex<F>>,
+{
+    type Out = Complex<F>;
+
+    fn context(&self) -> &Context {
+        self.source.context()
+    }
+
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let shape = self.source.shape();
+        let mut data = self.source.to_host(queue)?.into_vec();
+        let mut planner = FftPlanner::new();
+
+        for axis in self.axes.iter().copied() {
+            let dim = shape[axis];
+
+            let (fft, scale) = if self.inverse {
+                (
+                    planner.plan_fft_inverse(dim),
+                    <F as CDatatype>::from_f64(1. / dim as f64),
+                )
             } else {
-                l
-            }
+                (planner.plan_fft_forward(dim), <F as CDatatype>::one())
+            };
+
+            data = map_lanes(&data, dim, axis_stride(shape, axis), dim, |mut lane| {
+                fft.process(&mut lane);
+
+                if self.inverse {
+                    lane.iter_mut().for_each(|z| *z = z.scale(scale));
+                }
+
+                lane
+            });
         }
 
-        Self::new(source, stride, T::max(), min, "min")
+        queue.adopt(data)
     }
 
-    /// Initialize a new product-reduce [`Op`].
-    pub fn product(source: A, stride: usize) -> Self {
-        Self::new(source, stride, T::one(), Mul::mul, "mul")
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let shape = self.source.shape();
+
+        if !self.axes.iter().all(|axis| shape[*axis].is_power_of_two()) {
+            return enqueue_on_host(self, queue);
+        }
+
+        let input = self.source.to_cl_buffer(queue)?;
+        let cl_queue = input.as_ref().default_queue().expect("queue").clone();
+
+        let fft = |input: &ocl::Buffer<Complex<F>>, axis: usize| {
+            let dims = (shape[axis], axis_stride(shape, axis));
+            fft_passes(
+                &self.cl_op,
+                queue,
+                cl_queue.clone(),
+                input,
+                dims,
+                self.inverse,
+            )
+        };
+
+        let mut output = fft(input.as_ref(), self.axes[0])?;
+
+        for axis in self.axes[1..].iter().copied() {
+            output = fft(&output, axis)?;
+        }
+
+        Ok(output)
     }
 
-    /// Initialize a new sum-reduce [`Op`].
-    pub fn sum(source: A, stride: usize) -> Self {
-        Self::new(source, stride, T::zero(), Add::add, "add")
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        let shape = self.source.shape();
+        let dims = self
+            .axes
+            .iter()
+            .map(|axis| shape[*axis])
+            .collect::<Vec<_>>();
+        let index = self
+            .axes
+            .iter()
+            .map(|axis| coord[*axis])
+            .collect::<Vec<_>>();
+        let sign = if self.inverse { 1. } else { -1. };
+
+        let (re, im) = dft_value(&dims, &index, sign, |lane_coord| {
+            let mut source_coord = coord.to_vec();
+            for (axis, i) in self.axes.iter().zip(lane_coord) {
+                source_coord[*axis] = *i;
+            }
+
+            let z = self.source.read_value(&source_coord)?;
+            Ok((z.re.to_f64(), z.im.to_f64()))
+        })?;
+
+        let scale = if self.inverse {
+            1. / dims.iter().product::<usize>() as f64
+        } else {
+            1.
+        };
+
+        Ok(Complex::new(
+            <F as CDatatype>::from_f64(re * scale),
+            <F as CDatatype>::from_f64(im * scale),
+        ))
     }
 }
 
-impl<T, A> Op for ArrayReduceAxes<T, A>
+/// A fast Fourier transform [`Op`] along one axis of a real array,
+/// which outputs only the `n / 2 + 1` non-negative frequency terms
+#[cfg(feature = "fft")]
+#[derive(Clone)]
+pub struct ArrayRFFT<A> {
+    source: A,
+    axis: usize,
+    #[cfg(feature = "opencl")]
+    cl_op: ocl::Program,
+}
+
+#[cfg(feature = "fft")]
+impl<F, A> ArrayRFFT<A>
 where
-    T: CDatatype,
-    A: NDArrayRead<DType = T>,
+    F: Float + FftNum,
+    Complex<F>: CDatatype,
+    A: NDArray<DType = F>,
 {
-    type Out = T;
+    /// Initialize a new real FFT [`Op`] along the given `axis`.
+    pub fn new(source: A, axis: usize) -> Result<Self, Error> {
+        check_fft_axis(source.shape(), axis)?;
+
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::fft::<Complex<F>>(source.context())?;
+
+        Ok(Self {
+            source,
+            axis,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
+}
+
+#[cfg(feature = "fft")]
+impl<F, A> Op for ArrayRFFT<A>
+where
+    F: Float + FftNum,
+    Complex<F>: CDatatype,
+    A: NDArrayRead<DType = F>,
+{
+    type Out = Complex<F>;
 
     fn context(&self) -> &Context {
         self.source.context()
     }
 
-    fn enqueue_cpu(&self, queue: &Queue) -> Result<Vec<Self::Out>, Error> {
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let shape = self.source.shape();
         let input = self.source.to_host(queue)?;
-        debug_assert!(!input.as_ref().is_empty());
 
-        let output = input
-            .as_ref()
-            .par_chunks_exact(self.stride)
-            .map(|chunk| {
-                // encourage the compiler to vectorize the reduction
-                let reduced = chunk
-                    .chunks(8)
-                    .map(|cc| {
-                        let reduced = cc
-                            .into_iter()
-                            .copied()
-                            .reduce(self.cpu_op)
-                            .expect("reduce chunk");
+        let n = shape[self.axis];
+        let fft = FftPlanner::new().plan_fft_forward(n);
+
+        let output = map_lanes(
+            input.as_ref(),
+            n,
+            axis_stride(shape, self.axis),
+            (n / 2) + 1,
+            |lane| {
+                let mut lane = lane
+                    .into_iter()
+                    .map(|x| Complex::new(x, <F as CDatatype>::zero()))
+                    .collect::<Vec<_>>();
+
+                fft.process(&mut lane);
+                lane.truncate((n / 2) + 1);
+                lane
+            },
+        );
+
+        queue.adopt(output)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let shape = self.source.shape();
+        let n = shape[self.axis];
+
+        if !n.is_power_of_two() {
+            return enqueue_on_host(self, queue);
+        }
+
+        let stride = axis_stride(shape, self.axis);
+        let input = self.source.to_cl_buffer(queue)?;
+        let cl_queue = input.as_ref().default_queue().expect("queue").clone();
+        let num_lanes = input.len() / n;
+
+        let full = queue.cl_buffer(cl_queue.clone(), input.len())?;
+
+        let kernel = ocl::Kernel::builder()
+            .name("rfft_input")
+            .program(&self.cl_op)
+            .queue(cl_queue.clone())
+            .global_work_size((num_lanes, n))
+            .arg(n as u64)
+            .arg(stride as u64)
+            .arg(input.as_ref())
+            .arg(&*full)
+            .build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? }
+
+        let full = fft_passes(
+            &self.cl_op,
+            queue,
+            cl_queue.clone(),
+            &full,
+            (n, stride),
+            false,
+        )?;
+
+        let m = (n / 2) + 1;
+        let output = queue.cl_buffer(cl_queue.clone(), num_lanes * m)?;
+
+        let kernel = ocl::Kernel::builder()
+            .name("rfft_output")
+            .program(&self.cl_op)
+            .queue(cl_queue)
+            .global_work_size((num_lanes, m))
+            .arg(n as u64)
+            .arg(m as u64)
+            .arg(stride as u64)
+            .arg(&*full)
+            .arg(&*output)
+            .build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? }
+
+        Ok(output)
+    }
+
+    fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
+        let n = self.source.shape()[self.axis];
+
+        let (re, im) = dft_value(&[n], &[coord[self.axis]], -1., |lane_coord| {
+            let mut source_coord = coord.to_vec();
+            source_coord[self.axis] = lane_coord[0];
+            let x = self.source.read_value(&source_coord)?;
+            Ok((x.to_f64(), 0.))
+        })?;
+
+        Ok(Complex::new(
+            <F as CDatatype>::from_f64(re),
+            <F as CDatatype>::from_f64(im),
+        ))
+    }
+}
+
+/// The inverse of an [`ArrayRFFT`], which reconstructs a real signal of length `n`
+/// from its `n / 2 + 1` non-negative frequency terms along one axis
+#[cfg(feature = "fft")]
+#[derive(Clone)]
+pub struct ArrayIRFFT<A> {
+    source: A,
+    axis: usize,
+    n: usize,
+    #[cfg(feature = "opencl")]
+    cl_op: ocl::Program,
+}
+
+#[cfg(feature = "fft")]
+impl<F, A> ArrayIRFFT<A>
+where
+    F: Float + FftNum,
+    Complex<F>: CDatatype,
+    A: NDArray<DType = Complex<F>>,
+{
+    /// Initialize a new inverse real FFT [`Op`] with output length `n` along the given `axis`.
+    pub fn new(source: A, axis: usize, n: usize) -> Result<Self, Error> {
+        check_fft_axis(source.shape(), axis)?;
+
+        if n == 0 || source.shape()[axis] < (n / 2) + 1 {
+            return Err(Error::Bounds(format!(
+                "an inverse real FFT of length {n} requires {} input terms along axis {axis}, not {}",
+                (n / 2) + 1,
+                source.shape()[axis]
+            )));
+        }
+
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::fft::<Complex<F>>(source.context())?;
+
+        Ok(Self {
+            source,
+            axis,
+            n,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
+}
+
+#[cfg(feature = "fft")]
+impl<F, A> Op for ArrayIRFFT<A>
+where
+    F: Float + FftNum,
+    Complex<F>: CDatatype,
+    A: NDArrayRead<DType = Complex<F>>,
+{
+    type Out = F;
+
+    fn context(&self) -> &Context {
+        self.source.context()
+    }
+
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
+        let shape = self.source.shape();
+        let input = self.source.to_host(queue)?;
 
-                        reduced
+        let n = self.n;
+        let fft = FftPlanner::new().plan_fft_inverse(n);
+        let scale = <F as CDatatype>::from_f64(1. / n as f64);
+
+        let output = map_lanes(
+            input.as_ref(),
+            shape[self.axis],
+            axis_stride(shape, self.axis),
+            n,
+            |lane| {
+                let mut full = (0..n)
+                    .map(|k| {
+                        if k <= n / 2 {
+                            lane[k]
+                        } else {
+                            lane[n - k].conj()
+                        }
                     })
-                    .reduce(self.cpu_op)
-                    .expect("reduce");
+                    .collect::<Vec<_>>();
 
-                reduced
-            })
-            .collect();
+                fft.process(&mut full);
+                full.into_iter().map(|z| z.re * scale).collect()
+            },
+        );
 
-        Ok(output)
+        queue.adopt(output)
     }
 
     #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<Self::Out>, Error> {
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
+        let n = self.n;
+
+        if !n.is_power_of_two() {
+            return enqueue_on_host(self, queue);
+        }
+
+        let shape = self.source.shape();
+        let m = shape[self.axis];
+        let stride = axis_stride(shape, self.axis);
+
         let input = self.source.to_cl_buffer(queue)?;
         let cl_queue = input.as_ref().default_queue().expect("queue").clone();
-        let output = cl_programs::reduce_axis(
-            A::DType::zero(),
-            self.cl_op,
-            cl_queue,
-            input.as_ref(),
-            self.source.shape(),
-            self.stride,
+        let num_lanes = input.len() / m;
+
+        let full = queue.cl_buffer(cl_queue.clone(), num_lanes * n)?;
+
+        let kernel = ocl::Kernel::builder()
+            .name("irfft_input")
+            .program(&self.cl_op)
+            .queue(cl_queue.clone())
+            .global_work_size((num_lanes, n))
+            .arg(m as u64)
+            .arg(n as u64)
+            .arg(stride as u64)
+            .arg(input.as_ref())
+            .arg(&*full)
+            .build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? }
+
+        let full = fft_passes(
+            &self.cl_op,
+            queue,
+            cl_queue.clone(),
+            &full,
+            (n, stride),
+            true,
         )?;
 
+        let output = queue.cl_buffer(cl_queue.clone(), full.len())?;
+
+        let kernel = ocl::Kernel::builder()
+            .name("irfft_output")
+            .program(&self.cl_op)
+            .queue(cl_queue)
+            .global_work_size(full.len())
+            .arg(&*full)
+            .arg(&*output)
+            .build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? }
+
         Ok(output)
     }
 
     fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
-        let start = offset_of(coord, self.source.shape());
-        let stop = start + self.stride;
+        let n = self.n;
 
-        let strides = strides_for(self.source.shape(), self.source.ndim());
+        let (re, _im) = dft_value(&[n], &[coord[self.axis]], 1., |lane_coord| {
+            let k = lane_coord[0];
+            let mut source_coord = coord.to_vec();
 
-        (start..stop)
-            .into_par_iter()
-            .map(|offset| {
-                strides
-                    .iter()
-                    .zip(self.source.shape())
-                    .map(|(stride, dim)| (offset / stride) % dim)
-                    .collect::<Vec<usize>>()
-            })
-            .map(|source_coord| self.source.read_value(&source_coord))
-            .try_reduce(|| self.id, |r, v| Ok((self.cpu_op)(r, v)))
+            if k <= n / 2 {
+                source_coord[self.axis] = k;
+                let z = self.source.read_value(&source_coord)?;
+                Ok((z.re.to_f64(), z.im.to_f64()))
+            } else {
+                source_coord[self.axis] = n - k;
+                let z = self.source.read_value(&source_coord)?;
+                Ok((z.re.to_f64(), -z.im.to_f64()))
+            }
+        })?;
+
+        Ok(<F as CDatatype>::from_f64(re / n as f64))
+    }
+}
+
+#[cfg(feature = "fft")]
+fn check_fft_axis(shape: &[usize], axis: usize) -> Result<(), Error> {
+    if axis >= shape.len() {
+        Err(Error::Bounds(format!(
+            "invalid FFT axis {axis} for an array with shape {shape:?}"
+        )))
+    } else if shape[axis] == 0 {
+        Err(Error::Bounds(format!(
+            "cannot compute the FFT of an empty axis {axis}"
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Run a power-of-two length Stockham FFT along one axis, given as its `(dim, stride)`.
+#[cfg(all(feature = "fft", feature = "opencl"))]
+fn fft_passes<F>(
+    program: &ocl::Program,
+    queue: &Queue,
+    cl_queue: ocl::Queue,
+    input: &ocl::Buffer<Complex<F>>,
+    (n, stride): (usize, usize),
+    inverse: bool,
+) -> Result<Pooled<ocl::Buffer<Complex<F>>>, Error>
+where
+    F: Float,
+    Complex<F>: CDatatype,
+{
+    debug_assert!(n.is_power_of_two());
+
+    let new_buffer = || queue.cl_buffer::<Complex<F>>(cl_queue.clone(), input.len());
+
+    if n == 1 {
+        let output = new_buffer()?;
+        queue.chain(|wait, event| {
+            input
+                .copy(&*output, None, None)
+                .ewait(wait)
+                .enew(event)
+                .enq()
+        })?;
+        return Ok(output);
+    }
+
+    let passes = n.trailing_zeros() as usize;
+    let num_lanes = input.len() / n;
+    let sign = if inverse {
+        <F as CDatatype>::one()
+    } else {
+        <F as CDatatype>::zero() - <F as CDatatype>::one()
+    };
+    let buffers = [new_buffer()?, new_buffer()?];
+
+    for pass in 0..passes {
+        let source = if pass == 0 {
+            input
+        } else {
+            &*buffers[(pass + 1) % 2]
+        };
+
+        let scale = if inverse && pass == passes - 1 {
+            <F as CDatatype>::from_f64(1. / n as f64)
+        } else {
+            <F as CDatatype>::one()
+        };
+
+        let kernel = ocl::Kernel::builder()
+            .name("fft_pass")
+            .program(program)
+            .queue(cl_queue.clone())
+            .global_work_size((num_lanes, n / 2))
+            .arg(n as u64)
+            .arg(stride as u64)
+            .arg((1usize << pass) as u64)
+            .arg(sign)
+            .arg(scale)
+            .arg(source)
+            .arg(&*buffers[pass % 2])
+            .build()?;
+
+        unsafe { queue.enqueue_kernel(&kernel)? }
     }
+
+    let [even, odd] = buffers;
+    Ok(if (passes - 1) % 2 == 0 { even } else { odd })
+}
+
+/// Compute a single term of the discrete Fourier transform over the given `dims`,
+/// where `read` returns the (real, imaginary) value at a coordinate within those dims.
+#[cfg(feature = "fft")]
+fn dft_value<R>(dims: &[usize], index: &[usize], sign: f64, read: R) -> Result<(f64, f64), Error>
+where
+    R: Fn(&[usize]) -> Result<(f64, f64), Error> + Sync,
+{
+    let size = dims.iter().product::<usize>();
+
+    (0..size)
+        .into_par_iter()
+        .map(|offset| {
+            let lane_coord = coord_of(offset, dims);
+
+            let phase = dims
+                .iter()
+                .zip(index)
+                .zip(&lane_coord)
+                .map(|((dim, k), t)| ((k * t) % dim) as f64 / *dim as f64)
+                .sum::<f64>();
+
+            let (s, c) = (sign * 2. * std::f64::consts::PI * phase).sin_cos();
+            let (re, im) = read(&lane_coord)?;
+            Ok(((re * c) - (im * s), (re * s) + (im * c)))
+        })
+        .try_reduce(|| (0., 0.), |(lr, li), (rr, ri)| Ok((lr + rr, li + ri)))
+}
+
+/// Apply `transform` to each lane of `data` along an axis with the given `dim` and `stride`,
+/// where each transformed lane has length `out_dim`.
+#[cfg(feature = "fft")]
+fn map_lanes<I, O, T>(data: &[I], dim: usize, stride: usize, out_dim: usize, transform: T) -> Vec<O>
+where
+    I: Copy + Send + Sync,
+    O: Copy + Send + Sync,
+    T: Fn(Vec<I>) -> Vec<O> + Sync,
+{
+    let num_lanes = data.len() / dim;
+
+    let lanes = (0..num_lanes)
+        .into_par_iter()
+        .map(|lane| {
+            let base = lane_base(lane, dim, stride);
+            let lane = (0..dim).map(|k| data[base + (k * stride)]).collect();
+            let lane = transform(lane);
+            debug_assert_eq!(lane.len(), out_dim);
+            lane
+        })
+        .collect::<Vec<Vec<O>>>();
+
+    (0..num_lanes * out_dim)
+        .into_par_iter()
+        .map(|offset| {
+            let lane = ((offset / (out_dim * stride)) * stride) + (offset % stride);
+            lanes[lane][(offset / stride) % out_dim]
+        })
+        .collect()
+}
+
+#[cfg(feature = "fft")]
+#[inline]
+fn lane_base(lane: usize, dim: usize, stride: usize) -> usize {
+    ((lane / stride) * dim * stride) + (lane % stride)
+}
+
+#[cfg(feature = "fft")]
+#[inline]
+fn axis_stride(shape: &[usize], axis: usize) -> usize {
+    shape[axis + 1..].iter().product()
 }
 
 // other unary ops
@@ -1784,29 +5675,29 @@ impl<A: NDArrayRead, O: CDatatype> Op for ArrayCast<A, O> {
         self.source.context()
     }
 
-    fn enqueue_cpu(&self, queue: &Queue) -> Result<Vec<Self::Out>, Error> {
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
         let input = self.source.to_host(queue)?;
 
-        let output = input
+        let mut output = queue.host_buffer(input.len())?;
+
+        input
             .as_ref()
             .par_iter()
             .copied()
+            .with_min_len(queue.min_len(1))
             .map(|n| n.to_f64())
             .map(|f| O::from_f64(f))
-            .collect();
+            .collect_into_vec(&mut output);
 
         Ok(output)
     }
 
     #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<Self::Out>, Error> {
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
         let input = self.source.to_cl_buffer(queue)?;
         let cl_queue = input.as_ref().default_queue().expect("queue");
 
-        let output = ocl::Buffer::builder()
-            .queue(cl_queue.clone())
-            .len(input.len())
-            .build()?;
+        let output = queue.cl_buffer(cl_queue.clone(), input.len())?;
 
         let kernel = ocl::Kernel::builder()
             .name("cast_dtype")
@@ -1814,14 +5705,20 @@ impl<A: NDArrayRead, O: CDatatype> Op for ArrayCast<A, O> {
             .queue(cl_queue.clone())
             .global_work_size(input.len())
             .arg(input.as_ref())
-            .arg(&output)
+            .arg(&*output)
             .build()?;
 
-        unsafe { kernel.enq()? };
+        unsafe { queue.enqueue_kernel(&kernel)? };
 
         Ok(output)
     }
 
+    #[cfg(feature = "opencl")]
+    fn cl_compute(&self) -> bool {
+        let context = self.context();
+        context.cl_compute::<A::DType>() && context.cl_compute::<O>()
+    }
+
     fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
         let value = self.source.read_value(coord)?;
         Ok(O::from_f64(value.to_f64()))
@@ -1860,12 +5757,18 @@ impl<T: CDatatype, A: NDArray> ArrayUnary<T, T, A> {
     }
 
     /// Initialize a new natural log [`Op`].
-    pub fn ln(array: A) -> Result<Self, Error> {
+    pub fn ln(array: A) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
         Self::new(array, |n| T::from_float(n.to_float().ln()), "_log")
     }
 
     /// Initialize a new exponentiation [`Op`].
-    pub fn exp(array: A) -> Result<Self, Error> {
+    pub fn exp(array: A) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
         Self::new(array, |n| T::from_float(n.to_float().exp()), "exp")
     }
 
@@ -1873,9 +5776,183 @@ impl<T: CDatatype, A: NDArray> ArrayUnary<T, T, A> {
     pub fn round(array: A) -> Result<Self, Error> {
         Self::new(array, T::round, "round")
     }
+
+    /// Initialize a new ceiling [`Op`].
+    pub fn ceil(array: A) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(array, |n| T::from_float(n.to_float().ceil()), "_ceil")
+    }
+
+    /// Initialize a new error function [`Op`].
+    pub fn erf(array: A) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(array, |n| T::from_float(n.to_float().erf()), "_erf")
+    }
+
+    /// Initialize a new `e^x - 1` [`Op`].
+    pub fn expm1(array: A) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(array, |n| T::from_float(n.to_float().expm1()), "_expm1")
+    }
+
+    /// Initialize a new floor [`Op`].
+    pub fn floor(array: A) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(array, |n| T::from_float(n.to_float().floor()), "_floor")
+    }
+
+    /// Initialize a new `ln(1 + x)` [`Op`].
+    pub fn log1p(array: A) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(array, |n| T::from_float(n.to_float().log1p()), "_log1p")
+    }
+
+    /// Initialize a new reciprocal square root [`Op`].
+    pub fn rsqrt(array: A) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(
+            array,
+            |n| {
+                let n = n.to_float();
+                T::from_float(<T::Float as CDatatype>::one() / n.sqrt())
+            },
+            "_rsqrt",
+        )
+    }
+
+    /// Initialize a new sign [`Op`], which returns `-1`, `0`, or `1` (or `NaN` given `NaN`).
+    pub fn sign(array: A) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        fn sign<T: CDatatype + PartialOrd>(n: T) -> T {
+            if n > T::zero() {
+                T::one()
+            } else if n < T::zero() {
+                T::zero() - T::one()
+            } else {
+                n
+            }
+        }
+
+        Self::new(array, sign, "sign_")
+    }
+
+    /// Initialize a new square root [`Op`].
+    pub fn sqrt(array: A) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(array, |n| T::from_float(n.to_float().sqrt()), "_sqrt")
+    }
+
+    /// Initialize a new truncation [`Op`].
+    pub fn trunc(array: A) -> Result<Self, Error>
+    where
+        T: PartialOrd,
+    {
+        Self::new(array, |n| T::from_float(n.to_float().trunc()), "_trunc")
+    }
+}
+
+impl<T: Float, A: NDArray> ArrayUnary<T, T, A> {
+    /// Initialize a new Gaussian error linear unit [`Op`].
+    pub fn gelu(array: A) -> Result<Self, Error> {
+        fn gelu<T: Float>(x: T) -> T {
+            let x = x.to_f64();
+            T::from_f64(0.5 * x * (1. + erf(x * std::f64::consts::FRAC_1_SQRT_2)))
+        }
+
+        Self::new(array, gelu, "gelu")
+    }
+
+    /// Initialize a new Gaussian error linear unit [`Op`] using the `tanh` approximation.
+    pub fn gelu_tanh(array: A) -> Result<Self, Error> {
+        fn gelu_tanh<T: Float>(x: T) -> T {
+            const SQRT_2_OVER_PI: f64 = 0.7978845608028654;
+
+            let x = x.to_f64();
+            let inner = SQRT_2_OVER_PI * (x + 0.044715 * x * x * x);
+            T::from_f64(0.5 * x * (1. + inner.tanh()))
+        }
+
+        Self::new(array, gelu_tanh, "gelu_tanh")
+    }
+
+    /// Initialize a new hard hyperbolic tangent [`Op`], which clamps its input to `[-1, 1]`.
+    pub fn hardtanh(array: A) -> Result<Self, Error> {
+        fn hardtanh<T: Float>(x: T) -> T {
+            let min = T::zero() - T::one();
+            if x < min {
+                min
+            } else if x > T::one() {
+                T::one()
+            } else {
+                x
+            }
+        }
+
+        Self::new(array, hardtanh, "hardtanh")
+    }
+
+    /// Initialize a new rectified linear unit [`Op`].
+    pub fn relu(array: A) -> Result<Self, Error> {
+        fn relu<T: Float>(x: T) -> T {
+            if x > T::zero() {
+                x
+            } else {
+                T::zero()
+            }
+        }
+
+        Self::new(array, relu, "relu")
+    }
+
+    /// Initialize a new logistic sigmoid [`Op`].
+    pub fn sigmoid(array: A) -> Result<Self, Error> {
+        fn sigmoid<T: Float>(x: T) -> T {
+            T::one() / (T::one() + (T::zero() - x).exp())
+        }
+
+        Self::new(array, sigmoid, "sigmoid")
+    }
+
+    /// Initialize a new sigmoid linear unit (a.k.a. "swish") [`Op`].
+    pub fn silu(array: A) -> Result<Self, Error> {
+        fn silu<T: Float>(x: T) -> T {
+            x / (T::one() + (T::zero() - x).exp())
+        }
+
+        Self::new(array, silu, "silu")
+    }
+
+    /// Initialize a new softplus [`Op`].
+    pub fn softplus(array: A) -> Result<Self, Error> {
+        fn softplus<T: Float>(x: T) -> T {
+            // ln(1 + e^x) == max(x, 0) + ln(1 + e^-|x|), which does not overflow
+            let zero = T::zero();
+            let max = if x > zero { x } else { zero };
+            let min = if x > zero { zero - x } else { x };
+            max + (T::one() + min.exp()).ln()
+        }
+
+        Self::new(array, softplus, "softplus")
+    }
 }
 
-impl<T: CDatatype, A: NDArray> ArrayUnary<T, T::Float, A> {
+impl<T: CDatatype + PartialOrd, A: NDArray> ArrayUnary<T, T::Float, A> {
     /// Initialize a new arcsine [`Op`].
     pub fn asin(array: A) -> Result<Self, Error> {
         Self::new(array, |n| n.to_float().asin(), "asin")
@@ -1922,6 +5999,71 @@ impl<T: CDatatype, A: NDArray> ArrayUnary<T, T::Float, A> {
     }
 }
 
+impl<T: Integer, A: NDArray> ArrayUnary<T, T, A> {
+    /// Initialize a new bitwise not [`Op`].
+    pub fn bitnot(array: A) -> Result<Self, Error> {
+        Self::new(array, T::bitnot, "~")
+    }
+
+    /// Initialize a new [`Op`] to count the bits set in each element.
+    pub fn popcount(array: A) -> Result<Self, Error> {
+        Self::new(array, T::popcount, "popcount")
+    }
+}
+
+#[cfg(feature = "complex")]
+impl<IT: CDatatype, OT: CDatatype, A: NDArray> ArrayUnary<IT, OT, A> {
+    #[allow(unused_variables)]
+    fn complex(array: A, cpu_op: fn(IT) -> OT, cl_op: &'static str) -> Result<Self, Error> {
+        #[cfg(feature = "opencl")]
+        let cl_op = cl_programs::unary_complex::<IT, OT>(cl_op, array.context())?;
+
+        Ok(Self {
+            array,
+            cpu_op,
+            #[cfg(feature = "opencl")]
+            cl_op,
+        })
+    }
+}
+
+#[cfg(feature = "complex")]
+impl<F: Float, A: NDArray> ArrayUnary<Complex<F>, F, A>
+where
+    Complex<F>: CDatatype,
+{
+    /// Initialize a new [`Op`] to compute the phase angle of each complex element.
+    pub fn angle(array: A) -> Result<Self, Error> {
+        Self::complex(array, |z| z.im.atan2(z.re), "angle")
+    }
+
+    /// Initialize a new [`Op`] to extract the imaginary part of each complex element.
+    pub fn imag(array: A) -> Result<Self, Error> {
+        Self::complex(array, |z| z.im, "imag")
+    }
+
+    /// Initialize a new [`Op`] to compute the absolute value (modulus) of each complex element.
+    pub fn norm(array: A) -> Result<Self, Error> {
+        Self::complex(array, |z| z.re.hypot(z.im), "norm")
+    }
+
+    /// Initialize a new [`Op`] to extract the real part of each complex element.
+    pub fn real(array: A) -> Result<Self, Error> {
+        Self::complex(array, |z| z.re, "real")
+    }
+}
+
+#[cfg(feature = "complex")]
+impl<F: Float, A: NDArray> ArrayUnary<Complex<F>, Complex<F>, A>
+where
+    Complex<F>: CDatatype,
+{
+    /// Initialize a new complex conjugate [`Op`].
+    pub fn conj(array: A) -> Result<Self, Error> {
+        Self::complex(array, |z| Complex::new(z.re, F::zero() - z.im), "conj")
+    }
+}
+
 impl<T: CDatatype, A: NDArray> ArrayUnary<T, T::Neg, A> {
     pub fn neg(array: A) -> Result<Self, Error> {
         Self::new(array, T::neg, "-")
@@ -1951,27 +6093,27 @@ impl<IT: CDatatype, OT: CDatatype, A: NDArrayRead<DType = IT>> Op for ArrayUnary
         self.array.context()
     }
 
-    fn enqueue_cpu(&self, queue: &Queue) -> Result<Vec<Self::Out>, Error> {
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
         let input = self.array.to_host(queue)?;
-        let output = input
+        let mut output = queue.host_buffer(input.len())?;
+
+        input
             .as_ref()
             .par_iter()
             .copied()
+            .with_min_len(queue.min_len(1))
             .map(|n| (self.cpu_op)(n))
-            .collect();
+            .collect_into_vec(&mut output);
 
         Ok(output)
     }
 
     #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<Self::Out>, Error> {
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
         let input = self.array.to_cl_buffer(queue)?;
         let cl_queue = input.as_ref().default_queue().expect("queue").clone();
 
-        let output = ocl::Buffer::builder()
-            .queue(cl_queue.clone())
-            .len(input.len())
-            .build()?;
+        let output = queue.cl_buffer(cl_queue.clone(), input.len())?;
 
         let kernel = ocl::Kernel::builder()
             .name("unary")
@@ -1979,14 +6121,20 @@ impl<IT: CDatatype, OT: CDatatype, A: NDArrayRead<DType = IT>> Op for ArrayUnary
             .queue(cl_queue)
             .global_work_size(output.len())
             .arg(input.as_ref())
-            .arg(&output)
+            .arg(&*output)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { queue.enqueue_kernel(&kernel)? }
 
         Ok(output)
     }
 
+    #[cfg(feature = "opencl")]
+    fn cl_compute(&self) -> bool {
+        let context = self.context();
+        context.cl_compute::<IT>() && context.cl_compute::<OT>()
+    }
+
     fn read_value(&self, coord: &[usize]) -> Result<Self::Out, Error> {
         let value = self.array.read_value(coord)?;
         Ok((self.cpu_op)(value))
@@ -2037,7 +6185,7 @@ where
     }
 
     // TODO: this should only resolve elements present in the output
-    fn enqueue_cpu(&self, queue: &Queue) -> Result<Vec<Self::Out>, Error> {
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<Self::Out>>, Error> {
         let (cond, (left, right)) = try_join(
             || self.cond.to_host(queue),
             || try_join_read(&self.then, &self.or_else, queue),
@@ -2052,20 +6200,22 @@ where
             .copied()
             .zip(right.as_ref().par_iter().copied());
 
-        let output = cond
-            .as_ref()
+        let mut output = queue.host_buffer(cond.len())?;
+
+        cond.as_ref()
             .par_iter()
             .copied()
+            .with_min_len(queue.min_len(1))
             .map(|when| when != 0)
             .zip(lr)
             .map(|(when, (then, or_else))| if when { then } else { or_else })
-            .collect();
+            .collect_into_vec(&mut output);
 
         Ok(output)
     }
 
     #[cfg(feature = "opencl")]
-    fn enqueue_cl(&self, queue: &Queue) -> Result<ocl::Buffer<Self::Out>, Error> {
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<Self::Out>>, Error> {
         let cond = self.cond.to_cl_buffer(queue)?;
         let then = self.then.to_cl_buffer(&queue.split(self.then.size())?)?;
         let or_else = self
@@ -2077,10 +6227,7 @@ where
 
         let cl_queue = cond.as_ref().default_queue().expect("queue");
 
-        let output = ocl::Buffer::builder()
-            .queue(cl_queue.clone())
-            .len(cond.len())
-            .build()?;
+        let output = queue.cl_buffer(cl_queue.clone(), cond.len())?;
 
         let kernel = ocl::Kernel::builder()
             .name("gather_cond")
@@ -2090,10 +6237,10 @@ where
             .arg(cond.as_ref())
             .arg(then.as_ref())
             .arg(or_else.as_ref())
-            .arg(&output)
+            .arg(&*output)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { queue.enqueue_kernel(&kernel)? }
 
         Ok(output)
     }
@@ -2127,3 +6274,280 @@ fn try_join_read<'a, L: NDArrayRead, R: NDArrayRead>(
 ) -> Result<(SliceConverter<'a, L::DType>, SliceConverter<'a, R::DType>), Error> {
     try_join(|| left.to_host(queue), || right.to_host(queue))
 }
+
+#[inline]
+fn coord_of(offset: usize, shape: &[usize]) -> Vec<usize> {
+    strides_for(shape, shape.len())
+        .into_iter()
+        .zip(shape)
+        .map(|(stride, dim)| offset.checked_div(stride).map_or(0, |i| i % dim))
+        .collect()
+}
+
+/// Compute the mean and (biased) variance of the given values in a single pass.
+#[inline]
+fn moments<T: CDatatype>(values: &[T]) -> (T, T) {
+    let (n, mean, m2) =
+        values
+            .iter()
+            .copied()
+            .map(T::to_f64)
+            .fold((0., 0., 0.), |(n, mean, m2), x| {
+                let n = n + 1.;
+                let delta = x - mean;
+                let mean = mean + (delta / n);
+                (n, mean, m2 + (delta * (x - mean)))
+            });
+
+    (T::from_f64(mean), T::from_f64(m2 / n))
+}
+
+/// Compute the error function of `x` to within about one ulp of an `f64`,
+/// using the rational approximations of FreeBSD's `msun` library (from Sun's `fdlibm`).
+// the coefficients are copied exactly as published, although some have excess digits
+#[allow(clippy::excessive_precision)]
+pub(crate) fn erf(x: f64) -> f64 {
+    const ERX: f64 = 8.45062911510467529297e-01;
+
+    // erf(x) = x + x * R(x^2) in [0, 0.84375)
+    const PP: [f64; 5] = [
+        1.28379167095512558561e-01,
+        -3.25042107247001499370e-01,
+        -2.84817495755985104766e-02,
+        -5.77027029648944159157e-03,
+        -2.37630166566501626084e-05,
+    ];
+    const QQ: [f64; 6] = [
+        1.,
+        3.97917223959155352819e-01,
+        6.50222499887672944485e-02,
+        5.08130628187576562776e-03,
+        1.32494738004321644526e-04,
+        -3.96022827877536812320e-06,
+    ];
+
+    // erf(1 + s) = erx + P(s) / Q(s) in [0.84375, 1.25)
+    const PA: [f64; 7] = [
+        -2.36211856075265944077e-03,
+        4.14856118683748331666e-01,
+        -3.72207876035701323847e-01,
+        3.18346619901161753674e-01,
+        -1.10894694282396677476e-01,
+        3.54783043256182359371e-02,
+        -2.16637559486879084300e-03,
+    ];
+    const QA: [f64; 7] = [
+        1.,
+        1.06420880400844228286e-01,
+        5.40397917702171048937e-01,
+        7.18286544141962662868e-02,
+        1.26171219808761642112e-01,
+        1.36370839120290507362e-02,
+        1.19844998467991074170e-02,
+    ];
+
+    // erfc(x) = exp(-x^2 - 0.5625 + R(1 / x^2) / S(1 / x^2)) / x in [1.25, 1 / 0.35)
+    const RA: [f64; 8] = [
+        -9.86494403484714822705e-03,
+        -6.93858572707181764372e-01,
+        -1.05586262253232909814e+01,
+        -6.23753324503260060396e+01,
+        -1.62396669462573470355e+02,
+        -1.84605092906711035994e+02,
+        -8.12874355063065934246e+01,
+        -9.81432934416914548592e+00,
+    ];
+    const SA: [f64; 9] = [
+        1.,
+        1.96512716674392571292e+01,
+        1.37657754143519042600e+02,
+        4.34565877475229228821e+02,
+        6.45387271733267880336e+02,
+        4.29008140027567833386e+02,
+        1.08635005541779435134e+02,
+        6.57024977031928170135e+00,
+        -6.04244152148580987438e-02,
+    ];
+
+    // and likewise in [1 / 0.35, 6)
+    const RB: [f64; 7] = [
+        -9.86494292470009928597e-03,
+        -7.99283237680523006574e-01,
+        -1.77579549177547519889e+01,
+        -1.60636384855821916062e+02,
+        -6.37566443368389627722e+02,
+        -1.02509513161107724954e+03,
+        -4.83519191608651397019e+02,
+    ];
+    const SB: [f64; 8] = [
+        1.,
+        3.03380607434824582924e+01,
+        3.25792512996573918826e+02,
+        1.53672958608443695994e+03,
+        3.19985821950859553908e+03,
+        2.55305040643316442583e+03,
+        4.74528541206955367215e+02,
+        -2.24409524465858183362e+01,
+    ];
+
+    // evaluate the polynomial with the given coefficients at `x` by Horner's method
+    fn poly(coefficients: &[f64], x: f64) -> f64 {
+        coefficients.iter().rev().fold(0., |acc, c| (acc * x) + c)
+    }
+
+    if x.is_nan() {
+        return x;
+    }
+
+    let z = x.abs();
+
+    if z < 0.84375 {
+        let x2 = x * x;
+        return x + (x * (poly(&PP, x2) / poly(&QQ, x2)));
+    }
+
+    let erf = if z < 1.25 {
+        let s = z - 1.;
+        ERX + (poly(&PA, s) / poly(&QA, s))
+    } else if z < 6. {
+        let s = 1. / (z * z);
+        let (r, s) = if z < 1. / 0.35 {
+            (poly(&RA, s), poly(&SA, s))
+        } else {
+            (poly(&RB, s), poly(&SB, s))
+        };
+
+        // split z into a high part, whose square is exact, and a low part
+        let hi = f64::from_bits(z.to_bits() & 0xffff_ffff_0000_0000);
+        let erfc = (-hi * hi - 0.5625).exp() * (((hi - z) * (hi + z)) + (r / s)).exp() / z;
+        1. - erfc
+    } else {
+        1.
+    };
+
+    erf.copysign(x)
+}
+
+/// Compute an [`Op`] on the host and copy the result back to the device,
+/// for ops (or parameters, like FFT lengths) which have no OpenCL kernel.
+#[cfg(feature = "opencl")]
+fn enqueue_on_host<O: Op>(op: &O, queue: &Queue) -> Result<Pooled<ocl::Buffer<O::Out>>, Error> {
+    let output = queue.context().install(|| op.enqueue_cpu(queue))?;
+    cl_buffer_from(queue, queue.cl_queue(None), &output)
+}
+
+// draw a device buffer from the pool of the `queue`'s context and copy `data` into it
+#[cfg(feature = "opencl")]
+fn cl_buffer_from<T: CDatatype>(
+    queue: &Queue,
+    cl_queue: ocl::Queue,
+    data: &[T],
+) -> Result<Pooled<ocl::Buffer<T>>, Error> {
+    let buffer = queue.cl_buffer(cl_queue, data.len())?;
+    queue.chain(|wait, event| buffer.write(data).ewait(wait).enew(event).enq())?;
+    Ok(buffer)
+}
+
+// draw a device buffer of `len` copies of `value` from the pool of the `queue`'s context
+#[cfg(feature = "opencl")]
+fn cl_buffer_of<T: CDatatype>(
+    queue: &Queue,
+    cl_queue: ocl::Queue,
+    len: usize,
+    value: T,
+) -> Result<Pooled<ocl::Buffer<T>>, Error> {
+    let buffer = queue.cl_buffer(cl_queue, len)?;
+    queue.chain(|wait, event| buffer.cmd().fill(value, None).ewait(wait).enew(event).enq())?;
+    Ok(buffer)
+}
+
+#[inline]
+#[cfg(feature = "opencl")]
+fn check_overflow(flag: &ocl::Buffer<u8>) -> Result<(), Error> {
+    let mut overflow = [0u8];
+    flag.read(&mut overflow[..]).enq()?;
+
+    if overflow[0] == 0 {
+        Ok(())
+    } else {
+        Err(Error::Bounds("integer overflow".to_string()))
+    }
+}
+
+fn overflow<T: CDatatype>(left: T, right: T) -> Error {
+    Error::Bounds(format!(
+        "integer overflow in {} arithmetic with operands {} and {}",
+        T::TYPE_STR,
+        left.to_f64(),
+        right.to_f64()
+    ))
+}
+
+// the batch dimensions of a matrix, or vector, with the given shape
+#[inline]
+fn matrix_batch(shape: &[usize]) -> &[usize] {
+    &shape[..shape.len().saturating_sub(2)]
+}
+
+// the dimensions of a matrix or batch of matrices
+fn matrix_dims(shape: &[usize]) -> Result<[usize; 2], Error> {
+    let ndim = shape.len();
+
+    if ndim >= 2 && shape[ndim - 2] > 0 && shape[ndim - 1] > 0 {
+        Ok([shape[ndim - 2], shape[ndim - 1]])
+    } else {
+        Err(Error::Bounds(format!(
+            "expected a matrix or batch of matrices, not {shape:?}"
+        )))
+    }
+}
+
+// the broadcast batch dimensions of two matrix (or vector) operands
+fn broadcast_batch(left: &[usize], right: &[usize]) -> Result<Shape, Error> {
+    match (matrix_batch(left), matrix_batch(right)) {
+        ([], batch) | (batch, []) => Ok(batch.to_vec()),
+        (l, r) => broadcast_shape(l, r),
+    }
+}
+
+// the index of the left and right operand matrix of each matrix in the broadcast `batch`
+fn batch_offsets(batch: &[usize], left: &[usize], right: &[usize]) -> (Vec<usize>, Vec<usize>) {
+    let ndim = batch.len();
+    let left_strides = strides_for(matrix_batch(left), ndim);
+    let right_strides = strides_for(matrix_batch(right), ndim);
+    let num_matrices = batch.iter().product::<usize>();
+
+    (0..num_matrices)
+        .map(|n| {
+            let mut left = 0;
+            let mut right = 0;
+            let mut n = n;
+
+            for x in (0..ndim).rev() {
+                let i = n % batch[x];
+                n /= batch[x];
+                left += i * left_strides[x];
+                right += i * right_strides[x];
+            }
+
+            (left, right)
+        })
+        .unzip()
+}
+
+fn maximum<T: PartialOrd>(l: T, r: T) -> T {
+    if r > l {
+        r
+    } else {
+        l
+    }
+}
+
+#[inline]
+fn minimum<T: PartialOrd>(l: T, r: T) -> T {
+    if r < l {
+        r
+    } else {
+        l
+    }
+}
@@ -0,0 +1,445 @@
+//! A size-bucketed pool of host and device buffers, to reuse memory across ops
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
+
+use super::{CDatatype, Error};
+
+/// The free list of a [`BufferPool`] to which a buffer belongs
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PoolKey {
+    dtype: TypeId,
+    #[cfg(feature = "opencl")]
+    device: Option<ocl::Device>,
+    bucket: usize,
+}
+
+impl PoolKey {
+    fn host<T: 'static>(bucket: usize) -> Self {
+        Self {
+            dtype: TypeId::of::<T>(),
+            #[cfg(feature = "opencl")]
+            device: None,
+            bucket,
+        }
+    }
+
+    #[cfg(feature = "opencl")]
+    fn device<T: 'static>(device: ocl::Device, len: usize) -> Self {
+        Self {
+            dtype: TypeId::of::<T>(),
+            device: Some(device),
+            bucket: len,
+        }
+    }
+}
+
+/// A buffer which a [`BufferPool`] can hold for reuse
+pub trait Recycle: Send + Sync + Sized + 'static {
+    /// The free list to which to return this buffer.
+    fn key(&self) -> PoolKey;
+
+    /// The size of this buffer in bytes.
+    fn size(&self) -> usize;
+
+    /// Return `true` if this buffer may still be referenced elsewhere,
+    /// in which case it must not be reused.
+    fn is_shared(&self) -> bool {
+        false
+    }
+
+    #[cfg(feature = "opencl")]
+    /// Enqueue a marker which completes when all the work enqueued so far with this buffer
+    /// is complete, for the pool to wait on before this buffer is reused.
+    fn marker(&self) -> Result<Option<ocl::Event>, Error> {
+        Ok(None)
+    }
+}
+
+impl<T: CDatatype> Recycle for Vec<T> {
+    fn key(&self) -> PoolKey {
+        // file this buffer under the largest bucket it can hold
+        let bucket = match self.capacity() {
+            0 => 0,
+            capacity => 1 << capacity.ilog2(),
+        };
+
+        PoolKey::host::<T>(bucket)
+    }
+
+    fn size(&self) -> usize {
+        self.capacity() * std::mem::size_of::<T>()
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<T: CDatatype> Recycle for ocl::Buffer<T> {
+    fn key(&self) -> PoolKey {
+        let device = self.default_queue().map(|cl_queue| cl_queue.device());
+
+        PoolKey {
+            dtype: TypeId::of::<T>(),
+            device,
+            bucket: self.len(),
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.len() * std::mem::size_of::<T>()
+    }
+
+    fn is_shared(&self) -> bool {
+        use ocl::enums::{MemInfo, MemInfoResult};
+
+        match ocl::core::get_mem_object_info(self.as_core(), MemInfo::ReferenceCount) {
+            Ok(MemInfoResult::ReferenceCount(count)) => count > 1,
+            _ => true,
+        }
+    }
+
+    fn marker(&self) -> Result<Option<ocl::Event>, Error> {
+        // a kernel on this buffer's queue may still be reading it, and the next op to draw it
+        // may enqueue its kernels on a different queue, so the buffer is only reusable
+        // once all the work enqueued so far on its queue is complete
+        if let Some(cl_queue) = self.default_queue() {
+            let marker = cl_queue.enqueue_marker::<&ocl::Event>(None)?;
+            Ok(Some(marker))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Usage statistics of a [`BufferPool`]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct PoolStats {
+    /// The number of buffers drawn from the pool which were reused
+    pub hits: usize,
+    /// The number of buffers drawn from the pool which had to be allocated
+    pub misses: usize,
+    /// The number of cached buffers freed to stay within the memory budget
+    pub evictions: usize,
+    /// The number of bytes drawn from or adopted by the pool and not yet returned to it
+    pub in_use: usize,
+    /// The number of bytes held by the pool for reuse
+    pub cached: usize,
+    /// The maximum number of bytes in use or cached at any one time
+    pub peak: usize,
+}
+
+impl PoolStats {
+    /// The total number of bytes in use or cached.
+    pub fn total(&self) -> usize {
+        self.in_use + self.cached
+    }
+}
+
+// a cached buffer with its size in bytes, which is only known to its concrete type
+struct Cached {
+    size: usize,
+    buffer: Box<dyn Any + Send + Sync>,
+    #[cfg(feature = "opencl")]
+    marker: Option<ocl::Event>,
+}
+
+#[derive(Default)]
+struct PoolState {
+    free: HashMap<PoolKey, Vec<Cached>>,
+    stats: PoolStats,
+}
+
+impl PoolState {
+    // free cached buffers until `size` more bytes fit within the `budget`
+    fn evict(&mut self, budget: usize, size: usize) {
+        while self.stats.total() + size > budget && self.stats.cached > 0 {
+            let key = self.free.keys().next().cloned().expect("cached buffer");
+            let buffers = self.free.get_mut(&key).expect("free list");
+
+            if let Some(cached) = buffers.pop() {
+                self.stats.cached -= cached.size;
+                self.stats.evictions += 1;
+            }
+
+            if buffers.is_empty() {
+                self.free.remove(&key);
+            }
+        }
+    }
+}
+
+/// A size-bucketed cache of host and device buffers which ops draw their output buffers from
+/// and which arrays return their buffers to when dropped,
+/// with an optional budget for the total memory in use or cached.
+///
+/// Host buffers are bucketed by capacity in powers of two, so a buffer can be reused
+/// by any op whose output fits in its bucket. Device buffers have a fixed length,
+/// so they are bucketed by device and exact length.
+/// A device buffer is only reused once all the work enqueued with it before it was returned
+/// to the pool is complete, so drawing a device buffer may block until then.
+pub struct BufferPool {
+    budget: Option<usize>,
+    state: Mutex<PoolState>,
+}
+
+impl BufferPool {
+    /// Construct a new [`BufferPool`] with an optional memory `budget` in bytes.
+    pub fn new(budget: Option<usize>) -> Self {
+        Self {
+            budget,
+            state: Mutex::new(PoolState::default()),
+        }
+    }
+
+    /// The memory budget of this pool in bytes, if any.
+    pub fn budget(&self) -> Option<usize> {
+        self.budget
+    }
+
+    /// Return the current usage statistics of this pool.
+    pub fn stats(&self) -> PoolStats {
+        self.state().stats
+    }
+
+    /// Free all the buffers cached by this pool.
+    pub fn clear(&self) {
+        let mut state = self.state();
+        state.free.clear();
+        state.stats.cached = 0;
+    }
+
+    /// Draw an empty host buffer with a capacity of at least `len` elements from this pool.
+    pub fn host_buffer<T: CDatatype>(
+        self: &Arc<Self>,
+        len: usize,
+    ) -> Result<Pooled<Vec<T>>, Error> {
+        let bucket = len.next_power_of_two();
+        let key = PoolKey::host::<T>(bucket);
+        let size = bucket * std::mem::size_of::<T>();
+
+        let mut buffer = self.draw(key, size, || Ok(Vec::with_capacity(bucket)))?;
+        buffer.clear();
+
+        Ok(buffer)
+    }
+
+    #[cfg(feature = "opencl")]
+    /// Draw a buffer of exactly `len` elements on the device of `cl_queue` from this pool.
+    /// The contents of the returned buffer are unspecified.
+    pub fn cl_buffer<T: CDatatype>(
+        self: &Arc<Self>,
+        cl_queue: ocl::Queue,
+        len: usize,
+    ) -> Result<Pooled<ocl::Buffer<T>>, Error> {
+        let key = PoolKey::device::<T>(cl_queue.device(), len);
+        let size = len * std::mem::size_of::<T>();
+
+        let mut buffer = self.draw(key, size, || {
+            ocl::Buffer::builder()
+                .queue(cl_queue.clone())
+                .len(len)
+                .build()
+                .map_err(Error::from)
+        })?;
+
+        buffer.set_default_queue(cl_queue);
+
+        Ok(buffer)
+    }
+
+    /// Track a `buffer` allocated outside this pool as in use,
+    /// so that it's returned to this pool when dropped.
+    /// Returns [`Error::OutOfMemory`] if the buffer does not fit within the budget of this pool.
+    pub fn adopt<B: Recycle>(self: &Arc<Self>, buffer: B) -> Result<Pooled<B>, Error> {
+        let size = buffer.size();
+        let mut state = self.state();
+        self.reserve(&mut state, size)?;
+
+        Ok(Pooled {
+            buffer: Some(buffer),
+            size,
+            pool: Some(self.clone()),
+        })
+    }
+
+    // draw a buffer from the free list with the given `key`,
+    // or else allocate a new buffer of `size` bytes if it fits within the budget
+    fn draw<B, F>(self: &Arc<Self>, key: PoolKey, size: usize, alloc: F) -> Result<Pooled<B>, Error>
+    where
+        B: Recycle,
+        F: FnOnce() -> Result<B, Error>,
+    {
+        let mut state = self.state();
+
+        if let Some(cached) = state.free.get_mut(&key).and_then(|buffers| buffers.pop()) {
+            let buffer = *cached.buffer.downcast::<B>().expect("pooled buffer");
+            state.stats.cached -= cached.size;
+            state.stats.in_use += cached.size;
+            state.stats.hits += 1;
+            std::mem::drop(state);
+
+            let buffer = Pooled {
+                buffer: Some(buffer),
+                size: cached.size,
+                pool: Some(self.clone()),
+            };
+
+            #[cfg(feature = "opencl")]
+            if let Some(marker) = cached.marker {
+                marker.wait_for().map_err(ocl::Error::from)?;
+            }
+
+            return Ok(buffer);
+        }
+
+        state.stats.misses += 1;
+
+        // reserve the new buffer before allocating it, so that a concurrent draw can't exceed the budget
+        self.reserve(&mut state, size)?;
+        std::mem::drop(state);
+
+        match alloc() {
+            Ok(buffer) => {
+                let mut state = self.state();
+                state.stats.in_use = state.stats.in_use - size + buffer.size();
+                state.stats.peak = Ord::max(state.stats.peak, state.stats.total());
+
+                Ok(Pooled {
+                    size: buffer.size(),
+                    buffer: Some(buffer),
+                    pool: Some(self.clone()),
+                })
+            }
+            Err(cause) => {
+                self.release(size);
+                Err(cause)
+            }
+        }
+    }
+
+    // count `size` more bytes as in use, evicting cached buffers as needed to stay within the budget
+    fn reserve(&self, state: &mut PoolState, size: usize) -> Result<(), Error> {
+        if let Some(budget) = self.budget {
+            state.evict(budget, size);
+
+            if state.stats.total() + size > budget {
+                return Err(Error::OutOfMemory(format!(
+                    "cannot allocate {size} bytes with {} of a {budget} byte budget in use",
+                    state.stats.in_use
+                )));
+            }
+        }
+
+        state.stats.in_use += size;
+        state.stats.peak = Ord::max(state.stats.peak, state.stats.total());
+
+        Ok(())
+    }
+
+    // return a buffer which was counted as in use with the given size in bytes
+    fn recycle<B: Recycle>(&self, buffer: B, counted: usize) {
+        let size = buffer.size();
+
+        if buffer.is_shared() || size == 0 {
+            return self.release(counted);
+        }
+
+        #[cfg(feature = "opencl")]
+        let Ok(marker) = buffer.marker() else {
+            return self.release(counted);
+        };
+
+        let mut state = self.state();
+        state.stats.in_use -= counted;
+
+        if let Some(budget) = self.budget {
+            state.evict(budget, size);
+
+            if state.stats.total() + size > budget {
+                return;
+            }
+        }
+
+        state.stats.cached += size;
+        state.free.entry(buffer.key()).or_default().push(Cached {
+            size,
+            buffer: Box::new(buffer),
+            #[cfg(feature = "opencl")]
+            marker,
+        });
+    }
+
+    fn release(&self, size: usize) {
+        self.state().stats.in_use -= size;
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, PoolState> {
+        self.state.lock().expect("buffer pool")
+    }
+}
+
+impl Default for BufferPool {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl fmt::Debug for BufferPool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "buffer pool with stats {:?}", self.stats())
+    }
+}
+
+/// A buffer drawn from a [`BufferPool`], which is returned to the pool when dropped
+pub struct Pooled<B: Recycle> {
+    buffer: Option<B>,
+    size: usize,
+    pool: Option<Arc<BufferPool>>,
+}
+
+impl<B: Recycle> Pooled<B> {
+    /// Detach this buffer from its pool and return it.
+    pub fn into_inner(mut self) -> B {
+        let buffer = self.buffer.take().expect("pooled buffer");
+
+        if let Some(pool) = self.pool.take() {
+            pool.release(self.size);
+        }
+
+        buffer
+    }
+}
+
+impl<B: Recycle + Clone> Clone for Pooled<B> {
+    fn clone(&self) -> Self {
+        Self {
+            buffer: self.buffer.clone(),
+            size: self.size,
+            pool: None,
+        }
+    }
+}
+
+impl<B: Recycle> Deref for Pooled<B> {
+    type Target = B;
+
+    fn deref(&self) -> &B {
+        self.buffer.as_ref().expect("pooled buffer")
+    }
+}
+
+impl<B: Recycle> DerefMut for Pooled<B> {
+    fn deref_mut(&mut self) -> &mut B {
+        self.buffer.as_mut().expect("pooled buffer")
+    }
+}
+
+impl<B: Recycle> Drop for Pooled<B> {
+    fn drop(&mut self) {
+        if let (Some(buffer), Some(pool)) = (self.buffer.take(), self.pool.take()) {
+            pool.recycle(buffer, self.size);
+        }
+    }
+}
@@ -0,0 +1,449 @@
+use std::fmt;
+use std::ops::{Add, Mul, Range};
+use std::sync::Arc;
+
+use rayon::prelude::*;
+
+use super::{
+    reduce_axes, ArrayBase, Buffer, BufferConverter, BufferReduce, CDatatype, Context, Error,
+    NDArray, NDArrayRead, NDArrayReduce, Queue, Shape,
+};
+
+/// An n-dimensional array partitioned along one axis across the devices of its [`Context`],
+/// for data-parallel computation.
+///
+/// Each shard is resident on its own device (or the host, if there is no OpenCL device)
+/// and elementwise operations and reductions are computed on every shard in parallel.
+/// Reductions over the sharded axis are combined ("all-reduced") on the host;
+/// other results are gathered back into a single array.
+pub struct ShardedArray<T: CDatatype> {
+    context: Context,
+    shape: Shape,
+    axis: usize,
+    shards: Vec<Shard<T>>,
+}
+
+impl<T: CDatatype> ShardedArray<T> {
+    /// Partition the `source` array along `axis` into one shard per device in its [`Context`],
+    /// or fewer if `axis` is shorter than the number of devices.
+    pub fn new<A: NDArrayRead<DType = T>>(source: &A, axis: usize) -> Result<Self, Error> {
+        let dim = source.shape().get(axis).copied().ok_or_else(|| {
+            Error::Bounds(format!("{source:?} has no axis {axis} to shard along"))
+        })?;
+
+        let num_devices = Ord::max(source.context().platform().devices().len(), 1);
+
+        Self::with_shards(source, axis, Ord::min(num_devices, dim))
+    }
+
+    /// Partition the `source` array along `axis` into `num_shards` shards,
+    /// assigned to the devices of its [`Context`] in round-robin order.
+    pub fn with_shards<A: NDArrayRead<DType = T>>(
+        source: &A,
+        axis: usize,
+        num_shards: usize,
+    ) -> Result<Self, Error> {
+        let shape = source.shape().to_vec();
+
+        let dim = shape.get(axis).copied().ok_or_else(|| {
+            Error::Bounds(format!("{source:?} has no axis {axis} to shard along"))
+        })?;
+
+        if num_shards == 0 || num_shards > dim {
+            return Err(Error::Bounds(format!(
+                "cannot partition axis {axis} of {source:?} into {num_shards} shards"
+            )));
+        }
+
+        let context = source.context().clone();
+        let queue = Queue::new(context.clone(), source.size())?;
+        let data = source.read(&queue)?.to_slice()?;
+        let data = data.as_ref();
+
+        let inner = shape[axis + 1..].iter().product::<usize>();
+
+        let shards = queues(&context, num_shards)?
+            .into_par_iter()
+            .zip(partition(dim, num_shards))
+            .map(|(queue, range)| {
+                let mut shard_shape = shape.clone();
+                shard_shape[axis] = range.len();
+
+                let shard = data
+                    .chunks_exact(dim * inner)
+                    .flat_map(|block| &block[range.start * inner..range.end * inner])
+                    .copied()
+                    .collect::<Vec<T>>();
+
+                Shard::new(queue, shard_shape, shard.into())
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(Self {
+            context,
+            shape,
+            axis,
+            shards,
+        })
+    }
+
+    /// Borrow the execution [`Context`] of this array.
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    /// Borrow the shape of this array.
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Return the axis along which this array is partitioned.
+    pub fn axis(&self) -> usize {
+        self.axis
+    }
+
+    /// Return the number of shards in this array.
+    pub fn num_shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Compute the elementwise operation `op` on each shard, on the device of that shard.
+    pub fn map<F, A>(&self, op: F) -> Result<ShardedArray<A::DType>, Error>
+    where
+        F: Fn(ArrayBase<Arc<Buffer<T>>>) -> Result<A, Error> + Send + Sync,
+        A: NDArrayRead,
+    {
+        let shards = self
+            .shards
+            .par_iter()
+            .map(|shard| shard.output(op(shard.array.clone())?))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(ShardedArray {
+            context: self.context.clone(),
+            shape: self.shape.clone(),
+            axis: self.axis,
+            shards,
+        })
+    }
+
+    /// Compute the elementwise operation `op` on each pair of corresponding shards
+    /// of this array and `other`, on the device of the shard of this array.
+    pub fn zip<U, F, A>(
+        &self,
+        other: &ShardedArray<U>,
+        op: F,
+    ) -> Result<ShardedArray<A::DType>, Error>
+    where
+        U: CDatatype,
+        F: Fn(ArrayBase<Arc<Buffer<T>>>, ArrayBase<Arc<Buffer<U>>>) -> Result<A, Error>
+            + Send
+            + Sync,
+        A: NDArrayRead,
+    {
+        let partitioned_alike = self.shape == other.shape
+            && self.axis == other.axis
+            && self.shards.len() == other.shards.len()
+            && self
+                .shards
+                .iter()
+                .zip(&other.shards)
+                .all(|(l, r)| l.array.shape() == r.array.shape());
+
+        if !partitioned_alike {
+            return Err(Error::Bounds(format!(
+                "cannot zip {self:?} with {other:?} since they are partitioned differently"
+            )));
+        }
+
+        let shards = self
+            .shards
+            .par_iter()
+            .zip(&other.shards)
+            .map(|(l, r)| l.output(op(l.array.clone(), r.array.clone())?))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(ShardedArray {
+            context: self.context.clone(),
+            shape: self.shape.clone(),
+            axis: self.axis,
+            shards,
+        })
+    }
+
+    /// Copy the shards of this array back into a single array in host memory.
+    pub fn gather(&self) -> Result<ArrayBase<Vec<T>>, Error> {
+        let shards = self
+            .shards
+            .par_iter()
+            .map(Shard::to_vec)
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let dims = self
+            .shards
+            .iter()
+            .map(|shard| shard.array.shape()[self.axis])
+            .collect::<Vec<_>>();
+
+        let data = concatenate(shards, &dims, &self.shape, self.axis);
+        ArrayBase::<Vec<T>>::with_context(self.context.clone(), self.shape.clone(), data)
+    }
+
+    /// Return the maximum element in this array.
+    pub fn max_all(&self) -> Result<T, Error>
+    where
+        T: PartialOrd,
+    {
+        self.reduce_all(|data, queue| data.max(queue), max, T::min())
+    }
+
+    /// Return the minimum element in this array.
+    pub fn min_all(&self) -> Result<T, Error>
+    where
+        T: PartialOrd,
+    {
+        self.reduce_all(|data, queue| data.min(queue), min, T::max())
+    }
+
+    /// Return the product of all elements in this array.
+    pub fn product_all(&self) -> Result<T, Error> {
+        self.reduce_all(|data, queue| data.product(queue), Mul::mul, T::one())
+    }
+
+    /// Return the sum of all elements in this array.
+    pub fn sum_all(&self) -> Result<T, Error> {
+        self.reduce_all(|data, queue| data.sum(queue), Add::add, T::zero())
+    }
+
+    /// Compute the maximum of this array over the given `axes`.
+    pub fn max(&self, axes: Vec<usize>, keepdims: bool) -> Result<ArrayBase<Vec<T>>, Error>
+    where
+        T: PartialOrd,
+    {
+        self.reduce(axes, keepdims, |shard, axes| shard.max(axes, keepdims), max)
+    }
+
+    /// Compute the minimum of this array over the given `axes`.
+    pub fn min(&self, axes: Vec<usize>, keepdims: bool) -> Result<ArrayBase<Vec<T>>, Error>
+    where
+        T: PartialOrd,
+    {
+        self.reduce(axes, keepdims, |shard, axes| shard.min(axes, keepdims), min)
+    }
+
+    /// Compute the product of this array over the given `axes`.
+    pub fn product(&self, axes: Vec<usize>, keepdims: bool) -> Result<ArrayBase<Vec<T>>, Error> {
+        self.reduce(
+            axes,
+            keepdims,
+            |shard, axes| shard.product(axes, keepdims),
+            Mul::mul,
+        )
+    }
+
+    /// Compute the sum of this array over the given `axes`.
+    pub fn sum(&self, axes: Vec<usize>, keepdims: bool) -> Result<ArrayBase<Vec<T>>, Error> {
+        self.reduce(
+            axes,
+            keepdims,
+            |shard, axes| shard.sum(axes, keepdims),
+            Add::add,
+        )
+    }
+
+    // reduce each shard on its own device, then combine the per-shard results on the host
+    fn reduce_all<R>(&self, reduce: R, combine: fn(T, T) -> T, init: T) -> Result<T, Error>
+    where
+        R: Fn(BufferConverter<T>, &Queue) -> Result<T, Error> + Send + Sync,
+    {
+        self.shards
+            .par_iter()
+            .map(|shard| reduce(shard.array.read(&shard.queue)?, &shard.queue))
+            .try_reduce(|| init, |l, r| Ok(combine(l, r)))
+    }
+
+    // reduce each shard over `axes` on its own device, then either all-reduce the results
+    // (if the sharded axis is reduced) or gather them (if it's not)
+    fn reduce<R, A>(
+        &self,
+        mut axes: Vec<usize>,
+        keepdims: bool,
+        reduce: R,
+        combine: fn(T, T) -> T,
+    ) -> Result<ArrayBase<Vec<T>>, Error>
+    where
+        R: Fn(ArrayBase<Arc<Buffer<T>>>, Vec<usize>) -> Result<A, Error> + Send + Sync,
+        A: NDArrayRead<DType = T>,
+    {
+        axes.sort();
+        axes.dedup();
+
+        let shape = reduce_axes(&self.shape, &axes, keepdims)?;
+
+        let outputs = self
+            .shards
+            .par_iter()
+            .map(|shard| {
+                let output = reduce(shard.array.clone(), axes.clone())?;
+                let dims = output.shape().to_vec();
+                let data = output.read(&shard.queue)?.to_slice()?.into_vec();
+                Ok((dims, data))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let data = if axes.contains(&self.axis) {
+            outputs
+                .into_iter()
+                .map(|(_dims, data)| data)
+                .reduce(|l, r| {
+                    l.into_par_iter()
+                        .zip(r)
+                        .map(|(l, r)| combine(l, r))
+                        .collect()
+                })
+                .expect("shard")
+        } else {
+            let axis = if keepdims {
+                self.axis
+            } else {
+                self.axis - axes.iter().filter(|x| **x < self.axis).count()
+            };
+
+            let (dims, outputs): (Vec<_>, Vec<_>) = outputs
+                .into_iter()
+                .map(|(dims, data)| (dims[axis], data))
+                .unzip();
+
+            concatenate(outputs, &dims, &shape, axis)
+        };
+
+        ArrayBase::<Vec<T>>::with_context(self.context.clone(), shape, data)
+    }
+}
+
+impl<T: CDatatype> fmt::Debug for ShardedArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "array of shape {:?} sharded along axis {} into {} shards",
+            self.shape,
+            self.axis,
+            self.shards.len()
+        )
+    }
+}
+
+// one partition of a sharded array, resident on the device of its queue
+struct Shard<T: CDatatype> {
+    queue: Queue,
+    array: ArrayBase<Arc<Buffer<T>>>,
+}
+
+impl<T: CDatatype> Shard<T> {
+    fn new(queue: Queue, shape: Shape, data: BufferConverter<T>) -> Result<Self, Error> {
+        #[cfg(feature = "opencl")]
+        let data = if queue.cl_queue.is_some() {
+            data.to_cl(&queue)?.into_buffer().map(Buffer::CL)?
+        } else {
+            Buffer::Host(data.to_slice()?.into_vec())
+        };
+
+        #[cfg(not(feature = "opencl"))]
+        let data = data.into_buffer()?;
+
+        let context = queue.context().clone();
+        let array = ArrayBase::<Arc<Buffer<T>>>::with_context(context, shape, Arc::new(data))?;
+        Ok(Self { queue, array })
+    }
+
+    // compute the result of an elementwise operation on this shard, on the same device
+    fn output<A: NDArrayRead>(&self, output: A) -> Result<Shard<A::DType>, Error> {
+        if output.shape() == self.array.shape() {
+            let data = output.read(&self.queue)?;
+            Shard::new(self.queue.clone(), output.shape().to_vec(), data)
+        } else {
+            Err(Error::Bounds(format!(
+                "an elementwise operation on a shard of shape {:?} returned {output:?}",
+                self.array.shape()
+            )))
+        }
+    }
+
+    fn to_vec(&self) -> Result<Vec<T>, Error> {
+        let data = self.array.read(&self.queue)?;
+        data.to_slice().map(|data| data.into_vec())
+    }
+}
+
+// a queue for each of `num_shards` shards, assigned to the devices of `context` in turn
+fn queues(context: &Context, num_shards: usize) -> Result<Vec<Queue>, Error> {
+    #[cfg(feature = "opencl")]
+    {
+        let devices = context.platform().devices();
+
+        if !devices.is_empty() {
+            return devices
+                .iter()
+                .cycle()
+                .take(num_shards)
+                .map(|device| Queue::with_device(context.clone(), device))
+                .collect();
+        }
+    }
+
+    Ok(vec![Queue::default(context.clone()); num_shards])
+}
+
+// split an axis of length `dim` into `num_shards` contiguous ranges whose lengths
+// differ by at most one
+fn partition(dim: usize, num_shards: usize) -> Vec<Range<usize>> {
+    let (len, remainder) = (dim / num_shards, dim % num_shards);
+
+    let mut start = 0;
+    (0..num_shards)
+        .map(|i| {
+            let stop = start + len + usize::from(i < remainder);
+            let range = start..stop;
+            start = stop;
+            range
+        })
+        .collect()
+}
+
+// interleave `shards` of the given lengths `dims` along `axis` of an array with the given `shape`
+fn concatenate<T: Copy>(
+    shards: Vec<Vec<T>>,
+    dims: &[usize],
+    shape: &[usize],
+    axis: usize,
+) -> Vec<T> {
+    let inner = shape[axis + 1..].iter().product::<usize>();
+    let outer = shape[..axis].iter().product::<usize>();
+
+    let mut data = Vec::with_capacity(shape.iter().product());
+
+    for i in 0..outer {
+        for (shard, dim) in shards.iter().zip(dims) {
+            let len = dim * inner;
+            data.extend_from_slice(&shard[i * len..(i + 1) * len]);
+        }
+    }
+
+    data
+}
+
+fn max<T: PartialOrd>(l: T, r: T) -> T {
+    if r > l {
+        r
+    } else {
+        l
+    }
+}
+
+fn min<T: PartialOrd>(l: T, r: T) -> T {
+    if r < l {
+        r
+    } else {
+        l
+    }
+}
@@ -0,0 +1,59 @@
+use ha_ndarray::*;
+
+fn assert_close<A>(expected: Vec<f64>, actual: A) -> Result<(), Error>
+where
+    A: NDArrayRead<DType = f64>,
+{
+    let expected = ArrayBase::<Vec<f64>>::new(vec![expected.len()], expected)?;
+    assert!(expected.allclose(actual, 0., 1e-6, false)?);
+    Ok(())
+}
+
+#[test]
+fn test_activation() -> Result<(), Error> {
+    let context = Context::new(0, 0, None)?;
+    let data = vec![-2., -0.5, 0., 0.5, 2.];
+    let array = ArrayBase::<Vec<f64>>::with_context(context, vec![5], data.to_vec())?;
+
+    let relu = array.clone().relu()?;
+    assert_close(vec![0., 0., 0., 0.5, 2.], relu)?;
+
+    let leaky = array.clone().leaky_relu(0.1)?;
+    assert_close(vec![-0.2, -0.05, 0., 0.5, 2.], leaky)?;
+
+    let hardtanh = array.clone().hardtanh()?;
+    assert_close(vec![-1., -0.5, 0., 0.5, 1.], hardtanh)?;
+
+    let sigmoid = array.clone().sigmoid()?;
+    let expected = data
+        .iter()
+        .map(|x| 1. / (1. + (-x).exp()))
+        .collect::<Vec<_>>();
+    assert_close(expected, sigmoid)?;
+
+    let silu = array.clone().silu()?;
+    let expected = data
+        .iter()
+        .map(|x| x / (1. + (-x).exp()))
+        .collect::<Vec<_>>();
+    assert_close(expected, silu)?;
+
+    let softplus = array.clone().softplus()?;
+    let expected = data.iter().map(|x| x.exp().ln_1p()).collect::<Vec<_>>();
+    assert_close(expected, softplus)?;
+
+    let elu = array.clone().elu(1.)?;
+    let expected = data.iter().map(|x| if *x > 0. { *x } else { x.exp_m1() });
+    assert_close(expected.collect(), elu)?;
+
+    // reference values from scipy.special.erf
+    let gelu = array.clone().gelu()?;
+    let expected = vec![-0.0455003, -0.1542687, 0., 0.3457313, 1.9544997];
+    assert_close(expected, gelu)?;
+
+    let gelu_tanh = array.gelu_tanh()?;
+    let expected = vec![-0.0454023, -0.1542859, 0., 0.3457141, 1.9545977];
+    assert_close(expected, gelu_tanh)?;
+
+    Ok(())
+}
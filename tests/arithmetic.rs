@@ -43,3 +43,175 @@ fn test_expand_and_broadcast_and_sub() -> Result<(), Error> {
     assert!(expected.eq(actual)?.all()?);
     Ok(())
 }
+
+#[test]
+fn test_float_math() -> Result<(), Error> {
+    let context = Context::new(0, 0, None)?;
+    let data = vec![-2.5f64, -0.5, 0., 0.5, 4.];
+    let array = ArrayBase::<Vec<_>>::with_context(context.clone(), vec![5], data)?;
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![5], vec![-3., -1., 0., 0., 4.])?;
+    assert!(expected.eq(array.clone().floor()?)?.all()?);
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![5], vec![-2., -0., 0., 1., 4.])?;
+    assert!(expected.eq(array.clone().ceil()?)?.all()?);
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![5], vec![-2., 0., 0., 0., 4.])?;
+    assert!(expected.eq(array.clone().trunc()?)?.all()?);
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![5], vec![-1., -1., 0., 1., 1.])?;
+    assert!(expected.eq(array.clone().sign()?)?.all()?);
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![5], vec![-1., -0.5, 0., 0.5, 1.])?;
+    assert!(expected.eq(array.clone().clamp(-1., 1.)?)?.all()?);
+    assert_eq!(array.clone().clamp(-1., 1.)?.read_value(&[0])?, -1.);
+    assert!(array.clone().clamp(1., -1.).is_err());
+
+    let integers =
+        ArrayBase::<Vec<i32>>::with_context(context.clone(), vec![5], vec![-9, -2, 0, 3, 7])?;
+    let expected = ArrayBase::<Vec<_>>::new(vec![5], vec![-2, -2, 0, 3, 5])?;
+    assert!(expected.eq(integers.clamp(-2, 5)?)?.all()?);
+
+    let sqrt = array.clone().abs()?.sqrt()?;
+    assert_eq!(sqrt.read_value(&[4])?, 2.);
+
+    let rsqrt = array.clone().abs()?.rsqrt()?;
+    assert_eq!(rsqrt.read_value(&[4])?, 0.5);
+
+    let erf = array.clone().erf()?;
+    assert!((erf.read_value(&[3])? - 0.5204998778).abs() < 1e-7);
+    assert!((erf.read_value(&[1])? + 0.5204998778).abs() < 1e-7);
+
+    // reference values from scipy.special.erf, at every interval of the approximation
+    let x = vec![1e-10, -0.1, 0.5, 1., -2., 3., 5.5, 7.];
+    let expected = [
+        1.1283791670955126e-10,
+        -0.1124629160182849,
+        0.5204998778130465,
+        0.8427007929497149,
+        -0.9953222650189527,
+        0.9999779095030014,
+        0.9999999999999927,
+        1.,
+    ];
+
+    let x = ArrayBase::<Vec<f64>>::with_context(context.clone(), vec![8], x)?;
+    let erf = ArrayBase::<Vec<f64>>::copy(&x.erf()?)?;
+    for (actual, expected) in erf.as_slice().iter().zip(expected) {
+        assert!(
+            (actual - expected).abs() <= 1e-14 * expected.abs(),
+            "{actual} != {expected}"
+        );
+    }
+
+    let tiny = ArrayBase::<Vec<_>>::with_context(context, vec![1], vec![1e-10f64])?;
+    assert_eq!(tiny.clone().expm1()?.read_value(&[0])?, 1e-10f64.exp_m1());
+    assert_eq!(tiny.log1p()?.read_value(&[0])?, 1e-10f64.ln_1p());
+
+    Ok(())
+}
+
+#[test]
+fn test_minimum_maximum() -> Result<(), Error> {
+    let context = Context::new(0, 0, None)?;
+    let left = ArrayBase::<Vec<_>>::with_context(context.clone(), vec![4], vec![1, 5, 3, 7])?;
+    let right = ArrayBase::<Vec<_>>::with_context(context, vec![4], vec![4, 2, 6, 0])?;
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![4], vec![1, 2, 3, 0])?;
+    assert!(expected.eq(left.clone().minimum(right.clone())?)?.all()?);
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![4], vec![4, 5, 6, 7])?;
+    assert!(expected.eq(left.maximum(right)?)?.all()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_atan2_hypot() -> Result<(), Error> {
+    let context = Context::new(0, 0, None)?;
+    let y = ArrayBase::<Vec<_>>::with_context(context.clone(), vec![3], vec![3f32, -1., 0.])?;
+    let x = ArrayBase::<Vec<_>>::with_context(context, vec![3], vec![4f32, -1., 2.])?;
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![3], vec![5f32, 2f32.sqrt(), 2.])?;
+    assert!(expected.eq(y.clone().hypot(x.clone())?)?.all()?);
+
+    let actual = y.atan2(x)?;
+    assert_eq!(actual.read_value(&[1])?, -3. * std::f32::consts::FRAC_PI_4);
+    assert_eq!(actual.read_value(&[2])?, 0.);
+
+    Ok(())
+}
+
+#[test]
+fn test_promoted_arithmetic() -> Result<(), Error> {
+    let image = ArrayBase::<Vec<u8>>::new(vec![2, 2], vec![0, 64, 128, 255])?;
+    let scale = ArrayBase::<Vec<f32>>::new(vec![2, 2], vec![0.5; 4])?;
+
+    let actual = image.clone().mul_promoted(scale)?;
+    let expected = ArrayBase::<Vec<f32>>::new(vec![2, 2], vec![0., 32., 64., 127.5])?;
+    assert!(expected.eq(actual.clone())?.all()?);
+    assert_eq!(actual.read_value(&[1, 1])?, 127.5);
+
+    // u8 and i8 promote to i16, so neither operand overflows
+    let offset = ArrayBase::<Vec<i8>>::new(vec![2, 2], vec![-1, -128, 1, 127])?;
+    let actual = image.add_promoted(offset)?;
+    let expected = ArrayBase::<Vec<i16>>::new(vec![2, 2], vec![-1, -64, 129, 382])?;
+    assert!(expected.eq(actual)?.all()?);
+
+    // i64 and u32 promote to i64 without a lossy round-trip through f64
+    let left = ArrayBase::<Vec<i64>>::new(vec![2], vec![i64::MAX, -7])?;
+    let right = ArrayBase::<Vec<u32>>::new(vec![2], vec![1, 2])?;
+    let actual = left.clone().sub_promoted(right.clone())?;
+    assert_eq!(actual.read_value(&[0])?, i64::MAX - 1);
+
+    let actual = left.rem_promoted(right)?;
+    assert_eq!(actual.read_value(&[1])?, -1i64);
+
+    Ok(())
+}
+
+#[test]
+fn test_integer_overflow() -> Result<(), Error> {
+    let left = ArrayBase::<Vec<u8>>::new(vec![3], vec![200, 100, 5])?;
+    let right = ArrayBase::<Vec<u8>>::new(vec![3], vec![100, 100, 10])?;
+
+    let expected = ArrayBase::<Vec<u8>>::new(vec![3], vec![44, 200, 15])?;
+    let actual = left.clone().wrapping_add(right.clone())?;
+    assert!(expected.eq(actual)?.all()?);
+
+    let expected = ArrayBase::<Vec<u8>>::new(vec![3], vec![255, 200, 15])?;
+    let actual = left.clone().saturating_add(right.clone())?;
+    assert!(expected.eq(actual)?.all()?);
+
+    let expected = ArrayBase::<Vec<u8>>::new(vec![3], vec![100, 0, 0])?;
+    let actual = left.clone().saturating_sub(right.clone())?;
+    assert!(expected.eq(actual)?.all()?);
+
+    let sum = left.clone().checked_add(right.clone())?;
+    assert_eq!(sum.read_value(&[1])?, 200);
+    assert!(sum.read_value(&[0]).is_err());
+    assert!(sum.all().is_err());
+
+    assert!(left.clone().checked_sub(right.clone())?.all().is_err());
+    assert!(left.clone().checked_mul_scalar(2)?.all().is_err());
+    assert!(left.checked_add_scalar(55)?.all()?);
+
+    let left = ArrayBase::<Vec<i8>>::new(vec![3], vec![-128, 64, -3])?;
+    let right = ArrayBase::<Vec<i8>>::new(vec![3], vec![-1, 2, 4])?;
+
+    let expected = ArrayBase::<Vec<i8>>::new(vec![3], vec![-128, -128, -12])?;
+    let actual = left.clone().wrapping_mul(right.clone())?;
+    assert!(expected.eq(actual)?.all()?);
+
+    let expected = ArrayBase::<Vec<i8>>::new(vec![3], vec![127, 127, -12])?;
+    let actual = left.clone().saturating_mul(right)?;
+    assert!(expected.eq(actual)?.all()?);
+
+    let expected = ArrayBase::<Vec<i8>>::new(vec![3], vec![-28, 127, -28])?;
+    let actual = left
+        .saturating_mul_scalar(100)?
+        .saturating_sub_scalar(-100)?;
+    assert!(expected.eq(actual)?.all()?);
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use ha_ndarray::*;
+
+#[test]
+fn test_bitwise() -> Result<(), Error> {
+    let context = Context::new(0, 0, None)?;
+    let left = ArrayBase::<Vec<u8>>::with_context(
+        context.clone(),
+        vec![4],
+        vec![0b1100, 0b1010, 0xFF, 1],
+    )?;
+    let right =
+        ArrayBase::<Vec<u8>>::with_context(context, vec![4], vec![0b1010, 0b0110, 0x0F, 7])?;
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![4], vec![0b1000, 0b0010, 0x0F, 1])?;
+    assert!(expected.eq(left.clone() & right.clone())?.all()?);
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![4], vec![0b1110, 0b1110, 0xFF, 7])?;
+    assert!(expected.eq(left.clone() | right.clone())?.all()?);
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![4], vec![0b0110, 0b1100, 0xF0, 6])?;
+    assert!(expected.eq(left.clone().bitxor(right.clone())?)?.all()?);
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![4], vec![0xF3, 0xF5, 0x00, 0xFE])?;
+    assert!(expected.eq(left.clone().bitnot()?)?.all()?);
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![4], vec![2, 2, 8, 1])?;
+    assert!(expected.eq(left.clone().popcount()?)?.all()?);
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![4], vec![0b11000, 0b10100, 0xFE, 2])?;
+    assert!(expected.eq(left.clone() << 1)?.all()?);
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![4], vec![0b11, 0b10, 0x3F, 0])?;
+    assert!(expected.eq(left.shr_scalar(2)?)?.all()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_shift_signed() -> Result<(), Error> {
+    let context = Context::new(0, 0, None)?;
+    let array = ArrayBase::<Vec<i32>>::with_context(context.clone(), vec![3], vec![-8, 8, 1])?;
+    let shift = ArrayBase::<Vec<i32>>::with_context(context, vec![3], vec![1, 2, 33])?;
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![3], vec![-4, 2, 0])?;
+    assert!(expected.eq(array.clone() >> shift.clone())?.all()?);
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![3], vec![-16, 32, 2])?;
+    assert!(expected.eq(array.shl(shift)?)?.all()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_shift_narrow() -> Result<(), Error> {
+    // a shift is taken modulo the bit width of the type, not that of an int
+    let context = Context::new(0, 0, None)?;
+    let array = ArrayBase::<Vec<u8>>::with_context(context.clone(), vec![3], vec![1, 0x81, 3])?;
+    let shift = ArrayBase::<Vec<u8>>::with_context(context.clone(), vec![3], vec![9, 1, 8])?;
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![3], vec![2, 0x02, 3])?;
+    assert!(expected.eq(array.clone() << shift.clone())?.all()?);
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![3], vec![0, 0x40, 3])?;
+    assert!(expected.eq(array >> shift)?.all()?);
+
+    let array = ArrayBase::<Vec<i16>>::with_context(context, vec![2], vec![-8, 0x0101])?;
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![2], vec![-4, 0x80])?;
+    assert!(expected.eq(array.clone().shr_scalar(17)?)?.all()?);
+
+    let expected = ArrayBase::<Vec<_>>::new(vec![2], vec![-16, 0x0202])?;
+    assert!(expected.eq(array.shl_scalar(17)?)?.all()?);
+
+    Ok(())
+}
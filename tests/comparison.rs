@@ -105,3 +105,25 @@ fn constant<T: CDatatype>(
     let size = shape.iter().product();
     ArrayBase::<Arc<Vec<_>>>::with_context(context, shape, Arc::new(vec![value; size]))
 }
+
+#[test]
+fn test_isclose() -> Result<(), Error> {
+    let context = Context::new(0, 0, None)?;
+    let left = vec![1., 100., f32::NAN, f32::INFINITY, 0.];
+    let left = ArrayBase::<Vec<f32>>::with_context(context.clone(), vec![5], left)?;
+    let right = vec![1.000001, 100.1, f32::NAN, f32::INFINITY, 1e-9];
+    let right = ArrayBase::<Vec<f32>>::with_context(context, vec![5], right)?;
+
+    let expected = ArrayBase::<Vec<u8>>::new(vec![5], vec![1, 0, 0, 1, 1])?;
+    let actual = left.clone().isclose(right.clone(), 1e-5, 1e-8, false)?;
+    assert!(expected.eq(actual)?.all()?);
+
+    let expected = ArrayBase::<Vec<u8>>::new(vec![5], vec![1, 1, 1, 1, 1])?;
+    let actual = left.clone().isclose(right.clone(), 1e-3, 1e-8, true)?;
+    assert!(expected.eq(actual)?.all()?);
+
+    assert!(left.clone().allclose(right.clone(), 1e-3, 1e-8, true)?);
+    assert!(!left.allclose(right, 1e-3, 1e-8, false)?);
+
+    Ok(())
+}
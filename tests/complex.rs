@@ -0,0 +1,159 @@
+#![cfg(feature = "complex")]
+
+use ha_ndarray::*;
+
+#[test]
+fn test_complex_arithmetic() -> Result<(), Error> {
+    let left = vec![Complex::new(1., 2.), Complex::new(-3., 0.5)];
+    let right = vec![Complex::new(0., 1.), Complex::new(2., -1.)];
+
+    let left = ArrayBase::<Vec<Complex<f64>>>::new(vec![2], left)?;
+    let right = ArrayBase::<Vec<Complex<f64>>>::new(vec![2], right)?;
+
+    let product = left.clone() * right.clone();
+    let expected = vec![Complex::new(-2., 1.), Complex::new(-5.5, 4.)];
+    let expected = ArrayBase::<Vec<Complex<f64>>>::new(vec![2], expected)?;
+    assert!(product.clone().eq(expected)?.all()?);
+
+    let quotient = product / right;
+    assert!(quotient.eq(left)?.all()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_complex_matmul() -> Result<(), Error> {
+    let i = Complex::new(0f32, 1.);
+    let one = Complex::new(1f32, 0.);
+    let zero = Complex::new(0f32, 0.);
+
+    let left = ArrayBase::<Vec<Complex<f32>>>::new(vec![2, 2], vec![one, i, i, one])?;
+    let right = ArrayBase::<Vec<Complex<f32>>>::new(vec![2, 2], vec![one, -i, -i, one])?;
+
+    let product = left.matmul(right)?;
+    let two = Complex::new(2., 0.);
+    let expected = ArrayBase::<Vec<Complex<f32>>>::new(vec![2, 2], vec![two, zero, zero, two])?;
+    assert!(product.eq(expected)?.all()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_complex_parts() -> Result<(), Error> {
+    let data = vec![
+        Complex::new(3f32, 4.),
+        Complex::new(0., -2.),
+        Complex::new(-1., 0.),
+    ];
+    let array = ArrayBase::<Vec<Complex<f32>>>::new(vec![3], data)?;
+
+    let real = ArrayBase::<Vec<f32>>::new(vec![3], vec![3., 0., -1.])?;
+    assert!(array.clone().real()?.eq(real)?.all()?);
+
+    let imag = ArrayBase::<Vec<f32>>::new(vec![3], vec![4., -2., 0.])?;
+    assert!(array.clone().imag()?.eq(imag.clone())?.all()?);
+
+    let conj = array.clone().conj()?;
+    assert!(conj.imag()?.add(imag)?.eq_scalar(0.)?.all()?);
+
+    let norm = ArrayBase::<Vec<f32>>::new(vec![3], vec![5., 2., 1.])?;
+    assert!(array.clone().norm()?.allclose(norm, 1e-6, 0., false)?);
+
+    let angle = vec![
+        4f32.atan2(3.),
+        -std::f32::consts::FRAC_PI_2,
+        std::f32::consts::PI,
+    ];
+    let angle = ArrayBase::<Vec<f32>>::new(vec![3], angle)?;
+    assert!(array.angle()?.allclose(angle, 1e-6, 0., false)?);
+
+    Ok(())
+}
+
+#[test]
+fn test_complex_promoted() -> Result<(), Error> {
+    let real = ArrayBase::<Vec<f64>>::new(vec![2], vec![2., -1.])?;
+    let complex = vec![Complex::new(1f32, 1.), Complex::new(0., -2.)];
+    let complex = ArrayBase::<Vec<Complex<f32>>>::new(vec![2], complex)?;
+
+    let actual = real.mul_promoted(complex)?;
+    let expected = vec![Complex::new(2f64, 2.), Complex::new(0., 2.)];
+    let expected = ArrayBase::<Vec<Complex<f64>>>::new(vec![2], expected)?;
+    assert!(actual.eq(expected)?.all()?);
+
+    Ok(())
+}
+
+// elementwise ops, comparisons, casts, and reductions which must not act componentwise
+fn check_complex_ops(context: Context) -> Result<(), Error> {
+    let i = Complex::new(0f32, 1.);
+    let data = vec![Complex::new(3f32, 4.), Complex::new(3., -4.), i, -i];
+    let array = ArrayBase::<Vec<Complex<f32>>>::with_context(context.clone(), vec![4], data)?;
+
+    let conj = ArrayBase::<Vec<Complex<f32>>>::copy(&array.clone().conj()?)?;
+    let eq = ArrayBase::<Vec<u8>>::copy(&array.clone().eq(conj.clone())?)?;
+    assert_eq!(eq.as_slice(), &[0, 0, 0, 0]);
+
+    let ne = ArrayBase::<Vec<u8>>::copy(&array.clone().ne(conj)?)?;
+    assert_eq!(ne.as_slice(), &[1, 1, 1, 1]);
+
+    let eq_scalar = ArrayBase::<Vec<u8>>::copy(&array.clone().eq_scalar(i)?)?;
+    assert_eq!(eq_scalar.as_slice(), &[0, 0, 1, 0]);
+
+    let product = ArrayBase::<Vec<Complex<f32>>>::copy(&array.clone().mul_scalar(i)?)?;
+    let expected = [
+        Complex::new(-4., 3.),
+        Complex::new(4., 3.),
+        -Complex::one(),
+        Complex::one(),
+    ];
+    assert_eq!(product.as_slice(), &expected);
+
+    let quotient = ArrayBase::<Vec<Complex<f32>>>::copy(&product.div_scalar(i)?)?;
+    assert!(quotient.eq(array.clone())?.all()?);
+
+    let modulus = Complex::new(2f32, 1.);
+    let rem = ArrayBase::<Vec<Complex<f32>>>::copy(&array.clone().rem_scalar(modulus)?)?;
+    let expected = [
+        Complex::new(3f32, 4.) % modulus,
+        Complex::new(3., -4.) % modulus,
+        i % modulus,
+        -i % modulus,
+    ];
+    assert_eq!(rem.as_slice(), &expected);
+
+    let abs = ArrayBase::<Vec<Complex<f32>>>::copy(&array.clone().abs()?)?;
+    let expected = [5., 5., 1., 1.].map(|re| Complex::new(re, 0.));
+    assert_eq!(abs.as_slice(), &expected);
+
+    let cast = ArrayBase::<Vec<f64>>::copy(&array.clone().cast::<f64>()?)?;
+    assert_eq!(cast.as_slice(), &[3., 3., 0., 0.]);
+
+    let cast = ArrayBase::<Vec<Complex<f64>>>::copy(&cast.cast::<Complex<f64>>()?)?;
+    let expected = [3., 3., 0., 0.].map(|re| Complex::new(re, 0.));
+    assert_eq!(cast.as_slice(), &expected);
+
+    // (3 + 4i)(3 - 4i) = 25 and i * -i = 1
+    let array = array.reshape(vec![2, 2])?;
+    let product = ArrayBase::<Vec<Complex<f32>>>::copy(&array.product(vec![1], false)?)?;
+    assert_eq!(product.as_slice(), &[Complex::new(25., 0.), Complex::one()]);
+
+    Ok(())
+}
+
+#[test]
+fn test_complex_ops() -> Result<(), Error> {
+    check_complex_ops(Context::default()?)
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+fn test_complex_ops_opencl() -> Result<(), Error> {
+    let platform = Platform::try_from(ocl::Platform::first()?)?;
+    if platform.devices().is_empty() {
+        return Ok(());
+    }
+
+    // run every op on a device
+    check_complex_ops(Context::new(0, 0, Some(platform))?)
+}
@@ -33,6 +33,56 @@ fn test_random_normal() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn test_random_normal_read_value() -> Result<(), Error> {
+    // on the host, a value read individually is computed exactly like the whole array
+    let context = Context::new(0, 0, None)?.with_policy(HostOnly);
+    let size = 1_001;
+    let op = RandomNormal::with_context(context, size)?;
+    let array = ArrayOp::new(vec![size], op);
+    let values = ArrayBase::<Vec<f32>>::copy(&array)?;
+
+    for (offset, value) in values.as_slice().iter().enumerate() {
+        assert_eq!(array.read_value(&[offset])?, *value);
+    }
+
+    // reading the same array twice produces the same values
+    let again = ArrayBase::<Vec<f32>>::copy(&array)?;
+    assert_eq!(values.as_slice(), again.as_slice());
+
+    assert!(array.read_value(&[size]).is_err());
+    assert!(array.read_value(&[0, 0]).is_err());
+
+    Ok(())
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+fn test_random_normal_read_value_opencl() -> Result<(), Error> {
+    let platform = Platform::try_from(ocl::Platform::first()?)?;
+    if platform.devices().is_empty() {
+        return Ok(());
+    }
+
+    let context = Context::new(0, 0, Some(platform))?;
+    let size = 1_001;
+    let op = RandomNormal::with_context(context, size)?;
+    let array = ArrayOp::new(vec![size], op);
+    let values = ArrayBase::<Vec<f32>>::copy(&array)?;
+
+    // the device computes the same uniform random numbers, but its log, sin and cos
+    // are not required to round exactly like those of the host
+    for (offset, value) in values.as_slice().iter().enumerate() {
+        let expected = array.read_value(&[offset])?;
+        assert!(
+            (expected - value).abs() <= 1e-4 * expected.abs().max(1.),
+            "{offset}: {expected} != {value}"
+        );
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_random_uniform() -> Result<(), Error> {
     let context = Context::new(0, 0, None)?;
@@ -0,0 +1,97 @@
+use ha_ndarray::*;
+
+#[test]
+fn test_policies_without_devices() -> Result<(), Error> {
+    let policies: Vec<Box<dyn DevicePolicy>> = vec![
+        Box::new(HostOnly),
+        Box::new(Pinned::new(0)),
+        Box::new(SizeThresholds::new(0, 0)),
+        Box::new(LeastLoaded),
+    ];
+
+    for policy in policies {
+        assert_eq!(policy.select(&[], 1), None);
+        assert_eq!(policy.select(&[], usize::MAX), None);
+    }
+
+    let thresholds = SizeThresholds::new(8, 64);
+    assert_eq!((thresholds.gpu_min(), thresholds.acc_min()), (8, 64));
+
+    Ok(())
+}
+
+#[test]
+fn test_host_only_policy() -> Result<(), Error> {
+    let context = Context::default()?.with_policy(HostOnly);
+    let left = ArrayBase::<Vec<i32>>::with_context(context.clone(), vec![2, 3], (0..6).collect())?;
+    let right = ArrayBase::<Vec<i32>>::with_context(context, vec![2, 3], vec![1; 6])?;
+
+    let sum = ArrayBase::<Vec<i32>>::copy(&left.add(right)?)?;
+    assert_eq!(sum.as_slice(), &[1, 2, 3, 4, 5, 6]);
+
+    Ok(())
+}
+
+#[test]
+fn test_enumerate_platforms() -> Result<(), Error> {
+    for platform in Platform::list()? {
+        assert!(Platform::find(platform.name()).is_ok());
+
+        for device in platform.devices() {
+            assert!(device.compute_units() > 0);
+            assert!(device.max_work_group_size() > 0);
+
+            let matching = platform.clone().with_devices_matching(device.name())?;
+            assert!(matching
+                .devices()
+                .iter()
+                .all(|other| other.name().contains(device.name())));
+        }
+    }
+
+    assert!(Platform::find("no such platform").is_err());
+
+    Ok(())
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+fn test_device_policies() -> Result<(), Error> {
+    let platform = Platform::try_from(ocl::Platform::first()?)?;
+    let devices = platform.devices();
+
+    if devices.is_empty() {
+        return Ok(());
+    }
+
+    let last = devices.len() - 1;
+    assert_eq!(Pinned::new(last).select(devices, 1), Some(last));
+    assert_eq!(Pinned::new(devices.len()).select(devices, 1), None);
+
+    // a CPU is preferred for small operations, if there is one
+    let thresholds = SizeThresholds::new(usize::MAX, usize::MAX);
+    if let Some(i) = thresholds.select(devices, 1) {
+        assert_eq!(devices[i].kind(), DeviceKind::CPU);
+    }
+
+    let context = Context::new(0, 0, Some(platform.clone()))?.with_policy(Pinned::new(last));
+    let device = &context.platform().devices()[last];
+
+    let load = device.load();
+    let queue = Queue::new(context.clone(), 1)?;
+    assert_eq!(device.load(), load + 1);
+
+    // the least-loaded device is never the one with the most queues
+    let least_loaded = LeastLoaded.select(context.platform().devices(), 1);
+    if context.platform().devices().len() > 1 {
+        assert_ne!(least_loaded, Some(last));
+    }
+
+    std::mem::drop(queue);
+    assert_eq!(device.load(), load);
+
+    let array = ArrayBase::<Vec<f32>>::with_context(context, vec![4], vec![1., 2., 3., 4.])?;
+    assert_eq!(array.mul_scalar(2.)?.sum_all()?, 20.);
+
+    Ok(())
+}
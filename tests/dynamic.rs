@@ -0,0 +1,152 @@
+use ha_ndarray::*;
+
+#[test]
+fn test_promote() {
+    assert_eq!(DType::U8.promote(DType::U8), DType::U8);
+    assert_eq!(DType::U8.promote(DType::U32), DType::U32);
+    assert_eq!(DType::U8.promote(DType::I8), DType::I16);
+    assert_eq!(DType::U16.promote(DType::I64), DType::I64);
+    assert_eq!(DType::I32.promote(DType::U32), DType::I64);
+    assert_eq!(DType::U64.promote(DType::I8), DType::F64);
+    assert_eq!(DType::I16.promote(DType::F32), DType::F32);
+    assert_eq!(DType::F32.promote(DType::I32), DType::F64);
+    assert_eq!(DType::F64.promote(DType::F32), DType::F64);
+}
+
+#[test]
+fn test_cast_to() -> Result<(), Error> {
+    let array = ArrayBase::<Vec<i32>>::new(vec![2, 3], vec![-1, 0, 1, 2, 3, 4])?;
+    let array = DynArray::from(Array::from(array));
+    assert_eq!(array.dtype(), DType::I32);
+
+    let array = array.cast_to(DType::F64)?;
+    assert_eq!(array.dtype(), DType::F64);
+    assert_eq!(array.shape(), &[2, 3]);
+
+    let expected = ArrayBase::<Vec<f64>>::new(vec![2, 3], vec![-1., 0., 1., 2., 3., 4.])?;
+    let expected = DynArray::from(Array::from(expected));
+    assert!(array.eq(expected)?.all()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_dyn_ops() -> Result<(), Error> {
+    let left = ArrayBase::<Vec<u8>>::new(vec![2, 2], vec![1, 2, 3, 4])?;
+    let left = DynArray::from(Array::from(left));
+
+    let right = ArrayBase::<Vec<f32>>::new(vec![2], vec![0.5, 0.25])?;
+    let right = DynArray::from(Array::from(right)).broadcast(vec![2, 2])?;
+
+    let sum = left.add(right)?;
+    assert_eq!(sum.dtype(), DType::F32);
+
+    let expected = ArrayBase::<Vec<f32>>::new(vec![2, 2], vec![1.5, 2.25, 3.5, 4.25])?;
+    let expected = DynArray::from(Array::from(expected));
+    let compare = sum.gt(expected)?;
+    assert_eq!(compare.dtype(), DType::U8);
+    assert!(!compare.any()?);
+
+    let left = ArrayBase::<Vec<i8>>::new(vec![2, 2], vec![-1, 2, -3, 4])?;
+    let left = DynArray::from(Array::from(left));
+    let right = ArrayBase::<Vec<u16>>::new(vec![2, 2], vec![1, 1, 1, 1])?;
+    let right = DynArray::from(Array::from(right));
+
+    let product = left.mul(right)?.transpose(None)?;
+    assert_eq!(product.dtype(), DType::I32);
+
+    let sum = product.sum(vec![1], false)?;
+    let expected = ArrayBase::<Vec<i32>>::new(vec![2], vec![-4, 6])?;
+    let expected = DynArray::from(Array::from(expected));
+    assert!(sum.eq(expected)?.all()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_dyn_unary() -> Result<(), Error> {
+    let array = ArrayBase::<Vec<f64>>::new(vec![2, 2], vec![-1., 0.25, 4., -9.])?;
+    let array = DynArray::from(Array::from(array));
+
+    let sqrt = array.clone().abs()?.sqrt()?;
+    assert_eq!(sqrt.dtype(), DType::F64);
+
+    let expected = ArrayBase::<Vec<f64>>::new(vec![2, 2], vec![1., 0.5, 2., 3.])?;
+    let expected = DynArray::from(Array::from(expected));
+    assert!(sqrt.eq(expected)?.all()?);
+
+    let sign = array.clone().sign()?;
+    let expected = ArrayBase::<Vec<f64>>::new(vec![2, 2], vec![-1., 1., 1., -1.])?;
+    let expected = DynArray::from(Array::from(expected));
+    assert!(sign.eq(expected)?.all()?);
+
+    let not = array.not()?;
+    assert_eq!(not.dtype(), DType::U8);
+    assert!(!not.any()?);
+
+    let array = ArrayBase::<Vec<i16>>::new(vec![2], vec![0, 1])?;
+    let cos = DynArray::from(Array::from(array)).cos()?;
+    assert_eq!(cos.dtype(), DType::F32);
+
+    let expected = ArrayBase::<Vec<f32>>::new(vec![2], vec![1., 1f32.cos()])?;
+    let expected = DynArray::from(Array::from(expected));
+    assert!(cos.eq(expected)?.all()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_dyn_float_ops() -> Result<(), Error> {
+    let base = ArrayBase::<Vec<u8>>::new(vec![3], vec![2, 3, 4])?;
+    let base = DynArray::from(Array::from(base));
+
+    let exp = ArrayBase::<Vec<i64>>::new(vec![3], vec![3, 2, 0])?;
+    let exp = DynArray::from(Array::from(exp));
+
+    let pow = base.clone().pow(exp)?;
+    assert_eq!(pow.dtype(), DType::F64);
+
+    let expected = ArrayBase::<Vec<f64>>::new(vec![3], vec![8., 9., 1.])?;
+    let expected = DynArray::from(Array::from(expected));
+    assert!(pow.clone().eq(expected)?.all()?);
+
+    let log = pow.log(base)?;
+    let expected = ArrayBase::<Vec<f64>>::new(vec![3], vec![3., 2., 0.])?;
+    let expected = DynArray::from(Array::from(expected));
+    assert!(log.sub(expected)?.abs()?.lt(scalar(1e-12))?.all()?);
+
+    let y = ArrayBase::<Vec<f32>>::new(vec![3], vec![3.; 3])?;
+    let x = ArrayBase::<Vec<u16>>::new(vec![3], vec![4; 3])?;
+    let hypot = DynArray::from(Array::from(y)).hypot(DynArray::from(Array::from(x)))?;
+    assert_eq!(hypot.dtype(), DType::F32);
+    assert!(hypot.eq(scalar(5f32))?.all()?);
+
+    Ok(())
+}
+
+fn scalar<T: CDatatype>(value: T) -> DynArray
+where
+    DynArray: From<Array<T>>,
+{
+    let array = ArrayBase::<Vec<T>>::new(vec![1], vec![value]).expect("scalar");
+    let array = DynArray::from(Array::from(array));
+    array.broadcast(vec![3]).expect("broadcast")
+}
+
+#[cfg(feature = "complex")]
+#[test]
+fn test_promote_complex() -> Result<(), Error> {
+    assert_eq!(DType::C64.promote(DType::F64), DType::C128);
+    assert_eq!(DType::I8.promote(DType::C64), DType::C64);
+
+    let array = ArrayBase::<Vec<Complex<f32>>>::new(vec![2], vec![Complex::new(1., 2.); 2])?;
+    let array = DynArray::from(Array::from(array));
+    assert!(array.clone().max(vec![0], false).is_err());
+    assert!(array.clone().exp().is_err());
+
+    let real = ArrayBase::<Vec<f32>>::new(vec![2], vec![2.; 2])?;
+    let real = DynArray::from(Array::from(real));
+    assert!(array.pow(real).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "fft")]
+
+use std::f64::consts::PI;
+
+use ha_ndarray::*;
+
+fn dft(signal: &[Complex<f64>]) -> Vec<Complex<f64>> {
+    let n = signal.len();
+
+    (0..n)
+        .map(|k| {
+            signal
+                .iter()
+                .enumerate()
+                .map(|(t, x)| x * Complex::from_polar(1., -2. * PI * (k * t) as f64 / n as f64))
+                .sum()
+        })
+        .collect()
+}
+
+fn signal(size: usize) -> Vec<Complex<f64>> {
+    (0..size)
+        .map(|i| Complex::new((i as f64 * 0.7).sin(), (i as f64 * 0.3).cos()))
+        .collect()
+}
+
+#[test]
+fn test_fft_axis() -> Result<(), Error> {
+    // a non-power-of-two length along axis 0 and a power of two along axis 1
+    let data = signal(6 * 4);
+    let array = ArrayBase::<Vec<Complex<f64>>>::new(vec![6, 4], data.clone())?;
+
+    let mut expected = data.clone();
+    for col in 0..4 {
+        let lane = (0..6).map(|row| data[row * 4 + col]).collect::<Vec<_>>();
+        for (row, value) in dft(&lane).into_iter().enumerate() {
+            expected[row * 4 + col] = value;
+        }
+    }
+
+    let actual = array.clone().fft(0)?;
+    assert!((actual.read_value(&[5, 2])? - expected[5 * 4 + 2]).norm() < 1e-9);
+
+    let expected = ArrayBase::<Vec<Complex<f64>>>::new(vec![6, 4], expected)?;
+    assert!((actual.clone() - expected).norm()?.lt_scalar(1e-9)?.all()?);
+
+    let roundtrip = actual.ifft(0)?;
+    assert!((roundtrip - array).norm()?.lt_scalar(1e-9)?.all()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_fft2() -> Result<(), Error> {
+    let data = signal(2 * 3 * 8);
+    let array = ArrayBase::<Vec<Complex<f64>>>::new(vec![2, 3, 8], data)?;
+
+    let fft2 = array.clone().fft2()?;
+    let fftn = array.clone().fft(2)?.fft(1)?;
+    assert!((fft2.clone() - fftn).norm()?.lt_scalar(1e-9)?.all()?);
+
+    let roundtrip = fft2.ifftn(vec![1, 2])?;
+    assert!((roundtrip - array.clone()).norm()?.lt_scalar(1e-9)?.all()?);
+
+    assert!(array.clone().fftn(vec![1, 1]).is_err());
+    assert!(array.fft(3).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_rfft() -> Result<(), Error> {
+    for n in [7, 8] {
+        let data = (0..2 * n)
+            .map(|i| (i as f64 * 0.9).cos())
+            .collect::<Vec<_>>();
+        let array = ArrayBase::<Vec<f64>>::new(vec![2, n], data.clone())?;
+
+        let spectrum = array.clone().rfft(1)?;
+        assert_eq!(spectrum.shape(), &[2, (n / 2) + 1]);
+
+        let lane = data[n..]
+            .iter()
+            .map(|x| Complex::new(*x, 0.))
+            .collect::<Vec<_>>();
+        let expected = dft(&lane);
+        for k in 0..(n / 2) + 1 {
+            assert!((spectrum.read_value(&[1, k])? - expected[k]).norm() < 1e-9);
+        }
+
+        let signal = spectrum.irfft(1, Some(n))?;
+        assert_eq!(signal.shape(), &[2, n]);
+        assert!(signal.clone().allclose(array, 0., 1e-9, false)?);
+        assert!((signal.read_value(&[1, n - 1])? - data[(2 * n) - 1]).abs() < 1e-9);
+    }
+
+    Ok(())
+}
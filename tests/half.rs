@@ -0,0 +1,114 @@
+#![cfg(feature = "half")]
+
+use ha_ndarray::*;
+
+#[test]
+fn test_half_sum() -> Result<(), Error> {
+    // a half-precision accumulator would stop incrementing at 2048
+    let size = 4096;
+
+    let array = ArrayBase::<Vec<F16>>::new(vec![2, size], vec![F16::ONE; 2 * size])?;
+    assert_eq!(array.clone().sum_all()?, F16::from_f32(8192.));
+
+    let sum = array.sum(vec![1], false)?;
+    assert_eq!(sum.read_value(&[1])?, F16::from_f32(size as f32));
+
+    let array = ArrayBase::<Vec<BF16>>::new(vec![size], vec![BF16::ONE; size])?;
+    assert_eq!(array.sum_all()?, BF16::from_f32(size as f32));
+
+    // a half-precision sum of partial sums of eight elements would stop incrementing at 16384
+    let size = 32_768;
+    let array = ArrayBase::<Vec<F16>>::new(vec![size], vec![F16::ONE; size])?;
+    assert_eq!(array.sum_all()?, F16::from_f32(size as f32));
+
+    Ok(())
+}
+
+#[test]
+fn test_half_matmul() -> Result<(), Error> {
+    let size = 4096;
+
+    let left = ArrayBase::<Vec<F16>>::new(vec![1, size], vec![F16::ONE; size])?;
+    let right = ArrayBase::<Vec<F16>>::new(vec![size, 2], vec![F16::ONE; size * 2])?;
+
+    let product = left.matmul(right)?;
+    assert_eq!(product.shape(), &[1, 2]);
+
+    let expected = ArrayBase::<Vec<F16>>::new(vec![1, 2], vec![F16::from_f32(size as f32); 2])?;
+    assert!(product.eq(expected)?.all()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_half_cast() -> Result<(), Error> {
+    let data = vec![-1.5, 0., 0.25, 1024.];
+    let array = ArrayBase::<Vec<f32>>::new(vec![4], data.clone())?;
+
+    let half = array.clone().cast::<F16>()?;
+    let expected = data.iter().copied().map(F16::from_f32).collect();
+    let expected = ArrayBase::<Vec<F16>>::new(vec![4], expected)?;
+    assert!(half.clone().eq(expected)?.all()?);
+
+    let bf16 = half.cast::<BF16>()?.cast::<f32>()?;
+    assert!(bf16.eq(array)?.all()?);
+
+    Ok(())
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+fn test_half_opencl() -> Result<(), Error> {
+    let platform = Platform::try_from(ocl::Platform::first()?)?;
+    if platform.devices().is_empty() {
+        return Ok(());
+    }
+
+    // OpenCL can only store bfloat16 values, so these ops run on the host
+    let context = Context::new(0, 0, Some(platform))?;
+    let size = 4096;
+
+    let array = ArrayBase::<Vec<BF16>>::with_context(context, vec![size], vec![BF16::ONE; size])?;
+    let doubled = ArrayBase::<Vec<BF16>>::copy(&array.clone().add(array.clone())?)?;
+    assert_eq!(doubled.sum_all()?, BF16::from_f32(2. * size as f32));
+
+    let cast = array.cast::<f32>()?;
+    assert_eq!(cast.sum_all()?, size as f32);
+
+    Ok(())
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+fn test_half_opencl_f16() -> Result<(), Error> {
+    let platform = Platform::try_from(ocl::Platform::first()?)?;
+    if platform.devices().is_empty() {
+        return Ok(());
+    }
+
+    // these ops run on the device if every device supports cl_khr_fp16, or else on the host,
+    // and either way must accumulate at single precision
+    let context = Context::new(0, 0, Some(platform))?;
+    let size = 4096;
+
+    let array = ArrayBase::<Vec<F16>>::with_context(
+        context.clone(),
+        vec![2, size],
+        vec![F16::ONE; 2 * size],
+    )?;
+
+    assert_eq!(array.clone().sum_all()?, F16::from_f32(2. * size as f32));
+
+    let sum = ArrayBase::<Vec<F16>>::copy(&array.clone().sum(vec![1], false)?)?;
+    assert_eq!(sum.as_slice(), &[F16::from_f32(size as f32); 2]);
+
+    let left =
+        ArrayBase::<Vec<F16>>::with_context(context.clone(), vec![1, size], vec![F16::ONE; size])?;
+    let right =
+        ArrayBase::<Vec<F16>>::with_context(context, vec![size, 2], vec![F16::ONE; size * 2])?;
+
+    let product = ArrayBase::<Vec<F16>>::copy(&left.matmul(right)?)?;
+    assert_eq!(product.as_slice(), &[F16::from_f32(size as f32); 2]);
+
+    Ok(())
+}
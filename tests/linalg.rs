@@ -1,3 +1,5 @@
+use std::fmt;
+
 use ha_ndarray::*;
 
 #[test]
@@ -53,3 +55,784 @@ fn test_matmul_large() -> Result<(), Error> {
 
     Ok(())
 }
+
+fn range(shape: Vec<usize>, start: i64) -> Result<Array<i64>, Error> {
+    let size = shape.iter().product::<usize>() as i64;
+    let data = (start..start + size).collect();
+    ArrayBase::<Vec<i64>>::new(shape, data).map(Array::from)
+}
+
+#[test]
+fn test_matmul_transposed() -> Result<(), Error> {
+    let context = Context::new(0, 0, None)?;
+
+    let data = |shape: &[usize]| {
+        let size = shape.iter().product::<usize>();
+        (0..size).map(|i| ((i % 7) as f64) - 3.).collect::<Vec<_>>()
+    };
+
+    for (left_shape, right_shape) in [(vec![4, 3], vec![5, 4]), (vec![2, 6, 3], vec![2, 5, 6])] {
+        let left = ArrayBase::<Vec<f64>>::with_context(
+            context.clone(),
+            left_shape.to_vec(),
+            data(&left_shape),
+        )?;
+
+        let right = ArrayBase::<Vec<f64>>::with_context(
+            context.clone(),
+            right_shape.to_vec(),
+            data(&right_shape),
+        )?;
+
+        let ndim = left_shape.len();
+        let mut permutation = (0..ndim).collect::<Vec<_>>();
+        permutation.swap(ndim - 2, ndim - 1);
+
+        let left_t =
+            ArrayBase::<Vec<f64>>::copy(&left.clone().transpose(Some(permutation.to_vec()))?)?;
+        let right_t =
+            ArrayBase::<Vec<f64>>::copy(&right.clone().transpose(Some(permutation.to_vec()))?)?;
+        let expected = ArrayBase::<Vec<f64>>::copy(&left_t.clone().matmul(right_t.clone())?)?;
+
+        let left_view = left.transpose(Some(permutation.to_vec()))?;
+        let right_view = right.transpose(Some(permutation))?;
+
+        let actual = ArrayBase::<Vec<f64>>::copy(&left_view.matmul(right_t.clone())?)?;
+        assert_eq!(actual.as_slice(), expected.as_slice());
+
+        let actual = ArrayBase::<Vec<f64>>::copy(&left_t.matmul(right_view)?)?;
+        assert_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_matmul_blocked() -> Result<(), Error> {
+    fn check<T>(a: usize, b: usize, c: usize) -> Result<(), Error>
+    where
+        T: CDatatype + fmt::Debug,
+    {
+        // small integers, so that floating-point products are exact
+        let value = |i: usize| T::from_f64(((i * 7) % 11) as f64) - T::from_f64(5.);
+
+        let left = (0..a * b).map(value).collect::<Vec<T>>();
+        let right = (0..b * c).map(|i| value(i + 3)).collect::<Vec<T>>();
+
+        let expected = (0..a * c)
+            .map(|n| {
+                let (i, j) = (n / c, n % c);
+                (0..b)
+                    .map(|k| left[(i * b) + k] * right[(k * c) + j])
+                    .fold(T::zero(), |sum, x| sum + x)
+            })
+            .collect::<Vec<T>>();
+
+        let left = ArrayBase::<Vec<T>>::new(vec![a, b], left)?;
+        let right = ArrayBase::<Vec<T>>::new(vec![b, c], right)?;
+        let actual = ArrayBase::<Vec<T>>::copy(&left.matmul(right)?)?;
+
+        assert_eq!(actual.as_slice(), expected.as_slice());
+
+        Ok(())
+    }
+
+    // including a single row and a few rows, whose columns are split between threads
+    let dims = [
+        (1, 1, 1),
+        (7, 5, 17),
+        (70, 300, 37),
+        (101, 513, 9),
+        (1, 300, 4100),
+        (3, 257, 1000),
+    ];
+
+    for (a, b, c) in dims {
+        check::<f32>(a, b, c)?;
+        check::<f64>(a, b, c)?;
+        check::<i32>(a, b, c)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_matmul_broadcast() -> Result<(), Error> {
+    // a batch of matrices times a single matrix
+    let actual = range(vec![3, 2, 4], 0)?.matmul(range(vec![4, 5], -7)?)?;
+    assert_eq!(actual.shape(), &[3, 2, 5]);
+
+    let actual = ArrayBase::<Vec<i64>>::copy(&actual)?;
+    for n in 0..3 {
+        for i in 0..2 {
+            for j in 0..5 {
+                let expected = (0..4)
+                    .map(|k| ((n * 8) + (i * 4) + k) as i64 * (((k * 5) + j) as i64 - 7))
+                    .sum::<i64>();
+
+                assert_eq!(actual.as_slice()[(n * 10) + (i * 5) + j], expected);
+            }
+        }
+    }
+
+    // both batch prefixes are broadcast
+    let actual = range(vec![2, 1, 2, 3], 0)?.matmul(range(vec![4, 3, 2], 1)?)?;
+    assert_eq!(actual.shape(), &[2, 4, 2, 2]);
+
+    let actual = ArrayBase::<Vec<i64>>::copy(&actual)?;
+    for m in 0..2 {
+        for n in 0..4 {
+            for i in 0..2 {
+                for j in 0..2 {
+                    let expected = (0..3)
+                        .map(|k| {
+                            ((m * 6) + (i * 3) + k) as i64 * ((n * 6) + (k * 2) + j + 1) as i64
+                        })
+                        .sum::<i64>();
+
+                    let offset = (m * 16) + (n * 4) + (i * 2) + j;
+                    assert_eq!(actual.as_slice()[offset], expected);
+                }
+            }
+        }
+    }
+
+    let left = range(vec![2, 2, 3], 0)?;
+    let right = range(vec![3, 3, 2], 0)?;
+    assert!(left.matmul(right).is_err());
+
+    let left = range(vec![2, 3], 0)?;
+    let right = range(vec![2, 3], 0)?;
+    assert!(left.matmul(right).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_matmul_vector() -> Result<(), Error> {
+    // a vector times a matrix
+    let actual = range(vec![3], 1)?.matmul(range(vec![3, 4], 0)?)?;
+    assert_eq!(actual.shape(), &[4]);
+    let actual = ArrayBase::<Vec<i64>>::copy(&actual)?;
+    assert_eq!(actual.as_slice(), &[32, 38, 44, 50]);
+
+    // a matrix times a vector
+    let actual = range(vec![3, 4], 0)?.matmul(range(vec![4], 1)?)?;
+    assert_eq!(actual.shape(), &[3]);
+    let actual = ArrayBase::<Vec<i64>>::copy(&actual)?;
+    assert_eq!(actual.as_slice(), &[20, 60, 100]);
+
+    // a batch of matrices times a vector
+    let actual = range(vec![2, 3, 4], 0)?.matmul(range(vec![4], 1)?)?;
+    assert_eq!(actual.shape(), &[2, 3]);
+    let actual = ArrayBase::<Vec<i64>>::copy(&actual)?;
+    assert_eq!(actual.as_slice(), &[20, 60, 100, 140, 180, 220]);
+
+    // the inner product of two vectors
+    let actual = range(vec![4], 1)?.matmul(range(vec![4], 1)?)?;
+    assert_eq!(actual.shape(), &[1]);
+    let actual = ArrayBase::<Vec<i64>>::copy(&actual)?;
+    assert_eq!(actual.as_slice(), &[30]);
+
+    assert!(range(vec![3], 0)?
+        .matmul(range(vec![3, 4], 0)?.transpose(None)?)
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_matmul_read_value() -> Result<(), Error> {
+    let product = range(vec![2, 3], 0)?.matmul(range(vec![3, 4], 1)?)?;
+    let expected = ArrayBase::<Vec<i64>>::copy(&product)?;
+    assert_eq!(read_all(&product)?, expected.as_slice());
+
+    // broadcast batch dimensions
+    let product = range(vec![2, 1, 2, 3], 0)?.matmul(range(vec![3, 3, 2], -4)?)?;
+    assert_eq!(product.shape(), &[2, 3, 2, 2]);
+    let expected = ArrayBase::<Vec<i64>>::copy(&product)?;
+    assert_eq!(read_all(&product)?, expected.as_slice());
+
+    // vector operands
+    let product = range(vec![3], 1)?.matmul(range(vec![2, 3, 4], 0)?)?;
+    assert_eq!(
+        read_all(&product)?,
+        vec![32, 38, 44, 50, 104, 110, 116, 122]
+    );
+
+    let product = range(vec![2, 3, 4], 0)?.matmul(range(vec![4], 1)?)?;
+    assert_eq!(read_all(&product)?, vec![20, 60, 100, 140, 180, 220]);
+
+    let product = range(vec![4], 1)?.matmul(range(vec![4], 1)?)?;
+    assert_eq!(read_all(&product)?, vec![30]);
+
+    let product = range(vec![2, 3], 0)?.matmul(range(vec![3, 4], 1)?)?;
+    assert!(product.read_value(&[2, 0]).is_err());
+    assert!(product.read_value(&[0]).is_err());
+
+    // a slice of a matrix product
+    let product = range(vec![2, 3], 0)?.matmul(range(vec![3, 4], 1)?)?;
+    let slice = product.slice(vec![AxisBound::At(1), AxisBound::In(0, 4, 2)])?;
+    assert_eq!(slice.read_value(&[0])?, 68);
+    assert_eq!(slice.read_value(&[1])?, 92);
+
+    // a matrix product read through an `Array`
+    let product = Array::from(range(vec![2, 3], 0)?.matmul(range(vec![3, 4], 1)?)?);
+    assert_eq!(product.read_value(&[1, 3])?, 104);
+
+    Ok(())
+}
+
+#[test]
+fn test_tensordot() -> Result<(), Error> {
+    let left = range(vec![2, 3, 4], 0)?;
+    let right = range(vec![4, 3, 5], -20)?;
+
+    let actual = left.tensordot(right, (vec![1, 2], vec![1, 0]))?;
+    assert_eq!(actual.shape(), &[2, 5]);
+
+    let actual = ArrayBase::<Vec<i64>>::copy(&actual)?;
+
+    for i in 0..2 {
+        for l in 0..5 {
+            let mut expected = 0;
+            for j in 0..3 {
+                for k in 0..4 {
+                    let a = ((i * 12) + (j * 4) + k) as i64;
+                    let b = ((k * 15) + (j * 5) + l) as i64 - 20;
+                    expected += a * b;
+                }
+            }
+
+            assert_eq!(actual.as_slice()[(i * 5) + l], expected);
+        }
+    }
+
+    let left = range(vec![2, 3], 0)?;
+    let right = range(vec![3, 2], 0)?;
+    assert!(left.tensordot(right, (vec![1], vec![1])).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_einsum() -> Result<(), Error> {
+    let left = range(vec![2, 3, 4], 0)?;
+    let right = range(vec![2, 4, 5], 1)?;
+    let actual = einsum("bij,bjk->bik", vec![left, right])?;
+    let expected = range(vec![2, 3, 4], 0)?.matmul(range(vec![2, 4, 5], 1)?)?;
+    assert!(actual.eq(expected)?.all()?);
+
+    // a chain of three operands, with implicit output subscripts
+    let a = range(vec![2, 3], 0)?;
+    let b = range(vec![3, 4], 2)?;
+    let c = range(vec![4, 5], -3)?;
+    let actual = einsum("ij,jk,kl", vec![a, b, c])?;
+    let expected = range(vec![2, 3], 0)?
+        .matmul(range(vec![3, 4], 2)?)?
+        .matmul(range(vec![4, 5], -3)?)?;
+    assert!(actual.eq(expected)?.all()?);
+
+    // a bilinear form with a batch dimension
+    let x = range(vec![2, 3], 0)?;
+    let w = range(vec![3, 4], 1)?;
+    let y = range(vec![2, 4], 2)?;
+    let actual = einsum("bi,ij,bj->b", vec![x, w, y])?;
+    let expected = range(vec![2, 3], 0)?
+        .matmul(range(vec![3, 4], 1)?)?
+        .mul(range(vec![2, 4], 2)?)?
+        .sum(vec![1], false)?;
+    assert!(actual.eq(expected)?.all()?);
+
+    let matrix = range(vec![3, 3], 0)?;
+    let diagonal = einsum("ii->i", vec![matrix])?;
+    let expected = ArrayBase::<Vec<i64>>::new(vec![3], vec![0, 4, 8])?;
+    assert!(diagonal.eq(expected)?.all()?);
+
+    let trace = einsum("ii", vec![range(vec![3, 3], 0)?])?;
+    assert_eq!(trace.shape(), &[1]);
+    assert_eq!(trace.sum_all()?, 12);
+
+    let transpose = einsum("ij->ji", vec![range(vec![2, 3], 0)?])?;
+    let expected = range(vec![2, 3], 0)?.transpose(None)?;
+    assert!(transpose.eq(expected)?.all()?);
+
+    let outer = einsum("i,j->ij", vec![range(vec![2], 1)?, range(vec![3], 1)?])?;
+    let expected = ArrayBase::<Vec<i64>>::new(vec![2, 3], vec![1, 2, 3, 2, 4, 6])?;
+    assert!(outer.eq(expected)?.all()?);
+
+    assert!(einsum(
+        "ij,jk->iq",
+        vec![range(vec![2, 3], 0)?, range(vec![3, 4], 0)?]
+    )
+    .is_err());
+    assert!(einsum(
+        "ij,jk->ik",
+        vec![range(vec![2, 3], 0)?, range(vec![4, 4], 0)?]
+    )
+    .is_err());
+    assert!(einsum("ij,jk->ik", vec![range(vec![2, 3], 0)?]).is_err());
+    assert!(einsum("i...,i->i", vec![range(vec![2, 3], 0)?, range(vec![2], 0)?]).is_err());
+
+    Ok(())
+}
+
+fn matrix(shape: Vec<usize>, data: Vec<f64>) -> Result<ArrayBase<Vec<f64>>, Error> {
+    ArrayBase::<Vec<f64>>::new(shape, data)
+}
+
+fn assert_close(actual: &[f64], expected: &[f64]) {
+    assert_eq!(actual.len(), expected.len());
+
+    for (a, e) in actual.iter().zip(expected) {
+        assert!(
+            (a - e).abs() < 1e-9,
+            "expected {expected:?} but found {actual:?}"
+        );
+    }
+}
+
+#[test]
+fn test_lu() -> Result<(), Error> {
+    let data = vec![2., 1., 1., 4., -6., 0., -2., 7., 2.];
+    let a = matrix(vec![3, 3], data.to_vec())?;
+
+    let (p, l, u) = a.lu()?;
+    let p = ArrayBase::<Vec<f64>>::copy(&p)?;
+    let l = ArrayBase::<Vec<f64>>::copy(&l)?;
+    let u = ArrayBase::<Vec<f64>>::copy(&u)?;
+
+    // partial pivoting selects the row with the largest leading value
+    assert_eq!(p.as_slice(), &[0., 1., 0., 1., 0., 0., 0., 0., 1.]);
+
+    for i in 0..3 {
+        assert_eq!(l.as_slice()[(i * 3) + i], 1.);
+
+        for j in 0..3 {
+            if j > i {
+                assert_eq!(l.as_slice()[(i * 3) + j], 0.);
+            } else if j < i {
+                assert_eq!(u.as_slice()[(i * 3) + j], 0.);
+            }
+        }
+    }
+
+    let product = p.matmul(l)?.matmul(u)?;
+    let product = ArrayBase::<Vec<f64>>::copy(&product)?;
+    assert_close(product.as_slice(), &data);
+
+    Ok(())
+}
+
+#[test]
+fn test_det() -> Result<(), Error> {
+    let a = matrix(
+        vec![3, 2, 2],
+        vec![1., 2., 3., 4., 2., 0., 0., 3., 1., 2., 2., 4.],
+    )?;
+
+    let det = ArrayBase::<Vec<f64>>::copy(&a.clone().det()?)?;
+    assert_eq!(det.shape(), &[3]);
+    assert_close(det.as_slice(), &[-2., 6., 0.]);
+
+    let (sign, logabsdet) = a.slogdet()?;
+    let sign = ArrayBase::<Vec<f64>>::copy(&sign)?;
+    let logabsdet = ArrayBase::<Vec<f64>>::copy(&logabsdet)?;
+    assert_eq!(sign.as_slice(), &[-1., 1., 0.]);
+    assert_close(&logabsdet.as_slice()[..2], &[2f64.ln(), 6f64.ln()]);
+    assert_eq!(logabsdet.as_slice()[2], f64::NEG_INFINITY);
+
+    let a = matrix(vec![3, 3], vec![2., 1., 1., 4., -6., 0., -2., 7., 2.])?;
+    let det = ArrayBase::<Vec<f64>>::copy(&a.det()?)?;
+    assert_eq!(det.shape(), &[1]);
+    assert_close(det.as_slice(), &[-16.]);
+
+    assert!(matrix(vec![2, 3], vec![0.; 6])?.det().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_inverse() -> Result<(), Error> {
+    let data = vec![4., 7., 2., 6., 1., 0., 0., 2.];
+    let a = matrix(vec![2, 2, 2], data)?;
+
+    let inverse = ArrayBase::<Vec<f64>>::copy(&a.clone().inverse()?)?;
+    assert_close(inverse.as_slice(), &[0.6, -0.7, -0.2, 0.4, 1., 0., 0., 0.5]);
+
+    let identity = ArrayBase::<Vec<f64>>::copy(&a.matmul(inverse)?)?;
+    assert_close(identity.as_slice(), &[1., 0., 0., 1., 1., 0., 0., 1.]);
+
+    let singular = matrix(vec![2, 2], vec![1., 2., 2., 4.])?.inverse()?;
+    assert!(matches!(
+        ArrayBase::<Vec<f64>>::copy(&singular),
+        Err(Error::Singular(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_solve() -> Result<(), Error> {
+    let a = matrix(vec![3, 3], vec![3., 2., -1., 2., -2., 4., -1., 0.5, -1.])?;
+
+    // solve for a vector
+    let b = matrix(vec![3], vec![1., -2., 0.])?;
+    let x = a.clone().solve(b)?;
+    assert_eq!(x.shape(), &[3]);
+    assert_close(ArrayBase::<Vec<f64>>::copy(&x)?.as_slice(), &[1., -2., -2.]);
+
+    // solve for a batch of matrices with a single left-hand side
+    let b = matrix(
+        vec![2, 3, 2],
+        vec![1., 3., -2., 2., 0., -1., 2., 0., -4., 0., 0., 0.],
+    )?;
+
+    let x = a.clone().solve(b.clone())?;
+    assert_eq!(x.shape(), &[2, 3, 2]);
+
+    let x = ArrayBase::<Vec<f64>>::copy(&x)?;
+    let product = ArrayBase::<Vec<f64>>::copy(&a.clone().matmul(x)?)?;
+    assert_close(product.as_slice(), b.as_slice());
+
+    let singular = matrix(vec![2, 2], vec![1., 2., 2., 4.])?;
+    let x = singular.solve(matrix(vec![2], vec![1., 1.])?)?;
+    assert!(matches!(
+        ArrayBase::<Vec<f64>>::copy(&x),
+        Err(Error::Singular(_))
+    ));
+
+    assert!(a.solve(matrix(vec![2], vec![1., 1.])?).is_err());
+
+    Ok(())
+}
+
+fn copy<A: NDArrayRead<DType = f64>>(array: &A) -> Result<ArrayBase<Vec<f64>>, Error> {
+    ArrayBase::<Vec<f64>>::copy(array)
+}
+
+// the matrix or batch of matrices with its last two axes swapped
+fn transpose_matrices(array: ArrayBase<Vec<f64>>) -> Result<ArrayBase<Vec<f64>>, Error> {
+    let ndim = array.ndim();
+    let mut permutation = (0..ndim).collect::<Vec<_>>();
+    permutation.swap(ndim - 2, ndim - 1);
+    copy(&array.transpose(Some(permutation))?)
+}
+
+#[test]
+fn test_qr() -> Result<(), Error> {
+    for shape in [vec![4, 3], vec![3, 5], vec![2, 3, 3]] {
+        let size = shape.iter().product::<usize>();
+        let data = (0..size)
+            .map(|i| ((i * 7) % 5) as f64 - (i as f64 / 3.))
+            .collect::<Vec<_>>();
+
+        let a = matrix(shape.to_vec(), data.to_vec())?;
+        let (q, r) = a.qr()?;
+        let (q, r) = (copy(&q)?, copy(&r)?);
+
+        let ndim = shape.len();
+        let k = shape[ndim - 2].min(shape[ndim - 1]);
+        assert_eq!(q.shape()[ndim - 1], k);
+        assert_eq!(r.shape()[ndim - 2], k);
+
+        let product = copy(&q.clone().matmul(r.clone())?)?;
+        assert_close(product.as_slice(), &data);
+
+        // the columns of Q are orthonormal
+        let identity = copy(&transpose_matrices(q.clone())?.matmul(q)?)?;
+        for (offset, x) in identity.as_slice().iter().enumerate() {
+            let (i, j) = ((offset / k) % k, offset % k);
+            assert!((x - if i == j { 1. } else { 0. }).abs() < 1e-9);
+        }
+
+        // R is upper triangular
+        let n = shape[ndim - 1];
+        for (offset, x) in r.as_slice().iter().enumerate() {
+            if (offset / n) % k > offset % n {
+                assert_eq!(*x, 0.);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_cholesky() -> Result<(), Error> {
+    let data = vec![4., 12., -16., 12., 37., -43., -16., -43., 98.];
+    let a = matrix(vec![3, 3], data.to_vec())?;
+
+    let l = copy(&a.cholesky()?)?;
+    assert_close(l.as_slice(), &[2., 0., 0., 6., 1., 0., -8., 5., 3.]);
+
+    let product = copy(&l.clone().matmul(transpose_matrices(l)?)?)?;
+    assert_close(product.as_slice(), &data);
+
+    let a = matrix(vec![2, 2], vec![1., 2., 2., 1.])?;
+    assert!(matches!(copy(&a.cholesky()?), Err(Error::Singular(_))));
+
+    Ok(())
+}
+
+#[test]
+fn test_eigh() -> Result<(), Error> {
+    let data = vec![
+        2., -1., 0., -1., 2., -1., 0., -1., 2., //
+        4., 1., 0., 1., 3., 0., 0., 0., 5.,
+    ];
+
+    let a = matrix(vec![2, 3, 3], data.to_vec())?;
+    let (values, vectors) = a.eigh()?;
+    let (values, vectors) = (copy(&values)?, copy(&vectors)?);
+
+    assert_eq!(values.shape(), &[2, 3]);
+    assert_eq!(vectors.shape(), &[2, 3, 3]);
+
+    let sqrt2 = 2f64.sqrt();
+    let sqrt5 = 5f64.sqrt();
+    assert_close(
+        values.as_slice(),
+        &[
+            2. - sqrt2,
+            2.,
+            2. + sqrt2,
+            (7. - sqrt5) / 2.,
+            (7. + sqrt5) / 2.,
+            5.,
+        ],
+    );
+
+    // A = V diag(w) V^T
+    let scaled = values.expand_dims(vec![1])?.broadcast(vec![2, 3, 3])?;
+    let scaled = copy(&vectors.clone().mul(scaled)?)?;
+    let product = copy(&scaled.matmul(transpose_matrices(vectors)?)?)?;
+    assert_close(product.as_slice(), &data);
+
+    Ok(())
+}
+
+#[test]
+fn test_svd() -> Result<(), Error> {
+    for (shape, full_matrices) in [
+        (vec![4, 3], false),
+        (vec![4, 3], true),
+        (vec![2, 5], false),
+        (vec![2, 5], true),
+        (vec![2, 3, 3], false),
+    ] {
+        let size = shape.iter().product::<usize>();
+        let data = (0..size)
+            .map(|i| ((i * 5) % 7) as f64 - 3.)
+            .collect::<Vec<_>>();
+
+        let ndim = shape.len();
+        let (m, n) = (shape[ndim - 2], shape[ndim - 1]);
+        let k = m.min(n);
+
+        let a = matrix(shape.to_vec(), data.to_vec())?;
+        let (u, s, vh) = a.svd(full_matrices)?;
+        let (u, s, vh) = (copy(&u)?, copy(&s)?, copy(&vh)?);
+
+        let (u_cols, vh_rows) = if full_matrices { (m, n) } else { (k, k) };
+        assert_eq!(&u.shape()[ndim - 2..], &[m, u_cols]);
+        assert_eq!(&s.shape()[ndim - 2..], &[k]);
+        assert_eq!(&vh.shape()[ndim - 2..], &[vh_rows, n]);
+
+        for values in s.as_slice().chunks(k) {
+            assert!(values.windows(2).all(|w| w[0] >= w[1]));
+            assert!(values.iter().all(|s| *s >= 0.));
+        }
+
+        // A = U[:, :k] diag(S) V^T[:k, :]
+        let mut bounds = vec![AxisBound::In(0, 0, 1); ndim - 2];
+        let batch = shape[..ndim - 2].to_vec();
+        for (bound, dim) in bounds.iter_mut().zip(&batch) {
+            *bound = AxisBound::In(0, *dim, 1);
+        }
+
+        let mut u_bounds = bounds.to_vec();
+        u_bounds.extend([AxisBound::In(0, m, 1), AxisBound::In(0, k, 1)]);
+        let u = copy(&u.slice(u_bounds)?)?;
+
+        let mut vh_bounds = bounds;
+        vh_bounds.extend([AxisBound::In(0, k, 1), AxisBound::In(0, n, 1)]);
+        let vh = copy(&vh.slice(vh_bounds)?)?;
+
+        let mut scaled_shape = batch;
+        scaled_shape.extend([m, k]);
+        let s = s.expand_dims(vec![ndim - 2])?.broadcast(scaled_shape)?;
+        let scaled = copy(&u.mul(s)?)?;
+
+        let product = copy(&scaled.matmul(vh)?)?;
+        assert_close(product.as_slice(), &data);
+    }
+
+    Ok(())
+}
+
+// read every element of `array`, both by computing it in full and by calling `read_value`
+fn read_all<A: NDArrayRead<DType = i64>>(array: &A) -> Result<Vec<i64>, Error> {
+    let data = ArrayBase::<Vec<i64>>::copy(array)?.as_slice().to_vec();
+
+    let strides = array
+        .shape()
+        .iter()
+        .rev()
+        .scan(1, |stride, dim| {
+            let this = *stride;
+            *stride *= dim;
+            Some(this)
+        })
+        .collect::<Vec<_>>();
+
+    for (offset, expected) in data.iter().enumerate() {
+        let coord = strides
+            .iter()
+            .zip(array.shape().iter().rev())
+            .map(|(stride, dim)| (offset / stride) % dim)
+            .rev()
+            .collect::<Vec<_>>();
+
+        assert_eq!(array.read_value(&coord)?, *expected, "at {coord:?}");
+    }
+
+    Ok(data)
+}
+
+#[test]
+fn test_diagonal() -> Result<(), Error> {
+    // 0  1  2  3
+    // 4  5  6  7
+    // 8  9 10 11
+    let diagonal = range(vec![3, 4], 0)?.diagonal(0)?;
+    assert_eq!(diagonal.shape(), &[3]);
+    assert_eq!(read_all(&diagonal)?, vec![0, 5, 10]);
+
+    let diagonal = range(vec![3, 4], 0)?.diagonal(1)?;
+    assert_eq!(read_all(&diagonal)?, vec![1, 6, 11]);
+
+    let diagonal = range(vec![3, 4], 0)?.diagonal(2)?;
+    assert_eq!(read_all(&diagonal)?, vec![2, 7]);
+
+    let diagonal = range(vec![3, 4], 0)?.diagonal(-1)?;
+    assert_eq!(read_all(&diagonal)?, vec![4, 9]);
+
+    let diagonal = range(vec![2, 3, 2], 0)?.diagonal(-1)?;
+    assert_eq!(diagonal.shape(), &[2, 2]);
+    assert_eq!(read_all(&diagonal)?, vec![2, 5, 8, 11]);
+
+    assert!(range(vec![3, 4], 0)?.diagonal(4).is_err());
+    assert!(range(vec![3, 4], 0)?.diagonal(-3).is_err());
+    assert!(range(vec![4], 0)?.diagonal(0).is_err());
+
+    let matrix = range(vec![2, 3], 1)?.diag_embed()?;
+    assert_eq!(matrix.shape(), &[2, 3, 3]);
+    assert_eq!(
+        read_all(&matrix)?,
+        vec![1, 0, 0, 0, 2, 0, 0, 0, 3, 4, 0, 0, 0, 5, 0, 0, 0, 6]
+    );
+
+    let vector = range(vec![2, 3], 1)?.diag_embed()?.diagonal(0)?;
+    assert!(vector.eq(range(vec![2, 3], 1)?)?.all()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_trace() -> Result<(), Error> {
+    let trace = range(vec![3, 3], 0)?.trace()?;
+    assert_eq!(trace.shape(), &[1]);
+    assert_eq!(read_all(&trace)?, vec![12]);
+
+    let trace = range(vec![2, 2, 3], 0)?.trace()?;
+    assert_eq!(trace.shape(), &[2]);
+    assert_eq!(read_all(&trace)?, vec![4, 16]);
+
+    Ok(())
+}
+
+#[test]
+fn test_triangle() -> Result<(), Error> {
+    let lower = range(vec![3, 4], 1)?.tril(0)?;
+    assert_eq!(
+        read_all(&lower)?,
+        vec![1, 0, 0, 0, 5, 6, 0, 0, 9, 10, 11, 0]
+    );
+
+    let lower = range(vec![3, 4], 1)?.tril(-1)?;
+    assert_eq!(read_all(&lower)?, vec![0, 0, 0, 0, 5, 0, 0, 0, 9, 10, 0, 0]);
+
+    let upper = range(vec![3, 4], 1)?.triu(1)?;
+    assert_eq!(read_all(&upper)?, vec![0, 2, 3, 4, 0, 0, 7, 8, 0, 0, 0, 12]);
+
+    // a causal attention mask for a batch of two sequences of length 3
+    let ones = ArrayBase::<Vec<i64>>::new(vec![2, 3, 3], vec![1; 18])?;
+    let mask = ones.tril(0)?;
+    assert_eq!(mask.shape(), &[2, 3, 3]);
+    assert_eq!(
+        read_all(&mask)?,
+        vec![1, 0, 0, 1, 1, 0, 1, 1, 1, 1, 0, 0, 1, 1, 0, 1, 1, 1]
+    );
+
+    // the lower and upper triangles partition a matrix
+    let sum = range(vec![3, 4], 1)?
+        .tril(0)?
+        .add(range(vec![3, 4], 1)?.triu(1)?)?;
+    assert!(sum.eq(range(vec![3, 4], 1)?)?.all()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_outer() -> Result<(), Error> {
+    let outer = range(vec![2], 1)?.outer(range(vec![3], 1)?)?;
+    assert_eq!(outer.shape(), &[2, 3]);
+    assert_eq!(read_all(&outer)?, vec![1, 2, 3, 2, 4, 6]);
+
+    let outer = range(vec![2, 2], 1)?.outer(range(vec![2, 3], 0)?)?;
+    assert_eq!(outer.shape(), &[2, 2, 3]);
+    assert_eq!(
+        read_all(&outer)?,
+        vec![0, 1, 2, 0, 2, 4, 9, 12, 15, 12, 16, 20]
+    );
+
+    let expected = einsum(
+        "bi,bj->bij",
+        vec![range(vec![2, 2], 1)?, range(vec![2, 3], 0)?],
+    )?;
+    assert!(outer.eq(expected)?.all()?);
+
+    assert!(range(vec![2, 2], 1)?.outer(range(vec![3, 3], 0)?).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_kron() -> Result<(), Error> {
+    let left = ArrayBase::<Vec<i64>>::new(vec![2, 2], vec![1, 2, 3, 4])?;
+    let right = ArrayBase::<Vec<i64>>::new(vec![2, 3], vec![0, 5, 0, 6, 7, 1])?;
+
+    let kron = left.kron(right)?;
+    assert_eq!(kron.shape(), &[4, 6]);
+
+    #[rustfmt::skip]
+    let expected = vec![
+        0, 5, 0, 0, 10, 0,
+        6, 7, 1, 12, 14, 2,
+        0, 15, 0, 0, 20, 0,
+        18, 21, 3, 24, 28, 4,
+    ];
+
+    assert_eq!(read_all(&kron)?, expected);
+
+    let kron = range(vec![2, 1, 2], 1)?.kron(range(vec![2, 2, 1], 1)?)?;
+    assert_eq!(kron.shape(), &[2, 2, 2]);
+    assert_eq!(read_all(&kron)?, vec![1, 2, 2, 4, 9, 12, 12, 16]);
+
+    assert!(range(vec![2, 2, 2], 1)?
+        .kron(range(vec![3, 2, 2], 1)?)
+        .is_err());
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use ha_ndarray::*;
+
+#[test]
+fn test_layer_norm() -> Result<(), Error> {
+    let context = Context::new(0, 0, None)?;
+
+    let data = vec![1., 2., 3., 4., 2., 2., 2., 2.];
+    let array = ArrayBase::<Vec<f32>>::with_context(context.clone(), vec![2, 4], data)?;
+    let weight = ArrayBase::<Vec<f32>>::with_context(context.clone(), vec![4], vec![2.; 4])?;
+    let bias = ArrayBase::<Vec<f32>>::with_context(context, vec![4], vec![1.; 4])?;
+
+    let norm = array.layer_norm(vec![1], 1e-5, Some(weight.into()), Some(bias.into()))?;
+    let value = norm.read_value(&[0, 3])?;
+    assert!((value - (1. + 2. * (1.5 / 1.25f32.sqrt()))).abs() < 1e-4);
+
+    let r = 1.25f32.sqrt();
+    let expected = [
+        1. - 3. / r,
+        1. - 1. / r,
+        1. + 1. / r,
+        1. + 3. / r,
+        1.,
+        1.,
+        1.,
+        1.,
+    ];
+
+    let expected = ArrayBase::<Vec<f32>>::new(vec![2, 4], expected.to_vec())?;
+    assert!(expected.allclose(norm, 1e-4, 1e-5, false)?);
+
+    let array = ArrayBase::<Vec<f32>>::new(vec![2, 4], vec![0.; 8])?;
+    assert!(array.layer_norm(vec![0], 1e-5, None, None).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_group_norm() -> Result<(), Error> {
+    let context = Context::new(0, 0, None)?;
+
+    let data = vec![0., 2., 4., 6., 1., 1., 3., 3.];
+    let array = ArrayBase::<Vec<f32>>::with_context(context.clone(), vec![1, 4, 2], data)?;
+    let bias = ArrayBase::<Vec<f32>>::with_context(context, vec![4], vec![0., 0., 10., 10.])?;
+
+    let norm = array.group_norm(2, 0., None, Some(bias.into()))?;
+
+    let r = 5f32.sqrt();
+    let expected = [-3. / r, -1. / r, 1. / r, 3. / r, 9., 9., 11., 11.];
+
+    let expected = ArrayBase::<Vec<f32>>::new(vec![1, 4, 2], expected.to_vec())?;
+    assert!(expected.allclose(norm, 1e-5, 1e-6, false)?);
+
+    Ok(())
+}
+
+#[test]
+fn test_batch_norm() -> Result<(), Error> {
+    let context = Context::new(0, 0, None)?;
+
+    let data = vec![1., 3., 5., 7., 2., 4., 6., 8.];
+    let array = ArrayBase::<Vec<f32>>::with_context(context.clone(), vec![2, 2, 2], data)?;
+    let mean = ArrayBase::<Vec<f32>>::with_context(context.clone(), vec![2], vec![2., 6.])?;
+    let var = ArrayBase::<Vec<f32>>::with_context(context.clone(), vec![2], vec![4., 1.])?;
+    let weight = ArrayBase::<Vec<f32>>::with_context(context, vec![2], vec![1., 3.])?;
+
+    let norm = array.batch_norm(mean.into(), var.into(), 0., Some(weight.into()), None)?;
+    assert!((norm.read_value(&[1, 1, 1])? - 6.).abs() < 1e-6);
+
+    let expected = vec![-0.5, 0.5, -3., 3., 0., 1., 0., 6.];
+    let expected = ArrayBase::<Vec<f32>>::new(vec![2, 2, 2], expected)?;
+    assert!(expected.allclose(norm, 1e-5, 1e-6, false)?);
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use ha_ndarray::*;
+
+fn constant(
+    context: &Context,
+    shape: Vec<usize>,
+    value: f32,
+) -> Result<ArrayBase<Vec<f32>>, Error> {
+    let size = shape.iter().product();
+    ArrayBase::<Vec<f32>>::with_context(context.clone(), shape, vec![value; size])
+}
+
+#[test]
+fn test_buffer_reuse() -> Result<(), Error> {
+    let context = Context::default()?;
+
+    for _ in 0..4 {
+        let left = constant(&context, vec![8, 16], 2.)?;
+        let right = constant(&context, vec![8, 16], 3.)?;
+        let scale = constant(&context, vec![8, 16], 0.5)?;
+
+        // the output of the inner op is returned to the pool once the outer op has read it
+        let output = ArrayBase::<Vec<f32>>::copy(&left.add(right)?.mul(scale)?)?;
+        assert!(output.as_slice().iter().all(|n| *n == 2.5));
+    }
+
+    let stats = context.buffer_pool().stats();
+    assert_eq!(stats.misses, 5);
+    assert_eq!(stats.hits, 3);
+    assert_eq!(stats.in_use, 0);
+    assert_eq!(stats.cached, 128 * std::mem::size_of::<f32>());
+    assert!(stats.peak >= stats.total());
+
+    context.buffer_pool().clear();
+    assert_eq!(context.buffer_pool().stats().cached, 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_memory_budget() -> Result<(), Error> {
+    let context = Context::default()?.with_memory_budget(1024);
+    assert_eq!(context.buffer_pool().budget(), Some(1024));
+
+    let small = constant(&context, vec![16], 1.)?;
+    let sum = ArrayBase::<Vec<f32>>::copy(&small.add_scalar(1.)?)?;
+    assert!(sum.as_slice().iter().all(|n| *n == 2.));
+
+    let large = constant(&context, vec![32, 32], 1.)?;
+    match ArrayBase::<Vec<f32>>::copy(&large.add_scalar(1.)?) {
+        Err(Error::OutOfMemory(_)) => {}
+        other => panic!("expected an out-of-memory error but found {other:?}"),
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+fn test_device_buffer_reuse() -> Result<(), Error> {
+    let platform = Platform::try_from(ocl::Platform::first()?)?;
+    if platform.devices().is_empty() {
+        return Ok(());
+    }
+
+    // run every op on a device
+    let context = Context::new(0, 0, Some(platform))?;
+
+    for _ in 0..4 {
+        let left = constant(&context, vec![8, 16], 2.)?;
+        let right = constant(&context, vec![8, 16], 3.)?;
+        let output = ArrayBase::<Vec<f32>>::copy(&left.add(right)?.mul_scalar(0.5)?)?;
+        assert!(output.as_slice().iter().all(|n| *n == 2.5));
+    }
+
+    let stats = context.buffer_pool().stats();
+    assert!(stats.hits > 0);
+    assert_eq!(stats.in_use, 0);
+    assert!(stats.cached > 0);
+
+    Ok(())
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+fn test_device_memory_budget() -> Result<(), Error> {
+    let platform = Platform::try_from(ocl::Platform::first()?)?;
+    if platform.devices().is_empty() {
+        return Ok(());
+    }
+
+    let context = Context::new(0, 0, Some(platform))?.with_memory_budget(1024);
+
+    let small = constant(&context, vec![16], 1.)?;
+    let sum = ArrayBase::<Vec<f32>>::copy(&small.add_scalar(1.)?)?;
+    assert!(sum.as_slice().iter().all(|n| *n == 2.));
+    assert_eq!(context.buffer_pool().stats().in_use, 0);
+
+    let large = constant(&context, vec![32, 32], 1.)?;
+    match ArrayBase::<Vec<f32>>::copy(&large.add_scalar(1.)?) {
+        Err(Error::OutOfMemory(_)) => {}
+        other => panic!("expected an out-of-memory error but found {other:?}"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "futures")]
+
+use futures::executor::block_on;
+use futures::future::try_join;
+use ha_ndarray::*;
+
+fn range(shape: Vec<usize>) -> Result<ArrayBase<Vec<f64>>, Error> {
+    let size = shape.iter().product::<usize>();
+    let data = (0..size).map(|i| i as f64).collect();
+    ArrayBase::<Vec<f64>>::new(shape, data)
+}
+
+fn into_vec(buffer: Buffer<f64>) -> Result<Vec<f64>, Error> {
+    BufferConverter::from(buffer)
+        .to_slice()
+        .map(|slice| slice.into_vec())
+}
+
+#[test]
+fn test_read_async() -> Result<(), Error> {
+    let context = Context::default()?;
+    let queue = Queue::new(context, 64)?;
+
+    let product = range(vec![4, 8])?.matmul(range(vec![8, 2])?)?;
+    let expected = ArrayBase::<Vec<f64>>::copy(&product)?;
+
+    let actual = block_on(product.read_async(&queue))?;
+    assert_eq!(into_vec(actual)?, expected.as_slice());
+
+    // both reads run concurrently on the thread pool
+    let left = range(vec![3, 3])?
+        .add(range(vec![3, 3])?)?
+        .read_async(&queue);
+    let right = Array::from(range(vec![3, 3])?.transpose(None)?).read_async(&queue);
+    let (left, right) = block_on(try_join(left, right))?;
+    assert_eq!(
+        into_vec(left)?,
+        (0..9).map(|i| 2. * i as f64).collect::<Vec<_>>()
+    );
+    assert_eq!(into_vec(right)?, vec![0., 3., 6., 1., 4., 7., 2., 5., 8.]);
+
+    // an error computing the result is returned by the future
+    let not_positive_definite = ArrayBase::<Vec<f64>>::new(vec![2, 2], vec![1., 2., 2., 1.])?;
+    let cholesky = not_positive_definite.cholesky()?;
+    assert!(block_on(cholesky.read_async(&queue)).is_err());
+
+    Ok(())
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+fn test_read_async_opencl() -> Result<(), Error> {
+    let platform = Platform::try_from(ocl::Platform::first()?)?;
+    if platform.devices().is_empty() {
+        return Ok(());
+    }
+
+    let context = Context::new(0, 0, Some(platform))?;
+    let queue = Queue::new(context.clone(), 64)?;
+
+    let data = (0..64).map(|i| i as f64).collect();
+    let array = ArrayBase::<Vec<f64>>::with_context(context, vec![8, 8], data)?;
+
+    // the source of a slice is computed on its own queue, which the slice must wait for
+    let sum = array.clone().add(array)?;
+    let slice = sum.slice(vec![(2..4).into()])?;
+    let actual = block_on(slice.read_async(&queue))?;
+
+    let expected = (16..32).map(|i| 2. * i as f64).collect::<Vec<_>>();
+    assert_eq!(into_vec(actual)?, expected);
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use ha_ndarray::*;
+
+fn range(shape: Vec<usize>) -> Result<ArrayBase<Vec<i32>>, Error> {
+    let size = shape.iter().product::<usize>();
+    let data = (0..size as i32).collect();
+    ArrayBase::<Vec<i32>>::new(shape, data)
+}
+
+fn sharded_ops(context: Context) -> Result<(), Error> {
+    let shape = vec![7, 4, 3];
+    let size = shape.iter().product::<usize>();
+    let data = (0..size as i32).collect::<Vec<_>>();
+    let source = ArrayBase::<Vec<i32>>::with_context(context, shape.clone(), data.clone())?;
+
+    for (axis, dim) in shape.iter().copied().enumerate() {
+        for num_shards in 1..=Ord::min(dim, 4) {
+            let sharded = ShardedArray::with_shards(&source, axis, num_shards)?;
+            assert_eq!(sharded.num_shards(), num_shards);
+            assert_eq!(sharded.gather()?.as_slice(), source.as_slice());
+
+            let doubled = sharded.map(|shard| shard.mul_scalar(2))?;
+            let expected = data.iter().map(|n| n * 2).collect::<Vec<_>>();
+            assert_eq!(doubled.gather()?.as_slice(), &expected[..]);
+
+            let sum = sharded.zip(&doubled, |l, r| l.add(r))?;
+            let expected = data.iter().map(|n| n * 3).collect::<Vec<_>>();
+            assert_eq!(sum.gather()?.as_slice(), &expected[..]);
+
+            assert_eq!(sharded.sum_all()?, source.sum_all()?);
+            assert_eq!(sharded.max_all()?, source.max_all()?);
+            assert_eq!(sharded.min_all()?, source.min_all()?);
+
+            for axes in [vec![0], vec![1], vec![2], vec![0, 2], vec![0, 1, 2]] {
+                for keepdims in [false, true] {
+                    let actual = sharded.sum(axes.clone(), keepdims)?;
+                    let expected = source.clone().sum(axes.clone(), keepdims)?;
+                    let expected = ArrayBase::<Vec<i32>>::copy(&expected)?;
+                    assert_eq!(actual.shape(), expected.shape());
+                    assert_eq!(actual.as_slice(), expected.as_slice());
+
+                    let actual = sharded.max(axes.clone(), keepdims)?;
+                    let expected = source.clone().max(axes.clone(), keepdims)?;
+                    let expected = ArrayBase::<Vec<i32>>::copy(&expected)?;
+                    assert_eq!(actual.as_slice(), expected.as_slice());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_sharded_host() -> Result<(), Error> {
+    sharded_ops(Context::default()?)
+}
+
+#[test]
+fn test_sharded_product() -> Result<(), Error> {
+    let source = ArrayBase::<Vec<i64>>::new(vec![3, 2], vec![1, 2, 3, 4, 5, 6])?;
+    let sharded = ShardedArray::with_shards(&source, 0, 3)?;
+
+    assert_eq!(sharded.product_all()?, 720);
+    assert_eq!(sharded.product(vec![0], false)?.as_slice(), &[15, 48]);
+    assert_eq!(sharded.product(vec![1], true)?.as_slice(), &[2, 12, 30]);
+
+    Ok(())
+}
+
+#[test]
+fn test_sharded_errors() -> Result<(), Error> {
+    let source = range(vec![2, 3])?;
+
+    assert!(ShardedArray::with_shards(&source, 2, 1).is_err());
+    assert!(ShardedArray::with_shards(&source, 0, 0).is_err());
+    assert!(ShardedArray::with_shards(&source, 0, 3).is_err());
+
+    let left = ShardedArray::with_shards(&source, 1, 3)?;
+    let right = ShardedArray::with_shards(&source, 1, 2)?;
+    assert!(left.zip(&right, |l, r| l.add(r)).is_err());
+    assert!(left.map(|shard| shard.sum(vec![0], false)).is_err());
+
+    let sharded = ShardedArray::new(&source, 1)?;
+    assert!(sharded.num_shards() >= 1);
+    assert_eq!(sharded.gather()?.as_slice(), source.as_slice());
+
+    Ok(())
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+fn test_sharded_sub_devices() -> Result<(), Error> {
+    use ocl::core::ClDeviceIdPtr;
+    use ocl::ffi::{
+        clCreateSubDevices, cl_device_id, cl_device_partition_property, CL_DEVICE_PARTITION_EQUALLY,
+    };
+
+    let cl_platform = ocl::Platform::first()?;
+    let cpus = ocl::Device::list(cl_platform, Some(ocl::DeviceType::CPU))?;
+    let Some(cpu) = cpus.first() else {
+        return Ok(());
+    };
+
+    // partition the first CPU device into sub-devices of one compute unit each
+    let properties = [
+        CL_DEVICE_PARTITION_EQUALLY as cl_device_partition_property,
+        1,
+        0,
+    ];
+
+    // count the sub-devices first, then create them
+    let mut num_devices = 0;
+    let status = unsafe {
+        clCreateSubDevices(
+            cpu.as_core().as_ptr(),
+            properties.as_ptr(),
+            0,
+            std::ptr::null_mut(),
+            &mut num_devices,
+        )
+    };
+
+    let mut sub_devices: Vec<cl_device_id> = vec![std::ptr::null_mut(); num_devices as usize];
+    let status = if status == 0 && num_devices > 1 {
+        unsafe {
+            clCreateSubDevices(
+                cpu.as_core().as_ptr(),
+                properties.as_ptr(),
+                num_devices,
+                sub_devices.as_mut_ptr(),
+                std::ptr::null_mut(),
+            )
+        }
+    } else {
+        status
+    };
+
+    let devices = if status == 0 && num_devices > 1 {
+        sub_devices
+            .into_iter()
+            .map(|id| ocl::Device::from(unsafe { ocl::core::DeviceId::from_raw(id) }))
+            .collect()
+    } else {
+        // this CPU device can't be partitioned, so shard across the same device twice
+        vec![*cpu, *cpu]
+    };
+
+    let platform = Platform::from_devices(cl_platform, devices)?;
+    let context = Context::new(0, 0, Some(platform))?;
+    sharded_ops(context)
+}
@@ -0,0 +1,127 @@
+use std::sync::{Arc, Mutex};
+
+use ha_ndarray::construct::RandomUniform;
+use ha_ndarray::ops::Op;
+use ha_ndarray::*;
+
+// the name of the current thread, its index in the current rayon thread pool,
+// and the number of threads in that pool
+type ThreadInfo = (Option<String>, Option<usize>, usize);
+
+// an op which records the thread which enqueues it on the host
+struct Probe {
+    op: RandomUniform,
+    threads: Arc<Mutex<Vec<ThreadInfo>>>,
+}
+
+impl Probe {
+    fn new(context: Context, threads: Arc<Mutex<Vec<ThreadInfo>>>) -> Result<Self, Error> {
+        RandomUniform::with_context(context, vec![4]).map(|op| Self { op, threads })
+    }
+}
+
+impl Op for Probe {
+    type Out = f32;
+
+    fn context(&self) -> &Context {
+        self.op.context()
+    }
+
+    fn enqueue_cpu(&self, queue: &Queue) -> Result<Pooled<Vec<f32>>, Error> {
+        let thread = std::thread::current();
+
+        self.threads.lock().expect("threads").push((
+            thread.name().map(String::from),
+            rayon::current_thread_index(),
+            rayon::current_num_threads(),
+        ));
+
+        self.op.enqueue_cpu(queue)
+    }
+
+    #[cfg(feature = "opencl")]
+    fn enqueue_cl(&self, queue: &Queue) -> Result<Pooled<ocl::Buffer<f32>>, Error> {
+        self.op.enqueue_cl(queue)
+    }
+
+    fn read_value(&self, coord: &[usize]) -> Result<f32, Error> {
+        self.op.read_value(coord)
+    }
+}
+
+#[test]
+fn test_thread_pool() -> Result<(), Error> {
+    let context = Context::default()?
+        .with_num_threads(2)?
+        .with_parallel_min(16);
+
+    assert_eq!(context.num_threads(), 2);
+
+    let left = ArrayBase::<Vec<f32>>::with_context(context.clone(), vec![8, 8], vec![2.; 64])?;
+    let right = ArrayBase::<Vec<f32>>::with_context(context, vec![8, 8], vec![3.; 64])?;
+
+    let product = ArrayBase::<Vec<f32>>::copy(&left.mul(right)?)?;
+    assert!(product.as_slice().iter().all(|n| *n == 6.));
+
+    Ok(())
+}
+
+#[test]
+fn test_sequential_below_parallel_min() -> Result<(), Error> {
+    let context = Context::default()?.with_parallel_min(usize::MAX);
+    let array = ArrayBase::<Vec<i32>>::with_context(context, vec![3, 4], (0..12).collect())?;
+
+    let sum = ArrayBase::<Vec<i32>>::copy(&array.add_scalar(1)?)?;
+    assert_eq!(sum.as_slice(), (1..13).collect::<Vec<_>>().as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn test_enqueue_in_thread_pool() -> Result<(), Error> {
+    let thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(2)
+        .thread_name(|i| format!("test-pool-{i}"))
+        .build()
+        .expect("thread pool");
+
+    // run the op on the host even if there is an OpenCL device
+    let context = Context::new(0, 0, None)?
+        .with_policy(HostOnly)
+        .with_thread_pool(thread_pool);
+    let threads = Arc::new(Mutex::new(Vec::new()));
+
+    let array = ArrayOp::new(vec![4], Probe::new(context, threads.clone())?);
+    ArrayBase::<Vec<f32>>::copy(&array)?;
+
+    let threads = threads.lock().expect("threads");
+    assert_eq!(threads.len(), 1);
+
+    let (name, index, num_threads) = &threads[0];
+    assert!(name.as_deref().expect("name").starts_with("test-pool-"));
+    assert!(index.expect("index") < 2);
+    assert_eq!(*num_threads, 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_num_threads_limit() -> Result<(), Error> {
+    let context = Context::new(0, 0, None)?
+        .with_policy(HostOnly)
+        .with_num_threads(1)?;
+    let threads = Arc::new(Mutex::new(Vec::new()));
+
+    let array = ArrayOp::new(vec![4], Probe::new(context, threads.clone())?);
+    ArrayBase::<Vec<f32>>::copy(&array)?;
+
+    let threads = threads.lock().expect("threads");
+    assert_eq!(threads.len(), 1);
+
+    let (name, index, num_threads) = &threads[0];
+    assert_eq!(name.as_deref(), Some("ha-ndarray-0"));
+    assert_eq!(*index, Some(0));
+    assert_eq!(*num_threads, 1);
+
+    Ok(())
+}